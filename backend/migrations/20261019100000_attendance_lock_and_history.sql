@@ -0,0 +1,28 @@
+-- Migration: Attendance edit window and versioned change history
+-- Date: 2026-10-19
+
+-- Hours after the end of a class day during which attendance can still be edited directly.
+-- After the window closes the date is locked and only the correction workflow may change it.
+ALTER TABLE department_timings ADD COLUMN IF NOT EXISTS attendance_edit_window_hours INT NOT NULL DEFAULT 48;
+
+-- Every write to `attendance` is recorded here with who, when and why.
+CREATE TABLE IF NOT EXISTS attendance_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    attendance_id UUID REFERENCES attendance(id) ON DELETE SET NULL,
+    student_uuid UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    student_login_id VARCHAR(50) NOT NULL,
+    date DATE NOT NULL,
+    session VARCHAR(20) NOT NULL,
+    section VARCHAR(50),
+    version INT NOT NULL,
+    previous_status VARCHAR(10),
+    new_status VARCHAR(10) NOT NULL,
+    changed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    change_source VARCHAR(30) NOT NULL DEFAULT 'MANUAL', -- MANUAL, BATCH, CORRECTION
+    reason TEXT,
+    correction_request_id UUID REFERENCES attendance_correction_requests(id) ON DELETE SET NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(student_login_id, date, session, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_attendance_history_student_date ON attendance_history(student_uuid, date);
@@ -0,0 +1,24 @@
+-- Migration: Offline attendance sync ledger
+-- Date: 2026-10-19
+
+-- One row per client-generated record id so replays from a device are idempotent.
+CREATE TABLE IF NOT EXISTS attendance_sync_records (
+    client_record_id UUID PRIMARY KEY,
+    client_batch_id UUID NOT NULL,
+    device_id VARCHAR(100) NOT NULL,
+    marked_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    student_id VARCHAR(50) NOT NULL,
+    date DATE,
+    session VARCHAR(20),
+    section VARCHAR(50),
+    status VARCHAR(10) NOT NULL,
+    result VARCHAR(20) NOT NULL, -- ACCEPTED, REJECTED, CONFLICT
+    message TEXT,
+    existing_status VARCHAR(10),
+    existing_marked_by VARCHAR(150),
+    existing_marked_at TIMESTAMPTZ,
+    device_timestamp TIMESTAMPTZ NOT NULL,
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_attendance_sync_batch ON attendance_sync_records(client_batch_id);
@@ -0,0 +1,52 @@
+-- Migration: Biometric / RFID punch ingestion
+-- Date: 2026-10-19
+
+-- Device user codes enrolled on terminals, mapped to student login ids.
+-- Codes without a mapping are matched against users.login_id directly.
+CREATE TABLE IF NOT EXISTS biometric_user_map (
+    device_user_code VARCHAR(100) PRIMARY KEY,
+    login_id VARCHAR(50) NOT NULL REFERENCES users(login_id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Raw punch log. The unique key makes re-importing the same export a no-op.
+CREATE TABLE IF NOT EXISTS biometric_punches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    device_id VARCHAR(100) NOT NULL,
+    device_user_code VARCHAR(100) NOT NULL,
+    punched_at TIMESTAMP NOT NULL,
+    student_login_id VARCHAR(50),
+    session VARCHAR(20),
+    -- Reference only: attendance is kept per session, so no period-level mark is written.
+    period_index INT,
+    ingested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    ingested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Set once the punch's session has been applied to attendance, matched, filed as a conflict
+    -- or found locked. Punches still NULL are picked up again by the next ingestion.
+    applied_at TIMESTAMPTZ,
+    UNIQUE (device_id, device_user_code, punched_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_biometric_punches_student_date ON biometric_punches(student_login_id, punched_at);
+CREATE INDEX IF NOT EXISTS idx_biometric_punches_pending ON biometric_punches(student_login_id) WHERE applied_at IS NULL;
+
+-- Sessions where a punch disagrees with a manually marked attendance row.
+CREATE TABLE IF NOT EXISTS biometric_conflicts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    student_uuid UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    student_login_id VARCHAR(50) NOT NULL,
+    branch VARCHAR(100),
+    section VARCHAR(50),
+    date DATE NOT NULL,
+    session VARCHAR(20) NOT NULL,
+    first_punch TIMESTAMP NOT NULL,
+    manual_status VARCHAR(10) NOT NULL,
+    manual_marked_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'OPEN', -- OPEN, ACCEPTED_PUNCH, KEPT_MANUAL
+    resolved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    resolved_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (student_login_id, date, session)
+);
+
+CREATE INDEX IF NOT EXISTS idx_biometric_conflicts_branch_date ON biometric_conflicts(branch, date);
@@ -0,0 +1,27 @@
+-- Migration: Student leave applications
+-- Date: 2026-10-19
+
+CREATE TABLE IF NOT EXISTS leave_applications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    student_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    applied_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    applicant_role VARCHAR(20) NOT NULL, -- Student, Parent
+    parent_request_id UUID, -- parent_requests is created at startup; the foreign key is added there
+    leave_type VARCHAR(50) NOT NULL DEFAULT 'Personal',
+    from_date DATE NOT NULL,
+    from_session VARCHAR(20) NOT NULL DEFAULT 'MORNING',
+    to_date DATE NOT NULL,
+    to_session VARCHAR(20) NOT NULL DEFAULT 'AFTERNOON',
+    reason TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING', -- PENDING, APPROVED, REJECTED, CANCELLED
+    assigned_to UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    review_note TEXT,
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (to_date >= from_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_leave_applications_student ON leave_applications(student_id, from_date);
+CREATE INDEX IF NOT EXISTS idx_leave_applications_assigned ON leave_applications(assigned_to, status);
@@ -0,0 +1,26 @@
+-- Migration: Attendance analytics aggregates and indexes
+-- Date: 2026-10-19
+
+-- attendance.branch/year are not populated by the marking flows, so analytics group by the student's row in users.
+CREATE INDEX IF NOT EXISTS idx_attendance_student_date ON attendance(student_uuid, date);
+CREATE INDEX IF NOT EXISTS idx_attendance_faculty_date ON attendance(faculty_uuid, date);
+
+-- Per class, per session daily counts. Refreshed periodically by the backend.
+CREATE MATERIALIZED VIEW IF NOT EXISTS attendance_daily_summary AS
+SELECT
+    a.date,
+    COALESCE(u.branch, '') AS branch,
+    COALESCE(u.year, '') AS year,
+    COALESCE(a.section, u.section, '') AS section,
+    a.session,
+    COUNT(*) FILTER (WHERE UPPER(a.status) IN ('P', 'PRESENT'))::BIGINT AS present,
+    COUNT(*) FILTER (WHERE UPPER(a.status) IN ('A', 'ABSENT'))::BIGINT AS absent,
+    COUNT(*) FILTER (WHERE a.status = 'L')::BIGINT AS on_leave,
+    COUNT(*)::BIGINT AS total
+FROM attendance a
+JOIN users u ON u.id = a.student_uuid
+GROUP BY a.date, COALESCE(u.branch, ''), COALESCE(u.year, ''), COALESCE(a.section, u.section, ''), a.session;
+
+-- Required for REFRESH MATERIALIZED VIEW CONCURRENTLY
+CREATE UNIQUE INDEX IF NOT EXISTS idx_attendance_daily_summary_key
+ON attendance_daily_summary(date, branch, year, section, session);
@@ -0,0 +1,31 @@
+-- Migration: Generated timetable drafts
+-- Date: 2026-10-19
+
+CREATE TABLE IF NOT EXISTS timetable_drafts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    branch TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'DRAFT', -- DRAFT, PUBLISHED, DISCARDED
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    params JSONB NOT NULL DEFAULT '{}'::jsonb,
+    unplaced JSONB NOT NULL DEFAULT '[]'::jsonb,
+    warnings JSONB NOT NULL DEFAULT '[]'::jsonb,
+    published_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    published_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_timetable_drafts_branch ON timetable_drafts(branch, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS timetable_draft_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    draft_id UUID NOT NULL REFERENCES timetable_drafts(id) ON DELETE CASCADE,
+    year TEXT NOT NULL,
+    section TEXT NOT NULL,
+    day TEXT NOT NULL,
+    period_index INT NOT NULL,
+    subject TEXT NOT NULL,
+    subject_code TEXT,
+    faculty_id TEXT NOT NULL,
+    lab_room TEXT,
+    UNIQUE (draft_id, year, section, day, period_index)
+);
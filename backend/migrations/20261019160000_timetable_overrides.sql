@@ -0,0 +1,18 @@
+-- Migration: Audit of timetable assignments forced past clash checks
+-- Date: 2026-10-19
+
+CREATE TABLE IF NOT EXISTS timetable_overrides (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    branch TEXT NOT NULL,
+    year TEXT NOT NULL,
+    section TEXT NOT NULL,
+    day TEXT NOT NULL,
+    period_index INT NOT NULL,
+    faculty_id TEXT NOT NULL, -- login_id, same as timetable_entries
+    subject TEXT NOT NULL,
+    conflicts JSONB NOT NULL DEFAULT '[]',
+    forced_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_timetable_overrides_branch ON timetable_overrides (branch, created_at DESC);
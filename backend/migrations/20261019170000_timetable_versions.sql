@@ -0,0 +1,39 @@
+-- Migration: Versioned timetables with effective dates
+-- Date: 2026-10-19
+
+-- The version marked is_live is the one mirrored in timetable_entries, so day-to-day edits keep
+-- working against the live grid. Every other published version answers from its snapshot.
+CREATE TABLE IF NOT EXISTS timetable_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    branch TEXT NOT NULL,
+    name TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'DRAFT', -- DRAFT, PUBLISHED
+    effective_from DATE NOT NULL,
+    effective_to DATE,
+    is_live BOOLEAN NOT NULL DEFAULT FALSE,
+    cloned_from UUID REFERENCES timetable_versions(id) ON DELETE SET NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    published_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    published_at TIMESTAMPTZ,
+    activated_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (effective_to IS NULL OR effective_to >= effective_from)
+);
+
+CREATE INDEX IF NOT EXISTS idx_timetable_versions_branch ON timetable_versions(branch, status, effective_from);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_timetable_versions_live ON timetable_versions(branch) WHERE is_live;
+
+CREATE TABLE IF NOT EXISTS timetable_version_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    version_id UUID NOT NULL REFERENCES timetable_versions(id) ON DELETE CASCADE,
+    year TEXT NOT NULL,
+    section TEXT NOT NULL,
+    day TEXT NOT NULL,
+    period_index INT NOT NULL,
+    subject TEXT NOT NULL,
+    subject_code TEXT,
+    faculty_id TEXT NOT NULL,
+    -- Room the class is held in (timetable_entries.room); carried through clone and publish.
+    lab_room TEXT,
+    UNIQUE (version_id, year, section, day, period_index)
+);
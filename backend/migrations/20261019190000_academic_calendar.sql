@@ -0,0 +1,72 @@
+-- Migration: Academic calendar (years, semesters, holidays, working Saturdays, exam blocks)
+-- Date: 2026-10-19
+
+ALTER TABLE academic_years ADD COLUMN IF NOT EXISTS start_date DATE;
+ALTER TABLE academic_years ADD COLUMN IF NOT EXISTS end_date DATE;
+
+CREATE TABLE IF NOT EXISTS academic_semesters (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    academic_year_id INT NOT NULL REFERENCES academic_years(id) ON DELETE CASCADE,
+    term VARCHAR(10) NOT NULL, -- ODD (semesters 1/3/5), EVEN (2/4/6)
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    saturdays_working BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (academic_year_id, term),
+    CHECK (end_date >= start_date)
+);
+
+-- branch / year NULL means the event applies to every branch / year.
+CREATE TABLE IF NOT EXISTS academic_calendar_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_type VARCHAR(30) NOT NULL, -- HOLIDAY, WORKING_SATURDAY, EXAM
+    title TEXT NOT NULL,
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    branch TEXT,
+    year TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (end_date >= start_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_academic_calendar_events_dates ON academic_calendar_events(start_date, end_date);
+
+-- Same mapping as `normalize_branch` in the backend, so calendar events match a branch however
+-- either side spells it.
+CREATE OR REPLACE FUNCTION canonical_branch(b TEXT) RETURNS TEXT AS $$
+    SELECT CASE
+        WHEN UPPER(TRIM(b)) IN ('CME', 'CM', 'CSE', 'COMPUTER', 'COMPUTER ENGINEERING') THEN 'Computer Engineering'
+        WHEN UPPER(TRIM(b)) IN ('ECE', 'EC', 'ELECTRONICS & COMMUNICATION', 'ELECTRONICS & COMMUNICATION ENGINEERING', 'ELECTRONICS AND COMMUNICATION ENGINEERING') THEN 'Electronics & Communication Engineering'
+        WHEN UPPER(TRIM(b)) IN ('EEE', 'EE', 'ELECTRICAL & ELECTRONICS', 'ELECTRICAL AND ELECTRONICS', 'ELECTRICAL & ELECTRONICS ENGINEERING', 'ELECTRICAL AND ELECTRONICS ENGINEERING') THEN 'Electrical & Electronics Engineering'
+        WHEN UPPER(TRIM(b)) IN ('ME', 'MEC', 'MECH', 'MECHANICAL', 'MECHANICAL ENGINEERING') THEN 'Mechanical Engineering'
+        WHEN UPPER(TRIM(b)) IN ('CE', 'CIV', 'CIVIL', 'CIVIL ENGINEERING') THEN 'Civil Engineering'
+        WHEN UPPER(TRIM(b)) IN ('BS & H', 'BS&H', 'BSH', 'GENERAL', 'BASIC SCIENCE') THEN 'General'
+        ELSE TRIM(b)
+    END
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Whether regular classes run on a date for a branch and year. Holidays and exam blocks never
+-- teach; Sundays never do; Saturdays follow the semester unless marked as working Saturdays.
+-- Once any semester is defined, dates outside every semester are not instructional. Without
+-- semesters the old behaviour holds and every Monday to Saturday counts.
+CREATE OR REPLACE FUNCTION is_instructional_day(d DATE, b TEXT, y TEXT) RETURNS BOOLEAN AS $$
+    SELECT CASE
+        WHEN EXISTS (
+            SELECT 1 FROM academic_calendar_events e
+            WHERE e.event_type IN ('HOLIDAY', 'EXAM') AND d BETWEEN e.start_date AND e.end_date
+              AND (e.branch IS NULL OR canonical_branch(e.branch) = canonical_branch(b)) AND (e.year IS NULL OR e.year = y)
+        ) THEN FALSE
+        WHEN EXTRACT(ISODOW FROM d) = 7 THEN FALSE
+        WHEN EXISTS (SELECT 1 FROM academic_semesters)
+             AND NOT EXISTS (SELECT 1 FROM academic_semesters s WHERE d BETWEEN s.start_date AND s.end_date) THEN FALSE
+        WHEN EXTRACT(ISODOW FROM d) = 6 THEN
+            COALESCE((SELECT s.saturdays_working FROM academic_semesters s WHERE d BETWEEN s.start_date AND s.end_date LIMIT 1), TRUE)
+            OR EXISTS (
+                SELECT 1 FROM academic_calendar_events e
+                WHERE e.event_type = 'WORKING_SATURDAY' AND d BETWEEN e.start_date AND e.end_date
+                  AND (e.branch IS NULL OR canonical_branch(e.branch) = canonical_branch(b)) AND (e.year IS NULL OR e.year = y)
+            )
+        ELSE TRUE
+    END
+$$ LANGUAGE sql STABLE;
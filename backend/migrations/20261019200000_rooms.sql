@@ -0,0 +1,36 @@
+-- Migration: Rooms and labs registry with dated class relocations
+-- Date: 2026-10-19
+
+CREATE TABLE IF NOT EXISTS rooms (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    code TEXT NOT NULL UNIQUE, -- matches timetable_entries.room and the section of 'Lab' grid rows
+    name TEXT NOT NULL,
+    room_type VARCHAR(20) NOT NULL DEFAULT 'CLASSROOM', -- CLASSROOM, LAB, SEMINAR_HALL
+    capacity INT NOT NULL DEFAULT 60,
+    equipment TEXT[] NOT NULL DEFAULT '{}',
+    department TEXT, -- owning branch, NULL for shared rooms
+    status VARCHAR(20) NOT NULL DEFAULT 'ACTIVE', -- ACTIVE, MAINTENANCE, RETIRED
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rooms_department ON rooms (department, room_type);
+
+-- One-day moves of a class period to another room, e.g. while its lab is under maintenance.
+CREATE TABLE IF NOT EXISTS room_relocations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    relocation_date DATE NOT NULL,
+    branch TEXT NOT NULL,
+    year TEXT NOT NULL,
+    section TEXT NOT NULL,
+    day TEXT NOT NULL,
+    period_index INT NOT NULL,
+    from_room TEXT,
+    to_room TEXT NOT NULL REFERENCES rooms(code) ON UPDATE CASCADE,
+    reason TEXT,
+    relocated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (relocation_date, branch, year, section, period_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_relocations_room ON room_relocations (relocation_date, to_room);
@@ -0,0 +1,15 @@
+-- Migration: Teaching-load norms used to flag faculty over- and under-load
+-- Date: 2026-10-19
+
+-- One row per branch; the 'ALL' row applies to branches without their own.
+CREATE TABLE IF NOT EXISTS workload_norms (
+    branch TEXT PRIMARY KEY,
+    max_weekly_periods INT NOT NULL DEFAULT 24,
+    min_weekly_periods INT NOT NULL DEFAULT 12,
+    max_subjects INT NOT NULL DEFAULT 3,
+    max_sections INT NOT NULL DEFAULT 4,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO workload_norms (branch) VALUES ('ALL') ON CONFLICT (branch) DO NOTHING;
@@ -0,0 +1,18 @@
+-- Migration: Effective-dated slot templates per department
+-- Date: 2026-10-19
+
+-- Each schedule is {"startTime": "HH:MM", "slots": [{"kind", "minutes", "periods"?, "label"?}]}
+-- with kind PERIOD, LAB (a block of `periods` consecutive periods), SHORT_BREAK or LUNCH.
+-- A NULL saturday_schedule means Saturdays follow the weekday schedule.
+CREATE TABLE IF NOT EXISTS slot_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    branch TEXT NOT NULL,
+    effective_from DATE NOT NULL,
+    weekday_schedule JSONB NOT NULL,
+    saturday_schedule JSONB,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (branch, effective_from)
+);
+
+CREATE INDEX IF NOT EXISTS idx_slot_templates_branch ON slot_templates(branch, effective_from DESC);
@@ -0,0 +1,68 @@
+-- Migration: Curricula stored in the database with versioned revisions per subject
+-- Date: 2026-10-19
+
+CREATE TABLE IF NOT EXISTS curriculum_regulations (
+    code TEXT PRIMARY KEY, -- C23, C26
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One row per imported version of a subject's syllabus. A new revision is only written when the
+-- source changes; the latest one is current.
+CREATE TABLE IF NOT EXISTS curriculum_subject_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    regulation TEXT NOT NULL REFERENCES curriculum_regulations(code),
+    branch_code TEXT NOT NULL, -- cme, ece, eee, mech, civ, aiml
+    semester INT NOT NULL,
+    subject_code TEXT NOT NULL,
+    subject_name TEXT NOT NULL,
+    subject_type TEXT NOT NULL CHECK (subject_type IN ('THEORY', 'PRACTICAL')),
+    total_periods INT NOT NULL DEFAULT 0,
+    revision INT NOT NULL,
+    is_current BOOLEAN NOT NULL DEFAULT TRUE,
+    source JSONB NOT NULL,
+    source_path TEXT,
+    imported_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (regulation, branch_code, subject_code, revision)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_curriculum_current_revision
+    ON curriculum_subject_revisions(regulation, branch_code, subject_code) WHERE is_current;
+CREATE INDEX IF NOT EXISTS idx_curriculum_revisions_semester
+    ON curriculum_subject_revisions(regulation, branch_code, semester) WHERE is_current;
+
+CREATE TABLE IF NOT EXISTS curriculum_units (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    revision_id UUID NOT NULL REFERENCES curriculum_subject_revisions(id) ON DELETE CASCADE,
+    unit_no INT NOT NULL,
+    title TEXT NOT NULL,
+    total_periods INT NOT NULL DEFAULT 0,
+    UNIQUE (revision_id, unit_no)
+);
+
+-- A topic's identity across revisions. Renumbered or reworded topics keep theirs, so progress and
+-- feedback recorded against an earlier revision still apply.
+CREATE TABLE IF NOT EXISTS curriculum_topic_identities (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_code TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS curriculum_topics (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    revision_id UUID NOT NULL REFERENCES curriculum_subject_revisions(id) ON DELETE CASCADE,
+    unit_id UUID NOT NULL REFERENCES curriculum_units(id) ON DELETE CASCADE,
+    topic_uid UUID NOT NULL REFERENCES curriculum_topic_identities(id),
+    topic_key TEXT NOT NULL, -- the "id" in the source JSON, e.g. 26ec104t-1.1
+    sno TEXT,
+    topic TEXT NOT NULL,
+    periods INT NOT NULL DEFAULT 1,
+    topic_type TEXT NOT NULL DEFAULT 'theory',
+    order_index INT NOT NULL,
+    UNIQUE (revision_id, topic_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_curriculum_topics_uid ON curriculum_topics(topic_uid);
+
+ALTER TABLE curriculum_progress ADD COLUMN IF NOT EXISTS topic_uid UUID REFERENCES curriculum_topic_identities(id);
+ALTER TABLE student_curriculum_feedback ADD COLUMN IF NOT EXISTS topic_uid UUID REFERENCES curriculum_topic_identities(id);
+CREATE INDEX IF NOT EXISTS idx_curriculum_progress_topic_uid ON curriculum_progress(topic_uid);
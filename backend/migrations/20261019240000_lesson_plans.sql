@@ -0,0 +1,34 @@
+-- Migration: Lesson plans dated from the timetable and academic calendar
+-- Date: 2026-10-19
+
+-- One generated plan per subject and section. The topic dates themselves live in
+-- curriculum_progress.assigned_date; this keeps what they were computed from so the plan can be
+-- recomputed when periods are lost, and whether the syllabus fits before the exam block.
+CREATE TABLE IF NOT EXISTS lesson_plans (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_code TEXT NOT NULL,
+    regulation TEXT NOT NULL,
+    branch TEXT NOT NULL,
+    year TEXT NOT NULL,
+    section TEXT NOT NULL,
+    semester INT NOT NULL,
+    academic_semester_id UUID NOT NULL REFERENCES academic_semesters(id) ON DELETE CASCADE,
+    faculty_id UUID NOT NULL REFERENCES users(id),
+    plan_start DATE NOT NULL,
+    exam_start DATE, -- NULL when the calendar has no exam block in the semester
+    weekly_periods INT NOT NULL,
+    required_periods INT NOT NULL,
+    available_periods INT NOT NULL, -- before the exam block, lost periods excluded
+    lost_periods INT NOT NULL DEFAULT 0,
+    projected_finish DATE,
+    at_risk BOOLEAN NOT NULL DEFAULT FALSE,
+    generated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    generated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (subject_code, branch, year, section, semester)
+);
+
+CREATE INDEX IF NOT EXISTS idx_lesson_plans_section ON lesson_plans (branch, year, section);
+
+-- TRUE while a topic's assigned_date is the one the planner computed. A date set by hand is kept
+-- when the plan is recomputed unless the faculty asks to overwrite it.
+ALTER TABLE curriculum_progress ADD COLUMN IF NOT EXISTS date_from_plan BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,25 @@
+-- Migration: Syllabus lag forecasts and weekly early warnings
+-- Date: 2026-10-19
+
+-- Latest forecast per lesson plan, recomputed from the completion pace and the periods left.
+CREATE TABLE IF NOT EXISTS syllabus_forecasts (
+    lesson_plan_id UUID PRIMARY KEY REFERENCES lesson_plans(id) ON DELETE CASCADE,
+    completed_periods INT NOT NULL,
+    remaining_periods INT NOT NULL,
+    scheduled_periods INT NOT NULL, -- periods left in the timetable before the deadline
+    weekly_rate DOUBLE PRECISION NOT NULL, -- periods of syllabus completed per week
+    deadline DATE NOT NULL,
+    projected_completion DATE,
+    status VARCHAR(20) NOT NULL, -- ON_TRACK, AT_RISK, BEHIND
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_syllabus_forecasts_status ON syllabus_forecasts (status);
+
+-- When each branch's faculty and HOD were last sent the weekly forecast.
+CREATE TABLE IF NOT EXISTS syllabus_forecast_notices (
+    branch TEXT PRIMARY KEY,
+    sent_on DATE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_curriculum_completion_logs_progress ON curriculum_completion_logs (progress_id, timestamp DESC);
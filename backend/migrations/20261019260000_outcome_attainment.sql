@@ -0,0 +1,69 @@
+-- Migration: Course outcome / program outcome mapping and attainment
+-- Date: 2026-10-19
+
+-- Program outcomes (PO1..PO12) and program specific outcomes (PSO1..) of a branch's programme.
+CREATE TABLE IF NOT EXISTS program_outcomes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    branch TEXT NOT NULL,
+    code VARCHAR(10) NOT NULL,
+    kind VARCHAR(5) NOT NULL, -- PO, PSO
+    description TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (branch, code)
+);
+
+-- Course outcomes of a subject under a regulation (CO1..).
+CREATE TABLE IF NOT EXISTS course_outcomes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_code TEXT NOT NULL,
+    regulation TEXT NOT NULL,
+    code VARCHAR(10) NOT NULL,
+    description TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (subject_code, regulation, code)
+);
+
+-- The articulation matrix: how strongly a CO supports a PO or PSO (1 low, 2 medium, 3 high).
+CREATE TABLE IF NOT EXISTS co_po_mappings (
+    course_outcome_id UUID NOT NULL REFERENCES course_outcomes(id) ON DELETE CASCADE,
+    program_outcome_id UUID NOT NULL REFERENCES program_outcomes(id) ON DELETE CASCADE,
+    level SMALLINT NOT NULL CHECK (level BETWEEN 1 AND 3),
+    PRIMARY KEY (course_outcome_id, program_outcome_id)
+);
+
+-- Curriculum topics a CO is taught through, by the topic's identity across revisions.
+CREATE TABLE IF NOT EXISTS topic_co_mappings (
+    course_outcome_id UUID NOT NULL REFERENCES course_outcomes(id) ON DELETE CASCADE,
+    topic_uid UUID NOT NULL REFERENCES curriculum_topic_identities(id) ON DELETE CASCADE,
+    PRIMARY KEY (course_outcome_id, topic_uid)
+);
+
+-- Marked work of a subject (mid exams, assignments, lab records...). A student attains the
+-- component when they score at least threshold_percent of max_marks.
+CREATE TABLE IF NOT EXISTS assessment_components (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_code TEXT NOT NULL,
+    regulation TEXT NOT NULL,
+    name TEXT NOT NULL,
+    max_marks DOUBLE PRECISION NOT NULL CHECK (max_marks > 0),
+    threshold_percent DOUBLE PRECISION NOT NULL DEFAULT 60 CHECK (threshold_percent > 0 AND threshold_percent <= 100),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (subject_code, regulation, name)
+);
+
+CREATE TABLE IF NOT EXISTS assessment_component_outcomes (
+    component_id UUID NOT NULL REFERENCES assessment_components(id) ON DELETE CASCADE,
+    course_outcome_id UUID NOT NULL REFERENCES course_outcomes(id) ON DELETE CASCADE,
+    PRIMARY KEY (component_id, course_outcome_id)
+);
+
+CREATE TABLE IF NOT EXISTS assessment_marks (
+    component_id UUID NOT NULL REFERENCES assessment_components(id) ON DELETE CASCADE,
+    student_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    marks DOUBLE PRECISION NOT NULL CHECK (marks >= 0),
+    entered_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (component_id, student_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_student_curriculum_feedback_topic_uid ON student_curriculum_feedback (topic_uid);
@@ -0,0 +1,32 @@
+-- Migration: Regulation transition and subject equivalence
+-- Date: 2026-10-19
+
+-- seed_courses tags each subject with its regulation ('C-23', 'C-26'); older databases may lack it.
+ALTER TABLE subjects ADD COLUMN IF NOT EXISTS course_id TEXT;
+
+-- A subject of one regulation that counts for a subject of another. Equivalences hold both ways.
+CREATE TABLE IF NOT EXISTS subject_equivalences (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    from_course_id TEXT NOT NULL,
+    from_subject_code TEXT NOT NULL,
+    to_course_id TEXT NOT NULL,
+    to_subject_code TEXT NOT NULL,
+    notes TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (from_course_id <> to_course_id),
+    UNIQUE (from_course_id, from_subject_code, to_course_id, to_subject_code)
+);
+
+CREATE INDEX IF NOT EXISTS idx_subject_equivalences_to ON subject_equivalences (to_course_id, to_subject_code);
+
+-- The regulation a student studies under when it differs from their batch's (detained or
+-- readmitted students). previous_course_id is the regulation their earlier marks were earned in.
+CREATE TABLE IF NOT EXISTS student_regulations (
+    student_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    course_id TEXT NOT NULL,
+    previous_course_id TEXT,
+    reason TEXT NOT NULL, -- DETAINED, READMITTED, OTHER
+    assigned_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    assigned_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
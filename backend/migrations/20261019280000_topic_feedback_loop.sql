@@ -0,0 +1,33 @@
+-- Migration: Topic feedback response loop
+-- Date: 2026-10-19
+
+-- A class a faculty member schedules to re-teach a topic students reported trouble with.
+CREATE TABLE IF NOT EXISTS topic_revision_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_code TEXT NOT NULL,
+    topic_id TEXT NOT NULL,
+    topic_uid UUID REFERENCES curriculum_topic_identities(id) ON DELETE SET NULL,
+    branch TEXT NOT NULL,
+    year TEXT NOT NULL,
+    section TEXT NOT NULL,
+    faculty_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    scheduled_date DATE NOT NULL,
+    period_index INT,
+    notes TEXT,
+    status TEXT NOT NULL DEFAULT 'SCHEDULED' CHECK (status IN ('SCHEDULED', 'CONDUCTED', 'CANCELLED')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_topic_revision_sessions_faculty ON topic_revision_sessions (faculty_id, scheduled_date);
+
+-- Closure of a piece of feedback: acknowledged when a revision class is scheduled for it,
+-- resolved when that class is conducted or the faculty member closes it with a note.
+ALTER TABLE student_curriculum_feedback ADD COLUMN IF NOT EXISTS revision_session_id UUID REFERENCES topic_revision_sessions(id) ON DELETE SET NULL;
+ALTER TABLE student_curriculum_feedback ADD COLUMN IF NOT EXISTS acknowledged_at TIMESTAMPTZ;
+ALTER TABLE student_curriculum_feedback ADD COLUMN IF NOT EXISTS resolved_at TIMESTAMPTZ;
+ALTER TABLE student_curriculum_feedback ADD COLUMN IF NOT EXISTS resolved_by UUID REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE student_curriculum_feedback ADD COLUMN IF NOT EXISTS resolution_note TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_student_curriculum_feedback_student ON student_curriculum_feedback (student_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_student_curriculum_feedback_subject_created ON student_curriculum_feedback (subject_code, created_at);
@@ -0,0 +1,53 @@
+-- Migration: Single syllabus progress store
+-- Date: 2026-10-19
+
+-- curriculum_progress becomes the one place a section's topic progress is recorded. Progress on
+-- legacy lesson plan items moves into it, keyed by the item, and lesson_plan_progress is kept as
+-- a read-only view over it for anything still reading the old table.
+
+-- Progress carried over from lesson plans was never attributed to a faculty member.
+ALTER TABLE curriculum_progress ALTER COLUMN faculty_id DROP NOT NULL;
+ALTER TABLE curriculum_progress ADD COLUMN IF NOT EXISTS lesson_plan_item_id TEXT REFERENCES lesson_plan_items(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_curriculum_progress_lesson_item ON curriculum_progress(lesson_plan_item_id, section);
+
+-- A lesson plan item belongs to a subject, which fixes its branch and semester; the year follows
+-- from the semester. subjects.semester is free text ('3rd', '3rd Semester', '1st Year').
+INSERT INTO curriculum_progress (
+    topic_id, subject_code, faculty_id, branch, section, year, semester,
+    completed_date, status, lesson_plan_item_id, created_at, updated_at
+)
+SELECT lpi.id, lpi.subject_id, NULL, s.branch, TRIM(lpp.section), sem.year, sem.semester,
+       CASE WHEN lpp.completed THEN COALESCE(lpp.completed_date, NOW())::date END,
+       CASE WHEN lpp.completed THEN 'completed' ELSE 'pending' END,
+       lpi.id, COALESCE(lpp.completed_date, NOW()), COALESCE(lpp.completed_date, NOW())
+FROM lesson_plan_progress lpp
+JOIN lesson_plan_items lpi ON lpi.id = lpp.item_id
+JOIN subjects s ON s.id = lpi.subject_id
+CROSS JOIN LATERAL (
+    SELECT n AS semester,
+           CASE WHEN n <= 2 THEN '1st Year' WHEN n <= 4 THEN '2nd Year' ELSE '3rd Year' END AS year
+    FROM (SELECT COALESCE(SUBSTRING(s.semester FROM '[1-6]')::INT, 1) AS n) x
+) sem
+WHERE lpp.section IS NOT NULL
+ON CONFLICT (topic_id, subject_code, branch, section, year, semester) DO UPDATE SET
+    lesson_plan_item_id = EXCLUDED.lesson_plan_item_id,
+    status = CASE WHEN curriculum_progress.status = 'completed' THEN 'completed' ELSE EXCLUDED.status END,
+    completed_date = COALESCE(curriculum_progress.completed_date, EXCLUDED.completed_date);
+
+-- The completion history the syllabus forecasts read.
+INSERT INTO curriculum_completion_logs (progress_id, action, changed_by, timestamp)
+SELECT cp.id, 'marked_completed', NULL, COALESCE(cp.completed_date::timestamptz, cp.updated_at)
+FROM curriculum_progress cp
+WHERE cp.lesson_plan_item_id IS NOT NULL AND cp.status = 'completed'
+  AND NOT EXISTS (SELECT 1 FROM curriculum_completion_logs l WHERE l.progress_id = cp.id);
+
+ALTER TABLE lesson_plan_progress RENAME TO lesson_plan_progress_legacy;
+
+CREATE OR REPLACE VIEW lesson_plan_progress AS
+SELECT lesson_plan_item_id AS item_id,
+       section,
+       COALESCE(status = 'completed', FALSE) AS completed,
+       completed_date::timestamptz AS completed_date
+FROM curriculum_progress
+WHERE lesson_plan_item_id IS NOT NULL;
@@ -0,0 +1,55 @@
+-- Migration: Course material repository
+-- Date: 2026-10-19
+
+-- Notes, slides and links a faculty member shares against a subject, one of its units or one of
+-- its topics. Empty `sections` means every section of the branch can see it.
+CREATE TABLE IF NOT EXISTS course_materials (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_code TEXT NOT NULL,
+    branch TEXT NOT NULL,
+    regulation TEXT NOT NULL,
+    unit_no INT,
+    topic_id TEXT,
+    topic_uid UUID REFERENCES curriculum_topic_identities(id) ON DELETE SET NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    kind TEXT NOT NULL CHECK (kind IN ('FILE', 'LINK')),
+    sections TEXT[] NOT NULL DEFAULT '{}',
+    current_version INT NOT NULL DEFAULT 1,
+    uploaded_by UUID NOT NULL REFERENCES users(id),
+    archived BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_course_materials_subject ON course_materials (subject_code, branch);
+CREATE INDEX IF NOT EXISTS idx_course_materials_topic ON course_materials (topic_uid);
+
+-- Every upload or link change is a new version; earlier versions stay downloadable. Files are
+-- kept by the storage backend named here under `storage_key`.
+CREATE TABLE IF NOT EXISTS course_material_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    material_id UUID NOT NULL REFERENCES course_materials(id) ON DELETE CASCADE,
+    version INT NOT NULL,
+    storage_backend TEXT,
+    storage_key TEXT,
+    url TEXT,
+    file_name TEXT,
+    content_type TEXT,
+    size_bytes BIGINT,
+    change_note TEXT,
+    uploaded_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (material_id, version),
+    CHECK (url IS NOT NULL OR storage_key IS NOT NULL)
+);
+
+CREATE TABLE IF NOT EXISTS course_material_downloads (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    material_id UUID NOT NULL REFERENCES course_materials(id) ON DELETE CASCADE,
+    version INT NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    downloaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_course_material_downloads_material ON course_material_downloads (material_id, downloaded_at);
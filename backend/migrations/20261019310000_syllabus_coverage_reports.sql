@@ -0,0 +1,72 @@
+-- Migration: Signed syllabus coverage reports
+-- Date: 2026-10-19
+
+-- The end-of-semester statement of how much of a subject's syllabus a section was taught. The
+-- faculty member generates and signs it, the HOD countersigns it. `snapshot` holds the report as
+-- generated, so what was signed does not move when progress is edited afterwards.
+CREATE TABLE IF NOT EXISTS syllabus_coverage_reports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_code TEXT NOT NULL,
+    subject_name TEXT NOT NULL,
+    regulation TEXT NOT NULL,
+    branch TEXT NOT NULL,
+    year TEXT NOT NULL,
+    section TEXT NOT NULL,
+    semester INT NOT NULL,
+    academic_semester_id UUID NOT NULL REFERENCES academic_semesters(id),
+    faculty_id UUID NOT NULL REFERENCES users(id),
+    status TEXT NOT NULL DEFAULT 'DRAFT' CHECK (status IN ('DRAFT', 'FACULTY_SIGNED', 'RETURNED', 'FINALIZED')),
+    snapshot JSONB NOT NULL,
+    faculty_statement TEXT,
+    faculty_signed_at TIMESTAMPTZ,
+    hod_id UUID REFERENCES users(id),
+    hod_note TEXT,
+    hod_signed_at TIMESTAMPTZ,
+    generated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (subject_code, branch, year, section, semester, academic_semester_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_syllabus_coverage_reports_section ON syllabus_coverage_reports (branch, year, section);
+
+-- The countersigned PDF, written once when the HOD finalizes the report.
+CREATE TABLE IF NOT EXISTS syllabus_coverage_archive (
+    report_id UUID PRIMARY KEY REFERENCES syllabus_coverage_reports(id) ON DELETE RESTRICT,
+    file_name TEXT NOT NULL,
+    pdf BYTEA NOT NULL,
+    sha256 TEXT NOT NULL,
+    snapshot JSONB NOT NULL,
+    faculty_id UUID NOT NULL REFERENCES users(id),
+    hod_id UUID NOT NULL REFERENCES users(id),
+    finalized_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Finalized reports and their archived PDFs cannot be changed or removed, by the application or
+-- by hand.
+CREATE OR REPLACE FUNCTION reject_finalized_coverage_change() RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.status = 'FINALIZED' THEN
+        RAISE EXCEPTION 'Finalized syllabus coverage report % cannot be changed', OLD.id;
+    END IF;
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION reject_coverage_archive_change() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'Archived syllabus coverage report % cannot be changed', OLD.report_id;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_syllabus_coverage_reports_immutable ON syllabus_coverage_reports;
+CREATE TRIGGER trg_syllabus_coverage_reports_immutable
+    BEFORE UPDATE OR DELETE ON syllabus_coverage_reports
+    FOR EACH ROW EXECUTE FUNCTION reject_finalized_coverage_change();
+
+DROP TRIGGER IF EXISTS trg_syllabus_coverage_archive_immutable ON syllabus_coverage_archive;
+CREATE TRIGGER trg_syllabus_coverage_archive_immutable
+    BEFORE UPDATE OR DELETE ON syllabus_coverage_archive
+    FOR EACH ROW EXECUTE FUNCTION reject_coverage_archive_change();
@@ -0,0 +1,300 @@
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{Postgres, Transaction};
+use dotenvy::dotenv;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use uuid::Uuid;
+use backend::curriculum_validator::{self, Placement, Severity};
+
+// Imports curriculum JSON into the curriculum_* tables.
+//
+//   cargo run --bin import_curriculum -- [curriculum dir] [--check]
+//
+// Files are expected at {dir}/{regulation}/{branch}/semester N/[theory|practical/]{subject}.json.
+// A subject gets a new revision only when its file changed since the current one; topics keep
+// their identity across revisions when their id or wording is unchanged. Files the curriculum
+// validator finds errors in are skipped; --check only reports them. lint_curriculum gives the
+// full report, warnings included.
+
+#[derive(Debug, Deserialize)]
+struct CurriculumJson {
+    #[serde(rename = "subjectCode")]
+    subject_code: String,
+    #[serde(rename = "subjectName")]
+    subject_name: String,
+    #[serde(rename = "totalPeriods", default)]
+    total_periods: i32,
+    units: Vec<UnitJson>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnitJson {
+    #[serde(rename = "unitNo")]
+    unit_no: i32,
+    title: String,
+    #[serde(rename = "totalPeriods", default)]
+    total_periods: i32,
+    topics: Vec<TopicJson>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TopicJson {
+    id: String,
+    sno: Option<String>,
+    topic: String,
+    period: Option<i32>,
+    #[serde(rename = "type")]
+    topic_type: Option<String>,
+}
+
+enum Outcome {
+    Created(i32),
+    Unchanged,
+}
+
+fn traverse_dir(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), std::io::Error> {
+    if dir.is_dir() {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_dir() {
+                traverse_dir(&path, files)?;
+            } else if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                files.push(path);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn normalize_title(title: &str) -> String {
+    title.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+async fn import_subject(
+    tx: &mut Transaction<'_, Postgres>,
+    place: &Placement,
+    curriculum: &CurriculumJson,
+    source: &serde_json::Value,
+    source_path: &str,
+) -> Result<Outcome, sqlx::Error> {
+    sqlx::query("INSERT INTO curriculum_regulations (code) VALUES ($1) ON CONFLICT (code) DO NOTHING")
+        .bind(&place.regulation)
+        .execute(&mut **tx)
+        .await?;
+
+    let current: Option<(Uuid, i32, bool)> = sqlx::query_as(
+        "SELECT id, revision, source = $4 FROM curriculum_subject_revisions
+         WHERE regulation = $1 AND branch_code = $2 AND subject_code = $3 AND is_current
+         FOR UPDATE"
+    )
+    .bind(&place.regulation)
+    .bind(&place.branch_code)
+    .bind(&curriculum.subject_code)
+    .bind(source)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    if let Some((_, _, true)) = current {
+        return Ok(Outcome::Unchanged);
+    }
+
+    // Topics of the revision being replaced, to carry their identities forward.
+    let previous: Vec<(String, String, Uuid)> = match current {
+        Some((id, _, _)) => {
+            sqlx::query("UPDATE curriculum_subject_revisions SET is_current = FALSE WHERE id = $1")
+                .bind(id)
+                .execute(&mut **tx)
+                .await?;
+            sqlx::query_as("SELECT topic_key, topic, topic_uid FROM curriculum_topics WHERE revision_id = $1")
+                .bind(id)
+                .fetch_all(&mut **tx)
+                .await?
+        }
+        None => Vec::new(),
+    };
+    let revision = current.map(|(_, r, _)| r + 1).unwrap_or(1);
+
+    let revision_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO curriculum_subject_revisions
+            (regulation, branch_code, semester, subject_code, subject_name, subject_type, total_periods, revision, source, source_path)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+         RETURNING id"
+    )
+    .bind(&place.regulation)
+    .bind(&place.branch_code)
+    .bind(place.semester)
+    .bind(&curriculum.subject_code)
+    .bind(&curriculum.subject_name)
+    .bind(place.subject_type)
+    .bind(curriculum.total_periods)
+    .bind(revision)
+    .bind(source)
+    .bind(source_path)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let mut claimed: HashSet<Uuid> = HashSet::new();
+    let mut order_index = 0;
+    for unit in &curriculum.units {
+        let unit_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO curriculum_units (revision_id, unit_no, title, total_periods) VALUES ($1, $2, $3, $4) RETURNING id"
+        )
+        .bind(revision_id)
+        .bind(unit.unit_no)
+        .bind(&unit.title)
+        .bind(unit.total_periods)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        for topic in &unit.topics {
+            order_index += 1;
+            // Same id first, then the same wording; anything else is a new topic.
+            let title = normalize_title(&topic.topic);
+            let carried = previous
+                .iter()
+                .find(|(key, _, uid)| key == &topic.id && !claimed.contains(uid))
+                .or_else(|| previous.iter().find(|(_, t, uid)| normalize_title(t) == title && !claimed.contains(uid)))
+                .map(|(_, _, uid)| *uid);
+            let topic_uid = match carried {
+                Some(uid) => uid,
+                None => {
+                    sqlx::query_scalar::<Postgres, Uuid>("INSERT INTO curriculum_topic_identities (subject_code) VALUES ($1) RETURNING id")
+                        .bind(&curriculum.subject_code)
+                        .fetch_one(&mut **tx)
+                        .await?
+                }
+            };
+            claimed.insert(topic_uid);
+
+            sqlx::query(
+                "INSERT INTO curriculum_topics (revision_id, unit_id, topic_uid, topic_key, sno, topic, periods, topic_type, order_index)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+            )
+            .bind(revision_id)
+            .bind(unit_id)
+            .bind(topic_uid)
+            .bind(&topic.id)
+            .bind(&topic.sno)
+            .bind(&topic.topic)
+            .bind(topic.period.unwrap_or(1))
+            .bind(topic.topic_type.clone().unwrap_or_else(|| "theory".to_string()).to_lowercase())
+            .bind(order_index)
+            .execute(&mut **tx)
+            .await?;
+        }
+    }
+
+    // Progress and feedback recorded before topics had identities are linked by their topic id.
+    for table in ["curriculum_progress", "student_curriculum_feedback"] {
+        sqlx::query(&format!(
+            "UPDATE {} x SET topic_uid = t.topic_uid
+             FROM curriculum_topics t
+             WHERE t.revision_id = $1 AND x.subject_code = $2 AND x.topic_id = t.topic_key AND x.topic_uid IS NULL",
+            table
+        ))
+        .bind(revision_id)
+        .bind(&curriculum.subject_code)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(Outcome::Created(revision))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let check_only = args.iter().any(|a| a == "--check");
+    let root = PathBuf::from(args.iter().find(|a| !a.starts_with("--")).map(|s| s.as_str()).unwrap_or("../frontend/assets/curriculum"));
+    if !root.exists() {
+        eprintln!("Curriculum directory not found: {:?}", root);
+        std::process::exit(1);
+    }
+
+    let mut json_files = Vec::new();
+    traverse_dir(&root, &mut json_files)?;
+    json_files.sort();
+    println!("Found {} curriculum JSON files.", json_files.len());
+
+    let mut parsed = Vec::new();
+    let mut subjects = Vec::new();
+    let mut problems = 0;
+    for path in json_files {
+        let content = fs::read_to_string(&path)?;
+        let report = curriculum_validator::check_file(&root, &path, &content);
+        let errors: Vec<_> = report.problems.iter().filter(|p| p.severity == Severity::Error).collect();
+        if !errors.is_empty() {
+            problems += 1;
+            for e in errors {
+                eprintln!("{}", e);
+            }
+            continue;
+        }
+        let (Some(source), Some(subject)) = (report.document, report.subject) else { continue };
+        match serde_json::from_value::<CurriculumJson>(source.clone()) {
+            Ok(curriculum) => {
+                parsed.push((path, (subject.placement.clone(), curriculum, source)));
+                subjects.push(subject);
+            }
+            Err(e) => {
+                problems += 1;
+                eprintln!("{:?}: {}", path, e);
+            }
+        }
+    }
+
+    // A subject defined twice for the same regulation and branch is imported from the first file only.
+    let clashes: HashSet<String> = curriculum_validator::check_across_files(&subjects)
+        .into_iter()
+        .filter(|p| p.severity == Severity::Error)
+        .map(|p| {
+            eprintln!("{}", p);
+            p.file
+        })
+        .collect();
+    let before = parsed.len();
+    parsed.retain(|(path, _)| !clashes.contains(path.strip_prefix(&root).unwrap_or(path).to_string_lossy().as_ref()));
+    problems += before - parsed.len();
+
+    if check_only {
+        println!("{} files ok, {} with problems.", parsed.len(), problems);
+        return Ok(());
+    }
+
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&database_url)
+        .await?;
+    println!("Connected to database.");
+
+    let (mut created, mut unchanged) = (0, 0);
+    for (path, (place, curriculum, source)) in parsed {
+        let source_path = path.strip_prefix(&root).unwrap_or(&path).to_string_lossy().into_owned();
+        let mut tx = pool.begin().await?;
+        match import_subject(&mut tx, &place, &curriculum, &source, &source_path).await {
+            Ok(Outcome::Created(revision)) => {
+                tx.commit().await?;
+                created += 1;
+                println!("{} {} ({}): revision {}", place.regulation, curriculum.subject_code, place.branch_code, revision);
+            }
+            Ok(Outcome::Unchanged) => {
+                tx.rollback().await?;
+                unchanged += 1;
+            }
+            Err(e) => {
+                tx.rollback().await?;
+                problems += 1;
+                eprintln!("Failed to import {:?}: {}", path, e);
+            }
+        }
+    }
+
+    println!("{} revisions written, {} unchanged, {} files skipped.", created, unchanged, problems);
+    Ok(())
+}
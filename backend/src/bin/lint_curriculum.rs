@@ -0,0 +1,109 @@
+use backend::curriculum_validator::{self, Problem, RegisteredSubject, Severity};
+use sqlx::postgres::PgPoolOptions;
+use dotenvy::dotenv;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Lints curriculum JSON before deployment.
+//
+//   cargo run --bin lint_curriculum -- [curriculum dir] [--offline] [--strict]
+//
+// Prints every problem as `file: json path: level: message` and exits with status 1 when there
+// are errors (or, with --strict, warnings). Unless --offline is given, subjects are also checked
+// against the subjects table in DATABASE_URL.
+
+fn traverse_dir(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), std::io::Error> {
+    if dir.is_dir() {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_dir() {
+                traverse_dir(&path, files)?;
+            } else if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                files.push(path);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn registered_subjects(database_url: &str) -> Result<Vec<RegisteredSubject>, sqlx::Error> {
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(database_url)
+        .await?;
+    let rows: Vec<(String, String, String, String)> = sqlx::query_as("SELECT id, name, semester, type FROM subjects")
+        .fetch_all(&pool)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, name, semester, subject_type)| RegisteredSubject { id, name, semester, subject_type })
+        .collect())
+}
+
+#[tokio::main]
+async fn main() {
+    dotenv().ok();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let offline = args.iter().any(|a| a == "--offline");
+    let strict = args.iter().any(|a| a == "--strict");
+    let root = PathBuf::from(args.iter().find(|a| !a.starts_with("--")).map(|s| s.as_str()).unwrap_or("../frontend/assets/curriculum"));
+    if !root.is_dir() {
+        eprintln!("Curriculum directory not found: {:?}", root);
+        std::process::exit(1);
+    }
+
+    let mut files = Vec::new();
+    if let Err(e) = traverse_dir(&root, &mut files) {
+        eprintln!("Failed to read {:?}: {}", root, e);
+        std::process::exit(1);
+    }
+    files.sort();
+
+    let mut problems: Vec<Problem> = Vec::new();
+    let mut subjects = Vec::new();
+    for path in &files {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                let report = curriculum_validator::check_file(&root, path, &content);
+                problems.extend(report.problems);
+                subjects.extend(report.subject);
+            }
+            Err(e) => problems.push(Problem {
+                file: path.to_string_lossy().into_owned(),
+                path: "$".to_string(),
+                severity: Severity::Error,
+                message: format!("unreadable: {}", e),
+            }),
+        }
+    }
+    problems.extend(curriculum_validator::check_across_files(&subjects));
+
+    if !offline {
+        match env::var("DATABASE_URL") {
+            Ok(url) => match registered_subjects(&url).await {
+                Ok(registered) => problems.extend(curriculum_validator::check_against_registered(&subjects, &registered)),
+                Err(e) => {
+                    eprintln!("Could not read the subjects table ({}); use --offline to skip this check.", e);
+                    std::process::exit(1);
+                }
+            },
+            Err(_) => {
+                eprintln!("DATABASE_URL is not set; use --offline to skip the subjects table check.");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    for p in &problems {
+        println!("{}", p);
+    }
+    let errors = problems.iter().filter(|p| p.severity == Severity::Error).count();
+    let warnings = problems.len() - errors;
+    println!("{} files checked: {} errors, {} warnings.", files.len(), errors, warnings);
+
+    if errors > 0 || (strict && warnings > 0) {
+        std::process::exit(1);
+    }
+}
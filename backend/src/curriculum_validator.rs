@@ -0,0 +1,559 @@
+//! Checks the hand-edited curriculum JSON under `frontend/assets/curriculum` before it is imported.
+//!
+//! Files live at `{regulation}/{branch}/semester N/[theory|practical/]{subjectCode}.json`. Every
+//! problem is reported with its file and a JSON path such as `$.units[2].topics[5].period`, so one
+//! run lists everything to fix instead of stopping at the first deserialization error.
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+pub const TOPIC_TYPES: [&str; 6] = ["theory", "practical", "revision", "assessment", "problem solving", "practice"];
+pub const BRANCH_CODES: [&str; 6] = ["cme", "eee", "ece", "mech", "civ", "aiml"];
+pub const MAX_SEMESTER: i64 = 6;
+
+const ROOT_FIELDS: [&str; 6] = ["subjectCode", "subjectName", "regulation", "semester", "totalPeriods", "units"];
+const UNIT_FIELDS: [&str; 4] = ["unitNo", "title", "totalPeriods", "topics"];
+const TOPIC_FIELDS: [&str; 5] = ["id", "sno", "topic", "period", "type"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub file: String,
+    pub path: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {}: {}: {}", self.file, self.path, level, self.message)
+    }
+}
+
+/// Where a file sits in the tree, which the JSON itself does not fully say.
+#[derive(Debug, Clone)]
+pub struct Placement {
+    pub regulation: String,
+    pub branch_code: String,
+    pub semester: i32,
+    pub subject_type: &'static str, // THEORY, PRACTICAL
+}
+
+/// A file that passed without errors, for the checks that look across files.
+#[derive(Debug, Clone)]
+pub struct SubjectFile {
+    pub file: String,
+    pub subject_code: String,
+    pub subject_name: String,
+    pub placement: Placement,
+}
+
+pub struct FileReport {
+    pub problems: Vec<Problem>,
+    /// The parsed document with any byte-order mark removed, when it parsed.
+    pub document: Option<Value>,
+    /// Set only when the file has no errors.
+    pub subject: Option<SubjectFile>,
+}
+
+impl FileReport {
+    pub fn has_errors(&self) -> bool {
+        self.problems.iter().any(|p| p.severity == Severity::Error)
+    }
+}
+
+/// A row of the `subjects` table.
+#[derive(Debug, Clone)]
+pub struct RegisteredSubject {
+    pub id: String,
+    pub name: String,
+    pub semester: String, // "Semester 3"
+    pub subject_type: String,
+}
+
+struct Findings<'a> {
+    file: &'a str,
+    problems: Vec<Problem>,
+}
+
+impl<'a> Findings<'a> {
+    fn push(&mut self, severity: Severity, path: &str, message: String) {
+        self.problems.push(Problem { file: self.file.to_string(), path: path.to_string(), severity, message });
+    }
+
+    fn error(&mut self, path: &str, message: impl Into<String>) {
+        self.push(Severity::Error, path, message.into());
+    }
+
+    fn warn(&mut self, path: &str, message: impl Into<String>) {
+        self.push(Severity::Warning, path, message.into());
+    }
+
+    fn unknown_fields(&mut self, obj: &Map<String, Value>, known: &[&str], path: &str) {
+        for key in obj.keys().filter(|k| !known.contains(&k.as_str())) {
+            self.warn(&format!("{}.{}", path, key), "unknown field");
+        }
+    }
+
+    /// A required non-empty string.
+    fn text<'v>(&mut self, obj: &'v Map<String, Value>, key: &str, path: &str) -> Option<&'v str> {
+        let at = format!("{}.{}", path, key);
+        match obj.get(key) {
+            None => self.error(&at, "missing"),
+            Some(Value::String(s)) if s.trim().is_empty() => self.error(&at, "is empty"),
+            Some(Value::String(s)) => return Some(s),
+            Some(other) => self.error(&at, format!("expected a string, found {}", kind(other))),
+        }
+        None
+    }
+
+    /// A required whole number no smaller than `min`.
+    fn int(&mut self, obj: &Map<String, Value>, key: &str, path: &str, min: i64) -> Option<i64> {
+        let at = format!("{}.{}", path, key);
+        match obj.get(key) {
+            None => self.error(&at, "missing"),
+            Some(v) => match v.as_i64() {
+                Some(n) if n < min => self.error(&at, format!("must be at least {}, found {}", min, n)),
+                Some(n) => return Some(n),
+                None => self.error(&at, format!("expected a whole number, found {}", kind(v))),
+            },
+        }
+        None
+    }
+
+    fn array<'v>(&mut self, obj: &'v Map<String, Value>, key: &str, path: &str) -> Option<&'v Vec<Value>> {
+        let at = format!("{}.{}", path, key);
+        match obj.get(key) {
+            None => self.error(&at, "missing"),
+            Some(Value::Array(a)) => return Some(a),
+            Some(other) => self.error(&at, format!("expected an array, found {}", kind(other))),
+        }
+        None
+    }
+}
+
+fn kind(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// C-23, c23 and C23 are the same regulation.
+pub fn normalize_regulation(raw: &str) -> String {
+    raw.trim().replace('-', "").to_uppercase()
+}
+
+fn normalize_text(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Structure and arithmetic of one document: required fields and their types, unique unit
+/// numbers and topic ids, known topic types, and period totals that add up.
+pub fn check_document(file: &str, doc: &Value) -> Vec<Problem> {
+    let mut f = Findings { file, problems: Vec::new() };
+    let Some(root) = doc.as_object() else {
+        f.error("$", format!("expected an object, found {}", kind(doc)));
+        return f.problems;
+    };
+    f.unknown_fields(root, &ROOT_FIELDS, "$");
+
+    f.text(root, "subjectCode", "$");
+    f.text(root, "subjectName", "$");
+    if let Some(reg) = f.text(root, "regulation", "$") {
+        let norm = normalize_regulation(reg);
+        if norm.len() != 3 || !norm.starts_with('C') || !norm[1..].chars().all(|c| c.is_ascii_digit()) {
+            f.error("$.regulation", format!("'{}' is not a regulation like C23", reg));
+        }
+    }
+    match f.int(root, "semester", "$", 0) {
+        Some(0) => f.warn("$.semester", "is 0; the semester folder is used instead"),
+        Some(n) if n > MAX_SEMESTER => f.error("$.semester", format!("must be 1-{}, found {}", MAX_SEMESTER, n)),
+        _ => {}
+    }
+    let total = f.int(root, "totalPeriods", "$", 0);
+    let Some(units) = f.array(root, "units", "$") else { return f.problems };
+    if units.is_empty() {
+        f.warn("$.units", "no units");
+    }
+
+    let mut unit_numbers: HashMap<i64, String> = HashMap::new();
+    let mut topic_ids: HashMap<String, String> = HashMap::new();
+    let mut unit_sum = 0;
+    let mut unit_totals_known = true;
+    for (i, unit) in units.iter().enumerate() {
+        let path = format!("$.units[{}]", i);
+        let Some(u) = unit.as_object() else {
+            f.error(&path, format!("expected an object, found {}", kind(unit)));
+            unit_totals_known = false;
+            continue;
+        };
+        f.unknown_fields(u, &UNIT_FIELDS, &path);
+
+        if let Some(no) = f.int(u, "unitNo", &path, 1) {
+            if let Some(first) = unit_numbers.insert(no, path.clone()) {
+                f.error(&format!("{}.unitNo", path), format!("unit {} is already used at {}", no, first));
+            } else if no != i as i64 + 1 {
+                f.warn(&format!("{}.unitNo", path), format!("is {} at position {}", no, i + 1));
+            }
+        }
+        f.text(u, "title", &path);
+        let unit_total = f.int(u, "totalPeriods", &path, 0);
+        match unit_total {
+            Some(n) => unit_sum += n,
+            None => unit_totals_known = false,
+        }
+        let Some(topics) = f.array(u, "topics", &path) else { continue };
+        if topics.is_empty() {
+            f.warn(&format!("{}.topics", path), "no topics");
+        }
+
+        let mut topic_sum = 0;
+        let mut periods_known = true;
+        for (j, topic) in topics.iter().enumerate() {
+            let tpath = format!("{}.topics[{}]", path, j);
+            let Some(t) = topic.as_object() else {
+                f.error(&tpath, format!("expected an object, found {}", kind(topic)));
+                periods_known = false;
+                continue;
+            };
+            f.unknown_fields(t, &TOPIC_FIELDS, &tpath);
+
+            if let Some(id) = f.text(t, "id", &tpath) {
+                if let Some(first) = topic_ids.insert(id.to_string(), tpath.clone()) {
+                    f.error(&format!("{}.id", tpath), format!("'{}' is already used at {}", id, first));
+                }
+            }
+            if !t.contains_key("sno") {
+                f.warn(&format!("{}.sno", tpath), "missing");
+            } else if !t["sno"].is_string() {
+                f.error(&format!("{}.sno", tpath), format!("expected a string, found {}", kind(&t["sno"])));
+            }
+            f.text(t, "topic", &tpath);
+            match f.int(t, "period", &tpath, 0) {
+                Some(p) => topic_sum += p,
+                None => periods_known = false,
+            }
+            if let Some(ty) = f.text(t, "type", &tpath) {
+                if !TOPIC_TYPES.contains(&ty.to_lowercase().as_str()) {
+                    f.error(&format!("{}.type", tpath), format!("unknown type '{}'; expected one of {}", ty, TOPIC_TYPES.join(", ")));
+                }
+            }
+        }
+
+        if let (Some(expected), true) = (unit_total, periods_known) {
+            if expected != topic_sum {
+                f.error(&format!("{}.totalPeriods", path), format!("is {} but the topic periods sum to {}", expected, topic_sum));
+            }
+        }
+    }
+
+    // A unit without a usable total has already been reported; comparing would only add noise.
+    if let (Some(expected), true) = (total, unit_totals_known) {
+        if expected != unit_sum {
+            f.error("$.totalPeriods", format!("is {} but the unit totals sum to {}", expected, unit_sum));
+        }
+    }
+    f.problems
+}
+
+/// Checks the file's folders against its contents and works out its placement.
+fn check_placement(f: &mut Findings, root: &Path, path: &Path, doc: &Value) -> Option<Placement> {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+        .collect();
+    if parts.len() < 4 {
+        f.error("$", "expected {regulation}/{branch}/semester N/[theory|practical/]{subjectCode}.json");
+        return None;
+    }
+
+    let regulation = normalize_regulation(&parts[0]);
+    if let Some(declared) = doc["regulation"].as_str() {
+        if normalize_regulation(declared) != regulation {
+            f.error("$.regulation", format!("'{}' does not match the {} folder", declared, parts[0]));
+        }
+    }
+    if !BRANCH_CODES.contains(&parts[1].as_str()) {
+        f.warn("$", format!("branch folder '{}' is not one of {}", parts[1], BRANCH_CODES.join(", ")));
+    }
+    let semester = match parts[2].replace("%20", " ").trim_start_matches("semester").trim().parse::<i32>() {
+        Ok(s) => s,
+        Err(_) => {
+            f.error("$", format!("'{}' is not a semester folder", parts[2]));
+            return None;
+        }
+    };
+    if let Some(declared) = doc["semester"].as_i64() {
+        if declared != 0 && declared != semester as i64 {
+            f.error("$.semester", format!("is {} but the file is in '{}'", declared, parts[2]));
+        }
+    }
+
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let code = doc["subjectCode"].as_str().unwrap_or_default();
+    if !code.is_empty() && code != stem {
+        f.error("$.subjectCode", format!("'{}' does not match the file name {}.json", code, stem));
+    }
+
+    // C23 keeps theory/ and practical/ folders; C26 encodes the type in the code's last letter.
+    let subject_type = match parts.get(3).map(|p| p.as_str()) {
+        Some("theory") if parts.len() > 4 => "THEORY",
+        Some("practical") if parts.len() > 4 => "PRACTICAL",
+        _ => {
+            let suffix = code.chars().last().unwrap_or('T').to_ascii_uppercase();
+            if ['L', 'D', 'P', 'C'].contains(&suffix) { "PRACTICAL" } else { "THEORY" }
+        }
+    };
+
+    Some(Placement { regulation, branch_code: parts[1].clone(), semester, subject_type })
+}
+
+/// Everything that can be said about one file on its own.
+pub fn check_file(root: &Path, path: &Path, content: &str) -> FileReport {
+    let file = path.strip_prefix(root).unwrap_or(path).to_string_lossy().into_owned();
+    let mut f = Findings { file: &file, problems: Vec::new() };
+
+    let body = match content.strip_prefix('\u{feff}') {
+        Some(rest) => {
+            f.warn("$", "starts with a byte-order mark");
+            rest
+        }
+        None => content,
+    };
+    let doc: Value = match serde_json::from_str(body) {
+        Ok(d) => d,
+        Err(e) => {
+            f.error("$", format!("invalid JSON at line {} column {}: {}", e.line(), e.column(), e));
+            return FileReport { problems: f.problems, document: None, subject: None };
+        }
+    };
+
+    f.problems.extend(check_document(&file, &doc));
+    let placement = if doc.is_object() { check_placement(&mut f, root, path, &doc) } else { None };
+
+    let mut report = FileReport { problems: f.problems, document: None, subject: None };
+    if let (Some(placement), false) = (placement, report.has_errors()) {
+        report.subject = Some(SubjectFile {
+            file: file.clone(),
+            subject_code: doc["subjectCode"].as_str().unwrap_or_default().to_string(),
+            subject_name: doc["subjectName"].as_str().unwrap_or_default().to_string(),
+            placement,
+        });
+    }
+    report.document = Some(doc);
+    report
+}
+
+/// A subject defined twice for the same regulation and branch, or shared across branches under
+/// different names or semesters.
+pub fn check_across_files(subjects: &[SubjectFile]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut seen: HashMap<(String, String, String), &SubjectFile> = HashMap::new();
+    let mut by_code: HashMap<(String, String), &SubjectFile> = HashMap::new();
+    for s in subjects {
+        let p = &s.placement;
+        let key = (p.regulation.clone(), p.branch_code.clone(), s.subject_code.clone());
+        if let Some(first) = seen.insert(key, s) {
+            problems.push(Problem {
+                file: s.file.clone(),
+                path: "$.subjectCode".to_string(),
+                severity: Severity::Error,
+                message: format!("{} is also defined for {} {} in {}", s.subject_code, p.regulation, p.branch_code, first.file),
+            });
+            continue;
+        }
+
+        let Some(other) = by_code.get(&(p.regulation.clone(), s.subject_code.clone())) else {
+            by_code.insert((p.regulation.clone(), s.subject_code.clone()), s);
+            continue;
+        };
+        if normalize_text(&other.subject_name) != normalize_text(&s.subject_name) {
+            problems.push(Problem {
+                file: s.file.clone(),
+                path: "$.subjectName".to_string(),
+                severity: Severity::Warning,
+                message: format!("'{}' differs from '{}' in {}", s.subject_name, other.subject_name, other.file),
+            });
+        }
+        if other.placement.semester != p.semester {
+            problems.push(Problem {
+                file: s.file.clone(),
+                path: "$.semester".to_string(),
+                severity: Severity::Warning,
+                message: format!("semester {} differs from semester {} in {}", p.semester, other.placement.semester, other.file),
+            });
+        }
+    }
+    problems
+}
+
+/// Curriculum files against the `subjects` table: a different semester is an error, a missing
+/// row or a different name or type a warning.
+pub fn check_against_registered(subjects: &[SubjectFile], registered: &[RegisteredSubject]) -> Vec<Problem> {
+    let by_id: HashMap<&str, &RegisteredSubject> = registered.iter().map(|r| (r.id.as_str(), r)).collect();
+    let mut problems = Vec::new();
+    let mut problem = |s: &SubjectFile, path: &str, severity: Severity, message: String| {
+        problems.push(Problem { file: s.file.clone(), path: path.to_string(), severity, message });
+    };
+
+    for s in subjects {
+        let Some(row) = by_id.get(s.subject_code.as_str()) else {
+            problem(s, "$.subjectCode", Severity::Warning, format!("{} is not in the subjects table", s.subject_code));
+            continue;
+        };
+        let semester = row.semester.chars().filter(|c| c.is_ascii_digit()).collect::<String>().parse::<i32>().ok();
+        if semester.is_some() && semester != Some(s.placement.semester) {
+            problem(s, "$.semester", Severity::Error, format!("is semester {} but the subjects table says '{}'", s.placement.semester, row.semester));
+        }
+        if normalize_text(&row.name) != normalize_text(&s.subject_name) {
+            problem(s, "$.subjectName", Severity::Warning, format!("'{}' differs from '{}' in the subjects table", s.subject_name, row.name));
+        }
+        if !row.subject_type.to_uppercase().contains(s.placement.subject_type) {
+            problem(s, "$", Severity::Warning, format!("is {} but the subjects table says '{}'", s.placement.subject_type, row.subject_type));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn topic(id: &str, period: i64, ty: &str) -> Value {
+        json!({ "id": id, "sno": "1", "topic": format!("Topic {}", id), "period": period, "type": ty })
+    }
+
+    fn document(units: Vec<Value>) -> Value {
+        let total: i64 = units.iter().filter_map(|u| u["totalPeriods"].as_i64()).sum();
+        json!({
+            "subjectCode": "CM-301",
+            "subjectName": "Data Structures",
+            "regulation": "C23",
+            "semester": 3,
+            "totalPeriods": total,
+            "units": units,
+        })
+    }
+
+    fn unit(no: i64, total_periods: i64, topics: Vec<Value>) -> Value {
+        json!({ "unitNo": no, "title": format!("Unit {}", no), "totalPeriods": total_periods, "topics": topics })
+    }
+
+    fn errors(problems: &[Problem]) -> Vec<(&str, &str)> {
+        problems.iter().filter(|p| p.severity == Severity::Error).map(|p| (p.path.as_str(), p.message.as_str())).collect()
+    }
+
+    fn subject(file: &str, branch_code: &str, code: &str, name: &str, semester: i32) -> SubjectFile {
+        SubjectFile {
+            file: file.to_string(),
+            subject_code: code.to_string(),
+            subject_name: name.to_string(),
+            placement: Placement { regulation: "C23".to_string(), branch_code: branch_code.to_string(), semester, subject_type: "THEORY" },
+        }
+    }
+
+    #[test]
+    fn accepts_a_well_formed_document() {
+        let doc = document(vec![
+            unit(1, 5, vec![topic("1.1", 2, "theory"), topic("1.2", 3, "Problem Solving")]),
+            unit(2, 4, vec![topic("2.1", 4, "revision")]),
+        ]);
+        assert!(errors(&check_document("ds.json", &doc)).is_empty());
+    }
+
+    #[test]
+    fn reports_a_duplicate_topic_id_where_it_repeats() {
+        let doc = document(vec![
+            unit(1, 2, vec![topic("1.1", 2, "theory")]),
+            unit(2, 3, vec![topic("1.1", 3, "theory")]),
+        ]);
+        let problems = check_document("ds.json", &doc);
+        let errors = errors(&problems);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].0, "$.units[1].topics[0].id");
+        assert!(errors[0].1.contains("$.units[0].topics[0]"));
+    }
+
+    #[test]
+    fn reports_a_unit_total_that_does_not_match_its_topics() {
+        let doc = document(vec![unit(1, 6, vec![topic("1.1", 2, "theory"), topic("1.2", 3, "theory")])]);
+        let problems = check_document("ds.json", &doc);
+        assert_eq!(errors(&problems), vec![("$.units[0].totalPeriods", "is 6 but the topic periods sum to 5")]);
+    }
+
+    #[test]
+    fn reports_an_unknown_topic_type() {
+        let doc = document(vec![unit(1, 2, vec![topic("1.1", 2, "lecture")])]);
+        let problems = check_document("ds.json", &doc);
+        let errors = errors(&problems);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].0, "$.units[0].topics[0].type");
+        assert!(errors[0].1.starts_with("unknown type 'lecture'"));
+    }
+
+    #[test]
+    fn skips_the_subject_total_when_a_unit_total_is_missing() {
+        let mut doc = document(vec![
+            unit(1, 2, vec![topic("1.1", 2, "theory")]),
+            unit(2, 3, vec![topic("2.1", 3, "theory")]),
+        ]);
+        doc["units"][1].as_object_mut().unwrap().remove("totalPeriods");
+        doc["totalPeriods"] = json!(5);
+        let problems = check_document("ds.json", &doc);
+        assert_eq!(errors(&problems), vec![("$.units[1].totalPeriods", "missing")]);
+    }
+
+    #[test]
+    fn reports_a_subject_defined_twice_for_a_branch_and_differences_across_branches() {
+        let subjects = vec![
+            subject("C23/cme/semester 3/CM-301.json", "cme", "CM-301", "Data Structures", 3),
+            subject("C23/cme/semester 3/theory/CM-301.json", "cme", "CM-301", "Data Structures", 3),
+            subject("C23/aiml/semester 4/CM-301.json", "aiml", "CM-301", "Data  structures and Algorithms", 4),
+        ];
+        let problems = check_across_files(&subjects);
+
+        let clash: Vec<&Problem> = problems.iter().filter(|p| p.severity == Severity::Error).collect();
+        assert_eq!(clash.len(), 1, "{:?}", problems);
+        assert_eq!(clash[0].file, "C23/cme/semester 3/theory/CM-301.json");
+        assert!(clash[0].message.contains("C23/cme/semester 3/CM-301.json"));
+
+        let mut warned: Vec<&str> = problems.iter().filter(|p| p.severity == Severity::Warning).map(|p| p.path.as_str()).collect();
+        warned.sort();
+        assert_eq!(warned, vec!["$.semester", "$.subjectName"]);
+    }
+
+    #[test]
+    fn checks_files_against_the_subjects_table() {
+        let subjects = vec![
+            subject("a.json", "cme", "CM-301", "Data Structures", 3),
+            subject("b.json", "cme", "CM-302", "Operating Systems", 3),
+        ];
+        let registered = vec![RegisteredSubject {
+            id: "CM-301".to_string(),
+            name: "data structures".to_string(),
+            semester: "Semester 4".to_string(),
+            subject_type: "Theory".to_string(),
+        }];
+        let problems = check_against_registered(&subjects, &registered);
+        let found: Vec<(&str, &str, Severity)> = problems.iter().map(|p| (p.file.as_str(), p.path.as_str(), p.severity)).collect();
+        assert_eq!(found, vec![("a.json", "$.semester", Severity::Error), ("b.json", "$.subjectCode", Severity::Warning)]);
+    }
+}
@@ -135,24 +135,14 @@ pub async fn init_db() -> Pool<Postgres> {
     let _ = sqlx::query("ALTER TABLE faculty_subjects ADD PRIMARY KEY (user_id, subject_id, section)")
         .execute(&pool).await.err();
 
-    // FORCE FIX SCHEMA - LESSON PLAN PROGRESS
-    let _ = sqlx::query("
-        CREATE TABLE IF NOT EXISTS lesson_plan_progress (
-            item_id TEXT REFERENCES lesson_plan_items(id) ON DELETE CASCADE,
-            section VARCHAR(50),
-            completed BOOLEAN DEFAULT FALSE,
-            completed_date TIMESTAMPTZ,
-            PRIMARY KEY (item_id, section)
-        )
-    ").execute(&pool).await.err();
-
     // FORCE FIX SCHEMA - CURRICULUM INTEGRATION
+    // Lesson plan progress lives here too (lesson_plan_item_id); lesson_plan_progress is a view.
     let _ = sqlx::query("
         CREATE TABLE IF NOT EXISTS curriculum_progress (
             id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
             topic_id TEXT NOT NULL,
             subject_code TEXT NOT NULL,
-            faculty_id UUID NOT NULL REFERENCES users(id),
+            faculty_id UUID REFERENCES users(id),
             branch TEXT NOT NULL,
             section VARCHAR(50) NOT NULL,
             year VARCHAR(50) NOT NULL,
@@ -161,6 +151,7 @@ pub async fn init_db() -> Pool<Postgres> {
             completed_date DATE,
             status TEXT DEFAULT 'pending',
             remarks TEXT,
+            lesson_plan_item_id TEXT REFERENCES lesson_plan_items(id) ON DELETE CASCADE,
             created_at TIMESTAMPTZ DEFAULT NOW(),
             updated_at TIMESTAMPTZ DEFAULT NOW(),
             UNIQUE(topic_id, subject_code, branch, section, year, semester)
@@ -265,6 +256,9 @@ pub async fn init_db() -> Pool<Postgres> {
     
     let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_parent_requests_parent_id ON parent_requests(parent_id)").execute(&pool).await.err();
     let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_parent_requests_student_id ON parent_requests(student_id)").execute(&pool).await.err();
+    // Parent leave applications are mirrored into parent_requests.
+    let _ = sqlx::query("ALTER TABLE leave_applications ADD CONSTRAINT leave_applications_parent_request_id_fkey FOREIGN KEY (parent_request_id) REFERENCES parent_requests(id) ON DELETE SET NULL")
+        .execute(&pool).await.err();
 
     // STUDENT MARKS TABLE
     let _ = sqlx::query("
@@ -298,6 +292,15 @@ pub async fn init_db() -> Pool<Postgres> {
             UNIQUE(branch, year, section, day, period_index) 
         )
     ").execute(&pool).await.err();
+    let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_timetable_entries_faculty_slot ON timetable_entries (faculty_id, day, period_index)")
+        .execute(&pool).await.err();
+    let _ = sqlx::query("ALTER TABLE timetable_entries ADD COLUMN IF NOT EXISTS room TEXT")
+        .execute(&pool).await.err();
+    let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_timetable_entries_room_slot ON timetable_entries (room, day, period_index)")
+        .execute(&pool).await.err();
+    // Register labs that so far only exist as 'Lab' grid rows.
+    let _ = sqlx::query("INSERT INTO rooms (code, name, room_type, department) SELECT DISTINCT section, section, 'LAB', branch FROM timetable_entries WHERE year = 'Lab' ON CONFLICT (code) DO NOTHING")
+        .execute(&pool).await.err();
     
     // COURSES TABLE
     let _ = sqlx::query("
@@ -331,6 +334,8 @@ pub async fn init_db() -> Pool<Postgres> {
             UNIQUE(branch, year, section, day, period_index, status_date)
         )
     ").execute(&pool).await.err();
+    let _ = sqlx::query("CREATE INDEX IF NOT EXISTS idx_class_period_status_branch_date ON class_period_status(branch, status_date)")
+        .execute(&pool).await.err();
 
     // LESSON TOPICS TABLE
     let _ = sqlx::query("
@@ -0,0 +1,3 @@
+//! Code shared by the server and the command-line tools in `src/bin`.
+
+pub mod curriculum_validator;
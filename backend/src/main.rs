@@ -1,4 +1,5 @@
 use axum::{
+    extract::DefaultBodyLimit,
     routing::{get, post, delete},
     Router,
 };
@@ -41,6 +42,12 @@ async fn main() {
     
     let pool = db::connection::init_db().await;
 
+    tokio::spawn(services::analytics_service::run_summary_refresh(pool.clone()));
+    tokio::spawn(services::timetable_service::run_version_activation(pool.clone()));
+    tokio::spawn(services::syllabus_forecast_service::run_weekly_forecasts(pool.clone()));
+    let (live_status, _) = tokio::sync::broadcast::channel(256);
+    tokio::spawn(services::management::incharge_service::run_unattended_alerts(pool.clone(), live_status.clone()));
+
     // --- MULTIPLEXING SETUP ---
     let grpc_pool = pool.clone();
     let auth_service = MyAuthService { pool: grpc_pool };
@@ -110,6 +117,24 @@ async fn main() {
         .route("/api/attendance/class-record", get(faculty::get_class_attendance_record_handler))
         .route("/api/attendance/stats", get(faculty::get_attendance_stats_handler))
         .route("/api/attendance/absents", get(faculty::get_absent_students_handler))
+        .route("/api/attendance/lock-status", get(attendance::get_lock_status_handler))
+        .route("/api/attendance/edit-window", post(attendance::update_edit_window_handler))
+        .route("/api/attendance/history", get(attendance::get_attendance_history_handler))
+        .route("/api/attendance/sync", post(attendance::sync_attendance_handler))
+        .route("/api/attendance/biometric/mappings", post(attendance::save_biometric_mappings_handler))
+        .route("/api/attendance/biometric/punches", post(attendance::ingest_biometric_handler))
+        .route("/api/attendance/biometric/import", post(attendance::import_biometric_csv_handler))
+        .route("/api/attendance/biometric/conflicts", get(attendance::get_biometric_conflicts_handler))
+        .route("/api/attendance/biometric/conflicts/resolve", post(attendance::resolve_biometric_conflict_handler))
+        .route("/api/leave/apply", post(leave::apply_leave_handler))
+        .route("/api/leave/applications", get(leave::get_leave_applications_handler))
+        .route("/api/leave/review", post(leave::review_leave_handler))
+        .route("/api/leave/cancel", post(leave::cancel_leave_handler))
+        .route("/api/analytics/attendance/trends", get(analytics::get_attendance_trends_handler))
+        .route("/api/analytics/attendance/heatmap", get(analytics::get_attendance_heatmap_handler))
+        .route("/api/analytics/attendance/absence-streaks", get(analytics::get_absence_streaks_handler))
+        .route("/api/analytics/attendance/faculty-comparison", get(analytics::get_faculty_comparison_handler))
+        .route("/api/analytics/attendance/refresh", post(analytics::refresh_attendance_summary_handler))
         .route("/api/hod/approve", post(faculty::approve_handler))
         .route("/api/hod/approve-subject", post(faculty::approve_subject_handler))
         .route("/api/hod/approve-profile-change", post(faculty::approve_profile_change_handler))
@@ -117,6 +142,48 @@ async fn main() {
         .route("/api/timetable", get(faculty::get_timetable_handler))
         .route("/api/timetable/assign", post(faculty::assign_class_handler))
         .route("/api/timetable/clear", post(faculty::clear_class_handler))
+        .route("/api/timetable/generate", post(timetable::generate_timetable_handler))
+        .route("/api/timetable/drafts", get(timetable::get_timetable_drafts_handler))
+        .route("/api/timetable/drafts/:id", get(timetable::get_timetable_draft_handler))
+        .route("/api/timetable/drafts/:id/publish", post(timetable::publish_timetable_draft_handler))
+        .route("/api/timetable/drafts/:id/discard", post(timetable::discard_timetable_draft_handler))
+        .route("/api/timetable/versions", get(timetable::get_timetable_versions_handler).post(timetable::create_timetable_version_handler))
+        .route("/api/timetable/versions/diff", get(timetable::diff_timetable_versions_handler))
+        .route("/api/timetable/versions/:id", get(timetable::get_timetable_version_handler))
+        .route("/api/timetable/versions/:id/entries", post(timetable::set_timetable_version_entry_handler))
+        .route("/api/timetable/versions/:id/clone", post(timetable::clone_timetable_version_handler))
+        .route("/api/timetable/versions/:id/publish", post(timetable::publish_timetable_version_handler))
+        .route("/api/calendar/feeds", get(calendar_feed::get_calendar_feeds_handler).post(calendar_feed::create_calendar_feed_handler))
+        .route("/api/calendar/feeds/revoke", post(calendar_feed::revoke_calendar_feed_handler))
+        .route("/api/calendar/ics/:token", get(calendar_feed::calendar_ics_handler))
+        .route("/api/academic-calendar/years", get(academic_calendar::get_academic_years_handler).post(academic_calendar::create_academic_year_handler))
+        .route("/api/academic-calendar/semesters", get(academic_calendar::get_semesters_handler).post(academic_calendar::create_semester_handler))
+        .route("/api/academic-calendar/events", get(academic_calendar::get_calendar_events_handler).post(academic_calendar::create_calendar_event_handler))
+        .route("/api/academic-calendar/events/delete", post(academic_calendar::delete_calendar_event_handler))
+        .route("/api/academic-calendar/days", get(academic_calendar::get_calendar_days_handler))
+        .route("/api/rooms", get(room::get_rooms_handler).post(room::save_room_handler))
+        .route("/api/rooms/free", get(room::get_free_rooms_handler))
+        .route("/api/rooms/utilization", get(room::get_room_utilization_handler))
+        .route("/api/rooms/relocate", post(room::relocate_class_handler))
+        .route("/api/rooms/relocations", get(room::get_relocations_handler))
+        .route("/api/workload/report", get(workload::workload_report_handler))
+        .route("/api/workload/norms", get(workload::get_workload_norms_handler).post(workload::save_workload_norms_handler))
+        .route("/api/department/slot-templates", get(slot_template::get_slot_templates_handler).post(slot_template::save_slot_template_handler))
+        .route("/api/department/slot-templates/preview", get(slot_template::get_slot_template_preview_handler).post(slot_template::preview_slot_template_handler))
+        .route("/api/lesson-plans", get(lesson_plan::get_lesson_plans_handler).post(lesson_plan::generate_lesson_plan_handler))
+        .route("/api/syllabus/forecasts", get(syllabus_forecast::get_syllabus_forecasts_handler).post(syllabus_forecast::refresh_syllabus_forecasts_handler))
+        .route("/api/outcomes/program", get(outcome::get_program_outcomes_handler).post(outcome::save_program_outcome_handler))
+        .route("/api/outcomes/program/delete", post(outcome::delete_program_outcome_handler))
+        .route("/api/outcomes/course", get(outcome::get_course_outcomes_handler).post(outcome::save_course_outcome_handler))
+        .route("/api/outcomes/course/delete", post(outcome::delete_course_outcome_handler))
+        .route("/api/outcomes/assessments", get(outcome::get_assessment_components_handler).post(outcome::save_assessment_component_handler))
+        .route("/api/outcomes/assessments/delete", post(outcome::delete_assessment_component_handler))
+        .route("/api/outcomes/assessments/marks", get(outcome::get_assessment_marks_handler).post(outcome::save_assessment_marks_handler))
+        .route("/api/outcomes/attainment", get(outcome::subject_attainment_handler))
+        .route("/api/outcomes/program-attainment", get(outcome::program_attainment_handler))
+        .route("/api/regulations/equivalences", get(regulation::get_equivalences_handler).post(regulation::save_equivalence_handler))
+        .route("/api/regulations/equivalences/delete", post(regulation::delete_equivalence_handler))
+        .route("/api/regulations/students", get(regulation::get_student_regulation_handler).post(regulation::assign_regulation_handler))
         .route("/api/department/timing", get(faculty::get_department_timings))
         .route("/api/department/timing", post(faculty::update_department_timings))
         .route("/api/faculty/hod-courses", get(faculty::get_courses_handler))
@@ -154,8 +221,12 @@ async fn main() {
         .route("/api/hod/year-sections-progress", get(hod::get_year_sections_progress_handler))
         .route("/api/hod/section-subjects-progress", get(hod::get_section_subjects_progress_handler))
         .route("/api/incharge/timetable-lookup", get(incharge::incharge_timetable_lookup_handler))
+        .route("/api/incharge/substitutes/recommend", get(substitution::recommend_substitutes_handler))
+        .route("/api/incharge/substitutes/plan", post(substitution::plan_substitutes_handler))
         .route("/api/incharge/update-status", post(incharge::update_class_status_handler))
         .route("/api/incharge/class-status", get(incharge::get_section_class_status_handler))
+        .route("/api/incharge/class-status/stream", get(incharge::class_status_stream_handler))
+        .route("/api/incharge/class-status/unattended", get(incharge::get_unattended_periods_handler))
         .route("/api/hod/daily-activity-report", get(incharge::get_daily_activity_report_handler))
         .route("/api/incharge/branch-daily-detail-report", get(incharge::get_branch_daily_detail_report_handler))
         .route("/api/staff/all", get(hod::get_all_staff_handler))
@@ -164,6 +235,32 @@ async fn main() {
         .route("/api/curriculum/merged", get(curriculum::get_merged_curriculum_handler))
         .route("/api/curriculum/progress", post(curriculum::update_progress_handler))
         .route("/api/curriculum/feedback", post(curriculum::submit_feedback_handler))
+        .route("/api/curriculum/feedback/analytics", get(topic_feedback::get_feedback_analytics_handler))
+        .route("/api/curriculum/feedback/faculty-analytics", get(topic_feedback::get_faculty_feedback_analytics_handler))
+        .route("/api/curriculum/feedback/inbox", get(topic_feedback::get_feedback_inbox_handler))
+        .route("/api/curriculum/feedback/revisions", get(topic_feedback::get_revisions_handler).post(topic_feedback::schedule_revision_handler))
+        .route("/api/curriculum/feedback/revisions/status", post(topic_feedback::update_revision_handler))
+        .route("/api/curriculum/feedback/resolve", post(topic_feedback::resolve_feedback_handler))
+        .route("/api/curriculum/feedback/mine", get(topic_feedback::get_my_feedback_handler))
+        .route("/api/curriculum/regulations", get(curriculum::get_curriculum_regulations_handler))
+        .route("/api/curriculum/subjects", get(curriculum::get_curriculum_subjects_handler))
+        .route("/api/curriculum/revisions", get(curriculum::get_curriculum_revisions_handler))
+        // Course Material Routes
+        .route("/api/materials", get(material::get_materials_handler))
+        .route("/api/materials/upload", post(material::upload_material_handler).layer(DefaultBodyLimit::max(services::material_service::MAX_UPLOAD_BYTES)))
+        .route("/api/materials/link", post(material::save_link_handler))
+        .route("/api/materials/visibility", post(material::update_visibility_handler))
+        .route("/api/materials/archive", post(material::archive_material_handler))
+        .route("/api/materials/versions", get(material::get_versions_handler))
+        .route("/api/materials/download", get(material::download_material_handler))
+        .route("/api/materials/analytics", get(material::get_material_analytics_handler))
+        // Syllabus Coverage Report Routes
+        .route("/api/syllabus/coverage-reports", get(coverage::get_coverage_reports_handler))
+        .route("/api/syllabus/coverage-reports/detail", get(coverage::get_coverage_report_handler))
+        .route("/api/syllabus/coverage-reports/generate", post(coverage::generate_coverage_report_handler))
+        .route("/api/syllabus/coverage-reports/sign", post(coverage::sign_coverage_report_handler))
+        .route("/api/syllabus/coverage-reports/review", post(coverage::review_coverage_report_handler))
+        .route("/api/syllabus/coverage-reports/pdf", get(coverage::coverage_report_pdf_handler))
         // Chat / ERP Connect Messenger Routes
         .route("/api/chat/search", get(chat::search_user_handler))
         .route("/api/chat/requests", post(chat::send_request_handler).get(chat::get_requests_handler))
@@ -192,7 +289,7 @@ async fn main() {
         .route("/api/finance/accountants", get(finance::get_accountants_handler).post(finance::create_accountant_handler))
         .route("/api/finance/accountants/performance", get(finance::get_accountant_performance_handler))
         .route("/api/finance/work-assignments", get(finance::get_work_assignments_handler).post(finance::assign_work_handler))
-        .with_state(AppState { pool })
+        .with_state(AppState { pool, live_status })
 
         .nest_service("/web", tower_http::services::ServeDir::new("static"))
         .fallback(move |req: axum::extract::Request| {
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::NaiveDate;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAcademicYearRequest {
+    pub year_name: String, // e.g. 2026-27
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AcademicYear {
+    pub id: i32,
+    pub year_name: String,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSemesterRequest {
+    pub academic_year_id: i32,
+    pub term: String, // ODD, EVEN
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub saturdays_working: Option<bool>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AcademicSemester {
+    pub id: Uuid,
+    pub academic_year_id: i32,
+    pub year_name: String,
+    pub term: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub saturdays_working: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCalendarEventRequest {
+    pub event_type: String, // HOLIDAY, WORKING_SATURDAY, EXAM
+    pub title: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub branch: Option<String>,
+    pub year: Option<String>,
+    pub created_by: String,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+    pub id: Uuid,
+    pub event_type: String,
+    pub title: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub branch: Option<String>,
+    pub year: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteCalendarEventRequest {
+    pub id: Uuid,
+    pub deleted_by: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarRangeQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub branch: Option<String>,
+    pub year: Option<String>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub weekday: String,
+    pub instructional: bool,
+    pub events: Vec<String>,
+}
+
+/// The semester a date falls in, with the first calendar year of its academic year.
+#[derive(Debug, FromRow)]
+pub struct TermOnDate {
+    pub start_year: i32,
+    pub term: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemesterQuery {
+    pub academic_year_id: Option<i32>,
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::NaiveDate;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceAnalyticsQuery {
+    pub branch: String,
+    pub year: Option<String>,
+    pub section: Option<String>,
+    pub from: Option<String>, // YYYY-MM-DD, defaults to 30 days ago
+    pub to: Option<String>,   // YYYY-MM-DD, defaults to today
+    pub granularity: Option<String>, // daily (default), weekly
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceTrendPoint {
+    pub bucket: NaiveDate,
+    pub year: String,
+    pub section: String,
+    pub present: i64,
+    pub absent: i64,
+    pub on_leave: i64,
+    pub percentage: f64,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekdayHeatmapCell {
+    pub day_of_week: String,
+    pub session: String,
+    pub present: i64,
+    pub absent: i64,
+    pub percentage: f64,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodHeatmapCell {
+    pub day: String,
+    pub period_index: i32,
+    pub recorded: i64,
+    pub conducted: i64,
+    pub substitute: i64,
+    pub not_conducted: i64,
+    pub conducted_rate: f64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceHeatmap {
+    pub by_weekday: Vec<WeekdayHeatmapCell>,
+    pub by_period: Vec<PeriodHeatmapCell>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbsenceStreakQuery {
+    pub branch: String,
+    pub year: Option<String>,
+    pub section: Option<String>,
+    pub min_days: Option<i64>,
+    pub from: Option<String>,
+    pub ongoing_only: Option<bool>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AbsenceStreak {
+    pub student_login_id: String,
+    pub full_name: String,
+    pub year: Option<String>,
+    pub section: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub days: i64,
+    pub ongoing: bool,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FacultyPeriodComparison {
+    pub faculty: String,
+    pub scheduled: i64,
+    pub conducted: i64,
+    pub not_conducted: i64,
+    pub substituted_out: i64,
+    pub substitutions_taken: i64,
+    pub conducted_rate: f64,
+    pub sessions_marked: i64,
+    pub attendance_rate: Option<f64>,
+}
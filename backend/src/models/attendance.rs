@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc, NaiveDate, NaiveDateTime};
+
+// --- Edit Window / Locking ---
+
+#[derive(Deserialize)]
+pub struct AttendanceLockQuery {
+    pub branch: String,
+    pub date: String, // YYYY-MM-DD
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceLockStatus {
+    pub branch: String,
+    pub date: NaiveDate,
+    pub window_hours: i32,
+    pub locks_at: DateTime<Utc>,
+    pub locked: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateEditWindowRequest {
+    pub branch: String,
+    pub window_hours: i32,
+}
+
+// --- Versioned History ---
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceHistoryQuery {
+    pub student_id: String,
+    pub date: Option<String>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceHistoryEntry {
+    pub id: Uuid,
+    pub attendance_id: Option<Uuid>,
+    pub student_login_id: String,
+    pub date: NaiveDate,
+    pub session: String,
+    pub section: Option<String>,
+    pub version: i32,
+    pub previous_status: Option<String>,
+    pub new_status: String,
+    pub changed_by: Option<Uuid>,
+    pub changed_by_name: Option<String>,
+    pub change_source: String,
+    pub reason: Option<String>,
+    pub correction_request_id: Option<Uuid>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// A single attendance write, carrying the audit details recorded alongside it.
+pub struct AttendanceWrite<'a> {
+    pub student_uuid: Uuid,
+    pub student_login_id: &'a str,
+    pub faculty_uuid: Uuid,
+    pub date: &'a str,
+    pub status: &'a str,
+    pub session: &'a str,
+    pub section: &'a str,
+    pub change_source: &'a str,
+    pub reason: Option<&'a str>,
+    pub correction_request_id: Option<Uuid>,
+}
+
+// --- Offline Sync ---
+
+/// Queued `BatchAttendanceRequest`s from an offline device. Each batch must carry its
+/// `clientBatchId` and `deviceTimestamp` and each record its `clientRecordId`, so replays
+/// can be deduplicated.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceSyncRequest {
+    pub device_id: String,
+    pub batches: Vec<crate::models::BatchAttendanceRequest>,
+}
+
+#[derive(Serialize, Debug, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRecordResult {
+    pub client_record_id: Uuid,
+    pub client_batch_id: Uuid,
+    pub student_id: String,
+    pub result: String, // ACCEPTED, REJECTED, CONFLICT
+    pub message: Option<String>,
+    pub existing_status: Option<String>,
+    pub existing_marked_by: Option<String>,
+    /// When the mark this record lost to was last changed on the server.
+    pub existing_marked_at: Option<DateTime<Utc>>,
+    #[sqlx(default)]
+    pub duplicate: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceSyncResponse {
+    pub accepted: usize,
+    pub rejected: usize,
+    pub conflicts: usize,
+    pub duplicates: usize,
+    pub results: Vec<SyncRecordResult>,
+}
+
+// --- Biometric / RFID Ingestion ---
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricPunch {
+    pub device_user_code: String,
+    pub punched_at: NaiveDateTime, // Device local time
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricIngestRequest {
+    pub device_id: String,
+    pub ingested_by: String,
+    pub punches: Vec<BiometricPunch>,
+}
+
+/// Query parameters for the CSV importer; the punch log itself is the request body.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricImportQuery {
+    pub device_id: String,
+    pub ingested_by: String,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricIngestSummary {
+    pub received: usize,
+    pub stored: usize,
+    pub duplicates: usize,
+    pub outside_sessions: usize,
+    pub applied: usize,
+    pub matched: usize,
+    pub conflicts: usize,
+    pub locked: usize,
+    pub unmapped_codes: Vec<String>,
+    pub invalid_lines: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricUserMapping {
+    pub device_user_code: String,
+    pub login_id: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricMappingRequest {
+    pub mappings: Vec<BiometricUserMapping>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricConflictQuery {
+    pub branch: String,
+    pub date: Option<String>,
+    pub section: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricConflict {
+    pub id: Uuid,
+    pub student_uuid: Uuid,
+    pub student_login_id: String,
+    pub student_name: Option<String>,
+    pub branch: Option<String>,
+    pub section: Option<String>,
+    pub date: NaiveDate,
+    pub session: String,
+    pub first_punch: NaiveDateTime,
+    pub manual_status: String,
+    pub manual_marked_by: Option<Uuid>,
+    pub manual_marked_by_name: Option<String>,
+    pub status: String,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveBiometricConflictRequest {
+    pub conflict_id: Uuid,
+    pub action: String, // ACCEPT_PUNCH, KEEP_MANUAL
+    pub resolved_by: String,
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// `scope` is PERSONAL (default: a faculty member's own periods, or a student's section) or
+/// SECTION (the given class, for staff following a section).
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFeedRequest {
+    pub user_id: String,
+    pub scope: Option<String>,
+    pub branch: Option<String>,
+    pub year: Option<String>,
+    pub section: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedListQuery {
+    pub user_id: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeFeedRequest {
+    pub user_id: String,
+    pub feed_id: Uuid,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarFeed {
+    pub id: Uuid,
+    pub token: String,
+    pub scope: String,
+    pub branch: Option<String>,
+    pub year: Option<String>,
+    pub section: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_accessed_at: Option<DateTime<Utc>>,
+    #[sqlx(skip)]
+    pub url: String,
+}
+
+/// What a feed covers: one faculty member's periods, or one class.
+#[derive(Debug, Default)]
+pub struct FeedTarget {
+    pub faculty_id: Option<String>,
+    pub branch: Option<String>,
+    pub year: Option<String>,
+    pub section: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct FeedOwner {
+    pub feed_id: Uuid,
+    pub login_id: String,
+    pub role: String,
+    pub scope: String,
+    pub user_branch: Option<String>,
+    pub user_year: Option<String>,
+    pub user_section: Option<String>,
+    pub branch: Option<String>,
+    pub year: Option<String>,
+    pub section: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct FeedClass {
+    pub date: NaiveDate,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub period_index: i32,
+    pub subject: String,
+    pub faculty_id: String,
+    pub faculty_name: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct FeedAnnouncement {
+    pub id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub announcement_type: String,
+    pub audience: Vec<String>,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+}
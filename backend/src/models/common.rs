@@ -7,6 +7,8 @@ use chrono::{DateTime, Utc};
 #[derive(Clone)]
 pub struct AppState {
     pub pool: Pool<Postgres>,
+    /// Class status changes and unattended-period alerts for the live incharge board.
+    pub live_status: tokio::sync::broadcast::Sender<crate::models::live_status::LiveClassEvent>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -111,6 +113,7 @@ pub struct TimetableEntry {
     pub period_index: i32,
     pub subject: String,
     pub subject_code: Option<String>,
+    pub room: Option<String>,
     pub faculty_name: Option<String>,
     pub faculty_email: Option<String>,
     pub faculty_phone: Option<String>,
@@ -130,6 +133,14 @@ pub struct AssignClassRequest {
     pub subject: String,
     #[serde(rename = "subjectCode")]
     pub subject_code: Option<String>,
+    /// Lab the class is held in. Lab grid rows (`year = "Lab"`) use their section as the room.
+    #[serde(default)]
+    pub room: Option<String>,
+    /// Save even when clash checks fail. Requires `forcedBy`.
+    #[serde(default)]
+    pub force: bool,
+    #[serde(rename = "forcedBy", default)]
+    pub forced_by: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, FromRow)]
@@ -144,6 +155,9 @@ pub struct DepartmentTiming {
     pub short_code: Option<String>,
     #[sqlx(default)]
     pub slot_config: Option<serde_json::Value>, 
+    /// The `WeekSchedule` of the slot template in effect, when the department has one.
+    #[sqlx(default)]
+    pub slot_template: Option<serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -561,6 +575,8 @@ pub struct MarkCompleteRequest {
     pub item_id: String,
     pub completed: bool,
     pub section: Option<String>,
+    #[serde(rename = "facultyId")]
+    pub faculty_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, FromRow, Clone)]
@@ -686,6 +702,7 @@ pub struct SubmitAttendanceRequest {
     pub date: String, // YYYY-MM-DD
     pub status: String, 
     pub session: Option<String>,
+    pub reason: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, FromRow, Clone)]
@@ -703,6 +720,8 @@ pub struct AttendanceSummary {
     pub total_classes: i64,
     pub present_count: i64,
     pub absent_count: i64,
+    #[serde(default)]
+    pub leave_count: i64, // Excused; not counted in total_classes
     pub percentage: f64,
     pub history: Vec<AttendanceRecord>,
 }
@@ -757,14 +776,22 @@ pub struct BatchAttendanceRequest {
     pub section: String,
     #[serde(rename = "markedBy")]
     pub marked_by: String, 
-    pub records: Vec<BatchRecord>
+    pub records: Vec<BatchRecord>,
+    pub reason: Option<String>,
+    // Offline devices send these so a replayed upload can be recognised; see `AttendanceSyncRequest`.
+    #[serde(rename = "clientBatchId", default)]
+    pub client_batch_id: Option<Uuid>,
+    #[serde(rename = "deviceTimestamp", default)]
+    pub device_timestamp: Option<DateTime<Utc>>,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct BatchRecord {
     #[serde(rename = "studentId")]
     pub student_id: String, 
-    pub status: String
+    pub status: String,
+    #[serde(rename = "clientRecordId", default)]
+    pub client_record_id: Option<Uuid>,
 }
 
 #[derive(Deserialize)]
@@ -808,6 +835,8 @@ pub struct ApproveAttendanceCorrectionData {
     #[serde(rename = "notificationId")]
     pub notification_id: Option<Uuid>,
     pub action: String, // "APPROVE" or "REJECT"
+    #[serde(rename = "approvedBy")]
+    pub approved_by: Option<String>,
 }
 
 #[derive(Serialize, FromRow)]
@@ -1073,6 +1102,8 @@ pub struct InchargeTimetableLookupQuery {
     pub section: String,
     pub day: String,
     pub period_index: i32,
+    /// YYYY-MM-DD. Resolves the timetable version in effect on that date instead of the live grid.
+    pub date: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1120,6 +1151,7 @@ pub struct DailyClassActivityReport {
     pub conducted: i64,
     pub substitute: i64,
     pub not_conducted: i64,
+    pub working_day: bool, // false on holidays, exam blocks and non-working Saturdays/Sundays
 }
 
 #[derive(Deserialize)]
@@ -1137,6 +1169,9 @@ pub struct SubjectMarkResponse {
     pub credit: i32,
     pub grade: Option<String>,
     pub grade_points: Option<i32>,
+    /// Set when the mark was earned under another regulation for an equivalent subject,
+    /// e.g. "23CS101 (C-23)".
+    pub transferred_from: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -1145,6 +1180,7 @@ pub struct SemesterAcademicsResponse {
     pub semester_name: String,
     pub year_label: String,
     pub is_ongoing: bool,
+    pub regulation: String,
     pub subjects: Vec<SubjectMarkResponse>,
     pub sgpa: Option<f64>,
 }
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageReportQuery {
+    pub branch: String,
+    pub year: Option<String>,
+    pub section: Option<String>,
+    pub status: Option<String>,
+    pub faculty_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageReportIdQuery {
+    pub report_id: Uuid,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateCoverageRequest {
+    pub faculty_id: String,
+    pub subject_code: String,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub semester: i32,
+    pub regulation: Option<String>,
+    /// The calendar semester the report closes; the one running today when omitted.
+    pub academic_semester_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SignCoverageRequest {
+    pub report_id: Uuid,
+    pub faculty_id: String,
+    pub statement: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewCoverageRequest {
+    pub report_id: Uuid,
+    pub hod_id: String,
+    pub approve: bool,
+    pub note: Option<String>, // required when returning the report
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageSummary {
+    pub total_topics: i64,
+    pub completed: i64,
+    pub pending: i64,
+    pub completed_on_time: i64,
+    pub completed_late: i64,
+    /// Completed without ever being given a date.
+    pub completed_unplanned: i64,
+    /// Pending past their assigned date.
+    pub overdue: i64,
+    pub coverage_percent: f64,
+    /// Mean days completed after (positive) or before (negative) the assigned date.
+    pub average_deviation_days: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageTopic {
+    pub unit_no: i32,
+    pub sno: String,
+    pub topic_id: String,
+    pub topic: String,
+    pub status: String,
+    pub assigned_date: Option<NaiveDate>,
+    pub completed_date: Option<NaiveDate>,
+    pub deviation_days: Option<i64>,
+    pub remarks: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageAuditEntry {
+    pub topic_id: String,
+    pub topic_uid: Option<Uuid>,
+    pub action: String,
+    pub changed_by: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// The report as generated; stored with it and rendered into the PDF.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageSnapshot {
+    pub subject_code: String,
+    pub subject_name: String,
+    pub regulation: String,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub semester: i32,
+    pub term: String,
+    pub faculty_login: String,
+    pub faculty_name: String,
+    pub generated_at: DateTime<Utc>,
+    pub summary: CoverageSummary,
+    pub topics: Vec<CoverageTopic>,
+    pub audit: Vec<CoverageAuditEntry>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageReport {
+    pub id: Uuid,
+    pub subject_code: String,
+    pub subject_name: String,
+    pub regulation: String,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub semester: i32,
+    pub academic_semester_id: Uuid,
+    pub faculty_login: String,
+    pub faculty_name: String,
+    pub status: String, // DRAFT, FACULTY_SIGNED, RETURNED, FINALIZED
+    pub coverage_percent: Option<f64>,
+    pub faculty_statement: Option<String>,
+    pub faculty_signed_at: Option<DateTime<Utc>>,
+    pub hod_login: Option<String>,
+    pub hod_name: Option<String>,
+    pub hod_note: Option<String>,
+    pub hod_signed_at: Option<DateTime<Utc>>,
+    pub generated_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// SHA-256 of the archived PDF, once finalized.
+    pub archive_sha256: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageReportDetail {
+    #[serde(flatten)]
+    pub report: CoverageReport,
+    pub snapshot: CoverageSnapshot,
+}
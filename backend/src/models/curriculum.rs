@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use sqlx::FromRow;
 use chrono::{DateTime, Utc, NaiveDate};
+use crate::models::material::CourseMaterial;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -12,6 +13,12 @@ pub struct CurriculumJson {
     pub semester: i32,
     pub total_periods: i32,
     pub units: Vec<CurriculumUnit>,
+    // Set when loaded from the database
+    #[serde(skip_deserializing)]
+    pub revision: Option<i32>,
+    /// Materials shared for the subject as a whole.
+    #[serde(skip_deserializing)]
+    pub materials: Vec<CourseMaterial>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -21,6 +28,9 @@ pub struct CurriculumUnit {
     pub title: String,
     pub total_periods: i32,
     pub topics: Vec<CurriculumTopic>,
+    /// Materials shared for the unit rather than one of its topics.
+    #[serde(skip_deserializing)]
+    pub materials: Vec<CourseMaterial>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -32,6 +42,9 @@ pub struct CurriculumTopic {
     pub period: i32,
     #[serde(rename = "type")]
     pub topic_type: String,
+    /// Identity of the topic across curriculum revisions.
+    #[serde(skip_deserializing)]
+    pub uid: Option<Uuid>,
     // These will be populated from DB during merge
     #[serde(skip_deserializing)]
     pub status: Option<String>,
@@ -45,6 +58,8 @@ pub struct CurriculumTopic {
     pub feedback_count: Option<i32>,
     #[serde(skip_deserializing)]
     pub understood_percentage: Option<f64>,
+    #[serde(skip_deserializing)]
+    pub materials: Vec<CourseMaterial>,
 }
 
 #[derive(Debug, FromRow, Serialize, Deserialize)]
@@ -52,7 +67,7 @@ pub struct CurriculumProgressRow {
     pub id: Uuid,
     pub topic_id: String,
     pub subject_code: String,
-    pub faculty_id: Uuid,
+    pub faculty_id: Option<Uuid>, // NULL for progress carried over from lesson plans
     pub branch: String,
     pub section: String,
     pub year: String,
@@ -61,6 +76,38 @@ pub struct CurriculumProgressRow {
     pub completed_date: Option<NaiveDate>,
     pub status: Option<String>,
     pub remarks: Option<String>,
+    #[sqlx(default)]
+    pub topic_uid: Option<Uuid>,
+    #[sqlx(default)]
+    pub date_from_plan: bool,
+}
+
+/// A section's progress through a subject, counted over its lesson plan items when it has a
+/// lesson plan and over its curriculum topics otherwise.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SectionProgress {
+    pub total: i64,
+    pub completed: i64,
+    /// Topics due by today.
+    pub scheduled: i64,
+    pub has_schedule: bool,
+}
+
+impl SectionProgress {
+    pub fn percentage(&self) -> i32 {
+        if self.total > 0 { (self.completed * 100 / self.total) as i32 } else { 0 }
+    }
+
+    /// Lagging or Overfast against the schedule; On Track when there is none.
+    pub fn status(&self) -> &'static str {
+        if !self.has_schedule || self.completed == self.scheduled {
+            "On Track"
+        } else if self.completed < self.scheduled {
+            "Lagging"
+        } else {
+            "Overfast"
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -103,3 +150,63 @@ pub struct HodCurriculumAnalytics {
     pub pending_topics: usize,
     pub last_updated: Option<DateTime<Utc>>,
 }
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CurriculumRegulation {
+    pub code: String,
+    pub branches: i64,
+    pub subjects: i64,
+}
+
+/// One imported version of a subject's syllabus.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CurriculumRevision {
+    pub id: Uuid,
+    pub regulation: String,
+    pub branch_code: String,
+    pub semester: i32,
+    pub subject_code: String,
+    pub subject_name: String,
+    pub subject_type: String, // THEORY, PRACTICAL
+    pub total_periods: i32,
+    pub revision: i32,
+    pub is_current: bool,
+    pub imported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct CurriculumUnitRow {
+    pub id: Uuid,
+    pub unit_no: i32,
+    pub title: String,
+    pub total_periods: i32,
+}
+
+#[derive(Debug, FromRow)]
+pub struct CurriculumTopicRow {
+    pub unit_id: Uuid,
+    pub topic_uid: Uuid,
+    pub topic_key: String,
+    pub sno: Option<String>,
+    pub topic: String,
+    pub periods: i32,
+    pub topic_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurriculumSubjectsQuery {
+    pub regulation: Option<String>,
+    pub branch: String,
+    pub semester: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurriculumRevisionsQuery {
+    pub regulation: Option<String>,
+    pub branch: String,
+    pub subject_code: String,
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc, NaiveDate};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyLeaveRequest {
+    pub applicant_id: String,
+    pub student_id: Option<String>, // Required when a parent applies
+    pub leave_type: Option<String>,
+    pub from_date: NaiveDate,
+    pub from_session: Option<String>,
+    pub to_date: NaiveDate,
+    pub to_session: Option<String>,
+    pub reason: String,
+    pub route_to: Option<String>, // FACULTY (default), HOD
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaveApplicationQuery {
+    pub student_id: Option<String>,
+    pub reviewer_id: Option<String>,
+    pub branch: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaveApplication {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub student_login_id: Option<String>,
+    pub student_name: Option<String>,
+    pub branch: Option<String>,
+    pub section: Option<String>,
+    pub applied_by: Uuid,
+    pub applicant_role: String,
+    pub parent_request_id: Option<Uuid>,
+    pub leave_type: String,
+    pub from_date: NaiveDate,
+    pub from_session: String,
+    pub to_date: NaiveDate,
+    pub to_session: String,
+    pub reason: String,
+    pub status: String,
+    pub assigned_to: Option<Uuid>,
+    pub assigned_name: Option<String>,
+    pub reviewed_by: Option<Uuid>,
+    pub review_note: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewLeaveRequest {
+    pub leave_id: Uuid,
+    pub reviewer_id: String,
+    pub action: String, // APPROVE, REJECT
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelLeaveRequest {
+    pub leave_id: Uuid,
+    pub applicant_id: String,
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateLessonPlanRequest {
+    pub subject_code: String,
+    pub regulation: Option<String>,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub semester: i32,
+    /// Defaults to the semester covering today.
+    pub academic_semester_id: Option<Uuid>,
+    pub generated_by: String,
+    /// Also replaces topic dates set by hand; by default they are kept.
+    #[serde(default)]
+    pub overwrite_dates: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LessonPlanQuery {
+    pub branch: String,
+    pub year: Option<String>,
+    pub section: Option<String>,
+    pub at_risk: Option<bool>,
+}
+
+#[derive(Serialize, Debug, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LessonPlan {
+    pub id: Uuid,
+    pub subject_code: String,
+    pub regulation: String,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub semester: i32,
+    pub academic_semester_id: Uuid,
+    pub faculty_id: Uuid,
+    pub plan_start: NaiveDate,
+    pub exam_start: Option<NaiveDate>,
+    pub weekly_periods: i32,
+    pub required_periods: i32,
+    pub available_periods: i32,
+    pub lost_periods: i32,
+    pub projected_finish: Option<NaiveDate>,
+    pub at_risk: bool,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// A weekly timetable slot of the subject in the section.
+#[derive(Debug, FromRow)]
+pub struct SubjectSlot {
+    pub day: String,
+    pub period_index: i32,
+    pub faculty_id: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedTopic {
+    pub topic_id: String,
+    pub topic_uid: Option<Uuid>,
+    pub unit_no: i32,
+    pub topic: String,
+    pub periods: i32,
+    pub status: String,
+    /// First period of the topic; `None` when the semester has no period left for it.
+    pub assigned_date: Option<NaiveDate>,
+    pub after_exam_start: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LessonPlanDetail {
+    pub plan: LessonPlan,
+    pub subject_name: String,
+    pub topics: Vec<PlannedTopic>,
+}
+
+/// The subject and section a plan is generated for.
+#[derive(Debug, Clone)]
+pub struct PlanTarget {
+    pub subject_code: String,
+    pub regulation: String,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub semester: i32,
+    pub academic_semester_id: Uuid,
+    pub faculty_id: Uuid,
+}
+
+#[derive(Debug)]
+pub struct PlanSummary {
+    pub plan_start: NaiveDate,
+    pub exam_start: Option<NaiveDate>,
+    pub weekly_periods: i32,
+    pub required_periods: i32,
+    pub available_periods: i32,
+    pub lost_periods: i32,
+    pub projected_finish: Option<NaiveDate>,
+    pub at_risk: bool,
+}
+
+/// The periods a plan can use over its semester.
+#[derive(Debug)]
+pub struct PlanCalendar {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub exam_start: Option<NaiveDate>,
+    /// One entry per period still available, in order.
+    pub dates: Vec<NaiveDate>,
+    pub lost_periods: i32,
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveStatusQuery {
+    pub branch: String,
+    pub year: Option<String>,
+    pub section: Option<String>,
+}
+
+impl LiveStatusQuery {
+    /// Whether the event concerns the watched branch and, when given, year and section.
+    pub fn matches(&self, event: &LiveClassEvent) -> bool {
+        let (branch, year, section) = event.class();
+        branch == self.branch
+            && self.year.as_deref().map(|y| y == year).unwrap_or(true)
+            && self.section.as_deref().map(|s| s == section).unwrap_or(true)
+    }
+}
+
+/// A period status as `update_class_status` just wrote it.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassStatusChange {
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub day: String,
+    pub period_index: i32,
+    pub status_date: NaiveDate,
+    pub status: String,
+    pub original_subject: String,
+    pub original_faculty: String,
+    pub actual_subject: String,
+    pub actual_faculty: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A scheduled period that has been running for a while with no status recorded.
+#[derive(Serialize, Debug, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct UnattendedPeriod {
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub period_index: i32,
+    pub subject: String,
+    pub faculty_id: String,
+    pub faculty_name: Option<String>,
+    #[sqlx(skip)]
+    pub status_date: NaiveDate,
+    #[sqlx(skip)]
+    pub starts_at: String, // HH:MM campus time
+    #[sqlx(skip)]
+    pub minutes_elapsed: i64,
+}
+
+#[derive(Debug, Clone)]
+pub enum LiveClassEvent {
+    StatusChanged(ClassStatusChange),
+    Unattended(UnattendedPeriod),
+}
+
+impl LiveClassEvent {
+    /// SSE event name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LiveClassEvent::StatusChanged(_) => "status",
+            LiveClassEvent::Unattended(_) => "unattended",
+        }
+    }
+
+    pub fn class(&self) -> (&str, &str, &str) {
+        match self {
+            LiveClassEvent::StatusChanged(c) => (&c.branch, &c.year, &c.section),
+            LiveClassEvent::Unattended(u) => (&u.branch, &u.year, &u.section),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        match self {
+            LiveClassEvent::StatusChanged(c) => serde_json::to_string(c),
+            LiveClassEvent::Unattended(u) => serde_json::to_string(u),
+        }
+        .unwrap_or_default()
+    }
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterialQuery {
+    pub subject_code: String,
+    pub branch: String,
+    pub section: Option<String>,
+    pub unit_no: Option<i32>,
+    pub topic_id: Option<String>,
+    pub include_archived: Option<bool>,
+}
+
+/// Metadata of a file upload; the file itself is the request body.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadMaterialQuery {
+    /// Uploads a new version of this material instead of creating one.
+    pub material_id: Option<Uuid>,
+    pub subject_code: Option<String>,
+    pub branch: Option<String>,
+    pub regulation: Option<String>,
+    pub unit_no: Option<i32>,
+    pub topic_id: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub sections: Option<String>, // comma separated; empty for every section
+    pub file_name: String,
+    pub change_note: Option<String>,
+    pub uploaded_by: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveLinkRequest {
+    /// Points this material at a new URL as its next version instead of creating one.
+    pub material_id: Option<Uuid>,
+    pub subject_code: Option<String>,
+    pub branch: Option<String>,
+    pub regulation: Option<String>,
+    pub unit_no: Option<i32>,
+    pub topic_id: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub sections: Option<Vec<String>>,
+    pub url: String,
+    pub change_note: Option<String>,
+    pub uploaded_by: String,
+}
+
+/// Where a new material is attached, checked against the subject's current curriculum.
+#[derive(Debug)]
+pub struct MaterialPlacement {
+    pub subject_code: String,
+    pub branch: String,
+    pub regulation: String,
+    pub unit_no: Option<i32>,
+    pub topic_id: Option<String>,
+    pub topic_uid: Option<Uuid>,
+    pub title: String,
+    pub description: Option<String>,
+    pub sections: Vec<String>,
+}
+
+/// The stored content of one version: a file in a storage backend or a link.
+#[derive(Debug)]
+pub struct MaterialContent {
+    pub storage_backend: Option<String>,
+    pub storage_key: Option<String>,
+    pub url: Option<String>,
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub change_note: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateVisibilityRequest {
+    pub material_id: Uuid,
+    pub sections: Vec<String>,
+    pub updated_by: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveMaterialRequest {
+    pub id: Uuid,
+    pub archived_by: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterialVersionsQuery {
+    pub material_id: Uuid,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadMaterialQuery {
+    pub material_id: Uuid,
+    pub version: Option<i32>, // current version when omitted
+    pub user_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterialAnalyticsQuery {
+    pub branch: String,
+    pub subject_code: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Serialize, Debug, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseMaterial {
+    pub id: Uuid,
+    pub subject_code: String,
+    pub branch: String,
+    pub regulation: String,
+    pub unit_no: Option<i32>,
+    pub topic_id: Option<String>,
+    pub topic_uid: Option<Uuid>,
+    pub title: String,
+    pub description: Option<String>,
+    pub kind: String, // FILE, LINK
+    pub sections: Vec<String>, // empty for every section
+    pub current_version: i32,
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub url: Option<String>, // links only
+    pub uploaded_by_login: String,
+    pub uploaded_by_name: String,
+    pub downloads: i64,
+    pub archived: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseMaterialVersion {
+    pub version: i32,
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub url: Option<String>,
+    pub change_note: Option<String>,
+    pub uploaded_by_name: String,
+    pub downloads: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a download request resolves to.
+#[derive(Debug, FromRow)]
+pub struct MaterialDownload {
+    pub kind: String,
+    pub version: i32,
+    pub storage_backend: Option<String>,
+    pub storage_key: Option<String>,
+    pub url: Option<String>,
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+    pub branch: String,
+    pub sections: Vec<String>,
+    pub archived: bool,
+}
+
+pub enum MaterialBody {
+    File { file_name: String, content_type: String, bytes: Vec<u8> },
+    Link(String),
+}
+
+/// Downloads of one material over the period, for the HOD.
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterialDownloadStats {
+    pub material_id: Uuid,
+    pub subject_code: String,
+    pub unit_no: Option<i32>,
+    pub topic_id: Option<String>,
+    pub title: String,
+    pub kind: String,
+    pub sections: Vec<String>,
+    pub uploaded_by_login: String,
+    pub uploaded_by_name: String,
+    pub downloads: i64,
+    pub students: i64,
+    pub last_downloaded_at: Option<DateTime<Utc>>,
+}
@@ -3,9 +3,27 @@ pub mod common;
 pub mod curriculum;
 pub mod chat;
 pub mod finance;
+pub mod attendance;
+pub mod leave;
+pub mod analytics;
+pub mod timetable;
+pub mod substitution;
+pub mod calendar_feed;
+pub mod academic_calendar;
 
 pub use auth::*;
 pub use common::*;
 pub use curriculum::*;
 pub use chat::*;
 
+pub mod room;
+pub mod workload;
+pub mod live_status;
+pub mod slot_template;
+pub mod lesson_plan;
+pub mod syllabus_forecast;
+pub mod outcome;
+pub mod regulation;
+pub mod topic_feedback;
+pub mod material;
+pub mod coverage;
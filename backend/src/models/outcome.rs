@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct ProgramOutcomeQuery {
+    pub branch: String,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramOutcome {
+    pub id: Uuid,
+    pub branch: String,
+    pub code: String,
+    pub kind: String, // PO, PSO
+    pub description: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveProgramOutcomeRequest {
+    pub branch: String,
+    pub code: String, // PO1.., PSO1..
+    pub description: String,
+    pub updated_by: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteOutcomeRequest {
+    pub id: Uuid,
+    pub deleted_by: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseOutcomeQuery {
+    pub subject_code: String,
+    pub regulation: Option<String>,
+}
+
+/// A course outcome with its row of the articulation matrix and the topics that teach it.
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseOutcome {
+    pub id: Uuid,
+    pub subject_code: String,
+    pub regulation: String,
+    pub code: String,
+    pub description: String,
+    pub po_levels: serde_json::Value, // [{ programOutcomeId, code, branch, level }]
+    pub topic_uids: Vec<Uuid>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PoLevel {
+    pub program_outcome_id: Uuid,
+    pub level: i16, // 1 low, 2 medium, 3 high
+}
+
+/// Saves a CO and replaces its PO levels and topic mappings.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveCourseOutcomeRequest {
+    pub subject_code: String,
+    pub regulation: Option<String>,
+    pub code: String, // CO1..
+    pub description: String,
+    #[serde(default)]
+    pub po_levels: Vec<PoLevel>,
+    #[serde(default)]
+    pub topic_uids: Vec<Uuid>,
+    pub updated_by: String,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AssessmentComponent {
+    pub id: Uuid,
+    pub subject_code: String,
+    pub regulation: String,
+    pub name: String,
+    pub max_marks: f64,
+    pub threshold_percent: f64,
+    pub course_outcomes: Vec<String>,
+    pub marked_students: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveAssessmentComponentRequest {
+    pub subject_code: String,
+    pub regulation: Option<String>,
+    pub name: String,
+    pub max_marks: f64,
+    pub threshold_percent: Option<f64>,
+    pub course_outcomes: Vec<String>, // CO codes
+    pub updated_by: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssessmentMarksQuery {
+    pub component_id: Uuid,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StudentMarkEntry {
+    pub student_id: String, // login id or user id
+    pub marks: f64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveAssessmentMarksRequest {
+    pub component_id: Uuid,
+    pub marks: Vec<StudentMarkEntry>,
+    pub entered_by: String,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AssessmentMark {
+    pub student_id: Uuid,
+    pub login_id: String,
+    pub full_name: String,
+    pub marks: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttainmentQuery {
+    pub subject_code: String,
+    pub regulation: Option<String>,
+    /// The programme whose POs form the matrix columns; students are counted from it.
+    pub branch: String,
+    pub section: Option<String>,
+    pub format: Option<String>, // json (default), csv, pdf
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramAttainmentQuery {
+    pub branch: String,
+    pub regulation: Option<String>,
+    pub format: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CoAttainment {
+    pub code: String,
+    pub description: String,
+    pub direct_percent: Option<f64>,
+    pub direct_level: Option<i32>,
+    pub indirect_percent: Option<f64>,
+    pub indirect_level: Option<i32>,
+    pub attainment: Option<f64>,
+    /// Mapping level per column of `SubjectAttainment::outcomes`.
+    pub po_levels: Vec<Option<i16>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectAttainment {
+    pub subject_code: String,
+    pub regulation: String,
+    pub branch: String,
+    pub section: Option<String>,
+    pub outcomes: Vec<String>, // PO and PSO codes, the matrix columns
+    pub course_outcomes: Vec<CoAttainment>,
+    pub mapping_average: Vec<Option<f64>>,
+    pub po_attainment: Vec<Option<f64>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectPoAttainment {
+    pub subject_code: String,
+    pub po_attainment: Vec<Option<f64>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramAttainment {
+    pub branch: String,
+    pub regulation: String,
+    pub outcomes: Vec<String>,
+    pub subjects: Vec<SubjectPoAttainment>,
+    pub average: Vec<Option<f64>>,
+}
+
+/// Students attaining one assessment component.
+#[derive(Debug, FromRow)]
+pub struct ComponentResult {
+    pub component_id: Uuid,
+    pub students: i64,
+    pub attained: i64,
+}
+
+/// Topic feedback of the students for one CO.
+#[derive(Debug, FromRow)]
+pub struct OutcomeFeedback {
+    pub course_outcome_id: Uuid,
+    pub responses: i64,
+    pub understood: i64,
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquivalenceQuery {
+    pub course_id: Option<String>, // either side, e.g. C-23
+    pub subject_code: Option<String>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectEquivalence {
+    pub id: Uuid,
+    pub from_course_id: String,
+    pub from_subject_code: String,
+    pub from_subject_name: Option<String>,
+    pub to_course_id: String,
+    pub to_subject_code: String,
+    pub to_subject_name: Option<String>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveEquivalenceRequest {
+    pub from_course_id: String,
+    pub from_subject_code: String,
+    pub to_course_id: String,
+    pub to_subject_code: String,
+    pub notes: Option<String>,
+    pub created_by: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteEquivalenceRequest {
+    pub id: Uuid,
+    pub deleted_by: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StudentRegulationQuery {
+    pub student_id: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StudentRegulation {
+    pub student_id: Uuid,
+    pub login_id: String,
+    pub course_id: String,
+    pub previous_course_id: Option<String>,
+    pub reason: Option<String>,
+    /// False when the student follows their batch's regulation.
+    pub assigned: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignRegulationRequest {
+    pub student_id: String,
+    pub course_id: String,
+    pub previous_course_id: Option<String>,
+    pub reason: String, // DETAINED, READMITTED, OTHER
+    pub assigned_by: String,
+}
+
+/// A mark a student earned under another regulation for a subject equivalent to one of theirs.
+#[derive(Debug, FromRow)]
+pub struct TransferredMark {
+    pub subject_code: String,
+    pub course_id: String,
+    pub marks: Option<i32>,
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveRoomRequest {
+    pub code: String,
+    pub name: Option<String>,
+    pub room_type: Option<String>, // CLASSROOM, LAB, SEMINAR_HALL
+    pub capacity: Option<i32>,
+    pub equipment: Option<Vec<String>>,
+    pub department: Option<String>,
+    pub status: Option<String>, // ACTIVE, MAINTENANCE, RETIRED
+    pub updated_by: String,
+}
+
+#[derive(Serialize, Debug, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Room {
+    pub id: Uuid,
+    pub code: String,
+    pub name: String,
+    pub room_type: String,
+    pub capacity: i32,
+    pub equipment: Vec<String>,
+    pub department: Option<String>,
+    pub status: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomListQuery {
+    pub department: Option<String>,
+    pub room_type: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FreeRoomQuery {
+    pub day: String,
+    pub period_index: i32,
+    /// Counts one-day relocations into and out of rooms on this date.
+    pub date: Option<NaiveDate>,
+    pub room_type: Option<String>,
+    pub min_capacity: Option<i32>,
+    pub department: Option<String>,
+    pub equipment: Option<String>, // comma separated, all required
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocateClassRequest {
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub day: String,
+    pub period_index: i32,
+    /// Moves only this date's period; without it the timetable entry's room changes for good.
+    pub date: Option<NaiveDate>,
+    pub to_room: String,
+    pub reason: Option<String>,
+    pub relocated_by: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocationQuery {
+    pub date: NaiveDate,
+    pub branch: Option<String>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomRelocation {
+    pub id: Uuid,
+    pub relocation_date: NaiveDate,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub day: String,
+    pub period_index: i32,
+    pub from_room: Option<String>,
+    pub to_room: String,
+    pub reason: Option<String>,
+    pub relocated_by_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomUtilizationQuery {
+    pub department: Option<String>,
+    pub room_type: Option<String>,
+}
+
+/// Weekly periods a room is booked for, per day.
+#[derive(Debug, FromRow)]
+pub struct RoomDayLoad {
+    pub code: String,
+    pub day: String,
+    pub periods: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomUtilization {
+    pub code: String,
+    pub name: String,
+    pub room_type: String,
+    pub department: Option<String>,
+    pub status: String,
+    pub booked_periods: i64,
+    pub available_periods: i64,
+    pub utilization_percent: f64,
+    pub busiest_day: Option<String>,
+}
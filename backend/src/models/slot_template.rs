@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// One entry of a day schedule, in order. `LAB` is a double (or longer) period: `periods`
+/// consecutive periods of `minutes` each that labs are placed in as one block.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateSlot {
+    pub kind: String, // PERIOD, LAB, SHORT_BREAK, LUNCH
+    pub minutes: i32,
+    #[serde(default)]
+    pub periods: Option<i32>, // LAB only, defaults to 2
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DaySchedule {
+    pub start_time: String, // HH:MM
+    pub slots: Vec<TemplateSlot>,
+}
+
+/// The weekday schedule and, when it differs, the Saturday one.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WeekSchedule {
+    pub weekday: DaySchedule,
+    #[serde(default)]
+    pub saturday: Option<DaySchedule>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotTemplate {
+    pub id: Uuid,
+    pub branch: String,
+    pub effective_from: NaiveDate,
+    pub weekday_schedule: serde_json::Value,
+    pub saturday_schedule: Option<serde_json::Value>,
+    pub created_by_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveSlotTemplateRequest {
+    pub branch: String,
+    pub effective_from: NaiveDate,
+    pub weekday: DaySchedule,
+    pub saturday: Option<DaySchedule>,
+    pub created_by: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewSlotTemplateRequest {
+    pub weekday: DaySchedule,
+    pub saturday: Option<DaySchedule>,
+}
+
+#[derive(Deserialize)]
+pub struct SlotTemplateHistoryQuery {
+    pub branch: String,
+}
+
+#[derive(Deserialize)]
+pub struct SlotTemplateQuery {
+    pub branch: String,
+    pub date: Option<NaiveDate>, // defaults to today
+}
+
+/// The fixed-column form the department timing screen posts.
+#[derive(Deserialize, Debug)]
+pub struct UpdateDepartmentTimingRequest {
+    pub branch: String,
+    pub start_hour: i32,
+    pub start_minute: i32,
+    pub class_duration: i32,
+    pub short_break_duration: i32,
+    pub lunch_duration: i32,
+    pub slot_config: Option<Vec<String>>, // P, SB, LB
+    pub short_code: Option<String>,
+    pub updated_by: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewSlot {
+    pub kind: String,
+    pub period_index: Option<i32>,
+    pub lab_block: Option<i32>, // 1-based LAB entry the period belongs to
+    pub label: Option<String>,
+    pub start: String,
+    pub end: String,
+    pub minutes: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DayPreview {
+    pub starts_at: String,
+    pub ends_at: String,
+    pub periods: i32,
+    pub teaching_minutes: i64,
+    pub slots: Vec<PreviewSlot>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotTemplatePreview {
+    pub branch: Option<String>,
+    /// Start of the saved template previewed; None for a draft or the fixed-column timing.
+    pub effective_from: Option<NaiveDate>,
+    pub weekday: DayPreview,
+    pub saturday: Option<DayPreview>,
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::NaiveDate;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubstituteQuery {
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub date: NaiveDate,
+    pub period_index: i32,
+    pub limit: Option<i64>,
+}
+
+/// A scheduled period that needs cover, as it stands in the timetable in effect on `date`.
+#[derive(Serialize, Debug, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SubstituteSlot {
+    pub date: NaiveDate,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub day: String,
+    pub period_index: i32,
+    pub subject: String,
+    pub subject_code: Option<String>,
+    pub faculty_id: String,
+    pub faculty_name: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct CandidateRow {
+    pub login_id: String,
+    pub full_name: String,
+    pub branch: Option<String>,
+    pub teaches_subject: bool,
+    pub teaches_class: bool,
+    pub week_substitutions: i64,
+    pub periods_that_day: i64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubstituteCandidate {
+    pub faculty_id: String,
+    pub name: String,
+    pub department: Option<String>,
+    pub score: i64,
+    pub teaches_subject: bool,
+    pub teaches_class: bool,
+    pub same_department: bool,
+    pub week_substitutions: i64,
+    pub periods_that_day: i64,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubstituteRecommendation {
+    pub slot: SubstituteSlot,
+    pub candidates: Vec<SubstituteCandidate>,
+}
+
+/// Covers every period of `facultyId` between the dates. Without `apply` the plan is only previewed;
+/// with it each chosen substitute is recorded in `class_period_status` and notified.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanSubstitutesRequest {
+    pub faculty_id: String,
+    pub from_date: NaiveDate,
+    pub to_date: NaiveDate,
+    pub planned_by: String,
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubstitutePlanItem {
+    pub slot: SubstituteSlot,
+    pub substitute: Option<SubstituteCandidate>,
+}
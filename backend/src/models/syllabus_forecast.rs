@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyllabusForecastQuery {
+    pub branch: String,
+    pub year: Option<String>,
+    pub section: Option<String>,
+    pub status: Option<String>, // ON_TRACK, AT_RISK, BEHIND
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshForecastsRequest {
+    pub branch: String,
+    pub year: Option<String>,
+    pub section: Option<String>,
+    pub requested_by: String,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SyllabusForecast {
+    pub lesson_plan_id: Uuid,
+    pub subject_code: String,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub faculty_login: String,
+    pub faculty_name: String,
+    pub completed_periods: i32,
+    pub remaining_periods: i32,
+    pub scheduled_periods: i32,
+    pub weekly_rate: f64,
+    pub deadline: NaiveDate,
+    pub projected_completion: Option<NaiveDate>,
+    pub status: String,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// A freshly computed forecast, before it is stored.
+#[derive(Debug)]
+pub struct ForecastValues {
+    pub completed_periods: i32,
+    pub remaining_periods: i32,
+    pub scheduled_periods: i32,
+    pub weekly_rate: f64,
+    pub deadline: NaiveDate,
+    pub projected_completion: Option<NaiveDate>,
+    pub status: &'static str,
+}
+
+#[derive(Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskBreakdown {
+    pub on_track: i64,
+    pub at_risk: i64,
+    pub behind: i64,
+}
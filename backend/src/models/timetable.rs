@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+// --- Generator ---
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassKey {
+    pub year: String,
+    pub section: String,
+}
+
+/// Weekly load for one subject in one class. When a generation request carries none,
+/// they are derived from approved `faculty_subjects` rows for each class.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectRequirement {
+    pub year: String,
+    pub section: String,
+    pub subject_code: String,
+    pub subject_name: String,
+    pub faculty_id: String, // login_id
+    pub weekly_periods: i32,
+    #[serde(default)]
+    pub lab_block: Option<i32>, // Contiguous periods per lab session; None for theory
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FacultyUnavailability {
+    pub faculty_id: String,
+    pub day: String,
+    pub period_index: Option<i32>, // None blocks the whole day
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateTimetableRequest {
+    pub branch: String,
+    pub created_by: String,
+    pub classes: Option<Vec<ClassKey>>,
+    pub requirements: Option<Vec<SubjectRequirement>>,
+    pub working_days: Option<Vec<String>>,
+    #[serde(default)]
+    pub faculty_unavailable: Vec<FacultyUnavailability>,
+    pub max_consecutive_same_subject: Option<i32>,
+    pub max_consecutive_faculty: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftEntry {
+    pub year: String,
+    pub section: String,
+    pub day: String,
+    pub period_index: i32,
+    pub subject: String,
+    pub subject_code: Option<String>,
+    pub faculty_id: String,
+    pub lab_room: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UnplacedRequirement {
+    pub year: String,
+    pub section: String,
+    pub subject_code: String,
+    pub faculty_id: String,
+    pub missing_periods: i32,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TimetableDraftSummary {
+    pub id: Uuid,
+    pub branch: String,
+    pub status: String,
+    pub created_by: Option<Uuid>,
+    pub created_by_name: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub entry_count: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TimetableDraft {
+    pub id: Uuid,
+    pub branch: String,
+    pub status: String,
+    pub entries: Vec<DraftEntry>,
+    pub unplaced: Vec<UnplacedRequirement>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct DraftListQuery {
+    pub branch: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishDraftRequest {
+    pub published_by: String,
+}
+
+// --- Assignment checks ---
+
+/// A reason an assignment would clash. `kind` is FACULTY_BUSY, ROOM_OCCUPIED, ROOM_UNAVAILABLE or SUBJECT_NOT_ASSIGNED;
+/// the slot fields describe the existing entry it clashes with, when there is one.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleConflict {
+    pub kind: String,
+    pub message: String,
+    pub branch: Option<String>,
+    pub year: Option<String>,
+    pub section: Option<String>,
+    pub subject: Option<String>,
+    pub faculty_id: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct OccupiedSlot {
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub subject: String,
+    pub faculty_id: String,
+}
+
+// --- Versions ---
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TimetableVersion {
+    pub id: Uuid,
+    pub branch: String,
+    pub name: String,
+    pub status: String,
+    pub effective_from: NaiveDate,
+    pub effective_to: Option<NaiveDate>,
+    pub is_live: bool,
+    pub cloned_from: Option<Uuid>,
+    pub created_by_name: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TimetableVersionDetail {
+    #[serde(flatten)]
+    pub version: TimetableVersion,
+    pub entries: Vec<DraftEntry>,
+}
+
+/// Creates a draft version from the live grid of the branch.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVersionRequest {
+    pub branch: String,
+    pub name: String,
+    pub effective_from: String,
+    pub effective_to: Option<String>,
+    pub created_by: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneVersionRequest {
+    pub name: String,
+    pub effective_from: String,
+    pub effective_to: Option<String>,
+    pub created_by: String,
+}
+
+/// Sets or clears one slot of a draft version. `clear` ignores the other slot fields.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionEntryRequest {
+    pub year: String,
+    pub section: String,
+    pub day: String,
+    pub period_index: i32,
+    pub subject: Option<String>,
+    pub subject_code: Option<String>,
+    pub faculty_id: Option<String>,
+    /// Lab or classroom the class is held in; copied to the live grid on publish.
+    #[serde(default)]
+    pub room: Option<String>,
+    #[serde(default)]
+    pub clear: bool,
+}
+
+#[derive(Deserialize)]
+pub struct VersionDiffQuery {
+    pub from: Uuid,
+    pub to: Uuid,
+}
+
+/// `change` is ADDED, REMOVED or CHANGED going from the first version to the second.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionDiffItem {
+    pub year: String,
+    pub section: String,
+    pub day: String,
+    pub period_index: i32,
+    pub change: String,
+    pub before: Option<DraftEntry>,
+    pub after: Option<DraftEntry>,
+}
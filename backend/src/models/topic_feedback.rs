@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicFeedbackQuery {
+    pub subject_code: String,
+    pub branch: String,
+    pub year: Option<String>,
+    pub section: Option<String>,
+    pub regulation: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FacultyFeedbackQuery {
+    pub branch: String,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackInboxQuery {
+    pub faculty_id: String,
+    pub status: Option<String>, // open (default), all
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionSessionQuery {
+    pub faculty_id: Option<String>,
+    pub branch: Option<String>,
+    pub section: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StudentFeedbackQuery {
+    pub student_id: String,
+}
+
+/// Feedback on one topic, as counted by the database.
+#[derive(Debug, FromRow)]
+pub struct TopicFeedbackRow {
+    pub topic_id: String,
+    pub topic_uid: Option<Uuid>,
+    pub responses: i64,
+    pub understood: i64,
+    pub average_rating: Option<f64>,
+    pub rating_1: i64,
+    pub rating_2: i64,
+    pub rating_3: i64,
+    pub rating_4: i64,
+    pub rating_5: i64,
+    pub done: i64,
+    pub not_understood: i64,
+    pub not_done: i64,
+    pub open_items: i64,
+}
+
+#[derive(Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueBreakdown {
+    pub done: i64,
+    pub not_understood: i64,
+    pub not_done: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicFeedbackAnalytics {
+    pub topic_id: String,
+    pub topic_uid: Option<Uuid>,
+    pub topic_name: Option<String>,
+    pub responses: i64,
+    pub understood_percent: f64,
+    pub average_rating: Option<f64>,
+    /// Responses per star rating, 1 to 5.
+    pub rating_distribution: [i64; 5],
+    pub issues: IssueBreakdown,
+    /// Not-understood or not-done feedback nobody has acted on yet.
+    pub open_items: i64,
+    pub needs_revision: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueTrend {
+    pub week_start: NaiveDate,
+    pub issues: IssueBreakdown,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectFeedbackAnalytics {
+    pub subject_code: String,
+    pub branch: String,
+    pub section: Option<String>,
+    pub topics: Vec<TopicFeedbackAnalytics>,
+    pub trend: Vec<IssueTrend>,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FacultyFeedbackAnalytics {
+    pub faculty_login: String,
+    pub faculty_name: String,
+    pub subjects: i64,
+    pub responses: i64,
+    pub understood_percent: f64,
+    pub average_rating: Option<f64>,
+    pub not_understood: i64,
+    pub not_done: i64,
+    pub topics_needing_revision: i64,
+    pub open_items: i64,
+    pub resolved_items: i64,
+    pub average_hours_to_resolve: Option<f64>,
+    /// Course materials the faculty member has shared with the branch, and students' downloads of
+    /// them in the period.
+    pub materials_shared: i64,
+    pub material_downloads: i64,
+}
+
+/// Feedback on one topic of one section sharing an issue type, for the faculty inbox.
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackCluster {
+    pub subject_code: String,
+    pub topic_id: String,
+    pub topic_uid: Option<Uuid>,
+    #[sqlx(default)]
+    pub topic_name: Option<String>,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub issue_type: String,
+    pub responses: i64,
+    pub open_items: i64,
+    pub average_rating: Option<f64>,
+    pub latest_at: Option<DateTime<Utc>>,
+    pub comments: serde_json::Value, // [{ id, comment, rating, createdAt, resolved }]
+    pub feedback_ids: Vec<Uuid>,
+    pub next_revision: Option<NaiveDate>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleRevisionRequest {
+    pub faculty_id: String,
+    pub subject_code: String,
+    pub topic_id: String,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub scheduled_date: NaiveDate,
+    pub period_index: Option<i32>,
+    pub notes: Option<String>,
+    /// The feedback the class answers; defaults to all open feedback on the topic from the section.
+    pub feedback_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRevisionRequest {
+    pub session_id: Uuid,
+    pub status: String, // CONDUCTED, CANCELLED
+    pub note: Option<String>,
+    pub updated_by: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveFeedbackRequest {
+    pub feedback_ids: Vec<Uuid>,
+    pub note: String,
+    pub resolved_by: String,
+}
+
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionSession {
+    pub id: Uuid,
+    pub subject_code: String,
+    pub topic_id: String,
+    pub topic_uid: Option<Uuid>,
+    pub branch: String,
+    pub year: String,
+    pub section: String,
+    pub faculty_login: String,
+    pub faculty_name: String,
+    pub scheduled_date: NaiveDate,
+    pub period_index: Option<i32>,
+    pub notes: Option<String>,
+    pub status: String,
+    pub linked_feedback: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A student's own feedback and what became of it.
+#[derive(Serialize, Debug, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct StudentFeedbackStatus {
+    pub id: Uuid,
+    pub subject_code: String,
+    pub topic_id: String,
+    pub rating: Option<i32>,
+    pub issue_type: Option<String>,
+    pub comment: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub status: String, // OPEN, REVISION_SCHEDULED, RESOLVED, NO_ACTION_NEEDED
+    pub revision_date: Option<NaiveDate>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolution_note: Option<String>,
+}
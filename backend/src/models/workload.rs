@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkloadQuery {
+    pub branch: String,
+    /// Defaults to the semester covering today; `from`/`to` override it.
+    pub semester_id: Option<Uuid>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub format: Option<String>, // json (default), csv, pdf
+}
+
+#[derive(Deserialize)]
+pub struct WorkloadNormsQuery {
+    pub branch: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkloadNorms {
+    pub branch: String,
+    pub max_weekly_periods: i32,
+    pub min_weekly_periods: i32,
+    pub max_subjects: i32,
+    pub max_sections: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveWorkloadNormsRequest {
+    pub branch: Option<String>, // omitted for the college-wide default
+    pub max_weekly_periods: i32,
+    pub min_weekly_periods: i32,
+    pub max_subjects: i32,
+    pub max_sections: i32,
+    pub updated_by: String,
+}
+
+/// Timetable and assignment load for one faculty member.
+#[derive(Debug, FromRow)]
+pub struct FacultyLoadRow {
+    pub login_id: String,
+    pub full_name: String,
+    pub weekly_periods: i64,
+    pub subject_count: i64,
+    pub section_count: i64,
+}
+
+/// Dated periods for one faculty member over the report range.
+#[derive(Debug, Default, FromRow)]
+pub struct FacultyPeriodCounts {
+    pub login_id: String,
+    pub scheduled: i64,
+    pub conducted: i64,
+    pub not_conducted: i64,
+    pub substitutions_given: i64,
+    pub substitutions_taken: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FacultyWorkload {
+    pub login_id: String,
+    pub full_name: String,
+    pub weekly_periods: i64,
+    pub weekly_hours: f64,
+    pub subject_count: i64,
+    pub section_count: i64,
+    pub scheduled_periods: i64,
+    pub conducted_periods: i64,
+    pub not_conducted_periods: i64,
+    pub unrecorded_periods: i64,
+    /// Periods of others this faculty member covered.
+    pub substitutions_taken: i64,
+    /// Own periods someone else covered.
+    pub substitutions_given: i64,
+    pub delivery_percent: f64,
+    pub flags: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkloadReport {
+    pub branch: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub semester: Option<String>,
+    pub norms: WorkloadNorms,
+    pub faculty: Vec<FacultyWorkload>,
+}
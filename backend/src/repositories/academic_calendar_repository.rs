@@ -0,0 +1,193 @@
+use sqlx::{PgPool, Postgres};
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::academic_calendar::{AcademicSemester, AcademicYear, CalendarDay, CalendarEvent, CreateCalendarEventRequest, CreateSemesterRequest, TermOnDate};
+
+pub async fn upsert_academic_year(pool: &PgPool, year_name: &str, start_date: NaiveDate, end_date: NaiveDate) -> Result<AcademicYear, sqlx::Error> {
+    sqlx::query_as::<Postgres, AcademicYear>(
+        "INSERT INTO academic_years (year_name, start_date, end_date) VALUES ($1, $2, $3)
+         ON CONFLICT (year_name) DO UPDATE SET start_date = EXCLUDED.start_date, end_date = EXCLUDED.end_date
+         RETURNING id, year_name, start_date, end_date"
+    )
+    .bind(year_name)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn find_academic_years(pool: &PgPool) -> Result<Vec<AcademicYear>, sqlx::Error> {
+    sqlx::query_as::<Postgres, AcademicYear>(
+        "SELECT id, year_name, start_date, end_date FROM academic_years ORDER BY start_date DESC NULLS LAST, year_name DESC"
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_academic_year(pool: &PgPool, id: i32) -> Result<Option<AcademicYear>, sqlx::Error> {
+    sqlx::query_as::<Postgres, AcademicYear>("SELECT id, year_name, start_date, end_date FROM academic_years WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn upsert_semester(pool: &PgPool, req: &CreateSemesterRequest, term: &str) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO academic_semesters (academic_year_id, term, start_date, end_date, saturdays_working)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (academic_year_id, term) DO UPDATE
+         SET start_date = EXCLUDED.start_date, end_date = EXCLUDED.end_date, saturdays_working = EXCLUDED.saturdays_working
+         RETURNING id"
+    )
+    .bind(req.academic_year_id)
+    .bind(term)
+    .bind(req.start_date)
+    .bind(req.end_date)
+    .bind(req.saturdays_working.unwrap_or(true))
+    .fetch_one(pool)
+    .await
+}
+
+/// Semesters other than `exclude` whose dates overlap the range.
+pub async fn count_overlapping_semesters(pool: &PgPool, start: NaiveDate, end: NaiveDate, exclude: (i32, &str)) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, i64>(
+        "SELECT COUNT(*) FROM academic_semesters
+         WHERE start_date <= $2 AND end_date >= $1 AND NOT (academic_year_id = $3 AND term = $4)"
+    )
+    .bind(start)
+    .bind(end)
+    .bind(exclude.0)
+    .bind(exclude.1)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn find_semesters(pool: &PgPool, academic_year_id: Option<i32>) -> Result<Vec<AcademicSemester>, sqlx::Error> {
+    sqlx::query_as::<Postgres, AcademicSemester>(
+        "SELECT s.id, s.academic_year_id, y.year_name, s.term, s.start_date, s.end_date, s.saturdays_working
+         FROM academic_semesters s
+         JOIN academic_years y ON y.id = s.academic_year_id
+         WHERE ($1::int IS NULL OR s.academic_year_id = $1)
+         ORDER BY s.start_date DESC"
+    )
+    .bind(academic_year_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// The semester covering the date, if the calendar defines one.
+pub async fn find_term_on_date(pool: &PgPool, date: NaiveDate) -> Result<Option<TermOnDate>, sqlx::Error> {
+    sqlx::query_as::<Postgres, TermOnDate>(
+        "SELECT EXTRACT(YEAR FROM COALESCE(y.start_date, s.start_date))::int as start_year, s.term
+         FROM academic_semesters s
+         JOIN academic_years y ON y.id = s.academic_year_id
+         WHERE $1 BETWEEN s.start_date AND s.end_date
+         LIMIT 1"
+    )
+    .bind(date)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn insert_event(pool: &PgPool, req: &CreateCalendarEventRequest, event_type: &str, created_by: Uuid) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO academic_calendar_events (event_type, title, start_date, end_date, branch, year, created_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id"
+    )
+    .bind(event_type)
+    .bind(&req.title)
+    .bind(req.start_date)
+    .bind(req.end_date)
+    .bind(&req.branch)
+    .bind(&req.year)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn delete_event(pool: &PgPool, id: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query("DELETE FROM academic_calendar_events WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// Calendar events overlapping the dates that apply to the branch and year (all when unset).
+pub async fn find_events(pool: &PgPool, from: NaiveDate, to: NaiveDate, branch: Option<&str>, year: Option<&str>) -> Result<Vec<CalendarEvent>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CalendarEvent>(
+        "SELECT id, event_type, title, start_date, end_date, branch, year
+         FROM academic_calendar_events
+         WHERE start_date <= $2 AND end_date >= $1
+           AND ($3::text IS NULL OR branch IS NULL OR canonical_branch(branch) = canonical_branch($3))
+           AND ($4::text IS NULL OR year IS NULL OR year = $4)
+         ORDER BY start_date, event_type"
+    )
+    .bind(from)
+    .bind(to)
+    .bind(branch)
+    .bind(year)
+    .fetch_all(pool)
+    .await
+}
+
+/// One row per date with its instructional flag and the titles of events on it.
+pub async fn find_calendar_days(pool: &PgPool, from: NaiveDate, to: NaiveDate, branch: Option<&str>, year: Option<&str>) -> Result<Vec<CalendarDay>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CalendarDay>(
+        "SELECT d.date::date as date, TO_CHAR(d.date, 'FMDay') as weekday,
+                is_instructional_day(d.date::date, $3, $4) as instructional,
+                COALESCE(ARRAY(
+                    SELECT e.event_type || ': ' || e.title FROM academic_calendar_events e
+                    WHERE d.date::date BETWEEN e.start_date AND e.end_date
+                      AND ($3::text IS NULL OR e.branch IS NULL OR e.branch = $3)
+                      AND ($4::text IS NULL OR e.year IS NULL OR e.year = $4)
+                    ORDER BY e.start_date
+                ), '{}') as events
+         FROM generate_series($1::date, $2::date, INTERVAL '1 day') AS d(date)
+         ORDER BY d.date"
+    )
+    .bind(from)
+    .bind(to)
+    .bind(branch)
+    .bind(year)
+    .fetch_all(pool)
+    .await
+}
+
+/// Dates in the range on which no classes run for the branch and year.
+pub async fn find_non_instructional_dates(pool: &PgPool, from: NaiveDate, to: NaiveDate, branch: &str, year: &str) -> Result<Vec<NaiveDate>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, NaiveDate>(
+        "SELECT d.date::date FROM generate_series($1::date, $2::date, INTERVAL '1 day') AS d(date)
+         WHERE NOT is_instructional_day(d.date::date, $3, $4)"
+    )
+    .bind(from)
+    .bind(to)
+    .bind(branch)
+    .bind(year)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn is_instructional_day(pool: &PgPool, date: NaiveDate, branch: Option<&str>, year: Option<&str>) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, bool>("SELECT is_instructional_day($1, $2, $3)")
+        .bind(date)
+        .bind(branch)
+        .bind(year)
+        .fetch_one(pool)
+        .await
+}
+
+/// A semester by id, or the one covering `date` when no id is given.
+pub async fn find_semester(pool: &PgPool, id: Option<Uuid>, date: NaiveDate) -> Result<Option<AcademicSemester>, sqlx::Error> {
+    sqlx::query_as::<Postgres, AcademicSemester>(
+        "SELECT s.id, s.academic_year_id, y.year_name, s.term, s.start_date, s.end_date, s.saturdays_working
+         FROM academic_semesters s
+         JOIN academic_years y ON y.id = s.academic_year_id
+         WHERE CASE WHEN $1::uuid IS NULL THEN $2 BETWEEN s.start_date AND s.end_date ELSE s.id = $1 END
+         LIMIT 1"
+    )
+    .bind(id)
+    .bind(date)
+    .fetch_optional(pool)
+    .await
+}
@@ -0,0 +1,208 @@
+use sqlx::{PgPool, Postgres};
+use chrono::NaiveDate;
+use crate::models::analytics::{
+    AbsenceStreak, AttendanceTrendPoint, FacultyPeriodComparison, PeriodHeatmapCell, WeekdayHeatmapCell
+};
+
+pub async fn refresh_daily_summary(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    sqlx::query("REFRESH MATERIALIZED VIEW CONCURRENTLY attendance_daily_summary")
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+pub async fn find_trends(
+    pool: &PgPool,
+    branch_variations: &[String],
+    year: Option<&str>,
+    section: Option<&str>,
+    from: NaiveDate,
+    to: NaiveDate,
+    bucket: &str, // 'day' or 'week'
+) -> Result<Vec<AttendanceTrendPoint>, sqlx::Error> {
+    sqlx::query_as::<Postgres, AttendanceTrendPoint>(
+        "SELECT DATE_TRUNC($6, date)::DATE AS bucket, year, section,
+                SUM(present)::BIGINT AS present, SUM(absent)::BIGINT AS absent, SUM(on_leave)::BIGINT AS on_leave,
+                CASE WHEN SUM(present) + SUM(absent) > 0
+                     THEN SUM(present)::FLOAT8 * 100 / (SUM(present) + SUM(absent))::FLOAT8 ELSE 0 END AS percentage
+         FROM attendance_daily_summary
+         WHERE branch = ANY($1) AND ($2::TEXT IS NULL OR year = $2) AND ($3::TEXT IS NULL OR section = $3)
+           AND date BETWEEN $4 AND $5 AND is_instructional_day(date, branch, year)
+         GROUP BY 1, year, section
+         ORDER BY 1, year, section"
+    )
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(from)
+    .bind(to)
+    .bind(bucket)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_weekday_heatmap(
+    pool: &PgPool,
+    branch_variations: &[String],
+    year: Option<&str>,
+    section: Option<&str>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<WeekdayHeatmapCell>, sqlx::Error> {
+    sqlx::query_as::<Postgres, WeekdayHeatmapCell>(
+        "SELECT TRIM(TO_CHAR(date, 'Day')) AS day_of_week, session,
+                SUM(present)::BIGINT AS present, SUM(absent)::BIGINT AS absent,
+                CASE WHEN SUM(present) + SUM(absent) > 0
+                     THEN SUM(present)::FLOAT8 * 100 / (SUM(present) + SUM(absent))::FLOAT8 ELSE 0 END AS percentage
+         FROM attendance_daily_summary
+         WHERE branch = ANY($1) AND ($2::TEXT IS NULL OR year = $2) AND ($3::TEXT IS NULL OR section = $3)
+           AND date BETWEEN $4 AND $5 AND is_instructional_day(date, branch, year)
+         GROUP BY EXTRACT(ISODOW FROM date), 1, session
+         ORDER BY EXTRACT(ISODOW FROM date), session"
+    )
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_period_heatmap(
+    pool: &PgPool,
+    branch: &str,
+    year: Option<&str>,
+    section: Option<&str>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<PeriodHeatmapCell>, sqlx::Error> {
+    sqlx::query_as::<Postgres, PeriodHeatmapCell>(
+        "SELECT day, period_index, COUNT(*) AS recorded,
+                COUNT(*) FILTER (WHERE status = 'conducted') AS conducted,
+                COUNT(*) FILTER (WHERE status = 'substitute') AS substitute,
+                COUNT(*) FILTER (WHERE status = 'not_conducted') AS not_conducted,
+                COUNT(*) FILTER (WHERE status IN ('conducted', 'substitute'))::FLOAT8 * 100 / COUNT(*)::FLOAT8 AS conducted_rate
+         FROM class_period_status
+         WHERE branch = $1 AND ($2::TEXT IS NULL OR year = $2) AND ($3::TEXT IS NULL OR section = $3)
+           AND status_date BETWEEN $4 AND $5 AND is_instructional_day(status_date, branch, year)
+         GROUP BY EXTRACT(ISODOW FROM status_date), day, period_index
+         ORDER BY EXTRACT(ISODOW FROM status_date), period_index"
+    )
+    .bind(branch)
+    .bind(year)
+    .bind(section)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Runs of consecutive marked days on which every session was absent.
+/// Days marked entirely as leave, and days the academic calendar has no classes on, are
+/// skipped, so they neither extend nor break a streak.
+pub async fn find_absence_streaks(
+    pool: &PgPool,
+    branch_variations: &[String],
+    year: Option<&str>,
+    section: Option<&str>,
+    from: NaiveDate,
+    min_days: i64,
+    ongoing_only: bool,
+) -> Result<Vec<AbsenceStreak>, sqlx::Error> {
+    sqlx::query_as::<Postgres, AbsenceStreak>(
+        "WITH days AS (
+            SELECT a.student_uuid, a.date, BOOL_AND(UPPER(a.status) IN ('A', 'ABSENT')) AS absent
+            FROM attendance a
+            JOIN users u ON u.id = a.student_uuid
+            WHERE u.role = 'Student' AND u.branch = ANY($1) AND ($2::TEXT IS NULL OR u.year = $2) AND ($3::TEXT IS NULL OR u.section = $3)
+              AND a.date >= $4 AND is_instructional_day(a.date, u.branch, u.year)
+            GROUP BY a.student_uuid, a.date
+            HAVING NOT BOOL_AND(a.status = 'L')
+         ), numbered AS (
+            SELECT student_uuid, date, absent,
+                   ROW_NUMBER() OVER (PARTITION BY student_uuid ORDER BY date)
+                     - ROW_NUMBER() OVER (PARTITION BY student_uuid, absent ORDER BY date) AS grp,
+                   MAX(date) OVER (PARTITION BY student_uuid) AS last_marked
+            FROM days
+         ), streaks AS (
+            SELECT student_uuid, MIN(date) AS start_date, MAX(date) AS end_date, COUNT(*) AS days,
+                   MAX(date) = MAX(last_marked) AS ongoing
+            FROM numbered
+            WHERE absent
+            GROUP BY student_uuid, grp
+         )
+         SELECT u.login_id AS student_login_id, u.full_name, u.year, u.section, s.start_date, s.end_date, s.days, s.ongoing
+         FROM streaks s
+         JOIN users u ON u.id = s.student_uuid
+         WHERE s.days >= $5 AND (s.ongoing OR NOT $6)
+         ORDER BY s.ongoing DESC, s.days DESC, u.login_id"
+    )
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(from)
+    .bind(min_days)
+    .bind(ongoing_only)
+    .fetch_all(pool)
+    .await
+}
+
+/// Period delivery per faculty from `class_period_status`, joined with the attendance they marked.
+/// `class_period_status` stores faculty as free text, so it is matched on name or login id.
+pub async fn find_faculty_comparison(
+    pool: &PgPool,
+    branch: &str,
+    branch_variations: &[String],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<FacultyPeriodComparison>, sqlx::Error> {
+    sqlx::query_as::<Postgres, FacultyPeriodComparison>(
+        "WITH periods AS (
+            SELECT original_faculty AS faculty, COUNT(*) AS scheduled,
+                   COUNT(*) FILTER (WHERE status = 'conducted') AS conducted,
+                   COUNT(*) FILTER (WHERE status = 'not_conducted') AS not_conducted,
+                   COUNT(*) FILTER (WHERE status = 'substitute') AS substituted_out
+            FROM class_period_status
+            WHERE branch = $1 AND status_date BETWEEN $3 AND $4 AND is_instructional_day(status_date, branch, year)
+            GROUP BY original_faculty
+         ), subs AS (
+            SELECT actual_faculty AS faculty, COUNT(*) AS taken
+            FROM class_period_status
+            WHERE branch = $1 AND status = 'substitute' AND status_date BETWEEN $3 AND $4 AND is_instructional_day(status_date, branch, year)
+            GROUP BY actual_faculty
+         ), marks AS (
+            SELECT f.full_name, f.login_id,
+                   COUNT(DISTINCT (a.date, a.session, a.section)) AS sessions_marked,
+                   COUNT(*) FILTER (WHERE UPPER(a.status) IN ('P', 'PRESENT')) AS present,
+                   COUNT(*) FILTER (WHERE UPPER(a.status) IN ('A', 'ABSENT')) AS absent
+            FROM attendance a
+            JOIN users f ON f.id = a.faculty_uuid
+            JOIN users s ON s.id = a.student_uuid
+            WHERE s.branch = ANY($2) AND a.date BETWEEN $3 AND $4 AND is_instructional_day(a.date, s.branch, s.year)
+            GROUP BY f.id, f.full_name, f.login_id
+         ), faculty AS (
+            SELECT COALESCE(p.faculty, s.faculty) AS faculty,
+                   COALESCE(p.scheduled, 0) AS scheduled, COALESCE(p.conducted, 0) AS conducted,
+                   COALESCE(p.not_conducted, 0) AS not_conducted, COALESCE(p.substituted_out, 0) AS substituted_out,
+                   COALESCE(s.taken, 0) AS substitutions_taken
+            FROM periods p
+            FULL OUTER JOIN subs s ON s.faculty = p.faculty
+         )
+         SELECT fc.faculty, fc.scheduled, fc.conducted, fc.not_conducted, fc.substituted_out, fc.substitutions_taken,
+                CASE WHEN fc.scheduled > 0 THEN fc.conducted::FLOAT8 * 100 / fc.scheduled::FLOAT8 ELSE 0 END AS conducted_rate,
+                COALESCE(m.sessions_marked, 0) AS sessions_marked,
+                CASE WHEN m.present + m.absent > 0 THEN m.present::FLOAT8 * 100 / (m.present + m.absent)::FLOAT8 END AS attendance_rate
+         FROM faculty fc
+         LEFT JOIN LATERAL (
+            SELECT * FROM marks WHERE marks.full_name = fc.faculty OR marks.login_id = fc.faculty LIMIT 1
+         ) m ON TRUE
+         ORDER BY conducted_rate ASC, fc.faculty"
+    )
+    .bind(branch)
+    .bind(branch_variations)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,196 @@
+use sqlx::{PgPool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::models::BatchAttendanceRequest;
+use crate::models::attendance::{AttendanceHistoryEntry, AttendanceWrite, SyncRecordResult};
+
+pub async fn find_edit_window_hours(pool: &PgPool, branch_variations: &[String]) -> Result<Option<i32>, sqlx::Error> {
+    sqlx::query_scalar("SELECT attendance_edit_window_hours FROM department_timings WHERE branch = ANY($1) LIMIT 1")
+        .bind(branch_variations)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn upsert_edit_window_hours(pool: &PgPool, branch: &str, hours: i32) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO department_timings (branch, attendance_edit_window_hours) VALUES ($1, $2)
+         ON CONFLICT (branch) DO UPDATE SET attendance_edit_window_hours = EXCLUDED.attendance_edit_window_hours"
+    )
+    .bind(branch)
+    .bind(hours)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn find_student_branch(pool: &PgPool, student_uuid: Uuid) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT branch FROM users WHERE id = $1")
+        .bind(student_uuid)
+        .fetch_optional(pool)
+        .await
+        .map(|b: Option<Option<String>>| b.flatten())
+}
+
+/// Serializes writers of one student's session mark until the transaction ends, so concurrent
+/// first writes cannot both take history version 1.
+pub async fn lock_session_mark(executor: &mut sqlx::Transaction<'_, Postgres>, student_login_id: &str, date: &str, session: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('attendance:' || $1 || ':' || $2 || ':' || $3))")
+        .bind(student_login_id)
+        .bind(date)
+        .bind(session)
+        .execute(&mut **executor)
+        .await
+        .map(|_| ())
+}
+
+pub async fn find_current_status(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    student_login_id: &str,
+    date: &str,
+    session: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT status FROM attendance WHERE student_login_id = $1 AND date = $2::DATE AND session = $3 FOR UPDATE")
+        .bind(student_login_id)
+        .bind(date)
+        .bind(session)
+        .fetch_optional(&mut **executor)
+        .await
+}
+
+pub async fn insert_history(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    attendance_id: Uuid,
+    previous_status: Option<&str>,
+    write: &AttendanceWrite<'_>,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO attendance_history (
+            attendance_id, student_uuid, student_login_id, date, session, section, version,
+            previous_status, new_status, changed_by, change_source, reason, correction_request_id
+         ) VALUES (
+            $1, $2, $3, $4::DATE, $5, $6,
+            (SELECT COALESCE(MAX(version), 0) + 1 FROM attendance_history WHERE student_login_id = $3 AND date = $4::DATE AND session = $5),
+            $7, $8, $9, $10, $11, $12
+         )"
+    )
+    .bind(attendance_id)
+    .bind(write.student_uuid)
+    .bind(write.student_login_id)
+    .bind(write.date)
+    .bind(write.session)
+    .bind(write.section)
+    .bind(previous_status)
+    .bind(write.status)
+    .bind(write.faculty_uuid)
+    .bind(write.change_source)
+    .bind(write.reason)
+    .bind(write.correction_request_id)
+    .execute(&mut **executor)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn find_history(pool: &PgPool, student_uuid: Uuid, date: Option<NaiveDate>) -> Result<Vec<AttendanceHistoryEntry>, sqlx::Error> {
+    sqlx::query_as::<Postgres, AttendanceHistoryEntry>(
+        "SELECT h.id, h.attendance_id, h.student_login_id, h.date, h.session, h.section, h.version,
+                h.previous_status, h.new_status, h.changed_by, u.full_name as changed_by_name,
+                h.change_source, h.reason, h.correction_request_id, h.changed_at
+         FROM attendance_history h
+         LEFT JOIN users u ON h.changed_by = u.id
+         WHERE h.student_uuid = $1 AND (h.date = $2 OR $2 IS NULL)
+         ORDER BY h.date DESC, h.session ASC, h.version DESC"
+    )
+    .bind(student_uuid)
+    .bind(date)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_existing_mark(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    student_login_id: &str,
+    date: &str,
+    session: &str,
+) -> Result<Option<(String, Uuid, Option<String>)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT a.status, a.faculty_uuid, u.full_name
+         FROM attendance a
+         LEFT JOIN users u ON a.faculty_uuid = u.id
+         WHERE a.student_login_id = $1 AND a.date = $2::DATE AND a.session = $3"
+    )
+    .bind(student_login_id)
+    .bind(date)
+    .bind(session)
+    .fetch_optional(&mut **executor)
+    .await
+}
+
+/// When the student's mark for the session was last changed: its latest history entry, or the
+/// time it was first written.
+pub async fn find_last_marked_at(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    student_login_id: &str,
+    date: &str,
+    session: &str,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Option<DateTime<Utc>>>(
+        "SELECT COALESCE(
+            (SELECT MAX(h.changed_at) FROM attendance_history h
+             WHERE h.student_login_id = a.student_login_id AND h.date = a.date AND h.session = a.session),
+            a.created_at)
+         FROM attendance a
+         WHERE a.student_login_id = $1 AND a.date = $2::DATE AND a.session = $3"
+    )
+    .bind(student_login_id)
+    .bind(date)
+    .bind(session)
+    .fetch_optional(&mut **executor)
+    .await
+    .map(Option::flatten)
+}
+
+pub async fn find_sync_results(pool: &PgPool, client_record_ids: &[Uuid]) -> Result<Vec<SyncRecordResult>, sqlx::Error> {
+    sqlx::query_as::<Postgres, SyncRecordResult>(
+        "SELECT client_record_id, client_batch_id, student_id, result, message, existing_status, existing_marked_by, existing_marked_at, TRUE as duplicate
+         FROM attendance_sync_records WHERE client_record_id = ANY($1)"
+    )
+    .bind(client_record_ids)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn insert_sync_record(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    device_id: &str,
+    marked_by: Uuid,
+    batch: &BatchAttendanceRequest,
+    session: &str,
+    status: &str,
+    result: &SyncRecordResult,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO attendance_sync_records (
+            client_record_id, client_batch_id, device_id, marked_by, student_id, date, session, section,
+            status, result, message, existing_status, existing_marked_by, existing_marked_at, device_timestamp
+         ) VALUES ($1, $2, $3, $4, $5, $6::DATE, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+         ON CONFLICT (client_record_id) DO NOTHING"
+    )
+    .bind(result.client_record_id)
+    .bind(result.client_batch_id)
+    .bind(device_id)
+    .bind(marked_by)
+    .bind(&result.student_id)
+    .bind(&batch.date)
+    .bind(session)
+    .bind(&batch.section)
+    .bind(status)
+    .bind(&result.result)
+    .bind(&result.message)
+    .bind(&result.existing_status)
+    .bind(&result.existing_marked_by)
+    .bind(result.existing_marked_at)
+    .bind(batch.device_timestamp)
+    .execute(&mut **executor)
+    .await
+    .map(|r| r.rows_affected())
+}
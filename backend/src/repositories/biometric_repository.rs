@@ -0,0 +1,173 @@
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+use chrono::{NaiveDate, NaiveDateTime};
+use crate::models::attendance::{BiometricConflict, BiometricPunch};
+
+pub async fn upsert_user_mapping(pool: &PgPool, device_user_code: &str, login_id: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO biometric_user_map (device_user_code, login_id) VALUES ($1, $2)
+         ON CONFLICT (device_user_code) DO UPDATE SET login_id = EXCLUDED.login_id"
+    )
+    .bind(device_user_code)
+    .bind(login_id)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+/// Resolves device user codes to students as (code, id, login_id, branch, section).
+/// An explicit mapping wins; otherwise the code is taken to be the login id itself.
+pub async fn find_students_by_codes(
+    pool: &PgPool,
+    codes: &[String],
+) -> Result<Vec<(String, Uuid, String, Option<String>, Option<String>)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT c.code, u.id, u.login_id, u.branch, u.section
+         FROM UNNEST($1::TEXT[]) AS c(code)
+         LEFT JOIN biometric_user_map m ON m.device_user_code = c.code
+         JOIN users u ON u.login_id = COALESCE(m.login_id, c.code)
+         WHERE u.role = 'Student'"
+    )
+    .bind(codes)
+    .fetch_all(pool)
+    .await
+}
+
+/// Returns `None` when the punch was already ingested.
+pub async fn insert_punch(
+    pool: &PgPool,
+    device_id: &str,
+    punch: &BiometricPunch,
+    student_login_id: Option<&str>,
+    session: Option<&str>,
+    period_index: Option<i32>,
+    ingested_by: Uuid,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar(
+        "INSERT INTO biometric_punches (device_id, device_user_code, punched_at, student_login_id, session, period_index, ingested_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)
+         ON CONFLICT (device_id, device_user_code, punched_at) DO NOTHING
+         RETURNING id"
+    )
+    .bind(device_id)
+    .bind(&punch.device_user_code)
+    .bind(punch.punched_at)
+    .bind(student_login_id)
+    .bind(session)
+    .bind(period_index)
+    .bind(ingested_by)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Sessions with punches not yet applied to attendance, as (login_id, date, session, first punch).
+pub async fn find_pending_sessions(pool: &PgPool, login_ids: &[String]) -> Result<Vec<(String, NaiveDate, String, NaiveDateTime)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT student_login_id, punched_at::DATE, session, MIN(punched_at)
+         FROM biometric_punches
+         WHERE applied_at IS NULL AND session IS NOT NULL AND student_login_id = ANY($1)
+         GROUP BY student_login_id, punched_at::DATE, session
+         ORDER BY student_login_id, punched_at::DATE, session"
+    )
+    .bind(login_ids)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn mark_punches_applied(tx: &mut Transaction<'_, Postgres>, login_id: &str, date: NaiveDate, session: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "UPDATE biometric_punches SET applied_at = NOW()
+         WHERE student_login_id = $1 AND punched_at::DATE = $2 AND session = $3 AND applied_at IS NULL"
+    )
+    .bind(login_id)
+    .bind(date)
+    .bind(session)
+    .execute(&mut **tx)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+/// Records a conflict against the manually marked attendance row for the session.
+pub async fn insert_conflict(
+    tx: &mut Transaction<'_, Postgres>,
+    student_uuid: Uuid,
+    date: NaiveDate,
+    session: &str,
+    first_punch: NaiveDateTime,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO biometric_conflicts (student_uuid, student_login_id, branch, section, date, session, first_punch, manual_status, manual_marked_by)
+         SELECT u.id, u.login_id, u.branch, a.section, a.date, a.session, $4, a.status, a.faculty_uuid
+         FROM users u
+         JOIN attendance a ON a.student_login_id = u.login_id AND a.date = $2 AND a.session = $3
+         WHERE u.id = $1
+         ON CONFLICT (student_login_id, date, session) DO NOTHING"
+    )
+    .bind(student_uuid)
+    .bind(date)
+    .bind(session)
+    .bind(first_punch)
+    .execute(&mut **tx)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn find_conflicts(
+    pool: &PgPool,
+    branch_variations: &[String],
+    date: Option<NaiveDate>,
+    section: Option<&str>,
+    status: &str,
+) -> Result<Vec<BiometricConflict>, sqlx::Error> {
+    let mut query = QueryBuilder::new(
+        "SELECT c.id, c.student_uuid, c.student_login_id, s.full_name as student_name, c.branch, c.section, c.date, c.session,
+                c.first_punch, c.manual_status, c.manual_marked_by, f.full_name as manual_marked_by_name, c.status, c.resolved_at
+         FROM biometric_conflicts c
+         LEFT JOIN users s ON c.student_uuid = s.id
+         LEFT JOIN users f ON c.manual_marked_by = f.id
+         WHERE c.branch = ANY("
+    );
+    query.push_bind(branch_variations);
+    query.push(") AND c.status = ");
+    query.push_bind(status);
+    if let Some(d) = date {
+        query.push(" AND c.date = ");
+        query.push_bind(d);
+    }
+    if let Some(s) = section {
+        query.push(" AND c.section = ");
+        query.push_bind(s);
+    }
+    query.push(" ORDER BY c.date DESC, c.section ASC, c.student_login_id ASC, c.session ASC");
+
+    query.build_query_as::<BiometricConflict>().fetch_all(pool).await
+}
+
+pub async fn find_conflict_by_id(pool: &PgPool, id: Uuid) -> Result<Option<BiometricConflict>, sqlx::Error> {
+    sqlx::query_as::<Postgres, BiometricConflict>(
+        "SELECT c.id, c.student_uuid, c.student_login_id, s.full_name as student_name, c.branch, c.section, c.date, c.session,
+                c.first_punch, c.manual_status, c.manual_marked_by, f.full_name as manual_marked_by_name, c.status, c.resolved_at
+         FROM biometric_conflicts c
+         LEFT JOIN users s ON c.student_uuid = s.id
+         LEFT JOIN users f ON c.manual_marked_by = f.id
+         WHERE c.id = $1"
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn update_conflict_status(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    id: Uuid,
+    status: &str,
+    resolved_by: Uuid,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query("UPDATE biometric_conflicts SET status = $1, resolved_by = $2, resolved_at = NOW() WHERE id = $3 AND status = 'OPEN'")
+        .bind(status)
+        .bind(resolved_by)
+        .bind(id)
+        .execute(&mut **executor)
+        .await
+        .map(|r| r.rows_affected())
+}
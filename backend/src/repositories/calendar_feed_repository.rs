@@ -0,0 +1,101 @@
+use sqlx::{PgPool, Postgres};
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::calendar_feed::{CalendarFeed, FeedAnnouncement, FeedClass, FeedOwner, FeedTarget};
+use crate::repositories::timetable_repository::grid_on_date;
+
+pub async fn insert_feed(pool: &PgPool, user_id: Uuid, token: &str, scope: &str, target: &FeedTarget) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO calendar_feed_tokens (user_id, token, scope, branch, year, section) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id"
+    )
+    .bind(user_id)
+    .bind(token)
+    .bind(scope)
+    .bind(&target.branch)
+    .bind(&target.year)
+    .bind(&target.section)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn find_feeds(pool: &PgPool, user_id: Uuid) -> Result<Vec<CalendarFeed>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CalendarFeed>(
+        "SELECT id, token, scope, branch, year, section, created_at, last_accessed_at
+         FROM calendar_feed_tokens WHERE user_id = $1 AND revoked_at IS NULL
+         ORDER BY created_at DESC"
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn revoke_feed(pool: &PgPool, feed_id: Uuid, user_id: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query("UPDATE calendar_feed_tokens SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL")
+        .bind(feed_id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+pub async fn find_feed_owner(pool: &PgPool, token: &str) -> Result<Option<FeedOwner>, sqlx::Error> {
+    sqlx::query_as::<Postgres, FeedOwner>(
+        "SELECT f.id as feed_id, u.login_id, u.role, f.scope,
+                u.branch as user_branch, u.year as user_year, u.section as user_section,
+                f.branch, f.year, f.section
+         FROM calendar_feed_tokens f
+         JOIN users u ON u.id = f.user_id
+         WHERE f.token = $1 AND f.revoked_at IS NULL"
+    )
+    .bind(token)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn touch_feed(pool: &PgPool, feed_id: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query("UPDATE calendar_feed_tokens SET last_accessed_at = NOW() WHERE id = $1")
+        .bind(feed_id)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// Every dated class period for the target between the dates, each resolved against the
+/// timetable version in effect on its own date and skipping non-instructional days. Lab grid rows are left out; they mirror class periods.
+pub async fn find_feed_classes(pool: &PgPool, from: NaiveDate, to: NaiveDate, target: &FeedTarget) -> Result<Vec<FeedClass>, sqlx::Error> {
+    sqlx::query_as::<Postgres, FeedClass>(&format!(
+        "SELECT d.date::date as date, g.branch, g.year, g.section, g.period_index, g.subject, g.faculty_id, u.full_name as faculty_name
+         FROM generate_series($1::date, $2::date, INTERVAL '1 day') AS d(date)
+         CROSS JOIN LATERAL ({}) g
+         LEFT JOIN users u ON u.login_id = g.faculty_id
+         WHERE g.day = TO_CHAR(d.date, 'FMDay') AND g.year <> 'Lab'
+           AND is_instructional_day(d.date::date, canonical_branch(g.branch), g.year)
+           AND ($3::text IS NULL OR g.faculty_id = $3)
+           AND ($4::text IS NULL OR (canonical_branch(g.branch) = canonical_branch($4) AND g.year = $5 AND g.section = $6))
+         ORDER BY d.date, g.period_index",
+        grid_on_date("d.date::date")
+    ))
+    .bind(from)
+    .bind(to)
+    .bind(&target.faculty_id)
+    .bind(&target.branch)
+    .bind(&target.year)
+    .bind(&target.section)
+    .fetch_all(pool)
+    .await
+}
+
+/// Exam, event and holiday announcements overlapping the dates.
+pub async fn find_calendar_announcements(pool: &PgPool, from: NaiveDate, to: NaiveDate) -> Result<Vec<FeedAnnouncement>, sqlx::Error> {
+    sqlx::query_as::<Postgres, FeedAnnouncement>(
+        "SELECT id, title, description, type as announcement_type, audience, start_date, end_date
+         FROM announcements
+         WHERE LOWER(type) IN ('exam', 'event', 'holiday')
+           AND start_date::date <= $2 AND end_date::date >= $1
+         ORDER BY start_date"
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,185 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use crate::models::coverage::{CoverageAuditEntry, CoverageReport, CoverageSnapshot};
+
+const REPORT_SELECT: &str = "SELECT r.id, r.subject_code, r.subject_name, r.regulation, r.branch, r.year, r.section, r.semester,
+    r.academic_semester_id, f.login_id as faculty_login, f.full_name as faculty_name, r.status,
+    (r.snapshot->'summary'->>'coveragePercent')::FLOAT8 as coverage_percent,
+    r.faculty_statement, r.faculty_signed_at, h.login_id as hod_login, h.full_name as hod_name, r.hod_note, r.hod_signed_at,
+    r.generated_at, r.updated_at, a.sha256 as archive_sha256
+    FROM syllabus_coverage_reports r
+    JOIN users f ON f.id = r.faculty_id
+    LEFT JOIN users h ON h.id = r.hod_id
+    LEFT JOIN syllabus_coverage_archive a ON a.report_id = r.id";
+
+/// Completion log of the section's topics in the subject, oldest first.
+pub async fn find_audit(pool: &PgPool, subject_code: &str, branch: &str, year: &str, section: &str, semester: i32) -> Result<Vec<CoverageAuditEntry>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CoverageAuditEntry>(
+        "SELECT p.topic_id, p.topic_uid, l.action, u.full_name as changed_by, l.timestamp
+         FROM curriculum_completion_logs l
+         JOIN curriculum_progress p ON p.id = l.progress_id
+         LEFT JOIN users u ON u.id = l.changed_by
+         WHERE p.subject_code = $1 AND p.branch = $2 AND p.year = $3 AND p.section = $4 AND p.semester = $5
+         ORDER BY l.timestamp"
+    )
+    .bind(subject_code)
+    .bind(branch)
+    .bind(year)
+    .bind(section)
+    .bind(semester)
+    .fetch_all(pool)
+    .await
+}
+
+/// Saves a freshly generated report, replacing the previous draft. None when the existing report
+/// has already been signed.
+pub async fn upsert_report(pool: &PgPool, snapshot: &CoverageSnapshot, academic_semester_id: Uuid, faculty_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+    let json = serde_json::to_value(snapshot).unwrap_or_default();
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO syllabus_coverage_reports (subject_code, subject_name, regulation, branch, year, section, semester, academic_semester_id, faculty_id, snapshot)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+         ON CONFLICT (subject_code, branch, year, section, semester, academic_semester_id) DO UPDATE SET
+            subject_name = EXCLUDED.subject_name, regulation = EXCLUDED.regulation, faculty_id = EXCLUDED.faculty_id,
+            snapshot = EXCLUDED.snapshot, generated_at = NOW(), updated_at = NOW()
+         WHERE syllabus_coverage_reports.status IN ('DRAFT', 'RETURNED')
+         RETURNING id"
+    )
+    .bind(&snapshot.subject_code)
+    .bind(&snapshot.subject_name)
+    .bind(&snapshot.regulation)
+    .bind(&snapshot.branch)
+    .bind(&snapshot.year)
+    .bind(&snapshot.section)
+    .bind(snapshot.semester)
+    .bind(academic_semester_id)
+    .bind(faculty_id)
+    .bind(json)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn find_report(pool: &PgPool, id: Uuid) -> Result<Option<CoverageReport>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CoverageReport>(&format!("{} WHERE r.id = $1", REPORT_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn find_snapshot(pool: &PgPool, id: Uuid) -> Result<Option<serde_json::Value>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, serde_json::Value>("SELECT snapshot FROM syllabus_coverage_reports WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn find_reports(
+    pool: &PgPool,
+    branch_variations: &[String],
+    year: Option<&str>,
+    section: Option<&str>,
+    status: Option<&str>,
+    faculty_login: Option<&str>,
+) -> Result<Vec<CoverageReport>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CoverageReport>(&format!(
+        "{} WHERE r.branch = ANY($1) AND ($2::text IS NULL OR r.year = $2) AND ($3::text IS NULL OR r.section = $3)
+           AND ($4::text IS NULL OR r.status = $4) AND ($5::text IS NULL OR f.login_id = $5)
+         ORDER BY r.status = 'FACULTY_SIGNED' DESC, r.updated_at DESC",
+        REPORT_SELECT
+    ))
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(status)
+    .bind(faculty_login)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn sign_report(pool: &PgPool, id: Uuid, faculty_id: Uuid, statement: Option<&str>) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "UPDATE syllabus_coverage_reports
+         SET status = 'FACULTY_SIGNED', faculty_statement = $3, faculty_signed_at = NOW(), updated_at = NOW()
+         WHERE id = $1 AND faculty_id = $2 AND status IN ('DRAFT', 'RETURNED')"
+    )
+    .bind(id)
+    .bind(faculty_id)
+    .bind(statement)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+/// Sends a signed report back to the faculty member; their signature is withdrawn.
+pub async fn return_report(pool: &PgPool, id: Uuid, hod_id: Uuid, note: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "UPDATE syllabus_coverage_reports
+         SET status = 'RETURNED', hod_id = $2, hod_note = $3, faculty_signed_at = NULL, updated_at = NOW()
+         WHERE id = $1 AND status = 'FACULTY_SIGNED'"
+    )
+    .bind(id)
+    .bind(hod_id)
+    .bind(note)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+/// `signed_at` is the time printed on the archived PDF.
+pub async fn finalize_report(tx: &mut Transaction<'_, Postgres>, id: Uuid, hod_id: Uuid, note: Option<&str>, signed_at: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "UPDATE syllabus_coverage_reports
+         SET status = 'FINALIZED', hod_id = $2, hod_note = $3, hod_signed_at = $4, updated_at = NOW()
+         WHERE id = $1 AND status = 'FACULTY_SIGNED'"
+    )
+    .bind(id)
+    .bind(hod_id)
+    .bind(note)
+    .bind(signed_at)
+    .execute(&mut **tx)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+/// Archives the countersigned PDF with the snapshot it was rendered from; returns its SHA-256.
+pub async fn insert_archive(tx: &mut Transaction<'_, Postgres>, report_id: Uuid, file_name: &str, pdf: &[u8], hod_id: Uuid) -> Result<String, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, String>(
+        "INSERT INTO syllabus_coverage_archive (report_id, file_name, pdf, sha256, snapshot, faculty_id, hod_id)
+         SELECT r.id, $2, $3, encode(sha256($3), 'hex'), r.snapshot, r.faculty_id, $4
+         FROM syllabus_coverage_reports r WHERE r.id = $1
+         RETURNING sha256"
+    )
+    .bind(report_id)
+    .bind(file_name)
+    .bind(pdf)
+    .bind(hod_id)
+    .fetch_one(&mut **tx)
+    .await
+}
+
+/// (file name, PDF) of a finalized report.
+pub async fn find_archive(pool: &PgPool, report_id: Uuid) -> Result<Option<(String, Vec<u8>)>, sqlx::Error> {
+    sqlx::query_as::<Postgres, (String, Vec<u8>)>("SELECT file_name, pdf FROM syllabus_coverage_archive WHERE report_id = $1")
+        .bind(report_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// `recipient` is a login id.
+pub async fn insert_notification(pool: &PgPool, message: &str, recipient: &str, branch: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query("INSERT INTO notifications (type, message, recipient_id, branch, status) VALUES ('SYLLABUS_COVERAGE', $1, $2, $3, 'UNREAD')")
+        .bind(message)
+        .bind(recipient)
+        .bind(branch)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// Login ids of the branch's HODs.
+pub async fn find_hods(pool: &PgPool, branch_variations: &[String]) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, String>("SELECT login_id FROM users WHERE role = 'HOD' AND branch = ANY($1)")
+        .bind(branch_variations)
+        .fetch_all(pool)
+        .await
+}
@@ -1,6 +1,90 @@
-use sqlx::{PgPool, Postgres};
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
-use crate::models::curriculum::{CurriculumProgressRow, UpdateProgressRequest, SubmitFeedbackRequest};
+use crate::models::curriculum::{
+    CurriculumProgressRow, CurriculumRegulation, CurriculumRevision, CurriculumTopicRow, CurriculumUnitRow,
+    UpdateProgressRequest, SubmitFeedbackRequest,
+};
+
+const REVISION_COLUMNS: &str = "id, regulation, branch_code, semester, subject_code, subject_name, subject_type, total_periods, revision, is_current, imported_at";
+
+/// SQL for the stable identity of a subject's topic in its current revision; $1 topic id, $2 subject code.
+const CURRENT_TOPIC_UID: &str = "(SELECT t.topic_uid FROM curriculum_topics t
+    JOIN curriculum_subject_revisions r ON r.id = t.revision_id
+    WHERE r.is_current AND r.subject_code = $2 AND t.topic_key = $1 LIMIT 1)";
+
+pub async fn find_regulations(pool: &PgPool) -> Result<Vec<CurriculumRegulation>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CurriculumRegulation>(
+        "SELECT g.code, COUNT(DISTINCT r.branch_code) as branches, COUNT(r.id) as subjects
+         FROM curriculum_regulations g
+         LEFT JOIN curriculum_subject_revisions r ON r.regulation = g.code AND r.is_current
+         GROUP BY g.code ORDER BY g.code"
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// The subject's current revision, preferring the given regulation and branch. Subjects shared
+/// across branches, or asked for under the wrong regulation, still resolve.
+pub async fn find_current_revision(pool: &PgPool, regulation: &str, branch_code: &str, subject_code: &str) -> Result<Option<CurriculumRevision>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CurriculumRevision>(&format!(
+        "SELECT {} FROM curriculum_subject_revisions
+         WHERE subject_code = $3 AND is_current
+         ORDER BY (regulation = $1) DESC, (branch_code = $2) DESC LIMIT 1",
+        REVISION_COLUMNS
+    ))
+    .bind(regulation)
+    .bind(branch_code)
+    .bind(subject_code)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn find_current_subjects(pool: &PgPool, regulation: &str, branch_code: &str, semester: Option<i32>) -> Result<Vec<CurriculumRevision>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CurriculumRevision>(&format!(
+        "SELECT {} FROM curriculum_subject_revisions
+         WHERE regulation = $1 AND branch_code = $2 AND is_current AND ($3::int IS NULL OR semester = $3)
+         ORDER BY semester, subject_type DESC, subject_code",
+        REVISION_COLUMNS
+    ))
+    .bind(regulation)
+    .bind(branch_code)
+    .bind(semester)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_revisions(pool: &PgPool, regulation: &str, branch_code: &str, subject_code: &str) -> Result<Vec<CurriculumRevision>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CurriculumRevision>(&format!(
+        "SELECT {} FROM curriculum_subject_revisions
+         WHERE regulation = $1 AND branch_code = $2 AND subject_code = $3
+         ORDER BY revision DESC",
+        REVISION_COLUMNS
+    ))
+    .bind(regulation)
+    .bind(branch_code)
+    .bind(subject_code)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_revision_units(pool: &PgPool, revision_id: Uuid) -> Result<Vec<CurriculumUnitRow>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CurriculumUnitRow>(
+        "SELECT id, unit_no, title, total_periods FROM curriculum_units WHERE revision_id = $1 ORDER BY unit_no"
+    )
+    .bind(revision_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_revision_topics(pool: &PgPool, revision_id: Uuid) -> Result<Vec<CurriculumTopicRow>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CurriculumTopicRow>(
+        "SELECT unit_id, topic_uid, topic_key, sno, topic, periods, topic_type
+         FROM curriculum_topics WHERE revision_id = $1 ORDER BY order_index"
+    )
+    .bind(revision_id)
+    .fetch_all(pool)
+    .await
+}
 
 pub async fn get_progress(
     pool: &PgPool,
@@ -21,24 +105,45 @@ pub async fn get_progress(
     .await
 }
 
+/// Status of the section's progress row for the topic before an update, if it has one.
+pub async fn find_progress_status(tx: &mut Transaction<'_, Postgres>, req: &UpdateProgressRequest) -> Result<Option<Option<String>>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Option<String>>(
+        "SELECT status FROM curriculum_progress
+         WHERE topic_id = $1 AND subject_code = $2 AND branch = $3 AND section = $4 AND year = $5 AND semester = $6
+         FOR UPDATE"
+    )
+    .bind(&req.topic_id)
+    .bind(&req.subject_code)
+    .bind(&req.branch)
+    .bind(&req.section)
+    .bind(&req.year)
+    .bind(req.semester)
+    .fetch_optional(&mut **tx)
+    .await
+}
+
 pub async fn upsert_progress(
-    pool: &PgPool,
-    req: UpdateProgressRequest,
-) -> Result<u64, sqlx::Error> {
-    sqlx::query(
+    tx: &mut Transaction<'_, Postgres>,
+    req: &UpdateProgressRequest,
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(&format!(
         "INSERT INTO curriculum_progress (
             topic_id, subject_code, faculty_id, branch, section, year, semester, 
-            assigned_date, completed_date, status, remarks
-         ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            assigned_date, completed_date, status, remarks, topic_uid
+         ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, {})
          ON CONFLICT (topic_id, subject_code, branch, section, year, semester)
          DO UPDATE SET 
+            topic_uid = COALESCE(EXCLUDED.topic_uid, curriculum_progress.topic_uid),
             faculty_id = EXCLUDED.faculty_id,
             assigned_date = EXCLUDED.assigned_date,
+            date_from_plan = curriculum_progress.date_from_plan AND curriculum_progress.assigned_date IS NOT DISTINCT FROM EXCLUDED.assigned_date,
             completed_date = EXCLUDED.completed_date,
             status = EXCLUDED.status,
             remarks = EXCLUDED.remarks,
-            updated_at = NOW()"
-    )
+            updated_at = NOW()
+         RETURNING id",
+        CURRENT_TOPIC_UID
+    ))
     .bind(&req.topic_id)
     .bind(&req.subject_code)
     .bind(req.faculty_id)
@@ -50,9 +155,89 @@ pub async fn upsert_progress(
     .bind(req.completed_date)
     .bind(&req.status)
     .bind(&req.remarks)
-    .execute(pool)
+    .fetch_one(&mut **tx)
+    .await
+}
+
+pub async fn insert_completion_log(tx: &mut Transaction<'_, Postgres>, progress_id: Uuid, action: &str, changed_by: Option<Uuid>) -> Result<u64, sqlx::Error> {
+    sqlx::query("INSERT INTO curriculum_completion_logs (progress_id, action, changed_by) VALUES ($1, $2, $3)")
+        .bind(progress_id)
+        .bind(action)
+        .bind(changed_by)
+        .execute(&mut **tx)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// Status of the section's progress row for a lesson plan item before an update, if it has one.
+pub async fn find_lesson_item_status(tx: &mut Transaction<'_, Postgres>, item_id: &str, section: &str) -> Result<Option<Option<String>>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Option<String>>(
+        "SELECT status FROM curriculum_progress WHERE lesson_plan_item_id = $1 AND section = $2 FOR UPDATE"
+    )
+    .bind(item_id)
+    .bind(section)
+    .fetch_optional(&mut **tx)
+    .await
+}
+
+/// Records a lesson plan item's progress for a section. The branch and semester come from the
+/// item's subject and the year from the semester, as in the migration that merged the old rows.
+/// None when the item does not exist.
+pub async fn upsert_lesson_item_progress(
+    tx: &mut Transaction<'_, Postgres>,
+    item_id: &str,
+    section: &str,
+    completed: bool,
+    faculty_id: Option<Uuid>,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO curriculum_progress (
+            topic_id, subject_code, faculty_id, branch, section, year, semester, completed_date, status, lesson_plan_item_id
+         )
+         SELECT lpi.id, lpi.subject_id, $4, s.branch, $2,
+                CASE WHEN sem.n <= 2 THEN '1st Year' WHEN sem.n <= 4 THEN '2nd Year' ELSE '3rd Year' END, sem.n,
+                CASE WHEN $3 THEN CURRENT_DATE END, CASE WHEN $3 THEN 'completed' ELSE 'pending' END, lpi.id
+         FROM lesson_plan_items lpi
+         JOIN subjects s ON s.id = lpi.subject_id
+         CROSS JOIN LATERAL (SELECT COALESCE(SUBSTRING(s.semester FROM '[1-6]')::INT, 1) AS n) sem
+         WHERE lpi.id = $1
+         ON CONFLICT (topic_id, subject_code, branch, section, year, semester)
+         DO UPDATE SET
+            faculty_id = COALESCE(EXCLUDED.faculty_id, curriculum_progress.faculty_id),
+            completed_date = EXCLUDED.completed_date,
+            status = EXCLUDED.status,
+            lesson_plan_item_id = EXCLUDED.lesson_plan_item_id,
+            updated_at = NOW()
+         RETURNING id"
+    )
+    .bind(item_id)
+    .bind(section)
+    .bind(completed)
+    .bind(faculty_id)
+    .fetch_optional(&mut **tx)
+    .await
+}
+
+/// (items, completed, due by today, scheduled at all) over the subject's lesson plan items for a
+/// section; unit headings are not counted.
+pub async fn get_lesson_plan_stats(pool: &PgPool, subject_id: &str, section: &str) -> Result<(i64, i64, i64, i64), sqlx::Error> {
+    sqlx::query_as::<Postgres, (i64, i64, i64, i64)>(
+        "SELECT COUNT(lpi.id),
+                COUNT(*) FILTER (WHERE cp.status = 'completed'),
+                COUNT(*) FILTER (WHERE ls.schedule_date <= NOW()),
+                COUNT(ls.schedule_date)
+         FROM lesson_plan_items lpi
+         LEFT JOIN curriculum_progress cp ON cp.lesson_plan_item_id = lpi.id AND cp.section = $2
+         LEFT JOIN LATERAL (
+             SELECT MIN(schedule_date) AS schedule_date FROM lesson_schedule
+             WHERE topic_id = lpi.id AND section = $2
+         ) ls ON TRUE
+         WHERE lpi.subject_id = $1 AND LOWER(lpi.type) != 'unit'"
+    )
+    .bind(subject_id)
+    .bind(section)
+    .fetch_one(pool)
     .await
-    .map(|r| r.rows_affected())
 }
 
 pub async fn insert_feedback(
@@ -71,11 +256,12 @@ pub async fn insert_feedback(
         }
     });
 
-    sqlx::query(
+    sqlx::query(&format!(
         "INSERT INTO student_curriculum_feedback (
-            topic_id, subject_code, student_id, understood, rating, issue_type, comment
-         ) VALUES ($1, $2, $3, $4, $5, $6, $7)"
-    )
+            topic_id, subject_code, student_id, understood, rating, issue_type, comment, topic_uid
+         ) VALUES ($1, $2, $3, $4, $5, $6, $7, {})",
+        CURRENT_TOPIC_UID
+    ))
     .bind(&req.topic_id)
     .bind(&req.subject_code)
     .bind(student_id)
@@ -88,16 +274,17 @@ pub async fn insert_feedback(
     .map(|r| r.rows_affected())
 }
 
+/// Feedback per topic, keyed by the topic's stable identity when known, else by its topic id.
 pub async fn get_topic_feedback_stats(
     pool: &PgPool,
     subject_code: &str,
 ) -> Result<Vec<(String, i32, f64)>, sqlx::Error> {
     sqlx::query_as::<_, (String, i32, f64)>(
-        "SELECT topic_id, COUNT(*)::INT as count, 
+        "SELECT COALESCE(topic_uid::text, topic_id), COUNT(*)::INT as count, 
          AVG(CASE WHEN (rating >= 4 OR issue_type = 'DONE' OR understood = TRUE) THEN 100 ELSE 0 END)::FLOAT as understood_percentage
          FROM student_curriculum_feedback
          WHERE subject_code = $1
-         GROUP BY topic_id"
+         GROUP BY COALESCE(topic_uid::text, topic_id)"
     )
     .bind(subject_code)
     .fetch_all(pool)
@@ -0,0 +1,202 @@
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+use chrono::NaiveDate;
+use crate::models::leave::{ApplyLeaveRequest, LeaveApplication};
+
+const LEAVE_SELECT: &str = r#"
+    SELECT
+        l.id, l.student_id, s.login_id as student_login_id, s.full_name as student_name, s.branch, s.section,
+        l.applied_by, l.applicant_role, l.parent_request_id, l.leave_type,
+        l.from_date, l.from_session, l.to_date, l.to_session, l.reason, l.status,
+        l.assigned_to, a.full_name as assigned_name, l.reviewed_by, l.review_note, l.reviewed_at, l.created_at
+    FROM leave_applications l
+    LEFT JOIN users s ON l.student_id = s.id
+    LEFT JOIN users a ON l.assigned_to = a.id
+"#;
+
+/// Returns (login_id, role, full_name, branch, year, section).
+pub async fn find_user_basics(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Option<(String, String, String, Option<String>, Option<String>, Option<String>)>, sqlx::Error> {
+    sqlx::query_as("SELECT login_id, role, full_name, branch, year, section FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn is_parent_of(pool: &PgPool, parent_login_id: &str, student_login_id: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM parent_student WHERE parent_id = $1 AND student_id = $2) OR $1 = 'P-' || $2")
+        .bind(parent_login_id)
+        .bind(student_login_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Class incharge for the student's section, returned as (id, login_id).
+pub async fn find_section_incharge(
+    pool: &PgPool,
+    branch_variations: &[String],
+    year: Option<&str>,
+    section: Option<&str>,
+) -> Result<Option<(Uuid, String)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id, login_id FROM users
+         WHERE role = 'Incharge' AND branch = ANY($1) AND section = $2 AND (year = $3 OR $3 IS NULL OR year IS NULL)
+         LIMIT 1"
+    )
+    .bind(branch_variations)
+    .bind(section)
+    .bind(year)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn find_hod(pool: &PgPool, branch_variations: &[String]) -> Result<Option<(Uuid, String)>, sqlx::Error> {
+    sqlx::query_as("SELECT id, login_id FROM users WHERE role = 'HOD' AND branch = ANY($1) LIMIT 1")
+        .bind(branch_variations)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn count_overlapping(pool: &PgPool, student_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM leave_applications
+         WHERE student_id = $1 AND status IN ('PENDING', 'APPROVED') AND from_date <= $3 AND to_date >= $2"
+    )
+    .bind(student_id)
+    .bind(from)
+    .bind(to)
+    .fetch_one(pool)
+    .await
+}
+
+/// Mirrors a parent's leave application into `parent_requests` so it appears in the parent's request list.
+pub async fn insert_parent_leave_request(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    parent_id: Uuid,
+    student_id: Uuid,
+    subject: &str,
+    description: &str,
+    date_duration: &str,
+    assigned_to: Option<Uuid>,
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar(
+        "INSERT INTO parent_requests (parent_id, student_id, request_type, subject, description, date_duration, status, assigned_to)
+         VALUES ($1, $2, 'Leave', $3, $4, $5, 'Pending', $6) RETURNING id"
+    )
+    .bind(parent_id)
+    .bind(student_id)
+    .bind(subject)
+    .bind(description)
+    .bind(date_duration)
+    .bind(assigned_to)
+    .fetch_one(&mut **executor)
+    .await
+}
+
+/// Whether the parent request mirrors a leave application.
+pub async fn is_leave_mirror(pool: &PgPool, parent_request_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM leave_applications WHERE parent_request_id = $1)")
+        .bind(parent_request_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn insert_leave(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    student_id: Uuid,
+    applied_by: Uuid,
+    applicant_role: &str,
+    parent_request_id: Option<Uuid>,
+    assigned_to: Option<Uuid>,
+    payload: &ApplyLeaveRequest,
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar(
+        "INSERT INTO leave_applications (
+            student_id, applied_by, applicant_role, parent_request_id, leave_type,
+            from_date, from_session, to_date, to_session, reason, assigned_to
+         ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING id"
+    )
+    .bind(student_id)
+    .bind(applied_by)
+    .bind(applicant_role)
+    .bind(parent_request_id)
+    .bind(payload.leave_type.as_deref().unwrap_or("Personal"))
+    .bind(payload.from_date)
+    .bind(payload.from_session.as_deref().unwrap_or("MORNING"))
+    .bind(payload.to_date)
+    .bind(payload.to_session.as_deref().unwrap_or("AFTERNOON"))
+    .bind(&payload.reason)
+    .bind(assigned_to)
+    .fetch_one(&mut **executor)
+    .await
+}
+
+pub async fn find_leave_by_id(pool: &PgPool, id: Uuid) -> Result<Option<LeaveApplication>, sqlx::Error> {
+    let mut query = QueryBuilder::new(LEAVE_SELECT);
+    query.push(" WHERE l.id = ");
+    query.push_bind(id);
+    query.build_query_as::<LeaveApplication>().fetch_optional(pool).await
+}
+
+pub async fn find_leaves(
+    pool: &PgPool,
+    student_id: Option<Uuid>,
+    reviewer_id: Option<Uuid>,
+    branch_variations: Option<&[String]>,
+    status: Option<&str>,
+) -> Result<Vec<LeaveApplication>, sqlx::Error> {
+    let mut query = QueryBuilder::new(LEAVE_SELECT);
+    query.push(" WHERE 1=1");
+    if let Some(s) = student_id {
+        query.push(" AND l.student_id = ");
+        query.push_bind(s);
+    }
+    if let Some(r) = reviewer_id {
+        query.push(" AND l.assigned_to = ");
+        query.push_bind(r);
+    }
+    if let Some(b) = branch_variations {
+        query.push(" AND s.branch = ANY(");
+        query.push_bind(b);
+        query.push(")");
+    }
+    if let Some(st) = status {
+        query.push(" AND l.status = ");
+        query.push_bind(st);
+    }
+    query.push(" ORDER BY l.created_at DESC");
+    query.build_query_as::<LeaveApplication>().fetch_all(pool).await
+}
+
+pub async fn update_leave_status(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    id: Uuid,
+    status: &str,
+    reviewed_by: Option<Uuid>,
+    note: Option<&str>,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "UPDATE leave_applications
+         SET status = $1, reviewed_by = COALESCE($2, reviewed_by), review_note = COALESCE($3, review_note),
+             reviewed_at = CASE WHEN $2 IS NULL THEN reviewed_at ELSE NOW() END, updated_at = NOW()
+         WHERE id = $4 AND status = 'PENDING'"
+    )
+    .bind(status)
+    .bind(reviewed_by)
+    .bind(note)
+    .bind(id)
+    .execute(&mut **executor)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn update_parent_request_status(executor: &mut sqlx::Transaction<'_, Postgres>, request_id: Uuid, status: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query("UPDATE parent_requests SET status = $1, updated_at = NOW() WHERE id = $2")
+        .bind(status)
+        .bind(request_id)
+        .execute(&mut **executor)
+        .await
+        .map(|r| r.rows_affected())
+}
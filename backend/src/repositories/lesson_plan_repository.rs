@@ -0,0 +1,123 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::lesson_plan::{LessonPlan, PlanSummary, PlanTarget, SubjectSlot};
+
+const PLAN_COLUMNS: &str = "id, subject_code, regulation, branch, year, section, semester, academic_semester_id, faculty_id,
+    plan_start, exam_start, weekly_periods, required_periods, available_periods, lost_periods, projected_finish, at_risk, generated_at";
+
+/// The section's weekly timetable slots for the subject, matched by code or by name.
+pub async fn find_subject_slots(pool: &PgPool, branch_variations: &[String], year: &str, section: &str, subject_code: &str, subject_name: &str) -> Result<Vec<SubjectSlot>, sqlx::Error> {
+    sqlx::query_as::<Postgres, SubjectSlot>(
+        "SELECT day, period_index, faculty_id FROM timetable_entries
+         WHERE branch = ANY($1) AND year = $2 AND section = $3
+           AND (subject_code = $4 OR subject = $4 OR LOWER(subject) = LOWER($5))
+         ORDER BY day, period_index"
+    )
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(subject_code)
+    .bind(subject_name)
+    .fetch_all(pool)
+    .await
+}
+
+/// (date, period) of the section's periods recorded as not conducted in the range.
+pub async fn find_lost_periods(pool: &PgPool, branch_variations: &[String], year: &str, section: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<(NaiveDate, i32)>, sqlx::Error> {
+    sqlx::query_as::<Postgres, (NaiveDate, i32)>(
+        "SELECT status_date, period_index FROM class_period_status
+         WHERE branch = ANY($1) AND year = $2 AND section = $3 AND status = 'not_conducted'
+           AND status_date BETWEEN $4 AND $5"
+    )
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn upsert_plan(tx: &mut Transaction<'_, Postgres>, target: &PlanTarget, summary: &PlanSummary, generated_by: Option<Uuid>) -> Result<LessonPlan, sqlx::Error> {
+    sqlx::query_as::<Postgres, LessonPlan>(&format!(
+        "INSERT INTO lesson_plans (subject_code, regulation, branch, year, section, semester, academic_semester_id, faculty_id,
+            plan_start, exam_start, weekly_periods, required_periods, available_periods, lost_periods, projected_finish, at_risk, generated_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+         ON CONFLICT (subject_code, branch, year, section, semester) DO UPDATE SET
+            regulation = EXCLUDED.regulation, academic_semester_id = EXCLUDED.academic_semester_id, faculty_id = EXCLUDED.faculty_id,
+            plan_start = EXCLUDED.plan_start, exam_start = EXCLUDED.exam_start, weekly_periods = EXCLUDED.weekly_periods,
+            required_periods = EXCLUDED.required_periods, available_periods = EXCLUDED.available_periods,
+            lost_periods = EXCLUDED.lost_periods, projected_finish = EXCLUDED.projected_finish, at_risk = EXCLUDED.at_risk,
+            generated_by = COALESCE(EXCLUDED.generated_by, lesson_plans.generated_by), generated_at = NOW()
+         RETURNING {}",
+        PLAN_COLUMNS
+    ))
+    .bind(&target.subject_code)
+    .bind(&target.regulation)
+    .bind(&target.branch)
+    .bind(&target.year)
+    .bind(&target.section)
+    .bind(target.semester)
+    .bind(target.academic_semester_id)
+    .bind(target.faculty_id)
+    .bind(summary.plan_start)
+    .bind(summary.exam_start)
+    .bind(summary.weekly_periods)
+    .bind(summary.required_periods)
+    .bind(summary.available_periods)
+    .bind(summary.lost_periods)
+    .bind(summary.projected_finish)
+    .bind(summary.at_risk)
+    .bind(generated_by)
+    .fetch_one(&mut **tx)
+    .await
+}
+
+/// Sets a topic's planned date. Topics already completed keep the date they were planned for.
+pub async fn set_assigned_date(tx: &mut Transaction<'_, Postgres>, target: &PlanTarget, topic_id: &str, topic_uid: Option<Uuid>, date: Option<NaiveDate>) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO curriculum_progress (topic_id, subject_code, faculty_id, branch, section, year, semester, assigned_date, status, topic_uid, date_from_plan)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'pending', $9, TRUE)
+         ON CONFLICT (topic_id, subject_code, branch, section, year, semester) DO UPDATE SET
+            assigned_date = EXCLUDED.assigned_date,
+            date_from_plan = TRUE,
+            topic_uid = COALESCE(curriculum_progress.topic_uid, EXCLUDED.topic_uid),
+            updated_at = NOW()
+         WHERE curriculum_progress.status IS DISTINCT FROM 'completed'"
+    )
+    .bind(topic_id)
+    .bind(&target.subject_code)
+    .bind(target.faculty_id)
+    .bind(&target.branch)
+    .bind(&target.section)
+    .bind(&target.year)
+    .bind(target.semester)
+    .bind(date)
+    .bind(topic_uid)
+    .execute(&mut **tx)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn find_plans(pool: &PgPool, branch_variations: &[String], year: Option<&str>, section: Option<&str>, at_risk_only: bool) -> Result<Vec<LessonPlan>, sqlx::Error> {
+    sqlx::query_as::<Postgres, LessonPlan>(&format!(
+        "SELECT {} FROM lesson_plans
+         WHERE branch = ANY($1) AND ($2::text IS NULL OR year = $2) AND ($3::text IS NULL OR section = $3)
+           AND (NOT $4 OR at_risk)
+         ORDER BY year, section, subject_code",
+        PLAN_COLUMNS
+    ))
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(at_risk_only)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_all_plans(pool: &PgPool) -> Result<Vec<LessonPlan>, sqlx::Error> {
+    sqlx::query_as::<Postgres, LessonPlan>(&format!("SELECT {} FROM lesson_plans ORDER BY branch, year, section, subject_code", PLAN_COLUMNS))
+        .fetch_all(pool)
+        .await
+}
@@ -95,7 +95,7 @@ pub async fn get_dashboard_stats(pool: &PgPool) -> Result<serde_json::Value, sql
     let today_records: (i64, i64) = sqlx::query_as(
         "SELECT 
             COUNT(CASE WHEN status IN ('P', 'present', 'PRESENT', 'p') THEN 1 END)::bigint as present_cnt,
-            COUNT(*)::bigint as total_cnt
+            COUNT(CASE WHEN status <> 'L' THEN 1 END)::bigint as total_cnt
          FROM attendance 
          WHERE date = CURRENT_DATE"
     ).fetch_one(pool).await.unwrap_or((0, 0));
@@ -106,7 +106,7 @@ pub async fn get_dashboard_stats(pool: &PgPool) -> Result<serde_json::Value, sql
         let overall_records: (i64, i64) = sqlx::query_as(
             "SELECT 
                 COUNT(CASE WHEN status IN ('P', 'present', 'PRESENT', 'p') THEN 1 END)::bigint as present_cnt,
-                COUNT(*)::bigint as total_cnt
+                COUNT(CASE WHEN status <> 'L' THEN 1 END)::bigint as total_cnt
              FROM attendance"
         ).fetch_one(pool).await.unwrap_or((0, 0));
 
@@ -107,7 +107,7 @@ pub async fn find_timetable_entries_by_day(pool: &PgPool, branch: &str, day: &st
     sqlx::query_as::<Postgres, TimetableEntry>(
         r#"
         SELECT 
-            t.id, t.faculty_id, t.branch, t.year, t.section, t.day, t.period_index, t.subject, t.subject_code,
+            t.id, t.faculty_id, t.branch, t.year, t.section, t.day, t.period_index, t.subject, t.subject_code, t.room,
             u.full_name as faculty_name, u.email as faculty_email, u.phone_number as faculty_phone, u.branch as faculty_department
         FROM timetable_entries t
         LEFT JOIN users u ON t.faculty_id = u.login_id
@@ -170,24 +170,6 @@ pub async fn find_subjects_by_year_fallback(pool: &PgPool, branch: &str, course_
     .map(|rows| rows.into_iter().map(|r| (r.get("id"), r.get("name"))).collect())
 }
 
-pub async fn get_subject_progress_stats(pool: &PgPool, subject_id: &str, section: &str) -> Result<(i64, i64, i64), sqlx::Error> {
-    let row = sqlx::query(
-        r#"
-        SELECT 
-            COUNT(lpi.id) as total_topics,
-            COUNT(CASE WHEN lp.completed = TRUE THEN 1 END) as completed_topics,
-            COUNT(CASE WHEN ls.schedule_date <= NOW() AND LOWER(lpi.type) != 'unit' THEN 1 END) as scheduled_topics
-        FROM lesson_plan_items lpi
-        LEFT JOIN lesson_plan_progress lp ON lpi.id = lp.item_id AND lp.section = $2
-        LEFT JOIN lesson_schedule ls ON lpi.id = ls.topic_id AND ls.section = $2
-        WHERE lpi.subject_id = $1 AND LOWER(lpi.type) != 'unit'
-        "#
-    )
-    .bind(subject_id).bind(section).fetch_one(pool).await?;
-    
-    Ok((row.get("total_topics"), row.get("completed_topics"), row.get("scheduled_topics")))
-}
-
 pub async fn find_faculty_assignment(pool: &PgPool, branch: &str, year: &str, section: &str, subject_name: &str) -> Result<Option<(String, String)>, sqlx::Error> {
     let row = sqlx::query(
         "SELECT u.login_id, u.full_name FROM timetable_entries t
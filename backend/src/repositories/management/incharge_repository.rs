@@ -1,20 +1,28 @@
 use sqlx::{PgPool, Postgres, Row};
 use chrono::NaiveDate;
+use uuid::Uuid;
 use serde_json;
 use crate::models::{TimetableEntry, ClassPeriodStatus};
+use crate::models::live_status::UnattendedPeriod;
+use crate::repositories::timetable_repository::grid_on_date;
 
-pub async fn find_timetable_entry(pool: &PgPool, branch: &str, year: &str, section: &str, day: &str, period_index: i32) -> Result<Option<TimetableEntry>, sqlx::Error> {
+/// `version_id` selects a timetable version snapshot; `None` reads the live grid.
+pub async fn find_timetable_entry(pool: &PgPool, branch: &str, year: &str, section: &str, day: &str, period_index: i32, version_id: Option<Uuid>) -> Result<Option<TimetableEntry>, sqlx::Error> {
     sqlx::query_as::<_, TimetableEntry>(
         r#"
         SELECT 
-            t.id, t.faculty_id, t.branch, t.year, t.section, t.day, t.period_index, t.subject, t.subject_code,
+            t.id, t.faculty_id, t.branch, t.year, t.section, t.day, t.period_index, t.subject, t.subject_code, t.room,
             u.full_name as faculty_name, u.email as faculty_email, u.phone_number as faculty_phone, u.branch as faculty_department
-        FROM timetable_entries t
+        FROM (
+            SELECT id, faculty_id, branch, year, section, day, period_index, subject, subject_code, room FROM timetable_entries WHERE $6::uuid IS NULL
+            UNION ALL
+            SELECT id, faculty_id, $1, year, section, day, period_index, subject, subject_code, lab_room FROM timetable_version_entries WHERE version_id = $6
+        ) t
         LEFT JOIN users u ON t.faculty_id = u.login_id
         WHERE t.branch = $1 AND t.year = $2 AND t.section = $3 AND t.day = $4 AND t.period_index = $5
         "#
     )
-    .bind(branch).bind(year).bind(section).bind(day).bind(period_index)
+    .bind(branch).bind(year).bind(section).bind(day).bind(period_index).bind(version_id)
     .fetch_optional(pool).await
 }
 
@@ -33,6 +41,11 @@ pub async fn upsert_class_status(pool: &PgPool, branch: &str, year: &str, sectio
     .execute(pool).await.map(|r| r.rows_affected())
 }
 
+pub async fn find_period_status(pool: &PgPool, branch: &str, year: &str, section: &str, day: &str, period_index: i32, status_date: NaiveDate) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>("SELECT status FROM class_period_status WHERE branch = $1 AND year = $2 AND section = $3 AND day = $4 AND period_index = $5 AND status_date = $6")
+        .bind(branch).bind(year).bind(section).bind(day).bind(period_index).bind(status_date).fetch_optional(pool).await
+}
+
 pub async fn find_class_statuses(pool: &PgPool, branch: &str, year: &str, section: &str, date: NaiveDate) -> Result<Vec<ClassPeriodStatus>, sqlx::Error> {
     sqlx::query_as::<_, ClassPeriodStatus>("SELECT * FROM class_period_status WHERE branch = $1 AND year = $2 AND section = $3 AND status_date = $4")
         .bind(branch).bind(year).bind(section).bind(date).fetch_all(pool).await
@@ -45,18 +58,23 @@ pub async fn get_daily_activity_stats(pool: &PgPool, branch: &str, date: NaiveDa
     Ok((row.get("total"), row.get("conducted"), row.get("substitute"), row.get("not_conducted")))
 }
 
-pub async fn find_daily_detail_report(pool: &PgPool, branch: &str, date: NaiveDate, day: &str) -> Result<Vec<serde_json::Value>, sqlx::Error> {
+/// `version_id` selects a timetable version snapshot; `None` reads the live grid.
+pub async fn find_daily_detail_report(pool: &PgPool, branch: &str, date: NaiveDate, day: &str, version_id: Option<Uuid>) -> Result<Vec<serde_json::Value>, sqlx::Error> {
     let rows = sqlx::query(
         r#"
         SELECT t.year, t.section, t.period_index, t.subject, t.faculty_id, u.full_name as original_faculty, s.actual_subject, s.actual_faculty, s.status
-        FROM timetable_entries t
+        FROM (
+            SELECT branch, year, section, day, period_index, subject, faculty_id FROM timetable_entries WHERE $4::uuid IS NULL
+            UNION ALL
+            SELECT $1, year, section, day, period_index, subject, faculty_id FROM timetable_version_entries WHERE version_id = $4
+        ) t
         LEFT JOIN users u ON t.faculty_id = u.login_id
         LEFT JOIN class_period_status s ON t.branch = s.branch AND t.year = s.year AND t.section = s.section AND t.day = s.day AND t.period_index = s.period_index AND s.status_date = $2
-        WHERE t.branch = $1 AND t.day = $3
+        WHERE t.branch = $1 AND t.day = $3 AND is_instructional_day($2, $1, t.year)
         ORDER BY t.year, t.section, t.period_index
         "#
     )
-    .bind(branch).bind(date).bind(day).fetch_all(pool).await?;
+    .bind(branch).bind(date).bind(day).bind(version_id).fetch_all(pool).await?;
 
     Ok(rows.into_iter().map(|row| serde_json::json!({
         "year": row.get::<String, _>("year"), 
@@ -69,3 +87,36 @@ pub async fn find_daily_detail_report(pool: &PgPool, branch: &str, date: NaiveDa
         "status": row.get::<Option<String>, _>("status"),
     })).collect())
 }
+
+pub async fn find_timetable_branches(pool: &PgPool) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, String>("SELECT DISTINCT branch FROM timetable_entries ORDER BY branch")
+        .fetch_all(pool)
+        .await
+}
+
+/// Class periods of the branch in the given periods of the date, per the timetable in effect then,
+/// that have no status recorded yet. Holidays and other non-instructional days yield nothing.
+pub async fn find_unattended_periods(pool: &PgPool, branch: &str, date: NaiveDate, day: &str, periods: &[i32]) -> Result<Vec<UnattendedPeriod>, sqlx::Error> {
+    sqlx::query_as::<Postgres, UnattendedPeriod>(&format!(
+        r#"
+        SELECT g.branch, g.year, g.section, g.period_index, g.subject, g.faculty_id, u.full_name as faculty_name
+        FROM ({}) g
+        LEFT JOIN users u ON u.login_id = g.faculty_id
+        WHERE g.branch = $1 AND g.day = $3 AND g.period_index = ANY($4) AND g.year <> 'Lab'
+          AND is_instructional_day($2, g.branch, g.year)
+          AND NOT EXISTS (
+              SELECT 1 FROM class_period_status s
+              WHERE s.branch = g.branch AND s.year = g.year AND s.section = g.section
+                AND s.period_index = g.period_index AND s.status_date = $2
+          )
+        ORDER BY g.period_index, g.year, g.section
+        "#,
+        grid_on_date("$2::date")
+    ))
+    .bind(branch)
+    .bind(date)
+    .bind(day)
+    .bind(periods)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,189 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::material::{
+    CourseMaterial, CourseMaterialVersion, MaterialContent, MaterialDownload, MaterialDownloadStats, MaterialPlacement,
+};
+
+/// Materials with their current version, uploader and download count.
+const MATERIAL_SELECT: &str = "SELECT m.id, m.subject_code, m.branch, m.regulation, m.unit_no, m.topic_id, m.topic_uid, m.title,
+    m.description, m.kind, m.sections, m.current_version, v.file_name, v.content_type, v.size_bytes, v.url,
+    u.login_id as uploaded_by_login, u.full_name as uploaded_by_name,
+    (SELECT COUNT(*) FROM course_material_downloads d WHERE d.material_id = m.id) as downloads, m.archived, m.updated_at
+    FROM course_materials m
+    JOIN course_material_versions v ON v.material_id = m.id AND v.version = m.current_version
+    JOIN users u ON u.id = m.uploaded_by";
+
+/// The subject's materials for the branch, optionally only those a section can see or those on
+/// one unit or topic.
+pub async fn find_materials(
+    pool: &PgPool,
+    subject_code: &str,
+    branch_variations: &[String],
+    section: Option<&str>,
+    unit_no: Option<i32>,
+    topic_id: Option<&str>,
+    include_archived: bool,
+) -> Result<Vec<CourseMaterial>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CourseMaterial>(&format!(
+        "{} WHERE m.subject_code = $1 AND m.branch = ANY($2)
+           AND ($3::text IS NULL OR cardinality(m.sections) = 0 OR $3 = ANY(m.sections))
+           AND ($4::int IS NULL OR m.unit_no = $4) AND ($5::text IS NULL OR m.topic_id = $5)
+           AND ($6 OR NOT m.archived)
+         ORDER BY m.unit_no NULLS FIRST, m.topic_id NULLS FIRST, m.created_at",
+        MATERIAL_SELECT
+    ))
+    .bind(subject_code)
+    .bind(branch_variations)
+    .bind(section)
+    .bind(unit_no)
+    .bind(topic_id)
+    .bind(include_archived)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_material(pool: &PgPool, id: Uuid) -> Result<Option<CourseMaterial>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CourseMaterial>(&format!("{} WHERE m.id = $1", MATERIAL_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn insert_material(tx: &mut Transaction<'_, Postgres>, placement: &MaterialPlacement, kind: &str, uploaded_by: Uuid) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO course_materials (subject_code, branch, regulation, unit_no, topic_id, topic_uid, title, description, kind, sections, uploaded_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+         RETURNING id"
+    )
+    .bind(&placement.subject_code)
+    .bind(&placement.branch)
+    .bind(&placement.regulation)
+    .bind(placement.unit_no)
+    .bind(&placement.topic_id)
+    .bind(placement.topic_uid)
+    .bind(&placement.title)
+    .bind(&placement.description)
+    .bind(kind)
+    .bind(&placement.sections)
+    .bind(uploaded_by)
+    .fetch_one(&mut **tx)
+    .await
+}
+
+/// Makes the material's next version current and returns its number.
+pub async fn next_version(tx: &mut Transaction<'_, Postgres>, material_id: Uuid) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, i32>(
+        "UPDATE course_materials SET current_version = current_version + 1, updated_at = NOW()
+         WHERE id = $1 RETURNING current_version"
+    )
+    .bind(material_id)
+    .fetch_one(&mut **tx)
+    .await
+}
+
+pub async fn insert_version(tx: &mut Transaction<'_, Postgres>, material_id: Uuid, version: i32, content: &MaterialContent, uploaded_by: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO course_material_versions (material_id, version, storage_backend, storage_key, url, file_name, content_type, size_bytes, change_note, uploaded_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"
+    )
+    .bind(material_id)
+    .bind(version)
+    .bind(&content.storage_backend)
+    .bind(&content.storage_key)
+    .bind(&content.url)
+    .bind(&content.file_name)
+    .bind(&content.content_type)
+    .bind(content.size_bytes)
+    .bind(&content.change_note)
+    .bind(uploaded_by)
+    .execute(&mut **tx)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn update_sections(pool: &PgPool, id: Uuid, sections: &[String]) -> Result<u64, sqlx::Error> {
+    sqlx::query("UPDATE course_materials SET sections = $2, updated_at = NOW() WHERE id = $1")
+        .bind(id)
+        .bind(sections)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// Hides the material from students; its versions and download history are kept.
+pub async fn archive_material(pool: &PgPool, id: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query("UPDATE course_materials SET archived = TRUE, updated_at = NOW() WHERE id = $1 AND NOT archived")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+pub async fn find_versions(pool: &PgPool, material_id: Uuid) -> Result<Vec<CourseMaterialVersion>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CourseMaterialVersion>(
+        "SELECT v.version, v.file_name, v.content_type, v.size_bytes, v.url, v.change_note, u.full_name as uploaded_by_name,
+                (SELECT COUNT(*) FROM course_material_downloads d WHERE d.material_id = v.material_id AND d.version = v.version) as downloads,
+                v.created_at
+         FROM course_material_versions v
+         JOIN users u ON u.id = v.uploaded_by
+         WHERE v.material_id = $1
+         ORDER BY v.version DESC"
+    )
+    .bind(material_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// The requested version of the material, the current one when `version` is None.
+pub async fn find_download(pool: &PgPool, material_id: Uuid, version: Option<i32>) -> Result<Option<MaterialDownload>, sqlx::Error> {
+    sqlx::query_as::<Postgres, MaterialDownload>(
+        "SELECT m.kind, v.version, v.storage_backend, v.storage_key, v.url, v.file_name, v.content_type, m.branch, m.sections, m.archived
+         FROM course_materials m
+         JOIN course_material_versions v ON v.material_id = m.id AND v.version = COALESCE($2, m.current_version)
+         WHERE m.id = $1"
+    )
+    .bind(material_id)
+    .bind(version)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn insert_download(pool: &PgPool, material_id: Uuid, version: i32, user_id: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query("INSERT INTO course_material_downloads (material_id, version, user_id) VALUES ($1, $2, $3)")
+        .bind(material_id)
+        .bind(version)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// Downloads per material of the branch in the date range, most downloaded first. Materials
+/// nobody downloaded are listed with zero.
+pub async fn find_download_stats(
+    pool: &PgPool,
+    branch_variations: &[String],
+    subject_code: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<MaterialDownloadStats>, sqlx::Error> {
+    sqlx::query_as::<Postgres, MaterialDownloadStats>(
+        "SELECT m.id as material_id, m.subject_code, m.unit_no, m.topic_id, m.title, m.kind, m.sections,
+                u.login_id as uploaded_by_login, u.full_name as uploaded_by_name,
+                COUNT(d.id) as downloads, COUNT(DISTINCT d.user_id) as students, MAX(d.downloaded_at) as last_downloaded_at
+         FROM course_materials m
+         JOIN users u ON u.id = m.uploaded_by
+         LEFT JOIN course_material_downloads d ON d.material_id = m.id
+             AND ($3::date IS NULL OR d.downloaded_at >= $3) AND ($4::date IS NULL OR d.downloaded_at < $4 + 1)
+         WHERE m.branch = ANY($1) AND ($2::text IS NULL OR m.subject_code = $2) AND NOT m.archived
+         GROUP BY m.id, u.login_id, u.full_name
+         ORDER BY downloads DESC, m.subject_code, m.title"
+    )
+    .bind(branch_variations)
+    .bind(subject_code)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
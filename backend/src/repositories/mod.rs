@@ -3,3 +3,21 @@ pub mod management;
 pub mod common;
 pub mod auth;
 pub mod curriculum_repository;
+pub mod attendance_repository;
+pub mod biometric_repository;
+pub mod leave_repository;
+pub mod analytics_repository;
+pub mod timetable_repository;
+pub mod substitution_repository;
+pub mod calendar_feed_repository;
+pub mod academic_calendar_repository;
+pub mod room_repository;
+pub mod workload_repository;
+pub mod slot_template_repository;
+pub mod lesson_plan_repository;
+pub mod syllabus_forecast_repository;
+pub mod outcome_repository;
+pub mod regulation_repository;
+pub mod topic_feedback_repository;
+pub mod material_repository;
+pub mod coverage_repository;
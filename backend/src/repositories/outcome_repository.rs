@@ -0,0 +1,292 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use crate::models::outcome::{
+    AssessmentComponent, AssessmentMark, ComponentResult, CourseOutcome, OutcomeFeedback, PoLevel, ProgramOutcome,
+};
+
+const COURSE_OUTCOME_SELECT: &str = "SELECT co.id, co.subject_code, co.regulation, co.code, co.description,
+    COALESCE((SELECT jsonb_agg(jsonb_build_object('programOutcomeId', po.id, 'code', po.code, 'branch', po.branch, 'level', m.level) ORDER BY po.branch, po.kind, LENGTH(po.code), po.code)
+              FROM co_po_mappings m JOIN program_outcomes po ON po.id = m.program_outcome_id
+              WHERE m.course_outcome_id = co.id), '[]'::jsonb) as po_levels,
+    ARRAY(SELECT t.topic_uid FROM topic_co_mappings t WHERE t.course_outcome_id = co.id) as topic_uids
+    FROM course_outcomes co";
+
+const COMPONENT_SELECT: &str = "SELECT c.id, c.subject_code, c.regulation, c.name, c.max_marks, c.threshold_percent,
+    ARRAY(SELECT co.code FROM assessment_component_outcomes a JOIN course_outcomes co ON co.id = a.course_outcome_id
+          WHERE a.component_id = c.id ORDER BY LENGTH(co.code), co.code) as course_outcomes,
+    (SELECT COUNT(*) FROM assessment_marks m WHERE m.component_id = c.id) as marked_students
+    FROM assessment_components c";
+
+/// The branch's POs then PSOs, in number order.
+pub async fn find_program_outcomes(pool: &PgPool, branch: &str) -> Result<Vec<ProgramOutcome>, sqlx::Error> {
+    sqlx::query_as::<Postgres, ProgramOutcome>(
+        "SELECT id, branch, code, kind, description FROM program_outcomes
+         WHERE branch = $1 ORDER BY kind, LENGTH(code), code"
+    )
+    .bind(branch)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn upsert_program_outcome(pool: &PgPool, branch: &str, code: &str, kind: &str, description: &str) -> Result<ProgramOutcome, sqlx::Error> {
+    sqlx::query_as::<Postgres, ProgramOutcome>(
+        "INSERT INTO program_outcomes (branch, code, kind, description) VALUES ($1, $2, $3, $4)
+         ON CONFLICT (branch, code) DO UPDATE SET description = EXCLUDED.description
+         RETURNING id, branch, code, kind, description"
+    )
+    .bind(branch)
+    .bind(code)
+    .bind(kind)
+    .bind(description)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn find_program_outcome_branch(pool: &PgPool, id: Uuid) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, String>("SELECT branch FROM program_outcomes WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn delete_program_outcome(pool: &PgPool, id: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query("DELETE FROM program_outcomes WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+pub async fn find_course_outcomes(pool: &PgPool, subject_code: &str, regulation: &str) -> Result<Vec<CourseOutcome>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CourseOutcome>(&format!(
+        "{} WHERE co.subject_code = $1 AND co.regulation = $2 ORDER BY LENGTH(co.code), co.code",
+        COURSE_OUTCOME_SELECT
+    ))
+    .bind(subject_code)
+    .bind(regulation)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn upsert_course_outcome(tx: &mut Transaction<'_, Postgres>, subject_code: &str, regulation: &str, code: &str, description: &str) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO course_outcomes (subject_code, regulation, code, description) VALUES ($1, $2, $3, $4)
+         ON CONFLICT (subject_code, regulation, code) DO UPDATE SET description = EXCLUDED.description
+         RETURNING id"
+    )
+    .bind(subject_code)
+    .bind(regulation)
+    .bind(code)
+    .bind(description)
+    .fetch_one(&mut **tx)
+    .await
+}
+
+/// Replaces the CO's row of the articulation matrix and its topic mappings.
+pub async fn replace_course_outcome_mappings(tx: &mut Transaction<'_, Postgres>, id: Uuid, po_levels: &[PoLevel], topic_uids: &[Uuid]) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM co_po_mappings WHERE course_outcome_id = $1").bind(id).execute(&mut **tx).await?;
+    sqlx::query("DELETE FROM topic_co_mappings WHERE course_outcome_id = $1").bind(id).execute(&mut **tx).await?;
+    for p in po_levels {
+        sqlx::query("INSERT INTO co_po_mappings (course_outcome_id, program_outcome_id, level) VALUES ($1, $2, $3)")
+            .bind(id)
+            .bind(p.program_outcome_id)
+            .bind(p.level)
+            .execute(&mut **tx)
+            .await?;
+    }
+    sqlx::query("INSERT INTO topic_co_mappings (course_outcome_id, topic_uid) SELECT $1, UNNEST($2::uuid[]) ON CONFLICT DO NOTHING")
+        .bind(id)
+        .bind(topic_uids)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// (subject_code, regulation) of a course outcome.
+pub async fn find_course_outcome_subject(pool: &PgPool, id: Uuid) -> Result<Option<(String, String)>, sqlx::Error> {
+    sqlx::query_as::<Postgres, (String, String)>("SELECT subject_code, regulation FROM course_outcomes WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// The branch a subject belongs to: its current curriculum's folder code, else its row in subjects.
+pub async fn find_subject_branch(pool: &PgPool, subject_code: &str, regulation: &str) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Option<String>>(
+        "SELECT COALESCE(
+            (SELECT r.branch_code FROM curriculum_subject_revisions r WHERE r.subject_code = $1 AND r.regulation = $2 AND r.is_current LIMIT 1),
+            (SELECT s.branch FROM subjects s WHERE s.id = $1))"
+    )
+    .bind(subject_code)
+    .bind(regulation)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn delete_course_outcome(pool: &PgPool, id: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query("DELETE FROM course_outcomes WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// How many of the ids are topics of the subject.
+pub async fn count_subject_topics(pool: &PgPool, subject_code: &str, topic_uids: &[Uuid]) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, i64>("SELECT COUNT(*) FROM curriculum_topic_identities WHERE subject_code = $1 AND id = ANY($2)")
+        .bind(subject_code)
+        .bind(topic_uids)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn count_program_outcomes(pool: &PgPool, ids: &[Uuid]) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, i64>("SELECT COUNT(*) FROM program_outcomes WHERE id = ANY($1)")
+        .bind(ids)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn find_components(pool: &PgPool, subject_code: &str, regulation: &str) -> Result<Vec<AssessmentComponent>, sqlx::Error> {
+    sqlx::query_as::<Postgres, AssessmentComponent>(&format!(
+        "{} WHERE c.subject_code = $1 AND c.regulation = $2 ORDER BY c.created_at, c.name",
+        COMPONENT_SELECT
+    ))
+    .bind(subject_code)
+    .bind(regulation)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_component(pool: &PgPool, id: Uuid) -> Result<Option<AssessmentComponent>, sqlx::Error> {
+    sqlx::query_as::<Postgres, AssessmentComponent>(&format!("{} WHERE c.id = $1", COMPONENT_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Saves a component and links it to the subject's COs with the given codes; returns its id and
+/// how many of the codes matched a CO.
+pub async fn upsert_component(
+    tx: &mut Transaction<'_, Postgres>,
+    subject_code: &str,
+    regulation: &str,
+    name: &str,
+    max_marks: f64,
+    threshold_percent: f64,
+    outcome_codes: &[String],
+) -> Result<(Uuid, u64), sqlx::Error> {
+    let id = sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO assessment_components (subject_code, regulation, name, max_marks, threshold_percent) VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (subject_code, regulation, name) DO UPDATE SET max_marks = EXCLUDED.max_marks, threshold_percent = EXCLUDED.threshold_percent
+         RETURNING id"
+    )
+    .bind(subject_code)
+    .bind(regulation)
+    .bind(name)
+    .bind(max_marks)
+    .bind(threshold_percent)
+    .fetch_one(&mut **tx)
+    .await?;
+    sqlx::query("DELETE FROM assessment_component_outcomes WHERE component_id = $1").bind(id).execute(&mut **tx).await?;
+    let linked = sqlx::query(
+        "INSERT INTO assessment_component_outcomes (component_id, course_outcome_id)
+         SELECT $1, id FROM course_outcomes WHERE subject_code = $2 AND regulation = $3 AND code = ANY($4)"
+    )
+    .bind(id)
+    .bind(subject_code)
+    .bind(regulation)
+    .bind(outcome_codes)
+    .execute(&mut **tx)
+    .await?
+    .rows_affected();
+    Ok((id, linked))
+}
+
+pub async fn delete_component(pool: &PgPool, id: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query("DELETE FROM assessment_components WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+pub async fn find_marks(pool: &PgPool, component_id: Uuid) -> Result<Vec<AssessmentMark>, sqlx::Error> {
+    sqlx::query_as::<Postgres, AssessmentMark>(
+        "SELECT m.student_id, u.login_id, u.full_name, m.marks
+         FROM assessment_marks m JOIN users u ON u.id = m.student_id
+         WHERE m.component_id = $1 ORDER BY u.login_id"
+    )
+    .bind(component_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn upsert_mark(tx: &mut Transaction<'_, Postgres>, component_id: Uuid, student_id: Uuid, marks: f64, entered_by: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO assessment_marks (component_id, student_id, marks, entered_by) VALUES ($1, $2, $3, $4)
+         ON CONFLICT (component_id, student_id) DO UPDATE SET marks = EXCLUDED.marks, entered_by = EXCLUDED.entered_by, updated_at = NOW()"
+    )
+    .bind(component_id)
+    .bind(student_id)
+    .bind(marks)
+    .bind(entered_by)
+    .execute(&mut **tx)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+/// Per component of the subject, the students of the branch (and section) with marks and how
+/// many of them reached the component's threshold.
+pub async fn find_component_results(pool: &PgPool, subject_code: &str, regulation: &str, branch_variations: &[String], section: Option<&str>) -> Result<Vec<ComponentResult>, sqlx::Error> {
+    sqlx::query_as::<Postgres, ComponentResult>(
+        "SELECT c.id as component_id, COUNT(*) as students,
+                COUNT(*) FILTER (WHERE m.marks >= c.max_marks * c.threshold_percent / 100) as attained
+         FROM assessment_components c
+         JOIN assessment_marks m ON m.component_id = c.id
+         JOIN users u ON u.id = m.student_id
+         WHERE c.subject_code = $1 AND c.regulation = $2 AND u.branch = ANY($3) AND ($4::text IS NULL OR u.section = $4)
+         GROUP BY c.id"
+    )
+    .bind(subject_code)
+    .bind(regulation)
+    .bind(branch_variations)
+    .bind(section)
+    .fetch_all(pool)
+    .await
+}
+
+/// Per CO of the subject, the branch's (and section's) feedback on the topics mapped to it and
+/// how much of it reported the topic understood.
+pub async fn find_outcome_feedback(pool: &PgPool, subject_code: &str, regulation: &str, branch_variations: &[String], section: Option<&str>) -> Result<Vec<OutcomeFeedback>, sqlx::Error> {
+    sqlx::query_as::<Postgres, OutcomeFeedback>(
+        "SELECT t.course_outcome_id, COUNT(*) as responses,
+                COUNT(*) FILTER (WHERE f.rating >= 4 OR f.issue_type = 'DONE' OR f.understood = TRUE) as understood
+         FROM topic_co_mappings t
+         JOIN course_outcomes co ON co.id = t.course_outcome_id
+         JOIN student_curriculum_feedback f ON f.topic_uid = t.topic_uid AND f.subject_code = co.subject_code
+         JOIN users u ON u.id = f.student_id
+         WHERE co.subject_code = $1 AND co.regulation = $2 AND u.branch = ANY($3) AND ($4::text IS NULL OR u.section = $4)
+         GROUP BY t.course_outcome_id"
+    )
+    .bind(subject_code)
+    .bind(regulation)
+    .bind(branch_variations)
+    .bind(section)
+    .fetch_all(pool)
+    .await
+}
+
+/// Subjects of the branch's programme (by curriculum folder code) that declare course outcomes.
+pub async fn find_program_subjects(pool: &PgPool, regulation: &str, branch_code: &str) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, String>(
+        "SELECT DISTINCT co.subject_code FROM course_outcomes co
+         JOIN curriculum_subject_revisions r ON r.subject_code = co.subject_code AND r.regulation = co.regulation AND r.is_current
+         WHERE co.regulation = $1 AND r.branch_code = $2
+         ORDER BY co.subject_code"
+    )
+    .bind(regulation)
+    .bind(branch_code)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,137 @@
+use sqlx::{PgPool, Postgres};
+use uuid::Uuid;
+use crate::models::regulation::{SaveEquivalenceRequest, SubjectEquivalence, TransferredMark};
+
+pub async fn course_exists(pool: &PgPool, course_id: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, bool>("SELECT EXISTS(SELECT 1 FROM courses WHERE id = $1)")
+        .bind(course_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Whether the subject is offered under the regulation (or untagged).
+pub async fn subject_in_course(pool: &PgPool, subject_code: &str, course_id: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, bool>("SELECT EXISTS(SELECT 1 FROM subjects WHERE id = $1 AND (course_id = $2 OR course_id IS NULL))")
+        .bind(subject_code)
+        .bind(course_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn find_equivalences(pool: &PgPool, course_id: Option<&str>, subject_code: Option<&str>) -> Result<Vec<SubjectEquivalence>, sqlx::Error> {
+    sqlx::query_as::<Postgres, SubjectEquivalence>(
+        "SELECT e.id, e.from_course_id, e.from_subject_code, fs.name as from_subject_name,
+                e.to_course_id, e.to_subject_code, ts.name as to_subject_name, e.notes, e.created_at
+         FROM subject_equivalences e
+         LEFT JOIN subjects fs ON fs.id = e.from_subject_code
+         LEFT JOIN subjects ts ON ts.id = e.to_subject_code
+         WHERE ($1::text IS NULL OR e.from_course_id = $1 OR e.to_course_id = $1)
+           AND ($2::text IS NULL OR e.from_subject_code = $2 OR e.to_subject_code = $2)
+         ORDER BY e.from_course_id, e.from_subject_code, e.to_subject_code"
+    )
+    .bind(course_id)
+    .bind(subject_code)
+    .fetch_all(pool)
+    .await
+}
+
+/// Stores the equivalence unless it is already recorded in either direction; returns its id.
+pub async fn insert_equivalence(pool: &PgPool, req: &SaveEquivalenceRequest, created_by: Uuid) -> Result<Uuid, sqlx::Error> {
+    let existing = sqlx::query_scalar::<Postgres, Uuid>(
+        "SELECT id FROM subject_equivalences
+         WHERE (from_course_id = $1 AND from_subject_code = $2 AND to_course_id = $3 AND to_subject_code = $4)
+            OR (from_course_id = $3 AND from_subject_code = $4 AND to_course_id = $1 AND to_subject_code = $2)"
+    )
+    .bind(&req.from_course_id)
+    .bind(&req.from_subject_code)
+    .bind(&req.to_course_id)
+    .bind(&req.to_subject_code)
+    .fetch_optional(pool)
+    .await?;
+    if let Some(id) = existing {
+        sqlx::query("UPDATE subject_equivalences SET notes = $2 WHERE id = $1")
+            .bind(id)
+            .bind(&req.notes)
+            .execute(pool)
+            .await?;
+        return Ok(id);
+    }
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO subject_equivalences (from_course_id, from_subject_code, to_course_id, to_subject_code, notes, created_by)
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING id"
+    )
+    .bind(&req.from_course_id)
+    .bind(&req.from_subject_code)
+    .bind(&req.to_course_id)
+    .bind(&req.to_subject_code)
+    .bind(&req.notes)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn delete_equivalence(pool: &PgPool, id: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query("DELETE FROM subject_equivalences WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// (course_id, previous_course_id, reason) of a student's assigned regulation.
+pub async fn find_student_regulation(pool: &PgPool, student_id: Uuid) -> Result<Option<(String, Option<String>, String)>, sqlx::Error> {
+    sqlx::query_as::<Postgres, (String, Option<String>, String)>(
+        "SELECT course_id, previous_course_id, reason FROM student_regulations WHERE student_id = $1"
+    )
+    .bind(student_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn upsert_student_regulation(
+    pool: &PgPool,
+    student_id: Uuid,
+    course_id: &str,
+    previous_course_id: Option<&str>,
+    reason: &str,
+    assigned_by: Uuid,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO student_regulations (student_id, course_id, previous_course_id, reason, assigned_by) VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (student_id) DO UPDATE SET course_id = EXCLUDED.course_id, previous_course_id = EXCLUDED.previous_course_id,
+            reason = EXCLUDED.reason, assigned_by = EXCLUDED.assigned_by, assigned_at = NOW()"
+    )
+    .bind(student_id)
+    .bind(course_id)
+    .bind(previous_course_id)
+    .bind(reason)
+    .bind(assigned_by)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+/// The student's mark for a subject of another regulation equivalent to `subject_code` of
+/// `course_id`, marks being recorded against the subject's name and semester.
+pub async fn find_transferred_mark(pool: &PgPool, login_id: &str, subject_code: &str, course_id: &str) -> Result<Option<TransferredMark>, sqlx::Error> {
+    sqlx::query_as::<Postgres, TransferredMark>(
+        "SELECT s.id as subject_code, other.course_id, m.marks
+         FROM (
+             SELECT from_subject_code as subject_code, from_course_id as course_id FROM subject_equivalences
+             WHERE to_subject_code = $2 AND to_course_id = $3
+             UNION
+             SELECT to_subject_code, to_course_id FROM subject_equivalences
+             WHERE from_subject_code = $2 AND from_course_id = $3
+         ) other
+         JOIN subjects s ON s.id = other.subject_code
+         JOIN student_marks m ON m.subject_name = s.name AND m.semester = s.semester AND m.student_id = $1
+         WHERE m.marks IS NOT NULL
+         ORDER BY m.marks DESC
+         LIMIT 1"
+    )
+    .bind(login_id)
+    .bind(subject_code)
+    .bind(course_id)
+    .fetch_optional(pool)
+    .await
+}
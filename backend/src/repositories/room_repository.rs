@@ -0,0 +1,269 @@
+use sqlx::{PgPool, Postgres, Row};
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::timetable::OccupiedSlot;
+use crate::repositories::timetable_repository::grid_on_date;
+use crate::models::room::{FreeRoomQuery, RelocateClassRequest, Room, RoomDayLoad, RoomListQuery, RoomRelocation, SaveRoomRequest};
+
+const ROOM_COLUMNS: &str = "id, code, name, room_type, capacity, equipment, department, status, updated_at";
+
+/// Rooms held at `day` ($1) / `period_index` ($2) in the timetable in effect on `date` ($3; today
+/// when NULL), with one-day relocations on that date applied. Class rows hold `room`; 'Lab' grid rows
+/// hold their lab in `section` and move along with the class relocated out of that lab.
+fn slot_occupancy() -> String {
+    format!(
+        "SELECT COALESCE(rl.to_room, CASE WHEN t.year = 'Lab' THEN t.section ELSE t.room END) as code,
+                t.branch, t.year, t.section, t.subject, t.faculty_id
+         FROM ({}) t
+         LEFT JOIN room_relocations rl ON rl.relocation_date = $3 AND rl.day = t.day AND rl.period_index = t.period_index
+              AND rl.branch = t.branch
+              AND ((rl.year = t.year AND rl.section = t.section) OR (t.year = 'Lab' AND rl.from_room = t.section))
+         WHERE t.day = $1 AND t.period_index = $2",
+        grid_on_date("COALESCE($3::date, CURRENT_DATE)")
+    )
+}
+
+pub async fn save_room(pool: &PgPool, req: &SaveRoomRequest) -> Result<Room, sqlx::Error> {
+    sqlx::query_as::<Postgres, Room>(&format!(
+        "INSERT INTO rooms (code, name, room_type, capacity, equipment, department, status)
+         VALUES ($1, COALESCE($2, $1), COALESCE($3, 'CLASSROOM'), COALESCE($4, 60), COALESCE($5, '{{}}'), $6, COALESCE($7, 'ACTIVE'))
+         ON CONFLICT (code) DO UPDATE SET
+            name = COALESCE($2, rooms.name),
+            room_type = COALESCE($3, rooms.room_type),
+            capacity = COALESCE($4, rooms.capacity),
+            equipment = COALESCE($5, rooms.equipment),
+            department = COALESCE($6, rooms.department),
+            status = COALESCE($7, rooms.status),
+            updated_at = NOW()
+         RETURNING {}",
+        ROOM_COLUMNS
+    ))
+    .bind(&req.code)
+    .bind(&req.name)
+    .bind(&req.room_type)
+    .bind(req.capacity)
+    .bind(&req.equipment)
+    .bind(&req.department)
+    .bind(&req.status)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn find_rooms(pool: &PgPool, params: &RoomListQuery) -> Result<Vec<Room>, sqlx::Error> {
+    sqlx::query_as::<Postgres, Room>(&format!(
+        "SELECT {} FROM rooms
+         WHERE ($1::text IS NULL OR department = $1)
+           AND ($2::text IS NULL OR room_type = $2)
+           AND ($3::text IS NULL OR status = $3)
+         ORDER BY room_type, code",
+        ROOM_COLUMNS
+    ))
+    .bind(&params.department)
+    .bind(&params.room_type)
+    .bind(&params.status)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_room(pool: &PgPool, code: &str) -> Result<Option<Room>, sqlx::Error> {
+    sqlx::query_as::<Postgres, Room>(&format!("SELECT {} FROM rooms WHERE code = $1", ROOM_COLUMNS))
+        .bind(code)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Active labs owned by the department, for the timetable generator.
+pub async fn find_lab_codes(pool: &PgPool, branch_variations: &[String]) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, String>(
+        "SELECT code FROM rooms WHERE room_type = 'LAB' AND status = 'ACTIVE' AND department = ANY($1) ORDER BY code"
+    )
+    .bind(branch_variations)
+    .fetch_all(pool)
+    .await
+}
+
+/// Active rooms matching the filters that nobody holds at the slot.
+pub async fn find_free_rooms(pool: &PgPool, params: &FreeRoomQuery, department: Option<&str>, equipment: &[String]) -> Result<Vec<Room>, sqlx::Error> {
+    sqlx::query_as::<Postgres, Room>(&format!(
+        "WITH occupied AS ({})
+         SELECT {} FROM rooms r
+         WHERE r.status = 'ACTIVE'
+           AND ($4::text IS NULL OR r.room_type = $4)
+           AND ($5::int IS NULL OR r.capacity >= $5)
+           AND ($6::text IS NULL OR r.department IS NULL OR r.department = $6)
+           AND r.equipment @> $7
+           AND NOT EXISTS (SELECT 1 FROM occupied o WHERE o.code = r.code)
+         ORDER BY (r.department IS NOT DISTINCT FROM $6) DESC, r.capacity, r.code",
+        slot_occupancy(),
+        ROOM_COLUMNS.split(", ").map(|c| format!("r.{}", c)).collect::<Vec<_>>().join(", ")
+    ))
+    .bind(&params.day)
+    .bind(params.period_index)
+    .bind(params.date)
+    .bind(&params.room_type)
+    .bind(params.min_capacity)
+    .bind(department)
+    .bind(equipment)
+    .fetch_all(pool)
+    .await
+}
+
+/// Who holds the room at the slot (on the date, if given), ignoring the class being moved and its 'Lab' grid row.
+/// `branch` is the class's branch as stored in the timetable.
+pub async fn find_slot_holder(pool: &PgPool, req: &RelocateClassRequest, branch: &str) -> Result<Option<OccupiedSlot>, sqlx::Error> {
+    sqlx::query_as::<Postgres, OccupiedSlot>(&format!(
+        "WITH occupied AS ({})
+         SELECT o.branch, o.year, o.section, o.subject, o.faculty_id FROM occupied o
+         WHERE o.code = $4 AND NOT (o.branch = $5 AND o.year = $6 AND o.section = $7)
+           AND NOT (o.year = 'Lab' AND o.branch = $5 AND o.faculty_id IN (
+               SELECT faculty_id FROM occupied WHERE branch = $5 AND year = $6 AND section = $7))
+         LIMIT 1",
+        slot_occupancy()
+    ))
+    .bind(&req.day)
+    .bind(req.period_index)
+    .bind(req.date)
+    .bind(&req.to_room)
+    .bind(branch)
+    .bind(&req.year)
+    .bind(&req.section)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Stored branch, room and faculty of a class period in the timetable in effect on `date` (today when `None`).
+pub async fn find_entry_room(
+    pool: &PgPool,
+    branch_variations: &[String],
+    year: &str,
+    section: &str,
+    slot: (&str, i32),
+    date: Option<NaiveDate>,
+) -> Result<Option<(String, Option<String>, String)>, sqlx::Error> {
+    sqlx::query(&format!(
+        "SELECT g.branch, g.room, g.faculty_id FROM ({}) g
+         WHERE g.branch = ANY($1) AND g.year = $2 AND g.section = $3 AND g.day = $4 AND g.period_index = $5
+         LIMIT 1",
+        grid_on_date("COALESCE($6::date, CURRENT_DATE)")
+    ))
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(slot.0)
+    .bind(slot.1)
+    .bind(date)
+    .fetch_optional(pool)
+    .await
+    .map(|r| r.map(|row| (row.get("branch"), row.get("room"), row.get("faculty_id"))))
+}
+
+/// Moves a class period to another room for good. Its 'Lab' grid row follows when the new room is a lab
+/// and is dropped otherwise.
+pub async fn update_entry_room(pool: &PgPool, req: &RelocateClassRequest, branch: &str, lab_row: Option<(&str, &str)>, target_is_lab: bool) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let updated = sqlx::query("UPDATE timetable_entries SET room = $6 WHERE branch = $1 AND year = $2 AND section = $3 AND day = $4 AND period_index = $5")
+        .bind(branch)
+        .bind(&req.year)
+        .bind(&req.section)
+        .bind(&req.day)
+        .bind(req.period_index)
+        .bind(&req.to_room)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    if let Some((from_room, faculty_id)) = lab_row {
+        let query = if target_is_lab {
+            sqlx::query("UPDATE timetable_entries SET section = $5 WHERE branch = $1 AND year = 'Lab' AND section = $2 AND day = $3 AND period_index = $4 AND faculty_id = $6")
+                .bind(branch)
+                .bind(from_room)
+                .bind(&req.day)
+                .bind(req.period_index)
+                .bind(&req.to_room)
+                .bind(faculty_id)
+        } else {
+            sqlx::query("DELETE FROM timetable_entries WHERE branch = $1 AND year = 'Lab' AND section = $2 AND day = $3 AND period_index = $4 AND faculty_id = $5")
+                .bind(branch)
+                .bind(from_room)
+                .bind(&req.day)
+                .bind(req.period_index)
+                .bind(faculty_id)
+        };
+        query.execute(&mut *tx).await?;
+    }
+    tx.commit().await?;
+    Ok(updated)
+}
+
+pub async fn upsert_relocation(pool: &PgPool, req: &RelocateClassRequest, branch: &str, from_room: Option<&str>, relocated_by: Uuid) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO room_relocations (relocation_date, branch, year, section, day, period_index, from_room, to_room, reason, relocated_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+         ON CONFLICT (relocation_date, branch, year, section, period_index) DO UPDATE SET
+            to_room = EXCLUDED.to_room, reason = EXCLUDED.reason, relocated_by = EXCLUDED.relocated_by, created_at = NOW()
+         RETURNING id"
+    )
+    .bind(req.date)
+    .bind(branch)
+    .bind(&req.year)
+    .bind(&req.section)
+    .bind(&req.day)
+    .bind(req.period_index)
+    .bind(from_room)
+    .bind(&req.to_room)
+    .bind(&req.reason)
+    .bind(relocated_by)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn find_relocations(pool: &PgPool, date: NaiveDate, branch_variations: Option<&[String]>) -> Result<Vec<RoomRelocation>, sqlx::Error> {
+    sqlx::query_as::<Postgres, RoomRelocation>(
+        "SELECT rl.id, rl.relocation_date, rl.branch, rl.year, rl.section, rl.day, rl.period_index, rl.from_room, rl.to_room,
+                rl.reason, u.full_name as relocated_by_name, rl.created_at
+         FROM room_relocations rl
+         LEFT JOIN users u ON u.id = rl.relocated_by
+         WHERE rl.relocation_date = $1 AND ($2::text[] IS NULL OR rl.branch = ANY($2))
+         ORDER BY rl.period_index, rl.year, rl.section"
+    )
+    .bind(date)
+    .bind(branch_variations)
+    .fetch_all(pool)
+    .await
+}
+
+/// The room a class is in on the date after any one-day relocation.
+pub async fn find_relocated_room(pool: &PgPool, date: NaiveDate, branch_variations: &[String], year: &str, section: &str, period_index: i32) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, String>(
+        "SELECT to_room FROM room_relocations WHERE relocation_date = $1 AND branch = ANY($2) AND year = $3 AND section = $4 AND period_index = $5"
+    )
+    .bind(date)
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(period_index)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Weekly booked periods per room and day in the live grid. A lab class and its 'Lab' grid row count once.
+pub async fn find_room_day_loads(pool: &PgPool) -> Result<Vec<RoomDayLoad>, sqlx::Error> {
+    sqlx::query_as::<Postgres, RoomDayLoad>(
+        "SELECT code, day, COUNT(DISTINCT period_index) as periods
+         FROM (SELECT CASE WHEN year = 'Lab' THEN section ELSE room END as code, day, period_index FROM timetable_entries) x
+         WHERE code IS NOT NULL
+         GROUP BY code, day"
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn count_section_students(pool: &PgPool, branch_variations: &[String], year: &str, section: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, i64>(
+        "SELECT COUNT(*) FROM users WHERE role = 'Student' AND branch = ANY($1) AND year = $2 AND section = $3"
+    )
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .fetch_one(pool)
+    .await
+}
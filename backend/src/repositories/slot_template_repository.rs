@@ -0,0 +1,114 @@
+use sqlx::{PgPool, Postgres};
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::DepartmentTiming;
+use crate::models::slot_template::SlotTemplate;
+
+const TEMPLATE_COLUMNS: &str = "st.id, st.branch, st.effective_from, st.weekday_schedule, st.saturday_schedule,
+    u.full_name as created_by_name, st.created_at";
+
+/// SQL for the `WeekSchedule` JSON of the template in effect on `date_expr` for a branch array
+/// `variations_expr`, NULL when none has started yet.
+pub fn template_on(variations_expr: &str, date_expr: &str) -> String {
+    format!(
+        "SELECT jsonb_build_object('weekday', st.weekday_schedule, 'saturday', st.saturday_schedule)
+         FROM slot_templates st
+         WHERE st.branch = ANY({}) AND st.effective_from <= {}
+         ORDER BY st.effective_from DESC LIMIT 1",
+        variations_expr, date_expr
+    )
+}
+
+/// Saves the template starting on the date, replacing one already set to start that day.
+pub async fn upsert_template(
+    pool: &PgPool,
+    branch: &str,
+    effective_from: NaiveDate,
+    weekday: &serde_json::Value,
+    saturday: Option<&serde_json::Value>,
+    created_by: Option<Uuid>,
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO slot_templates (branch, effective_from, weekday_schedule, saturday_schedule, created_by)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (branch, effective_from) DO UPDATE SET
+            weekday_schedule = EXCLUDED.weekday_schedule, saturday_schedule = EXCLUDED.saturday_schedule,
+            created_by = EXCLUDED.created_by, created_at = NOW()
+         RETURNING id"
+    )
+    .bind(branch)
+    .bind(effective_from)
+    .bind(weekday)
+    .bind(saturday)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn find_template(pool: &PgPool, id: Uuid) -> Result<Option<SlotTemplate>, sqlx::Error> {
+    sqlx::query_as::<Postgres, SlotTemplate>(&format!(
+        "SELECT {} FROM slot_templates st LEFT JOIN users u ON u.id = st.created_by WHERE st.id = $1",
+        TEMPLATE_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Every template of the branch, newest start first.
+pub async fn find_templates(pool: &PgPool, branch_variations: &[String]) -> Result<Vec<SlotTemplate>, sqlx::Error> {
+    sqlx::query_as::<Postgres, SlotTemplate>(&format!(
+        "SELECT {} FROM slot_templates st LEFT JOIN users u ON u.id = st.created_by
+         WHERE st.branch = ANY($1) ORDER BY st.effective_from DESC",
+        TEMPLATE_COLUMNS
+    ))
+    .bind(branch_variations)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_template_on(pool: &PgPool, branch_variations: &[String], date: NaiveDate) -> Result<Option<SlotTemplate>, sqlx::Error> {
+    sqlx::query_as::<Postgres, SlotTemplate>(&format!(
+        "SELECT {} FROM slot_templates st LEFT JOIN users u ON u.id = st.created_by
+         WHERE st.branch = ANY($1) AND st.effective_from <= $2
+         ORDER BY st.effective_from DESC LIMIT 1",
+        TEMPLATE_COLUMNS
+    ))
+    .bind(branch_variations)
+    .bind(date)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Writes the fixed columns the timing screen reads. `short_code` is kept when not given.
+pub async fn upsert_department_timing(pool: &PgPool, timing: &DepartmentTiming) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO department_timings (branch, start_hour, start_minute, class_duration, short_break_duration, lunch_duration, slot_config, short_code)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+         ON CONFLICT (branch) DO UPDATE SET
+            start_hour = EXCLUDED.start_hour, start_minute = EXCLUDED.start_minute,
+            class_duration = EXCLUDED.class_duration, short_break_duration = EXCLUDED.short_break_duration,
+            lunch_duration = EXCLUDED.lunch_duration, slot_config = EXCLUDED.slot_config,
+            short_code = COALESCE(EXCLUDED.short_code, department_timings.short_code)"
+    )
+    .bind(&timing.branch)
+    .bind(timing.start_hour)
+    .bind(timing.start_minute)
+    .bind(timing.class_duration)
+    .bind(timing.short_break_duration)
+    .bind(timing.lunch_duration)
+    .bind(&timing.slot_config)
+    .bind(&timing.short_code)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+/// Makes sure the branch has a timing row, so a template starting later is picked up on its date.
+pub async fn ensure_department_timing(pool: &PgPool, branch: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query("INSERT INTO department_timings (branch) VALUES ($1) ON CONFLICT (branch) DO NOTHING")
+        .bind(branch)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
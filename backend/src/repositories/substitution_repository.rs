@@ -0,0 +1,106 @@
+use sqlx::{PgPool, Postgres};
+use chrono::NaiveDate;
+use crate::models::substitution::{CandidateRow, SubstituteSlot};
+use crate::repositories::timetable_repository::grid_on_date;
+
+pub async fn find_scheduled_slot(
+    pool: &PgPool,
+    date: NaiveDate,
+    class: (&str, &str, &str), // (branch, year, section)
+    day: &str,
+    period_index: i32,
+) -> Result<Option<SubstituteSlot>, sqlx::Error> {
+    sqlx::query_as::<Postgres, SubstituteSlot>(&format!(
+        "WITH grid AS ({})
+         SELECT $1::date as date, g.branch, g.year, g.section, g.day, g.period_index, g.subject, g.subject_code, g.faculty_id, u.full_name as faculty_name
+         FROM grid g
+         LEFT JOIN users u ON u.login_id = g.faculty_id
+         WHERE g.branch = $2 AND g.year = $3 AND g.section = $4 AND g.day = $5 AND g.period_index = $6
+         LIMIT 1",
+        grid_on_date("$1::date")
+    ))
+    .bind(date)
+    .bind(class.0)
+    .bind(class.1)
+    .bind(class.2)
+    .bind(day)
+    .bind(period_index)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Class periods a faculty member teaches on a date that have no status recorded yet.
+/// Lab grid rows are skipped since they mirror a class period.
+pub async fn find_uncovered_slots(pool: &PgPool, faculty_id: &str, date: NaiveDate, day: &str) -> Result<Vec<SubstituteSlot>, sqlx::Error> {
+    sqlx::query_as::<Postgres, SubstituteSlot>(&format!(
+        "WITH grid AS ({})
+         SELECT $1::date as date, g.branch, g.year, g.section, g.day, g.period_index, g.subject, g.subject_code, g.faculty_id, u.full_name as faculty_name
+         FROM grid g
+         LEFT JOIN users u ON u.login_id = g.faculty_id
+         WHERE g.faculty_id = $2 AND g.day = $3 AND g.year <> 'Lab'
+           AND NOT EXISTS (
+               SELECT 1 FROM class_period_status s
+               WHERE s.branch = g.branch AND s.year = g.year AND s.section = g.section
+                 AND s.period_index = g.period_index AND s.status_date = $1::date
+           )
+         ORDER BY g.period_index",
+        grid_on_date("$1::date")
+    ))
+    .bind(date)
+    .bind(faculty_id)
+    .bind(day)
+    .fetch_all(pool)
+    .await
+}
+
+/// Teaching staff free at the slot's period on its date, with the signals used to rank them.
+/// A member counts as busy if the timetable in effect has them teaching, or they are already
+/// recorded as a substitute for that period. `class_period_status` stores faculty as free text,
+/// so substitutions are matched on login id or name.
+pub async fn find_candidates(pool: &PgPool, slot: &SubstituteSlot, week: (NaiveDate, NaiveDate)) -> Result<Vec<CandidateRow>, sqlx::Error> {
+    sqlx::query_as::<Postgres, CandidateRow>(&format!(
+        r#"
+        WITH grid AS ({})
+        SELECT
+            u.login_id, u.full_name, u.branch,
+            EXISTS (
+                SELECT 1 FROM faculty_subjects fs
+                LEFT JOIN subjects sub ON sub.id = fs.subject_id
+                WHERE fs.user_id = u.id AND COALESCE(fs.status, 'APPROVED') = 'APPROVED'
+                  AND (fs.subject_id = $6 OR LOWER(TRIM(COALESCE(fs.subject_name, sub.name))) = LOWER(TRIM($7)))
+            ) as teaches_subject,
+            EXISTS (
+                SELECT 1 FROM grid g
+                WHERE g.faculty_id = u.login_id AND g.branch = $8 AND g.year = $9 AND g.section = $10
+            ) as teaches_class,
+            (
+                SELECT COUNT(*) FROM class_period_status c
+                WHERE c.status = 'substitute' AND c.status_date BETWEEN $4 AND $5
+                  AND c.actual_faculty IN (u.login_id, u.full_name)
+            ) as week_substitutions,
+            (SELECT COUNT(*) FROM grid g WHERE g.faculty_id = u.login_id AND g.day = $2) as periods_that_day
+        FROM users u
+        WHERE u.role IN ('Faculty', 'HOD', 'Incharge') AND u.login_id <> $11
+          AND NOT EXISTS (SELECT 1 FROM grid g WHERE g.faculty_id = u.login_id AND g.day = $2 AND g.period_index = $3)
+          AND NOT EXISTS (
+              SELECT 1 FROM class_period_status c
+              WHERE c.status = 'substitute' AND c.status_date = $1::date AND c.period_index = $3
+                AND c.actual_faculty IN (u.login_id, u.full_name)
+          )
+        "#,
+        grid_on_date("$1::date")
+    ))
+    .bind(slot.date)
+    .bind(&slot.day)
+    .bind(slot.period_index)
+    .bind(week.0)
+    .bind(week.1)
+    .bind(&slot.subject_code)
+    .bind(&slot.subject)
+    .bind(&slot.branch)
+    .bind(&slot.year)
+    .bind(&slot.section)
+    .bind(&slot.faculty_id)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,115 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::lesson_plan::PlanTarget;
+use crate::models::syllabus_forecast::{ForecastValues, SyllabusForecast};
+
+/// (topic id, topic identity, date completed) of the section's completed topics. The date is the
+/// latest `marked_completed` log, else the recorded completion date.
+pub async fn find_completion_dates(pool: &PgPool, target: &PlanTarget) -> Result<Vec<(String, Option<Uuid>, NaiveDate)>, sqlx::Error> {
+    sqlx::query_as::<Postgres, (String, Option<Uuid>, NaiveDate)>(
+        "SELECT p.topic_id, p.topic_uid,
+                COALESCE((SELECT MAX(l.timestamp)::date FROM curriculum_completion_logs l
+                          WHERE l.progress_id = p.id AND l.action = 'marked_completed'),
+                         p.completed_date, p.updated_at::date)
+         FROM curriculum_progress p
+         WHERE p.subject_code = $1 AND p.branch = $2 AND p.section = $3 AND p.year = $4 AND p.semester = $5
+           AND p.status = 'completed'"
+    )
+    .bind(&target.subject_code)
+    .bind(&target.branch)
+    .bind(&target.section)
+    .bind(&target.year)
+    .bind(target.semester)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn upsert_forecast(pool: &PgPool, lesson_plan_id: Uuid, f: &ForecastValues) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO syllabus_forecasts (lesson_plan_id, completed_periods, remaining_periods, scheduled_periods, weekly_rate, deadline, projected_completion, status)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+         ON CONFLICT (lesson_plan_id) DO UPDATE SET
+            completed_periods = EXCLUDED.completed_periods, remaining_periods = EXCLUDED.remaining_periods,
+            scheduled_periods = EXCLUDED.scheduled_periods, weekly_rate = EXCLUDED.weekly_rate, deadline = EXCLUDED.deadline,
+            projected_completion = EXCLUDED.projected_completion, status = EXCLUDED.status, computed_at = NOW()"
+    )
+    .bind(lesson_plan_id)
+    .bind(f.completed_periods)
+    .bind(f.remaining_periods)
+    .bind(f.scheduled_periods)
+    .bind(f.weekly_rate)
+    .bind(f.deadline)
+    .bind(f.projected_completion)
+    .bind(f.status)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn find_forecasts(pool: &PgPool, branch_variations: &[String], year: Option<&str>, section: Option<&str>, status: Option<&str>) -> Result<Vec<SyllabusForecast>, sqlx::Error> {
+    sqlx::query_as::<Postgres, SyllabusForecast>(
+        "SELECT f.lesson_plan_id, p.subject_code, p.branch, p.year, p.section,
+                COALESCE(u.login_id, '') as faculty_login, COALESCE(u.full_name, 'Unknown') as faculty_name,
+                f.completed_periods, f.remaining_periods, f.scheduled_periods, f.weekly_rate, f.deadline,
+                f.projected_completion, f.status, f.computed_at
+         FROM syllabus_forecasts f
+         JOIN lesson_plans p ON p.id = f.lesson_plan_id
+         LEFT JOIN users u ON u.id = p.faculty_id
+         WHERE p.branch = ANY($1) AND ($2::text IS NULL OR p.year = $2) AND ($3::text IS NULL OR p.section = $3)
+           AND ($4::text IS NULL OR f.status = $4)
+         ORDER BY CASE f.status WHEN 'BEHIND' THEN 0 WHEN 'AT_RISK' THEN 1 ELSE 2 END, p.year, p.section, p.subject_code"
+    )
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(status)
+    .fetch_all(pool)
+    .await
+}
+
+/// (branch, status, plans) over every plan with a forecast.
+pub async fn count_statuses(pool: &PgPool) -> Result<Vec<(String, String, i64)>, sqlx::Error> {
+    sqlx::query_as::<Postgres, (String, String, i64)>(
+        "SELECT p.branch, f.status, COUNT(*)
+         FROM syllabus_forecasts f JOIN lesson_plans p ON p.id = f.lesson_plan_id
+         GROUP BY p.branch, f.status"
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Branches with lesson plans not sent a forecast notice since `last_due`.
+pub async fn find_branches_due_notice(pool: &PgPool, last_due: NaiveDate) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, String>(
+        "SELECT DISTINCT p.branch FROM lesson_plans p
+         LEFT JOIN syllabus_forecast_notices n ON n.branch = p.branch
+         WHERE n.sent_on IS NULL OR n.sent_on <= $1"
+    )
+    .bind(last_due)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn mark_notice_sent(tx: &mut Transaction<'_, Postgres>, branch: &str, today: NaiveDate) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO syllabus_forecast_notices (branch, sent_on) VALUES ($1, $2)
+         ON CONFLICT (branch) DO UPDATE SET sent_on = EXCLUDED.sent_on"
+    )
+    .bind(branch)
+    .bind(today)
+    .execute(&mut **tx)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+/// `recipient` is a login id, or HOD_RECIPIENT with the branch for the department's HOD.
+pub async fn insert_notification(tx: &mut Transaction<'_, Postgres>, message: &str, recipient: &str, branch: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query("INSERT INTO notifications (type, message, recipient_id, branch, status) VALUES ('SYLLABUS_FORECAST', $1, $2, $3, 'UNREAD')")
+        .bind(message)
+        .bind(recipient)
+        .bind(branch)
+        .execute(&mut **tx)
+        .await
+        .map(|r| r.rows_affected())
+}
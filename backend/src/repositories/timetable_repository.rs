@@ -0,0 +1,532 @@
+use sqlx::{PgPool, Postgres};
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::{AssignClassRequest, DepartmentTiming};
+use crate::models::timetable::{DraftEntry, OccupiedSlot, TimetableDraftSummary, TimetableVersion};
+use crate::repositories::slot_template_repository::template_on;
+
+/// The department's timing row with the slot template in effect on `date` folded in.
+pub async fn find_department_timing(pool: &PgPool, branch_variations: &[String], date: NaiveDate) -> Result<Option<DepartmentTiming>, sqlx::Error> {
+    sqlx::query_as::<Postgres, DepartmentTiming>(&format!(
+        "SELECT dt.*, ({}) AS slot_template FROM department_timings dt WHERE dt.branch = ANY($1) LIMIT 1",
+        template_on("$1", "$2")
+    ))
+        .bind(branch_variations)
+        .bind(date)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Approved subjects taught to a section, as (subject_id, subject_name, type, credit, faculty login_id).
+pub async fn find_section_subjects(
+    pool: &PgPool,
+    branch_variations: &[String],
+    section: &str,
+    semester_patterns: &[String],
+) -> Result<Vec<(String, String, String, Option<i32>, String)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT s.id, s.name, s.type, s.credit, u.login_id
+         FROM faculty_subjects fs
+         JOIN subjects s ON s.id = fs.subject_id
+         JOIN users u ON u.id = fs.user_id
+         WHERE fs.branch = ANY($1) AND fs.section = $2 AND fs.status = 'APPROVED'
+           AND s.semester LIKE ANY($3)
+         ORDER BY s.id"
+    )
+    .bind(branch_variations)
+    .bind(section)
+    .bind(semester_patterns)
+    .fetch_all(pool)
+    .await
+}
+
+/// Periods the given faculty already teach in other branches, as (faculty_id, day, period_index).
+pub async fn find_external_faculty_load(
+    pool: &PgPool,
+    branch: &str,
+    faculty_ids: &[String],
+) -> Result<Vec<(String, String, i32)>, sqlx::Error> {
+    sqlx::query_as("SELECT faculty_id, day, period_index FROM timetable_entries WHERE branch <> $1 AND faculty_id = ANY($2)")
+        .bind(branch)
+        .bind(faculty_ids)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn insert_draft(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    branch: &str,
+    created_by: Uuid,
+    params: serde_json::Value,
+    unplaced: serde_json::Value,
+    warnings: serde_json::Value,
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar(
+        "INSERT INTO timetable_drafts (branch, created_by, params, unplaced, warnings) VALUES ($1, $2, $3, $4, $5) RETURNING id"
+    )
+    .bind(branch)
+    .bind(created_by)
+    .bind(params)
+    .bind(unplaced)
+    .bind(warnings)
+    .fetch_one(&mut **executor)
+    .await
+}
+
+pub async fn insert_draft_entry(executor: &mut sqlx::Transaction<'_, Postgres>, draft_id: Uuid, entry: &DraftEntry) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO timetable_draft_entries (draft_id, year, section, day, period_index, subject, subject_code, faculty_id, lab_room)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+    )
+    .bind(draft_id)
+    .bind(&entry.year)
+    .bind(&entry.section)
+    .bind(&entry.day)
+    .bind(entry.period_index)
+    .bind(&entry.subject)
+    .bind(&entry.subject_code)
+    .bind(&entry.faculty_id)
+    .bind(&entry.lab_room)
+    .execute(&mut **executor)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn find_drafts(pool: &PgPool, branch: &str) -> Result<Vec<TimetableDraftSummary>, sqlx::Error> {
+    sqlx::query_as::<Postgres, TimetableDraftSummary>(
+        "SELECT d.id, d.branch, d.status, d.created_by, u.full_name as created_by_name, d.published_at, d.created_at,
+                (SELECT COUNT(*) FROM timetable_draft_entries e WHERE e.draft_id = d.id) as entry_count
+         FROM timetable_drafts d
+         LEFT JOIN users u ON d.created_by = u.id
+         WHERE d.branch = $1
+         ORDER BY d.created_at DESC"
+    )
+    .bind(branch)
+    .fetch_all(pool)
+    .await
+}
+
+/// Returns (branch, status, unplaced, warnings).
+pub async fn find_draft(pool: &PgPool, id: Uuid) -> Result<Option<(String, String, serde_json::Value, serde_json::Value)>, sqlx::Error> {
+    sqlx::query_as("SELECT branch, status, unplaced, warnings FROM timetable_drafts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn find_draft_entries(pool: &PgPool, draft_id: Uuid) -> Result<Vec<DraftEntry>, sqlx::Error> {
+    sqlx::query_as::<Postgres, DraftEntry>(
+        "SELECT year, section, day, period_index, subject, subject_code, faculty_id, lab_room
+         FROM timetable_draft_entries WHERE draft_id = $1
+         ORDER BY year, section, day, period_index"
+    )
+    .bind(draft_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn update_draft_status(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    id: Uuid,
+    status: &str,
+    published_by: Option<Uuid>,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "UPDATE timetable_drafts
+         SET status = $1, published_by = $2, published_at = CASE WHEN $2 IS NULL THEN NULL ELSE NOW() END
+         WHERE id = $3 AND status = 'DRAFT'"
+    )
+    .bind(status)
+    .bind(published_by)
+    .bind(id)
+    .execute(&mut **executor)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn delete_class_entries(executor: &mut sqlx::Transaction<'_, Postgres>, branch: &str, year: &str, section: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query("DELETE FROM timetable_entries WHERE branch = $1 AND year = $2 AND section = $3")
+        .bind(branch)
+        .bind(year)
+        .bind(section)
+        .execute(&mut **executor)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+pub async fn insert_timetable_entry(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    branch: &str,
+    year: &str,
+    section: &str,
+    entry: &DraftEntry,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO timetable_entries (id, faculty_id, branch, year, section, day, period_index, subject, subject_code, room)
+         VALUES (gen_random_uuid(), $1, $2, $3, $4, $5, $6, $7, $8, $9)"
+    )
+    .bind(&entry.faculty_id)
+    .bind(branch)
+    .bind(year)
+    .bind(section)
+    .bind(&entry.day)
+    .bind(entry.period_index)
+    .bind(&entry.subject)
+    .bind(&entry.subject_code)
+    .bind(&entry.lab_room)
+    .execute(&mut **executor)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+// --- Assignment checks ---
+
+/// Serializes assignments touching the same faculty member or room at a day and period until
+/// the transaction ends, so the clash check and the insert see each other's rows.
+pub async fn lock_assignment_slot(executor: &mut sqlx::Transaction<'_, Postgres>, key: &str, day: &str, period_index: i32) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('timetable:' || $1 || ':' || $2 || ':' || $3::TEXT))")
+        .bind(key)
+        .bind(day)
+        .bind(period_index)
+        .execute(&mut **executor)
+        .await
+        .map(|_| ())
+}
+
+/// Entries in any branch where the faculty member is already teaching at this day and period,
+/// other than the slot being assigned and the row mirroring it in the same branch: the lab grid
+/// row of the class's room, or the class held in the lab being assigned.
+pub async fn find_faculty_clashes(executor: &mut sqlx::Transaction<'_, Postgres>, payload: &AssignClassRequest) -> Result<Vec<OccupiedSlot>, sqlx::Error> {
+    sqlx::query_as::<Postgres, OccupiedSlot>(
+        r#"
+        SELECT branch, year, section, subject, faculty_id
+        FROM timetable_entries
+        WHERE faculty_id = $1 AND day = $2 AND period_index = $3
+          AND NOT (branch = $4 AND year = $5 AND section = $6)
+          AND NOT (branch = $4 AND year = 'Lab' AND $5 <> 'Lab' AND section IS NOT DISTINCT FROM $7)
+          AND NOT (branch = $4 AND year <> 'Lab' AND $5 = 'Lab' AND room IS NOT DISTINCT FROM $6)
+        ORDER BY branch, year, section
+        "#
+    )
+    .bind(&payload.faculty_id)
+    .bind(&payload.day)
+    .bind(payload.period_index)
+    .bind(&payload.branch)
+    .bind(&payload.year)
+    .bind(&payload.section)
+    .bind(&payload.room)
+    .fetch_all(&mut **executor)
+    .await
+}
+
+/// Entry holding the room at this slot when it is someone else's: a lab grid row of the branch, or a
+/// class of any branch booked into the room.
+pub async fn find_room_occupant(executor: &mut sqlx::Transaction<'_, Postgres>, payload: &AssignClassRequest, room: &str) -> Result<Option<OccupiedSlot>, sqlx::Error> {
+    sqlx::query_as::<Postgres, OccupiedSlot>(
+        "SELECT branch, year, section, subject, faculty_id FROM timetable_entries
+         WHERE ((branch = $1 AND year = 'Lab' AND section = $2) OR room = $2)
+           AND day = $3 AND period_index = $4 AND faculty_id <> $5
+           AND NOT (branch = $1 AND year = $6 AND section = $7)
+         LIMIT 1"
+    )
+    .bind(&payload.branch)
+    .bind(room)
+    .bind(&payload.day)
+    .bind(payload.period_index)
+    .bind(&payload.faculty_id)
+    .bind(&payload.year)
+    .bind(&payload.section)
+    .fetch_optional(&mut **executor)
+    .await
+}
+
+/// Whether the faculty member has an approved `faculty_subjects` row for the subject, matched by
+/// code when one is given and by name otherwise.
+pub async fn faculty_has_subject(pool: &PgPool, payload: &AssignClassRequest, branch_variations: &[String]) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, bool>(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM faculty_subjects fs
+            JOIN users u ON u.id = fs.user_id
+            LEFT JOIN subjects s ON s.id = fs.subject_id
+            WHERE u.login_id = $1
+              AND COALESCE(fs.status, 'APPROVED') = 'APPROVED'
+              AND (fs.branch IS NULL OR fs.branch = ANY($2))
+              AND (($3::TEXT IS NOT NULL AND fs.subject_id = $3) OR LOWER(TRIM(COALESCE(fs.subject_name, s.name))) = LOWER(TRIM($4)))
+        )
+        "#
+    )
+    .bind(&payload.faculty_id)
+    .bind(branch_variations)
+    .bind(payload.subject_code.as_deref().map(str::trim).filter(|c| !c.is_empty()))
+    .bind(&payload.subject)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn insert_override(executor: &mut sqlx::Transaction<'_, Postgres>, payload: &AssignClassRequest, forced_by: Uuid, conflicts: serde_json::Value) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO timetable_overrides (branch, year, section, day, period_index, faculty_id, subject, conflicts, forced_by) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+    )
+    .bind(&payload.branch)
+    .bind(&payload.year)
+    .bind(&payload.section)
+    .bind(&payload.day)
+    .bind(payload.period_index)
+    .bind(&payload.faculty_id)
+    .bind(&payload.subject)
+    .bind(conflicts)
+    .bind(forced_by)
+    .execute(&mut **executor)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+// --- Versions ---
+
+/// Query for the timetable in effect on a date across all branches: the snapshot of the version
+/// in effect where that version is not live, the live grid otherwise. `date` is a SQL expression,
+/// a bind such as `$1::date` or a column of an outer query joined LATERAL.
+/// Columns: branch, year, section, day, period_index, subject, subject_code, faculty_id, room.
+pub fn grid_on_date(date: &str) -> String {
+    format!(
+        "SELECT t.branch, t.year, t.section, t.day, t.period_index, t.subject, t.subject_code, t.faculty_id, t.room
+         FROM timetable_entries t
+         WHERE NOT EXISTS (
+             SELECT 1 FROM timetable_versions v
+             WHERE v.branch = t.branch AND v.status = 'PUBLISHED' AND NOT v.is_live
+               AND v.effective_from <= {d} AND (v.effective_to IS NULL OR v.effective_to >= {d})
+         )
+         UNION ALL
+         SELECT v.branch, e.year, e.section, e.day, e.period_index, e.subject, e.subject_code, e.faculty_id, e.lab_room
+         FROM timetable_version_entries e
+         JOIN timetable_versions v ON v.id = e.version_id
+         WHERE v.status = 'PUBLISHED' AND NOT v.is_live
+           AND v.effective_from <= {d} AND (v.effective_to IS NULL OR v.effective_to >= {d})",
+        d = date
+    )
+}
+
+const VERSION_COLUMNS: &str = "v.id, v.branch, v.name, v.status, v.effective_from, v.effective_to, v.is_live, v.cloned_from,
+    u.full_name as created_by_name, v.published_at, v.created_at
+    FROM timetable_versions v LEFT JOIN users u ON v.created_by = u.id";
+
+pub async fn insert_version(
+    executor: &mut sqlx::Transaction<'_, Postgres>,
+    branch: &str,
+    name: &str,
+    effective: (NaiveDate, Option<NaiveDate>),
+    cloned_from: Option<Uuid>,
+    created_by: Uuid,
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO timetable_versions (branch, name, effective_from, effective_to, cloned_from, created_by)
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING id"
+    )
+    .bind(branch)
+    .bind(name)
+    .bind(effective.0)
+    .bind(effective.1)
+    .bind(cloned_from)
+    .bind(created_by)
+    .fetch_one(&mut **executor)
+    .await
+}
+
+/// Overwrites the snapshot of a version with the live grid of its branch.
+pub async fn snapshot_live_grid(executor: &mut sqlx::Transaction<'_, Postgres>, version_id: Uuid, branch: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query("DELETE FROM timetable_version_entries WHERE version_id = $1")
+        .bind(version_id)
+        .execute(&mut **executor)
+        .await?;
+    sqlx::query(
+        "INSERT INTO timetable_version_entries (version_id, year, section, day, period_index, subject, subject_code, faculty_id, lab_room)
+         SELECT $1, year, section, day, period_index, subject, subject_code, faculty_id, room
+         FROM timetable_entries WHERE branch = $2"
+    )
+    .bind(version_id)
+    .bind(branch)
+    .execute(&mut **executor)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn copy_version_entries(executor: &mut sqlx::Transaction<'_, Postgres>, source_id: Uuid, target_id: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO timetable_version_entries (version_id, year, section, day, period_index, subject, subject_code, faculty_id, lab_room)
+         SELECT $2, year, section, day, period_index, subject, subject_code, faculty_id, lab_room
+         FROM timetable_version_entries WHERE version_id = $1"
+    )
+    .bind(source_id)
+    .bind(target_id)
+    .execute(&mut **executor)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+/// Replaces the live grid of a branch with a version's snapshot.
+pub async fn load_live_grid(executor: &mut sqlx::Transaction<'_, Postgres>, version_id: Uuid, branch: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query("DELETE FROM timetable_entries WHERE branch = $1")
+        .bind(branch)
+        .execute(&mut **executor)
+        .await?;
+    sqlx::query(
+        "INSERT INTO timetable_entries (id, faculty_id, branch, year, section, day, period_index, subject, subject_code, room)
+         SELECT gen_random_uuid(), faculty_id, $2, year, section, day, period_index, subject, subject_code, lab_room
+         FROM timetable_version_entries WHERE version_id = $1"
+    )
+    .bind(version_id)
+    .bind(branch)
+    .execute(&mut **executor)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn find_versions(pool: &PgPool, branch: &str) -> Result<Vec<TimetableVersion>, sqlx::Error> {
+    sqlx::query_as::<Postgres, TimetableVersion>(&format!("SELECT {} WHERE v.branch = $1 ORDER BY v.effective_from DESC, v.created_at DESC", VERSION_COLUMNS))
+        .bind(branch)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn find_version(pool: &PgPool, id: Uuid) -> Result<Option<TimetableVersion>, sqlx::Error> {
+    sqlx::query_as::<Postgres, TimetableVersion>(&format!("SELECT {} WHERE v.id = $1", VERSION_COLUMNS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn find_version_entries(pool: &PgPool, version_id: Uuid) -> Result<Vec<DraftEntry>, sqlx::Error> {
+    sqlx::query_as::<Postgres, DraftEntry>(
+        "SELECT year, section, day, period_index, subject, subject_code, faculty_id, lab_room
+         FROM timetable_version_entries WHERE version_id = $1
+         ORDER BY year, section, day, period_index"
+    )
+    .bind(version_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_live_entries(pool: &PgPool, branch: &str) -> Result<Vec<DraftEntry>, sqlx::Error> {
+    sqlx::query_as::<Postgres, DraftEntry>(
+        "SELECT year, section, day, period_index, subject, subject_code, faculty_id, room as lab_room
+         FROM timetable_entries WHERE branch = $1
+         ORDER BY year, section, day, period_index"
+    )
+    .bind(branch)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn upsert_version_entry(pool: &PgPool, version_id: Uuid, entry: &DraftEntry) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO timetable_version_entries (version_id, year, section, day, period_index, subject, subject_code, faculty_id, lab_room)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+         ON CONFLICT (version_id, year, section, day, period_index) DO UPDATE SET
+            subject = EXCLUDED.subject, subject_code = EXCLUDED.subject_code, faculty_id = EXCLUDED.faculty_id, lab_room = EXCLUDED.lab_room"
+    )
+    .bind(version_id)
+    .bind(&entry.year)
+    .bind(&entry.section)
+    .bind(&entry.day)
+    .bind(entry.period_index)
+    .bind(&entry.subject)
+    .bind(&entry.subject_code)
+    .bind(&entry.faculty_id)
+    .bind(&entry.lab_room)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn delete_version_entry(pool: &PgPool, version_id: Uuid, entry: &DraftEntry) -> Result<u64, sqlx::Error> {
+    sqlx::query("DELETE FROM timetable_version_entries WHERE version_id = $1 AND year = $2 AND section = $3 AND day = $4 AND period_index = $5")
+        .bind(version_id)
+        .bind(&entry.year)
+        .bind(&entry.section)
+        .bind(&entry.day)
+        .bind(entry.period_index)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// Published versions of the branch starting on or after `from` (and, when bounded, on or before `to`).
+/// Publishing a version over them would leave two versions in effect on the same day.
+pub async fn count_later_versions(pool: &PgPool, branch: &str, from: NaiveDate, to: Option<NaiveDate>) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, i64>(
+        "SELECT COUNT(*) FROM timetable_versions
+         WHERE branch = $1 AND status = 'PUBLISHED' AND effective_from >= $2 AND ($3::date IS NULL OR effective_from <= $3)"
+    )
+    .bind(branch)
+    .bind(from)
+    .bind(to)
+    .fetch_one(pool)
+    .await
+}
+
+/// Ends earlier published versions the day before `from`.
+pub async fn close_previous_versions(executor: &mut sqlx::Transaction<'_, Postgres>, branch: &str, from: NaiveDate) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "UPDATE timetable_versions SET effective_to = $2 - 1
+         WHERE branch = $1 AND status = 'PUBLISHED' AND effective_from < $2 AND (effective_to IS NULL OR effective_to >= $2)"
+    )
+    .bind(branch)
+    .bind(from)
+    .execute(&mut **executor)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn mark_version_published(executor: &mut sqlx::Transaction<'_, Postgres>, id: Uuid, published_by: Uuid) -> Result<u64, sqlx::Error> {
+    sqlx::query("UPDATE timetable_versions SET status = 'PUBLISHED', published_by = $2, published_at = NOW() WHERE id = $1 AND status = 'DRAFT'")
+        .bind(id)
+        .bind(published_by)
+        .execute(&mut **executor)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// For each branch, the published version in effect today as (id, branch, is_live).
+pub async fn find_current_versions(pool: &PgPool) -> Result<Vec<(Uuid, String, bool)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT DISTINCT ON (branch) id, branch, is_live FROM timetable_versions
+         WHERE status = 'PUBLISHED' AND effective_from <= CURRENT_DATE AND (effective_to IS NULL OR effective_to >= CURRENT_DATE)
+         ORDER BY branch, effective_from DESC"
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_live_version(executor: &mut sqlx::Transaction<'_, Postgres>, branch: &str) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>("SELECT id FROM timetable_versions WHERE branch = $1 AND is_live FOR UPDATE")
+        .bind(branch)
+        .fetch_optional(&mut **executor)
+        .await
+}
+
+pub async fn set_version_live(executor: &mut sqlx::Transaction<'_, Postgres>, id: Uuid, live: bool) -> Result<u64, sqlx::Error> {
+    sqlx::query("UPDATE timetable_versions SET is_live = $2, activated_at = CASE WHEN $2 THEN NOW() ELSE activated_at END WHERE id = $1")
+        .bind(id)
+        .bind(live)
+        .execute(&mut **executor)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// The published version in effect on `date` when it answers from its snapshot. `None` means the
+/// live grid applies, either because that version is live or because the branch has no version for the date.
+pub async fn find_snapshot_version(pool: &PgPool, branch: &str, date: NaiveDate) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "SELECT id FROM (
+            SELECT id, is_live FROM timetable_versions
+            WHERE branch = $1 AND status = 'PUBLISHED' AND effective_from <= $2 AND (effective_to IS NULL OR effective_to >= $2)
+            ORDER BY effective_from DESC LIMIT 1
+         ) v WHERE NOT v.is_live"
+    )
+    .bind(branch)
+    .bind(date)
+    .fetch_optional(pool)
+    .await
+}
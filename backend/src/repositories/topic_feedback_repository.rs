@@ -0,0 +1,377 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::topic_feedback::{
+    FacultyFeedbackAnalytics, FeedbackCluster, RevisionSession, ScheduleRevisionRequest, StudentFeedbackStatus, TopicFeedbackRow,
+};
+
+/// Whether a feedback row `f` reports the topic understood, as `get_topic_feedback_stats` counts it.
+const UNDERSTOOD: &str = "COALESCE(f.rating >= 4 OR f.issue_type = 'DONE' OR f.understood, FALSE)";
+
+/// Subjects each faculty member teaches a section, from the timetable.
+const TEACHING: &str = "SELECT DISTINCT t.faculty_id, COALESCE(NULLIF(t.subject_code, ''), t.subject) as subject_code, t.branch, t.year, t.section
+    FROM timetable_entries t";
+
+const SESSION_SELECT: &str = "SELECT s.id, s.subject_code, s.topic_id, s.topic_uid, s.branch, s.year, s.section,
+    u.login_id as faculty_login, u.full_name as faculty_name, s.scheduled_date, s.period_index, s.notes, s.status,
+    (SELECT COUNT(*) FROM student_curriculum_feedback f WHERE f.revision_session_id = s.id) as linked_feedback, s.created_at
+    FROM topic_revision_sessions s JOIN users u ON u.id = s.faculty_id";
+
+/// Feedback per topic of a subject from the branch's students, optionally narrowed to a year,
+/// section and date range.
+pub async fn find_topic_stats(
+    pool: &PgPool,
+    subject_code: &str,
+    branch_variations: &[String],
+    year: Option<&str>,
+    section: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<TopicFeedbackRow>, sqlx::Error> {
+    sqlx::query_as::<Postgres, TopicFeedbackRow>(&format!(
+        "SELECT MIN(f.topic_id) as topic_id, f.topic_uid, COUNT(*) as responses,
+                COUNT(*) FILTER (WHERE {u}) as understood, AVG(f.rating)::FLOAT8 as average_rating,
+                COUNT(*) FILTER (WHERE f.rating = 1) as rating_1, COUNT(*) FILTER (WHERE f.rating = 2) as rating_2,
+                COUNT(*) FILTER (WHERE f.rating = 3) as rating_3, COUNT(*) FILTER (WHERE f.rating = 4) as rating_4,
+                COUNT(*) FILTER (WHERE f.rating = 5) as rating_5,
+                COUNT(*) FILTER (WHERE f.issue_type = 'DONE') as done,
+                COUNT(*) FILTER (WHERE f.issue_type = 'NOT_UNDERSTOOD') as not_understood,
+                COUNT(*) FILTER (WHERE f.issue_type = 'NOT_DONE') as not_done,
+                COUNT(*) FILTER (WHERE NOT {u} AND f.resolved_at IS NULL) as open_items
+         FROM student_curriculum_feedback f
+         JOIN users u ON u.id = f.student_id
+         WHERE f.subject_code = $1 AND u.branch = ANY($2) AND ($3::text IS NULL OR u.year = $3) AND ($4::text IS NULL OR u.section = $4)
+           AND ($5::date IS NULL OR f.created_at >= $5) AND ($6::date IS NULL OR f.created_at < $6 + 1)
+         GROUP BY f.topic_uid, CASE WHEN f.topic_uid IS NULL THEN f.topic_id END",
+        u = UNDERSTOOD
+    ))
+    .bind(subject_code)
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// (week start, done, not understood, not done) over the same feedback as `find_topic_stats`.
+pub async fn find_issue_trend(
+    pool: &PgPool,
+    subject_code: &str,
+    branch_variations: &[String],
+    year: Option<&str>,
+    section: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<(NaiveDate, i64, i64, i64)>, sqlx::Error> {
+    sqlx::query_as::<Postgres, (NaiveDate, i64, i64, i64)>(
+        "SELECT date_trunc('week', f.created_at)::date as week,
+                COUNT(*) FILTER (WHERE f.issue_type = 'DONE'),
+                COUNT(*) FILTER (WHERE f.issue_type = 'NOT_UNDERSTOOD'),
+                COUNT(*) FILTER (WHERE f.issue_type = 'NOT_DONE')
+         FROM student_curriculum_feedback f
+         JOIN users u ON u.id = f.student_id
+         WHERE f.subject_code = $1 AND u.branch = ANY($2) AND ($3::text IS NULL OR u.year = $3) AND ($4::text IS NULL OR u.section = $4)
+           AND ($5::date IS NULL OR f.created_at >= $5) AND ($6::date IS NULL OR f.created_at < $6 + 1)
+           AND f.created_at IS NOT NULL
+         GROUP BY week ORDER BY week"
+    )
+    .bind(subject_code)
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Feedback on the subjects each faculty member of the branch teaches, from the sections they
+/// teach them to. A topic of a section needs revision once it has `min_responses` and is
+/// understood by less than `understood_below` percent or rated below `rating_below`.
+pub async fn find_faculty_stats(
+    pool: &PgPool,
+    branch_variations: &[String],
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    min_responses: i64,
+    understood_below: f64,
+    rating_below: f64,
+) -> Result<Vec<FacultyFeedbackAnalytics>, sqlx::Error> {
+    sqlx::query_as::<Postgres, FacultyFeedbackAnalytics>(&format!(
+        "WITH teaching AS ({teaching} WHERE t.branch = ANY($1)),
+         fb AS (
+             SELECT t.faculty_id, f.subject_code, f.topic_id, f.topic_uid, u.section, f.rating, f.issue_type,
+                    f.created_at, f.resolved_at, {u} as understood
+             FROM student_curriculum_feedback f
+             JOIN users u ON u.id = f.student_id AND u.branch = ANY($1)
+             JOIN teaching t ON t.subject_code = f.subject_code AND t.year = u.year AND t.section = u.section
+             WHERE ($2::date IS NULL OR f.created_at >= $2) AND ($3::date IS NULL OR f.created_at < $3 + 1)
+         ),
+         weak_topics AS (
+             SELECT faculty_id, COUNT(*) as needing FROM (
+                 SELECT faculty_id FROM fb
+                 GROUP BY faculty_id, subject_code, section, COALESCE(topic_uid::text, topic_id)
+                 HAVING COUNT(*) >= $4
+                    AND (AVG(CASE WHEN understood THEN 100.0 ELSE 0 END) < $5 OR AVG(rating) < $6)
+             ) w GROUP BY faculty_id
+         ),
+         materials AS (
+             SELECT mu.login_id as faculty_id, COUNT(DISTINCT m.id) as shared, COUNT(d.id) as downloads
+             FROM course_materials m
+             JOIN users mu ON mu.id = m.uploaded_by
+             LEFT JOIN course_material_downloads d ON d.material_id = m.id
+                 AND ($2::date IS NULL OR d.downloaded_at >= $2) AND ($3::date IS NULL OR d.downloaded_at < $3 + 1)
+             WHERE m.branch = ANY($1) AND NOT m.archived
+             GROUP BY mu.login_id
+         )
+         SELECT fb.faculty_id as faculty_login, COALESCE(MAX(usr.full_name), fb.faculty_id) as faculty_name,
+                COUNT(DISTINCT fb.subject_code) as subjects, COUNT(*) as responses,
+                AVG(CASE WHEN fb.understood THEN 100.0 ELSE 0 END)::FLOAT8 as understood_percent,
+                AVG(fb.rating)::FLOAT8 as average_rating,
+                COUNT(*) FILTER (WHERE fb.issue_type = 'NOT_UNDERSTOOD') as not_understood,
+                COUNT(*) FILTER (WHERE fb.issue_type = 'NOT_DONE') as not_done,
+                COALESCE(MAX(w.needing), 0) as topics_needing_revision,
+                COUNT(*) FILTER (WHERE NOT fb.understood AND fb.resolved_at IS NULL) as open_items,
+                COUNT(*) FILTER (WHERE fb.resolved_at IS NOT NULL) as resolved_items,
+                (AVG(EXTRACT(EPOCH FROM (fb.resolved_at - fb.created_at))) FILTER (WHERE fb.resolved_at IS NOT NULL) / 3600)::FLOAT8 as average_hours_to_resolve,
+                COALESCE(MAX(mat.shared), 0) as materials_shared,
+                COALESCE(MAX(mat.downloads), 0) as material_downloads
+         FROM fb
+         LEFT JOIN weak_topics w ON w.faculty_id = fb.faculty_id
+         LEFT JOIN users usr ON usr.login_id = fb.faculty_id
+         LEFT JOIN materials mat ON mat.faculty_id = fb.faculty_id
+         GROUP BY fb.faculty_id
+         ORDER BY open_items DESC, faculty_name",
+        teaching = TEACHING,
+        u = UNDERSTOOD
+    ))
+    .bind(branch_variations)
+    .bind(from)
+    .bind(to)
+    .bind(min_responses)
+    .bind(understood_below)
+    .bind(rating_below)
+    .fetch_all(pool)
+    .await
+}
+
+/// The faculty member's feedback grouped by subject, topic, section and issue type, clusters
+/// with open feedback first.
+pub async fn find_inbox(pool: &PgPool, faculty_login: &str, open_only: bool) -> Result<Vec<FeedbackCluster>, sqlx::Error> {
+    sqlx::query_as::<Postgres, FeedbackCluster>(&format!(
+        "WITH teaching AS ({teaching} WHERE t.faculty_id = $1),
+         fb AS (
+             SELECT f.id, f.subject_code, f.topic_id, f.topic_uid, t.branch, t.year, t.section, f.rating, f.comment,
+                    f.created_at, f.resolved_at, {u} as understood,
+                    COALESCE(f.issue_type, CASE WHEN {u} THEN 'DONE' ELSE 'NOT_UNDERSTOOD' END) as issue
+             FROM student_curriculum_feedback f
+             JOIN users u ON u.id = f.student_id
+             JOIN teaching t ON t.subject_code = f.subject_code AND t.branch = u.branch AND t.year = u.year AND t.section = u.section
+         ),
+         clusters AS (
+             SELECT subject_code, MIN(topic_id) as topic_id, topic_uid, branch, year, section, issue as issue_type,
+                    COUNT(*) as responses,
+                    COUNT(*) FILTER (WHERE NOT understood AND resolved_at IS NULL) as open_items,
+                    AVG(rating)::FLOAT8 as average_rating, MAX(created_at) as latest_at,
+                    COALESCE(jsonb_agg(jsonb_build_object('id', id, 'comment', comment, 'rating', rating, 'createdAt', created_at, 'resolved', resolved_at IS NOT NULL)
+                             ORDER BY created_at DESC) FILTER (WHERE COALESCE(comment, '') <> ''), '[]'::jsonb) as comments,
+                    ARRAY_AGG(id ORDER BY created_at) as feedback_ids
+             FROM fb
+             GROUP BY subject_code, topic_uid, CASE WHEN topic_uid IS NULL THEN topic_id END, branch, year, section, issue
+         )
+         SELECT c.*,
+                (SELECT MIN(s.scheduled_date) FROM topic_revision_sessions s
+                 WHERE s.subject_code = c.subject_code AND s.branch = c.branch AND s.year = c.year AND s.section = c.section
+                   AND (s.topic_uid = c.topic_uid OR s.topic_id = c.topic_id) AND s.status = 'SCHEDULED') as next_revision
+         FROM clusters c
+         WHERE NOT $2 OR c.open_items > 0
+         ORDER BY c.open_items DESC, c.latest_at DESC NULLS LAST",
+        teaching = TEACHING,
+        u = UNDERSTOOD
+    ))
+    .bind(faculty_login)
+    .bind(open_only)
+    .fetch_all(pool)
+    .await
+}
+
+/// Whether the timetable has the faculty member teaching the subject to the section.
+pub async fn teaches_section(pool: &PgPool, faculty_login: &str, subject_code: &str, branch_variations: &[String], year: &str, section: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, bool>(
+        "SELECT EXISTS(SELECT 1 FROM timetable_entries
+                       WHERE faculty_id = $1 AND (subject_code = $2 OR subject = $2) AND branch = ANY($3) AND year = $4 AND section = $5)"
+    )
+    .bind(faculty_login)
+    .bind(subject_code)
+    .bind(branch_variations)
+    .bind(year)
+    .bind(section)
+    .fetch_one(pool)
+    .await
+}
+
+/// How many of the feedback rows come from sections the faculty member teaches the subject to.
+pub async fn count_feedback_taught_by(pool: &PgPool, faculty_login: &str, feedback_ids: &[Uuid]) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, i64>(&format!(
+        "SELECT COUNT(*) FROM student_curriculum_feedback f
+         JOIN users u ON u.id = f.student_id
+         WHERE f.id = ANY($2) AND EXISTS (
+             SELECT 1 FROM ({} WHERE t.faculty_id = $1) t
+             WHERE t.subject_code = f.subject_code AND t.branch = u.branch AND t.year = u.year AND t.section = u.section)",
+        TEACHING
+    ))
+    .bind(faculty_login)
+    .bind(feedback_ids)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn insert_session(tx: &mut Transaction<'_, Postgres>, req: &ScheduleRevisionRequest, branch: &str, faculty_id: Uuid) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, Uuid>(
+        "INSERT INTO topic_revision_sessions (subject_code, topic_id, topic_uid, branch, year, section, faculty_id, scheduled_date, period_index, notes)
+         VALUES ($1, $2, (SELECT t.topic_uid FROM curriculum_topics t JOIN curriculum_subject_revisions r ON r.id = t.revision_id
+                          WHERE r.is_current AND r.subject_code = $1 AND t.topic_key = $2 LIMIT 1),
+                 $3, $4, $5, $6, $7, $8, $9)
+         RETURNING id"
+    )
+    .bind(&req.subject_code)
+    .bind(&req.topic_id)
+    .bind(branch)
+    .bind(&req.year)
+    .bind(&req.section)
+    .bind(faculty_id)
+    .bind(req.scheduled_date)
+    .bind(req.period_index)
+    .bind(&req.notes)
+    .fetch_one(&mut **tx)
+    .await
+}
+
+/// Links the section's open feedback on the session's topic (or just `feedback_ids` of it) to the
+/// session and marks it acknowledged. Returns the login ids of the students who gave it.
+pub async fn link_feedback(tx: &mut Transaction<'_, Postgres>, session_id: Uuid, branch_variations: &[String], feedback_ids: Option<&[Uuid]>) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, String>(&format!(
+        "UPDATE student_curriculum_feedback f
+         SET revision_session_id = s.id, acknowledged_at = COALESCE(f.acknowledged_at, NOW())
+         FROM topic_revision_sessions s, users u
+         WHERE s.id = $1 AND u.id = f.student_id AND f.subject_code = s.subject_code
+           AND (f.topic_uid = s.topic_uid OR f.topic_id = s.topic_id)
+           AND u.branch = ANY($2) AND u.year = s.year AND u.section = s.section
+           AND f.resolved_at IS NULL AND NOT {} AND ($3::uuid[] IS NULL OR f.id = ANY($3))
+         RETURNING u.login_id",
+        UNDERSTOOD
+    ))
+    .bind(session_id)
+    .bind(branch_variations)
+    .bind(feedback_ids)
+    .fetch_all(&mut **tx)
+    .await
+}
+
+pub async fn find_session(pool: &PgPool, id: Uuid) -> Result<Option<RevisionSession>, sqlx::Error> {
+    sqlx::query_as::<Postgres, RevisionSession>(&format!("{} WHERE s.id = $1", SESSION_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn find_sessions(pool: &PgPool, faculty_login: Option<&str>, branch_variations: Option<&[String]>, section: Option<&str>) -> Result<Vec<RevisionSession>, sqlx::Error> {
+    sqlx::query_as::<Postgres, RevisionSession>(&format!(
+        "{} WHERE ($1::text IS NULL OR u.login_id = $1) AND ($2::text[] IS NULL OR s.branch = ANY($2)) AND ($3::text IS NULL OR s.section = $3)
+         ORDER BY s.status = 'SCHEDULED' DESC, s.scheduled_date DESC",
+        SESSION_SELECT
+    ))
+    .bind(faculty_login)
+    .bind(branch_variations)
+    .bind(section)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn update_session_status(tx: &mut Transaction<'_, Postgres>, id: Uuid, status: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query("UPDATE topic_revision_sessions SET status = $2, updated_at = NOW() WHERE id = $1 AND status = 'SCHEDULED'")
+        .bind(id)
+        .bind(status)
+        .execute(&mut **tx)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// Resolves the open feedback linked to a conducted session; returns the students' login ids.
+pub async fn resolve_session_feedback(tx: &mut Transaction<'_, Postgres>, session_id: Uuid, resolved_by: Uuid, note: &str) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, String>(
+        "UPDATE student_curriculum_feedback f
+         SET resolved_at = NOW(), resolved_by = $2, resolution_note = $3
+         FROM users u
+         WHERE f.revision_session_id = $1 AND f.resolved_at IS NULL AND u.id = f.student_id
+         RETURNING u.login_id"
+    )
+    .bind(session_id)
+    .bind(resolved_by)
+    .bind(note)
+    .fetch_all(&mut **tx)
+    .await
+}
+
+/// Returns a cancelled session's open feedback to the inbox; returns the students' login ids.
+pub async fn release_session_feedback(tx: &mut Transaction<'_, Postgres>, session_id: Uuid) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<Postgres, String>(
+        "UPDATE student_curriculum_feedback f
+         SET revision_session_id = NULL, acknowledged_at = NULL
+         FROM users u
+         WHERE f.revision_session_id = $1 AND f.resolved_at IS NULL AND u.id = f.student_id
+         RETURNING u.login_id"
+    )
+    .bind(session_id)
+    .fetch_all(&mut **tx)
+    .await
+}
+
+/// Closes feedback without a revision class; returns (student login id, subject code, student
+/// branch) per row closed.
+pub async fn resolve_feedback(tx: &mut Transaction<'_, Postgres>, feedback_ids: &[Uuid], resolved_by: Uuid, note: &str) -> Result<Vec<(String, String, Option<String>)>, sqlx::Error> {
+    sqlx::query_as::<Postgres, (String, String, Option<String>)>(
+        "UPDATE student_curriculum_feedback f
+         SET resolved_at = NOW(), resolved_by = $2, resolution_note = $3, acknowledged_at = COALESCE(f.acknowledged_at, NOW())
+         FROM users u
+         WHERE f.id = ANY($1) AND f.resolved_at IS NULL AND u.id = f.student_id
+         RETURNING u.login_id, f.subject_code, u.branch"
+    )
+    .bind(feedback_ids)
+    .bind(resolved_by)
+    .bind(note)
+    .fetch_all(&mut **tx)
+    .await
+}
+
+pub async fn find_student_feedback(pool: &PgPool, student_id: Uuid) -> Result<Vec<StudentFeedbackStatus>, sqlx::Error> {
+    sqlx::query_as::<Postgres, StudentFeedbackStatus>(&format!(
+        "SELECT f.id, f.subject_code, f.topic_id, f.rating, f.issue_type, f.comment, f.created_at,
+                CASE WHEN f.resolved_at IS NOT NULL THEN 'RESOLVED'
+                     WHEN s.status = 'SCHEDULED' THEN 'REVISION_SCHEDULED'
+                     WHEN {} THEN 'NO_ACTION_NEEDED'
+                     ELSE 'OPEN' END as status,
+                s.scheduled_date as revision_date, f.acknowledged_at, f.resolved_at, f.resolution_note
+         FROM student_curriculum_feedback f
+         LEFT JOIN topic_revision_sessions s ON s.id = f.revision_session_id
+         WHERE f.student_id = $1
+         ORDER BY f.created_at DESC NULLS LAST",
+        UNDERSTOOD
+    ))
+    .bind(student_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// `recipient` is a student's login id.
+pub async fn insert_notification(tx: &mut Transaction<'_, Postgres>, message: &str, recipient: &str, branch: &str) -> Result<u64, sqlx::Error> {
+    sqlx::query("INSERT INTO notifications (type, message, recipient_id, branch, status) VALUES ('FEEDBACK_RESPONSE', $1, $2, $3, 'UNREAD')")
+        .bind(message)
+        .bind(recipient)
+        .bind(branch)
+        .execute(&mut **tx)
+        .await
+        .map(|r| r.rows_affected())
+}
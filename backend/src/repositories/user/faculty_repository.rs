@@ -87,17 +87,6 @@ pub async fn delete_faculty_subject(pool: &PgPool, user_uuid: Uuid, subject_id:
         .execute(pool).await.map(|r| r.rows_affected())
 }
 
-pub async fn update_lesson_plan_complete(pool: &PgPool, item_id: &str, section: &str, completed: bool) -> Result<u64, sqlx::Error> {
-    sqlx::query(
-        "INSERT INTO lesson_plan_progress (item_id, section, completed, completed_date) 
-         VALUES ($1, $2, $3, NOW()) 
-         ON CONFLICT (item_id, section) 
-         DO UPDATE SET completed = $3, completed_date = CASE WHEN $3 THEN NOW() ELSE NULL END"
-    )
-    .bind(item_id).bind(section).bind(completed)
-    .execute(pool).await.map(|r| r.rows_affected())
-}
-
 pub async fn update_feedback_reply(pool: &PgPool, feedback_id: Uuid, reply: &str) -> Result<u64, sqlx::Error> {
     sqlx::query("UPDATE lesson_plan_feedback SET reply = $1, replied_at = NOW() WHERE id = $2")
         .bind(reply).bind(feedback_id)
@@ -169,11 +158,12 @@ pub async fn insert_attendance(
     status: &str, 
     session: &str, 
     section: &str
-) -> Result<u64, sqlx::Error> {
-    sqlx::query(
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar(
         "INSERT INTO attendance (student_uuid, student_login_id, faculty_uuid, date, status, session, section) 
          VALUES ($1, $2, $3, $4::DATE, $5, $6, $7) 
-         ON CONFLICT (student_login_id, date, session) DO UPDATE SET status = $5, section = $7"
+         ON CONFLICT (student_login_id, date, session) DO UPDATE SET status = $5, section = $7
+         RETURNING id"
     )
     .bind(student_uuid)
     .bind(student_login_id)
@@ -182,7 +172,7 @@ pub async fn insert_attendance(
     .bind(status)
     .bind(session)
     .bind(section)
-    .execute(&mut **executor).await.map(|r| r.rows_affected())
+    .fetch_one(&mut **executor).await
 }
 
 pub async fn find_attendance_status(pool: &PgPool, branch: &str, year: Option<&str>, section: Option<&str>, date: &str, session: Option<&str>) -> Result<serde_json::Value, sqlx::Error> {
@@ -222,11 +212,33 @@ pub async fn find_attendance_status(pool: &PgPool, branch: &str, year: Option<&s
     }
     
     let total_present: i64 = query.build_query_scalar().fetch_one(pool).await?;
+
+    let mut leave_query = QueryBuilder::new("SELECT COUNT(*) FROM attendance WHERE date = ");
+    leave_query.push_bind(date);
+    leave_query.push("::DATE AND status = 'L' AND student_uuid IN (SELECT id FROM users WHERE branch = ANY(");
+    leave_query.push_bind(&variations);
+    leave_query.push(")");
+    if let Some(y) = year {
+        leave_query.push(" AND year = ");
+        leave_query.push_bind(y);
+    }
+    if let Some(s) = section {
+        leave_query.push(" AND section = ");
+        leave_query.push_bind(s);
+    }
+    leave_query.push(")");
+
+    if let Some(s) = session {
+        leave_query.push(" AND session = ");
+        leave_query.push_bind(s);
+    }
+
+    let total_leave: i64 = leave_query.build_query_scalar().fetch_one(pool).await?;
     
     // Check if it's marked
     let mut check_query = QueryBuilder::new("SELECT COUNT(*) FROM attendance WHERE date = ");
     check_query.push_bind(date);
-    check_query.push("::DATE AND student_uuid IN (SELECT id FROM users WHERE role = 'Student' AND branch = ANY(");
+    check_query.push("::DATE AND status <> 'L' AND student_uuid IN (SELECT id FROM users WHERE role = 'Student' AND branch = ANY(");
     check_query.push_bind(&variations);
     check_query.push(")");
     if let Some(y) = year {
@@ -247,12 +259,13 @@ pub async fn find_attendance_status(pool: &PgPool, branch: &str, year: Option<&s
     let is_marked_count: i64 = check_query.build_query_scalar().fetch_one(pool).await.unwrap_or(0);
     let is_marked = is_marked_count > 0;
     
-    let total_absent = if is_marked { total_students - total_present } else { 0 };
+    let total_absent = if is_marked { total_students - total_present - total_leave } else { 0 };
 
     Ok(serde_json::json!({
         "totalStudents": total_students,
         "totalPresent": total_present,
         "totalAbsent": total_absent,
+        "totalLeave": total_leave,
         "isMarked": is_marked
     }))
 }
@@ -278,7 +291,7 @@ pub async fn find_absent_students(pool: &PgPool, branch: &str, year: Option<&str
     query.push(" AND id NOT IN (
         SELECT student_uuid FROM attendance WHERE date = ");
     query.push_bind(date);
-    query.push("::DATE AND (status = 'present' OR status = 'P' OR status = 'PRESENT' OR status = 'HOLIDAY')");
+    query.push("::DATE AND (status = 'present' OR status = 'P' OR status = 'PRESENT' OR status = 'HOLIDAY' OR status = 'L')");
     
     if let Some(s) = session {
         query.push(" AND session = ");
@@ -343,21 +356,23 @@ pub async fn update_profile_request_status(executor: &mut sqlx::Transaction<'_,
 }
 
 pub async fn find_correction_request(pool: &PgPool, id: Uuid) -> Result<Option<serde_json::Value>, sqlx::Error> {
-    let row = sqlx::query("SELECT user_id, dates FROM attendance_correction_requests WHERE id = $1").bind(id).fetch_optional(pool).await?;
+    let row = sqlx::query("SELECT user_id, dates, reason FROM attendance_correction_requests WHERE id = $1").bind(id).fetch_optional(pool).await?;
     Ok(row.map(|r| serde_json::json!({
         "user_id": r.get::<Uuid, _>("user_id"),
-        "dates": r.get::<serde_json::Value, _>("dates")
+        "dates": r.get::<serde_json::Value, _>("dates"),
+        "reason": r.get::<String, _>("reason")
     })))
 }
 
 pub async fn find_pending_correction_request_by_user(pool: &PgPool, user_id: Uuid) -> Result<Option<serde_json::Value>, sqlx::Error> {
-    let row = sqlx::query("SELECT id, dates FROM attendance_correction_requests WHERE user_id = $1 AND status = 'PENDING' LIMIT 1")
+    let row = sqlx::query("SELECT id, dates, reason FROM attendance_correction_requests WHERE user_id = $1 AND status = 'PENDING' LIMIT 1")
         .bind(user_id)
         .fetch_optional(pool)
         .await?;
     Ok(row.map(|r| serde_json::json!({
         "id": r.get::<Uuid, _>("id"),
-        "dates": r.get::<serde_json::Value, _>("dates")
+        "dates": r.get::<serde_json::Value, _>("dates"),
+        "reason": r.get::<String, _>("reason")
     })))
 }
 
@@ -395,22 +410,18 @@ pub async fn update_users_section_name(pool: &PgPool, branch: &str, year: &str,
     sqlx::query("UPDATE users SET section = $1 WHERE branch = $2 AND year = $3 AND section = $4 AND role = 'Student'").bind(new_name).bind(branch).bind(year).bind(old_name).execute(pool).await.map(|r| r.rows_affected())
 }
 
-pub async fn insert_timetable_entry(pool: &PgPool, payload: &crate::models::AssignClassRequest) -> Result<u64, sqlx::Error> {
-    let mut tx = pool.begin().await?;
+pub async fn insert_timetable_entry(executor: &mut sqlx::Transaction<'_, Postgres>, payload: &crate::models::AssignClassRequest) -> Result<u64, sqlx::Error> {
     sqlx::query("DELETE FROM timetable_entries WHERE branch = $1 AND year = $2 AND section = $3 AND day = $4 AND period_index = $5")
         .bind(&payload.branch).bind(&payload.year).bind(&payload.section).bind(&payload.day).bind(payload.period_index)
-        .execute(&mut *tx).await?;
+        .execute(&mut **executor).await?;
         
-    let res = sqlx::query("INSERT INTO timetable_entries (faculty_id, branch, year, section, day, period_index, subject) VALUES ($1, $2, $3, $4, $5, $6, $7)")
-        .bind(&payload.faculty_id).bind(&payload.branch).bind(&payload.year).bind(&payload.section).bind(&payload.day).bind(payload.period_index).bind(&payload.subject)
-        .execute(&mut *tx).await.map(|r| r.rows_affected())?;
-        
-    tx.commit().await?;
-    Ok(res)
+    sqlx::query("INSERT INTO timetable_entries (id, faculty_id, branch, year, section, day, period_index, subject, subject_code, room) VALUES (gen_random_uuid(), $1, $2, $3, $4, $5, $6, $7, $8, $9)")
+        .bind(&payload.faculty_id).bind(&payload.branch).bind(&payload.year).bind(&payload.section).bind(&payload.day).bind(payload.period_index).bind(&payload.subject).bind(&payload.subject_code).bind(&payload.room)
+        .execute(&mut **executor).await.map(|r| r.rows_affected())
 }
 
 pub async fn find_timetable(pool: &PgPool, params: &std::collections::HashMap<String, String>) -> Result<Vec<crate::models::TimetableEntry>, sqlx::Error> {
-    let mut query = QueryBuilder::new("SELECT t.id, t.faculty_id, t.branch, t.year, t.section, t.day, t.period_index, t.subject, t.subject_code, u.full_name as faculty_name, u.email as faculty_email, u.phone_number as faculty_phone, u.branch as faculty_department FROM timetable_entries t LEFT JOIN users u ON t.faculty_id = u.login_id WHERE 1=1");
+    let mut query = QueryBuilder::new("SELECT t.id, t.faculty_id, t.branch, t.year, t.section, t.day, t.period_index, t.subject, t.subject_code, t.room, u.full_name as faculty_name, u.email as faculty_email, u.phone_number as faculty_phone, u.branch as faculty_department FROM timetable_entries t LEFT JOIN users u ON t.faculty_id = u.login_id WHERE 1=1");
     if let Some(f) = params.get("facultyId") { query.push(" AND t.faculty_id = "); query.push_bind(f); }
     if let Some(b) = params.get("branch") { query.push(" AND t.branch = "); query.push_bind(b); }
     if let Some(y) = params.get("year") { query.push(" AND t.year = "); query.push_bind(y); }
@@ -425,16 +436,16 @@ pub async fn delete_timetable_entry(pool: &PgPool, payload: &crate::models::Assi
         .execute(pool).await.map(|r| r.rows_affected())
 }
 
-pub async fn find_department_timings(pool: &PgPool, branch: Option<&str>) -> Result<Vec<crate::models::DepartmentTiming>, sqlx::Error> {
-    let mut query = QueryBuilder::new("SELECT id, branch, start_time, end_time, total_periods, period_duration, lunch_after, lunch_duration FROM department_timings");
-    if let Some(b) = branch { query.push(" WHERE branch = "); query.push_bind(b); }
-    query.build_query_as::<crate::models::DepartmentTiming>().fetch_all(pool).await
-}
-
-pub async fn insert_department_timing(pool: &PgPool, branch: &str, start_time: &str, end_time: &str, total_periods: i32, period_duration: i32, lunch_after: i32, lunch_duration: i32) -> Result<u64, sqlx::Error> {
-    sqlx::query("INSERT INTO department_timings (branch, start_time, end_time, total_periods, period_duration, lunch_after, lunch_duration) VALUES ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT (branch) DO UPDATE SET start_time = $2, end_time = $3, total_periods = $4, period_duration = $5, lunch_after = $6, lunch_duration = $7")
-        .bind(branch).bind(start_time).bind(end_time).bind(total_periods).bind(period_duration).bind(lunch_after).bind(lunch_duration)
-        .execute(pool).await.map(|r| r.rows_affected())
+/// Timing rows with the slot template in effect on `date` folded in.
+pub async fn find_department_timings(pool: &PgPool, branch: Option<&str>, date: chrono::NaiveDate) -> Result<Vec<crate::models::DepartmentTiming>, sqlx::Error> {
+    sqlx::query_as::<Postgres, crate::models::DepartmentTiming>(&format!(
+        "SELECT dt.*, ({}) AS slot_template FROM department_timings dt WHERE ($2::text IS NULL OR dt.branch = $2)",
+        crate::repositories::slot_template_repository::template_on("ARRAY[dt.branch]", "$1")
+    ))
+    .bind(date)
+    .bind(branch)
+    .fetch_all(pool)
+    .await
 }
 
 pub async fn find_courses(pool: &PgPool) -> Result<Vec<crate::models::CourseResponse>, sqlx::Error> {
@@ -453,11 +464,11 @@ pub async fn find_lesson_topics(pool: &PgPool, params: crate::models::LessonTopi
             lpi.id::TEXT as id, 
             lpi.topic, 
             lpi.sno, 
-            COALESCE(lpp.completed, FALSE) as completed,
-            lpp.completed_date,
+            COALESCE(cp.status = 'completed', FALSE) as completed,
+            cp.completed_date::TIMESTAMPTZ as completed_date,
             ls.schedule_date
         FROM lesson_plan_items lpi
-        LEFT JOIN lesson_plan_progress lpp ON lpi.id = lpp.item_id AND (TRIM(lpp.section) = TRIM($2) OR $2 IS NULL)
+        LEFT JOIN curriculum_progress cp ON lpi.id = cp.lesson_plan_item_id AND (TRIM(cp.section) = TRIM($2) OR $2 IS NULL)
         LEFT JOIN lesson_schedule ls ON lpi.id = ls.topic_id AND (TRIM(ls.section) = TRIM($2) OR $2 IS NULL) AND (ls.branch = $3 OR $3 IS NULL)
         WHERE TRIM(lpi.subject_id) ILIKE TRIM($1) AND LOWER(lpi.type) != 'unit'
         ORDER BY lpi.order_index ASC
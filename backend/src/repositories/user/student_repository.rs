@@ -75,7 +75,7 @@ pub async fn get_student_basics(pool: &PgPool, user_uuid: Uuid) -> Result<Option
         )))
 }
 
-pub async fn find_subjects_by_branch_and_semester(pool: &PgPool, branch: &str, semester: &str, section: &str) -> Result<Vec<(String, String, String, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)>, sqlx::Error> {
+pub async fn find_subjects_by_branch_and_semester(pool: &PgPool, branch: &str, semester: &str, section: &str, course_id: &str) -> Result<Vec<(String, String, String, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)>, sqlx::Error> {
     sqlx::query(
         r#"
         SELECT 
@@ -98,13 +98,14 @@ pub async fn find_subjects_by_branch_and_semester(pool: &PgPool, branch: &str, s
         LEFT JOIN users u2 ON t.faculty_id = u2.login_id
         LEFT JOIN course_subjects cs ON (cs.subject_code = s.id OR cs.subject_name = s.name) AND cs.branch = s.branch AND cs.section = $3
         LEFT JOIN users u3 ON (u3.id::text = cs.created_by OR u3.login_id = cs.created_by)
-        WHERE s.branch = $1 AND s.semester = $2
+        WHERE s.branch = $1 AND s.semester = $2 AND (s.course_id = $4 OR s.course_id IS NULL)
         ORDER BY s.id ASC
         "#
     )
     .bind(branch)
     .bind(semester)
     .bind(section)
+    .bind(course_id)
     .fetch_all(pool)
     .await
     .map(|rows| rows.into_iter().map(|r| (
@@ -119,21 +120,6 @@ pub async fn find_subjects_by_branch_and_semester(pool: &PgPool, branch: &str, s
     )).collect())
 }
 
-pub async fn count_lesson_plan_items(pool: &PgPool, subject_id: &str) -> Result<i64, sqlx::Error> {
-    sqlx::query_scalar("SELECT COUNT(*) FROM lesson_plan_items WHERE subject_id = $1 AND LOWER(type) != 'unit'")
-        .bind(subject_id)
-        .fetch_one(pool)
-        .await
-}
-
-pub async fn count_completed_lesson_plan_items(pool: &PgPool, subject_id: &str, section: &str) -> Result<i64, sqlx::Error> {
-    sqlx::query_scalar("SELECT COUNT(*) FROM lesson_plan_items lpi JOIN lesson_plan_progress lpp ON lpi.id = lpp.item_id WHERE lpi.subject_id = $1 AND lpp.section = $2 AND lpp.completed = TRUE AND LOWER(lpi.type) != 'unit'")
-        .bind(subject_id)
-        .bind(section)
-        .fetch_one(pool)
-        .await
-}
-
 pub async fn get_lesson_plan_items(pool: &PgPool, subject_id: &str, section: &str, branch: Option<&str>) -> Result<Vec<LessonPlanItemResponse>, sqlx::Error> {
     sqlx::query_as::<Postgres, LessonPlanItemResponse>(
         r#"
@@ -143,12 +129,12 @@ pub async fn get_lesson_plan_items(pool: &PgPool, subject_id: &str, section: &st
             lpi.topic, 
             lpi.text, 
             lpi.sno, 
-            COALESCE(lpp.completed, FALSE) as completed,
-            lpp.completed_date as completed_at,
+            COALESCE(cp.status = 'completed', FALSE) as completed,
+            cp.completed_date::TIMESTAMPTZ as completed_at,
             lpi.student_review,
             ls.schedule_date as scheduled_date
         FROM lesson_plan_items lpi
-        LEFT JOIN lesson_plan_progress lpp ON lpi.id = lpp.item_id AND (TRIM(lpp.section) = TRIM($2) OR $2 IS NULL)
+        LEFT JOIN curriculum_progress cp ON lpi.id = cp.lesson_plan_item_id AND (TRIM(cp.section) = TRIM($2) OR $2 IS NULL)
         LEFT JOIN lesson_schedule ls ON lpi.id = ls.topic_id AND (TRIM(ls.section) = TRIM($2) OR $2 IS NULL) AND (ls.branch = $3 OR $3 IS NULL)
         WHERE TRIM(lpi.subject_id) ILIKE TRIM($1)
         ORDER BY lpi.order_index ASC
@@ -207,8 +193,8 @@ pub async fn delete_attendance_correction_requests(pool: &PgPool, ids: Vec<Uuid>
     sqlx::query("DELETE FROM attendance_correction_requests WHERE id = ANY($1)").bind(&ids).execute(pool).await.map(|r| r.rows_affected())
 }
 
-pub async fn get_subjects_by_semester(pool: &PgPool, branch: &str, semester: &str) -> Result<Vec<(String, String, Option<i32>)>, sqlx::Error> {
-    sqlx::query_as("SELECT id, name, credit FROM subjects WHERE branch = $1 AND semester = $2 ORDER BY id ASC").bind(branch).bind(semester).fetch_all(pool).await
+pub async fn get_subjects_by_semester(pool: &PgPool, branch: &str, semester: &str, course_id: &str) -> Result<Vec<(String, String, Option<i32>)>, sqlx::Error> {
+    sqlx::query_as("SELECT id, name, credit FROM subjects WHERE branch = $1 AND semester = $2 AND (course_id = $3 OR course_id IS NULL) ORDER BY id ASC").bind(branch).bind(semester).bind(course_id).fetch_all(pool).await
 }
 
 pub async fn get_student_mark(pool: &PgPool, login_id: &str, semester: &str, subject_name: &str) -> Result<Option<Option<i32>>, sqlx::Error> {
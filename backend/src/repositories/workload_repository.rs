@@ -0,0 +1,104 @@
+use sqlx::{PgPool, Postgres};
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::workload::{FacultyLoadRow, FacultyPeriodCounts, SaveWorkloadNormsRequest, WorkloadNorms};
+use crate::repositories::timetable_repository::grid_on_date;
+
+const NORM_COLUMNS: &str = "branch, max_weekly_periods, min_weekly_periods, max_subjects, max_sections, updated_at";
+
+/// The branch's own norms, else the college-wide 'ALL' row.
+pub async fn find_norms(pool: &PgPool, branch: &str) -> Result<Option<WorkloadNorms>, sqlx::Error> {
+    sqlx::query_as::<Postgres, WorkloadNorms>(&format!(
+        "SELECT {} FROM workload_norms WHERE branch IN ($1, 'ALL') ORDER BY (branch = $1) DESC LIMIT 1",
+        NORM_COLUMNS
+    ))
+    .bind(branch)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn save_norms(pool: &PgPool, branch: &str, req: &SaveWorkloadNormsRequest, updated_by: Uuid) -> Result<WorkloadNorms, sqlx::Error> {
+    sqlx::query_as::<Postgres, WorkloadNorms>(&format!(
+        "INSERT INTO workload_norms (branch, max_weekly_periods, min_weekly_periods, max_subjects, max_sections, updated_by)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT (branch) DO UPDATE SET
+            max_weekly_periods = EXCLUDED.max_weekly_periods, min_weekly_periods = EXCLUDED.min_weekly_periods,
+            max_subjects = EXCLUDED.max_subjects, max_sections = EXCLUDED.max_sections,
+            updated_by = EXCLUDED.updated_by, updated_at = NOW()
+         RETURNING {}",
+        NORM_COLUMNS
+    ))
+    .bind(branch)
+    .bind(req.max_weekly_periods)
+    .bind(req.min_weekly_periods)
+    .bind(req.max_subjects)
+    .bind(req.max_sections)
+    .bind(updated_by)
+    .fetch_one(pool)
+    .await
+}
+
+/// Department faculty with their weekly periods in the live timetable (lab grid rows left out, they
+/// mirror class periods) and their approved subjects and sections.
+pub async fn find_faculty_loads(pool: &PgPool, branch_variations: &[String]) -> Result<Vec<FacultyLoadRow>, sqlx::Error> {
+    sqlx::query_as::<Postgres, FacultyLoadRow>(
+        r#"
+        SELECT u.login_id, u.full_name,
+               (SELECT COUNT(*) FROM timetable_entries t WHERE t.faculty_id = u.login_id AND t.year <> 'Lab') as weekly_periods,
+               (SELECT COUNT(DISTINCT fs.subject_id) FROM faculty_subjects fs
+                WHERE fs.user_id = u.id AND COALESCE(fs.status, 'APPROVED') = 'APPROVED') as subject_count,
+               (SELECT COUNT(DISTINCT COALESCE(fs.branch, '') || '/' || COALESCE(fs.section, '')) FROM faculty_subjects fs
+                WHERE fs.user_id = u.id AND COALESCE(fs.status, 'APPROVED') = 'APPROVED') as section_count
+        FROM users u
+        WHERE u.role IN ('Faculty', 'HOD') AND u.branch = ANY($1)
+        ORDER BY u.full_name
+        "#
+    )
+    .bind(branch_variations)
+    .fetch_all(pool)
+    .await
+}
+
+/// Scheduled periods from the timetable in effect on each instructional day up to `scheduled_to`,
+/// against the statuses recorded between `from` and `to`. Status rows name faculty by login or by
+/// full name, so both are matched.
+pub async fn find_period_counts(pool: &PgPool, logins: &[String], from: NaiveDate, to: NaiveDate, scheduled_to: NaiveDate) -> Result<Vec<FacultyPeriodCounts>, sqlx::Error> {
+    sqlx::query_as::<Postgres, FacultyPeriodCounts>(&format!(
+        r#"
+        WITH f AS (SELECT login_id, full_name FROM users WHERE login_id = ANY($1)),
+        sched AS (
+            SELECT g.faculty_id, COUNT(*) as n
+            FROM generate_series($2::date, $4::date, INTERVAL '1 day') AS d(date)
+            CROSS JOIN LATERAL ({}) g
+            WHERE g.day = TO_CHAR(d.date, 'FMDay') AND g.year <> 'Lab' AND g.faculty_id = ANY($1)
+              AND is_instructional_day(d.date::date, g.branch, g.year)
+            GROUP BY g.faculty_id
+        ),
+        st AS (
+            SELECT f.login_id,
+                   COUNT(*) FILTER (WHERE s.status = 'conducted' AND s.original_faculty IN (f.login_id, f.full_name)) as conducted,
+                   COUNT(*) FILTER (WHERE s.status = 'not_conducted' AND s.original_faculty IN (f.login_id, f.full_name)) as not_conducted,
+                   COUNT(*) FILTER (WHERE s.status = 'substitute' AND s.original_faculty IN (f.login_id, f.full_name)) as given,
+                   COUNT(*) FILTER (WHERE s.status = 'substitute' AND s.actual_faculty IN (f.login_id, f.full_name)
+                                      AND s.original_faculty NOT IN (f.login_id, f.full_name)) as taken
+            FROM f
+            JOIN class_period_status s ON (s.original_faculty IN (f.login_id, f.full_name) OR s.actual_faculty IN (f.login_id, f.full_name))
+                 AND s.status_date BETWEEN $2 AND $3
+            GROUP BY f.login_id
+        )
+        SELECT f.login_id, COALESCE(sched.n, 0) as scheduled, COALESCE(st.conducted, 0) as conducted,
+               COALESCE(st.not_conducted, 0) as not_conducted, COALESCE(st.given, 0) as substitutions_given,
+               COALESCE(st.taken, 0) as substitutions_taken
+        FROM f
+        LEFT JOIN sched ON sched.faculty_id = f.login_id
+        LEFT JOIN st ON st.login_id = f.login_id
+        "#,
+        grid_on_date("d.date::date")
+    ))
+    .bind(logins)
+    .bind(from)
+    .bind(to)
+    .bind(scheduled_to)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,152 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::academic_calendar::{CalendarRangeQuery, CreateAcademicYearRequest, CreateCalendarEventRequest, CreateSemesterRequest, DeleteCalendarEventRequest, SemesterQuery};
+use crate::services::academic_calendar_service;
+
+pub async fn create_academic_year_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateAcademicYearRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match academic_calendar_service::create_academic_year(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Academic year saved",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_academic_years_handler(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match academic_calendar_service::get_academic_years(&state.pool).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Academic years fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch academic years",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn create_semester_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateSemesterRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match academic_calendar_service::create_semester(&state.pool, payload).await {
+        Ok(id) => Ok(Json(json!({
+            "success": true,
+            "message": "Semester saved",
+            "data": { "id": id }
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_semesters_handler(
+    State(state): State<AppState>,
+    Query(params): Query<SemesterQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match academic_calendar_service::get_semesters(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Semesters fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch semesters",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn create_calendar_event_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateCalendarEventRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match academic_calendar_service::create_event(&state.pool, payload).await {
+        Ok(id) => Ok(Json(json!({
+            "success": true,
+            "message": "Calendar event added",
+            "data": { "id": id }
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn delete_calendar_event_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<DeleteCalendarEventRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match academic_calendar_service::delete_event(&state.pool, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Calendar event deleted",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_calendar_events_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CalendarRangeQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match academic_calendar_service::get_events(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Calendar events fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_calendar_days_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CalendarRangeQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match academic_calendar_service::get_calendar_days(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Calendar days fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
@@ -0,0 +1,98 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::analytics::{AbsenceStreakQuery, AttendanceAnalyticsQuery};
+use crate::services::analytics_service;
+
+pub async fn get_attendance_trends_handler(
+    State(state): State<AppState>,
+    Query(params): Query<AttendanceAnalyticsQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match analytics_service::get_trends(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Attendance trends fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch attendance trends",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_attendance_heatmap_handler(
+    State(state): State<AppState>,
+    Query(params): Query<AttendanceAnalyticsQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match analytics_service::get_heatmap(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Attendance heatmap fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch attendance heatmap",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_absence_streaks_handler(
+    State(state): State<AppState>,
+    Query(params): Query<AbsenceStreakQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match analytics_service::get_absence_streaks(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Absence streaks fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch absence streaks",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_faculty_comparison_handler(
+    State(state): State<AppState>,
+    Query(params): Query<AttendanceAnalyticsQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match analytics_service::get_faculty_comparison(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Faculty comparison fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch faculty comparison",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn refresh_attendance_summary_handler(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match analytics_service::refresh_summary(&state.pool).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Attendance summary refreshed",
+            "data": null
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to refresh attendance summary",
+            "data": null
+        })))),
+    }
+}
@@ -0,0 +1,189 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::attendance::{
+    AttendanceHistoryQuery, AttendanceLockQuery, AttendanceSyncRequest, BiometricConflictQuery, BiometricImportQuery,
+    BiometricIngestRequest, BiometricMappingRequest, ResolveBiometricConflictRequest, UpdateEditWindowRequest
+};
+use crate::services::{attendance_service, biometric_service};
+
+// --- Edit Window / Locking ---
+
+pub async fn get_lock_status_handler(
+    State(state): State<AppState>,
+    Query(params): Query<AttendanceLockQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match attendance_service::get_lock_status(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Lock status fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch lock status",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn update_edit_window_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<UpdateEditWindowRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match attendance_service::update_edit_window(&state.pool, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Attendance edit window updated",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+// --- Versioned History ---
+
+pub async fn get_attendance_history_handler(
+    State(state): State<AppState>,
+    Query(params): Query<AttendanceHistoryQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match attendance_service::get_attendance_history(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Attendance history fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch attendance history",
+            "data": null
+        })))),
+    }
+}
+
+// --- Offline Sync ---
+
+pub async fn sync_attendance_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<AttendanceSyncRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match attendance_service::sync_attendance(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Attendance synced",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+// --- Biometric / RFID Ingestion ---
+
+pub async fn save_biometric_mappings_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<BiometricMappingRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match biometric_service::save_mappings(&state.pool, payload).await {
+        Ok(count) => Ok(Json(json!({
+            "success": true,
+            "message": format!("{} device codes mapped", count),
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn ingest_biometric_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<BiometricIngestRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match biometric_service::ingest_punches(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Punches ingested",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn import_biometric_csv_handler(
+    State(state): State<AppState>,
+    Query(params): Query<BiometricImportQuery>,
+    body: String,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let (punches, invalid_lines) = biometric_service::parse_punch_csv(&body);
+    let payload = BiometricIngestRequest { device_id: params.device_id, ingested_by: params.ingested_by, punches };
+
+    match biometric_service::ingest_punches(&state.pool, payload).await {
+        Ok(mut res) => {
+            res.invalid_lines = invalid_lines;
+            Ok(Json(json!({
+                "success": true,
+                "message": "Punch log imported",
+                "data": res
+            })))
+        }
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_biometric_conflicts_handler(
+    State(state): State<AppState>,
+    Query(params): Query<BiometricConflictQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match biometric_service::get_conflicts(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Biometric conflicts fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch biometric conflicts",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn resolve_biometric_conflict_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<ResolveBiometricConflictRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match biometric_service::resolve_conflict(&state.pool, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Conflict resolved",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
@@ -0,0 +1,75 @@
+use axum::{
+    extract::{State, Query, Path},
+    Json, http::{header, StatusCode},
+    response::IntoResponse,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::calendar_feed::{CreateFeedRequest, FeedListQuery, RevokeFeedRequest};
+use crate::services::calendar_feed_service;
+
+pub async fn create_calendar_feed_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateFeedRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match calendar_feed_service::create_feed(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Calendar feed created",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_calendar_feeds_handler(
+    State(state): State<AppState>,
+    Query(params): Query<FeedListQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match calendar_feed_service::get_feeds(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Calendar feeds fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch calendar feeds",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn revoke_calendar_feed_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<RevokeFeedRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match calendar_feed_service::revoke_feed(&state.pool, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Calendar feed revoked",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+/// The token in the URL is the credential, since calendar apps cannot send headers.
+pub async fn calendar_ics_handler(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    match calendar_feed_service::render_feed(&state.pool, &token).await {
+        Ok(body) => (StatusCode::OK, [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")], body),
+        Err(code) => (code, [(header::CONTENT_TYPE, "text/plain; charset=utf-8")], "Calendar feed unavailable".to_string()),
+    }
+}
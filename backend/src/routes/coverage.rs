@@ -0,0 +1,119 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::coverage::{CoverageReportIdQuery, CoverageReportQuery, GenerateCoverageRequest, ReviewCoverageRequest, SignCoverageRequest};
+use crate::services::coverage_service;
+
+pub async fn get_coverage_reports_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CoverageReportQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match coverage_service::get_reports(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Coverage reports fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_coverage_report_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CoverageReportIdQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match coverage_service::get_report(&state.pool, params.report_id).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Coverage report fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn generate_coverage_report_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<GenerateCoverageRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match coverage_service::generate_report(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Coverage report generated",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn sign_coverage_report_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SignCoverageRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match coverage_service::sign_report(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Coverage report signed and sent to the HOD",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn review_coverage_report_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<ReviewCoverageRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let message = if payload.approve { "Coverage report countersigned and archived" } else { "Coverage report returned to the faculty member" };
+    match coverage_service::review_report(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": message,
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+/// The archived PDF once the HOD has countersigned; an unsigned preview before that.
+pub async fn coverage_report_pdf_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CoverageReportIdQuery>,
+) -> Response {
+    match coverage_service::get_pdf(&state.pool, params.report_id).await {
+        Ok((file_name, bytes)) => {
+            let disposition = format!("attachment; filename=\"{}\"", file_name);
+            (StatusCode::OK, [(header::CONTENT_TYPE, "application/pdf".to_string()), (header::CONTENT_DISPOSITION, disposition)], bytes).into_response()
+        }
+        Err((c, msg)) => (c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        }))).into_response(),
+    }
+}
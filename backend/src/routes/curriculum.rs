@@ -1,11 +1,11 @@
 use axum::{
     extract::{Query, State, Path},
-    Json,
+    Json, http::StatusCode,
     response::IntoResponse,
 };
 use serde_json::json;
 use crate::models::{AppState, ApiResponse};
-use crate::models::curriculum::{UpdateProgressRequest, SubmitFeedbackRequest};
+use crate::models::curriculum::{CurriculumRevisionsQuery, CurriculumSubjectsQuery, UpdateProgressRequest, SubmitFeedbackRequest};
 use crate::services::curriculum_service;
 use crate::repositories::curriculum_repository;
 use std::collections::HashMap;
@@ -42,7 +42,7 @@ pub async fn update_progress_handler(
     State(state): State<AppState>,
     Json(req): Json<UpdateProgressRequest>,
 ) -> impl IntoResponse {
-    match curriculum_repository::upsert_progress(&state.pool, req).await {
+    match curriculum_service::update_progress(&state.pool, req).await {
         Ok(_) => Json(ApiResponse {
             success: true,
             message: "Progress updated successfully".to_string(),
@@ -85,3 +85,56 @@ pub async fn submit_feedback_handler(
         }),
     }
 }
+
+pub async fn get_curriculum_regulations_handler(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match curriculum_service::get_regulations(&state.pool).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Regulations fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch regulations",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_curriculum_subjects_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CurriculumSubjectsQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match curriculum_service::get_subjects(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Curriculum subjects fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch curriculum subjects",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_curriculum_revisions_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CurriculumRevisionsQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match curriculum_service::get_revisions(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Curriculum revisions fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch curriculum revisions",
+            "data": null
+        })))),
+    }
+}
@@ -0,0 +1,81 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::leave::{ApplyLeaveRequest, CancelLeaveRequest, LeaveApplicationQuery, ReviewLeaveRequest};
+use crate::services::leave_service;
+
+pub async fn apply_leave_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<ApplyLeaveRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match leave_service::apply_leave(&state.pool, payload).await {
+        Ok(id) => Ok(Json(json!({
+            "success": true,
+            "message": "Leave application submitted",
+            "data": { "id": id }
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_leave_applications_handler(
+    State(state): State<AppState>,
+    Query(params): Query<LeaveApplicationQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match leave_service::get_leave_applications(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Leave applications fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch leave applications",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn review_leave_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<ReviewLeaveRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match leave_service::review_leave(&state.pool, payload).await {
+        Ok(locked_dates) => Ok(Json(json!({
+            "success": true,
+            "message": if locked_dates.is_empty() { "Leave application reviewed" } else { "Leave approved; attendance on locked dates needs a correction request" },
+            "data": { "lockedDates": locked_dates }
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn cancel_leave_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<CancelLeaveRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match leave_service::cancel_leave(&state.pool, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Leave application cancelled",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
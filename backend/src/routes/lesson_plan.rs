@@ -0,0 +1,47 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::lesson_plan::{GenerateLessonPlanRequest, LessonPlanQuery};
+use crate::services::lesson_plan_service;
+
+/// Plans of a branch, optionally narrowed to a section; `atRisk=true` lists only the subjects
+/// that will not finish before the exam block.
+pub async fn get_lesson_plans_handler(
+    State(state): State<AppState>,
+    Query(params): Query<LessonPlanQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match lesson_plan_service::get_plans(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Lesson plans fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch lesson plans",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn generate_lesson_plan_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<GenerateLessonPlanRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match lesson_plan_service::generate_plan(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": if res.plan.at_risk { "Lesson plan generated; the syllabus will not finish before the exam block" } else { "Lesson plan generated successfully" },
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
@@ -2,10 +2,15 @@ use axum::{
     extract::{State, Query},
     Json,
     http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
 };
+use futures::stream::{self, Stream, StreamExt};
+use std::convert::Infallible;
+use tokio::sync::broadcast::error::RecvError;
 use crate::models::{
-    AppState, InchargeTimetableLookupQuery, UpdateClassStatusRequest, DailyReportQuery
+    AppState, InchargeTimetableLookupQuery, UpdateClassStatusRequest, DailyReportQuery, normalize_branch
 };
+use crate::models::live_status::{LiveClassEvent, LiveStatusQuery};
 use serde_json::json;
 
 pub async fn incharge_timetable_lookup_handler(
@@ -36,7 +41,7 @@ pub async fn update_class_status_handler(
     State(state): State<AppState>,
     Json(payload): Json<UpdateClassStatusRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    match crate::services::management::incharge_service::update_class_status(&state.pool, payload).await {
+    match crate::services::management::incharge_service::update_class_status(&state.pool, &state.live_status, payload).await {
         Ok(res) => {
             println!("UPDATE Class Status Result: {:?}", res);
             Ok(Json(json!({
@@ -135,3 +140,57 @@ pub async fn get_branch_daily_detail_report_handler(
         },
     }
 }
+
+pub async fn get_unattended_periods_handler(
+    State(state): State<AppState>,
+    Query(params): Query<LiveStatusQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match crate::services::management::incharge_service::get_unattended_periods(&state.pool, &params.branch).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Unattended periods fetched",
+            "data": res
+        }))),
+        Err(e) => Err((e, Json(json!({
+            "success": false,
+            "message": "Failed to fetch unattended periods",
+            "data": null
+        })))),
+    }
+}
+
+fn sse_event(event: &LiveClassEvent) -> Result<Event, Infallible> {
+    Ok(Event::default().event(event.name()).data(event.to_json()))
+}
+
+/// Server-sent events for the live class-status board: `status` whenever a period status is
+/// written and `unattended` when a period has been running without one. The stream opens with
+/// the periods already unattended today.
+pub async fn class_status_stream_handler(
+    State(state): State<AppState>,
+    Query(mut params): Query<LiveStatusQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    params.branch = normalize_branch(&params.branch);
+    // Subscribe before reading the backlog so nothing written in between is missed.
+    let rx = state.live_status.subscribe();
+    let backlog: Vec<LiveClassEvent> = crate::services::management::incharge_service::get_unattended_periods(&state.pool, &params.branch)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(LiveClassEvent::Unattended)
+        .filter(|e| params.matches(e))
+        .collect();
+
+    let initial = stream::iter(backlog.iter().map(sse_event).collect::<Vec<_>>());
+    let live = stream::unfold((rx, params), |(mut rx, params)| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) if params.matches(&event) => return Some((sse_event(&event), (rx, params))),
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(initial.chain(live)).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,161 @@
+use axum::{
+    body::Bytes,
+    extract::{State, Query},
+    Json, http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::material::{
+    ArchiveMaterialRequest, DownloadMaterialQuery, MaterialAnalyticsQuery, MaterialBody, MaterialQuery, MaterialVersionsQuery,
+    SaveLinkRequest, UpdateVisibilityRequest, UploadMaterialQuery,
+};
+use crate::services::material_service;
+
+pub async fn get_materials_handler(
+    State(state): State<AppState>,
+    Query(params): Query<MaterialQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match material_service::get_materials(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Course materials fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+/// The file is the raw request body; its name and placement come in the query string.
+pub async fn upload_material_handler(
+    State(state): State<AppState>,
+    Query(params): Query<UploadMaterialQuery>,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match material_service::upload_file(&state.pool, params, &body).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Material uploaded",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn save_link_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SaveLinkRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match material_service::save_link(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Link saved",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn update_visibility_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<UpdateVisibilityRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match material_service::update_visibility(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Material visibility updated",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn archive_material_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<ArchiveMaterialRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match material_service::archive_material(&state.pool, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Material archived",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_versions_handler(
+    State(state): State<AppState>,
+    Query(params): Query<MaterialVersionsQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match material_service::get_versions(&state.pool, params.material_id).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Material versions fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+/// Sends the file, or redirects to the link, of a material version.
+pub async fn download_material_handler(
+    State(state): State<AppState>,
+    Query(params): Query<DownloadMaterialQuery>,
+) -> Response {
+    match material_service::download(&state.pool, params).await {
+        Ok(MaterialBody::File { file_name, content_type, bytes }) => {
+            let disposition = format!("attachment; filename=\"{}\"", file_name);
+            (StatusCode::OK, [(header::CONTENT_TYPE, content_type), (header::CONTENT_DISPOSITION, disposition)], bytes).into_response()
+        }
+        Ok(MaterialBody::Link(url)) => Redirect::temporary(&url).into_response(),
+        Err((c, msg)) => (c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        }))).into_response(),
+    }
+}
+
+pub async fn get_material_analytics_handler(
+    State(state): State<AppState>,
+    Query(params): Query<MaterialAnalyticsQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match material_service::get_download_analytics(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Material downloads fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
@@ -5,6 +5,7 @@ pub mod management;
 pub mod curriculum;
 pub mod chat;
 pub mod finance;
+pub mod attendance;
 
 pub use auth::*;
 pub use user::*;
@@ -13,3 +14,19 @@ pub use curriculum::*;
 pub use chat::*;
 pub use finance::*;
 
+pub mod leave;
+pub mod analytics;
+pub mod timetable;
+pub mod substitution;
+pub mod calendar_feed;
+pub mod academic_calendar;
+pub mod room;
+pub mod workload;
+pub mod slot_template;
+pub mod lesson_plan;
+pub mod syllabus_forecast;
+pub mod outcome;
+pub mod regulation;
+pub mod topic_feedback;
+pub mod material;
+pub mod coverage;
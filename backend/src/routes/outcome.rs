@@ -0,0 +1,276 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::outcome::{
+    AssessmentMarksQuery, AttainmentQuery, CourseOutcomeQuery, DeleteOutcomeRequest, ProgramAttainmentQuery,
+    ProgramOutcomeQuery, SaveAssessmentComponentRequest, SaveAssessmentMarksRequest, SaveCourseOutcomeRequest,
+    SaveProgramOutcomeRequest,
+};
+use crate::services::outcome_service;
+
+pub async fn get_program_outcomes_handler(
+    State(state): State<AppState>,
+    Query(params): Query<ProgramOutcomeQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match outcome_service::get_program_outcomes(&state.pool, &params.branch).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Program outcomes fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn save_program_outcome_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SaveProgramOutcomeRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match outcome_service::save_program_outcome(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Program outcome saved",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn delete_program_outcome_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<DeleteOutcomeRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match outcome_service::delete_program_outcome(&state.pool, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Program outcome deleted",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_course_outcomes_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CourseOutcomeQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match outcome_service::get_course_outcomes(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Course outcomes fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn save_course_outcome_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SaveCourseOutcomeRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match outcome_service::save_course_outcome(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Course outcome saved",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn delete_course_outcome_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<DeleteOutcomeRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match outcome_service::delete_course_outcome(&state.pool, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Course outcome deleted",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_assessment_components_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CourseOutcomeQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match outcome_service::get_components(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Assessment components fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn save_assessment_component_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SaveAssessmentComponentRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match outcome_service::save_component(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Assessment component saved",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn delete_assessment_component_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<DeleteOutcomeRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match outcome_service::delete_component(&state.pool, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Assessment component deleted",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_assessment_marks_handler(
+    State(state): State<AppState>,
+    Query(params): Query<AssessmentMarksQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match outcome_service::get_marks(&state.pool, params.component_id).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Assessment marks fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+/// Saves a component's marks for a batch of students, all or none.
+pub async fn save_assessment_marks_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SaveAssessmentMarksRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match outcome_service::save_marks(&state.pool, payload).await {
+        Ok(saved) => Ok(Json(json!({
+            "success": true,
+            "message": format!("{} marks saved", saved),
+            "data": { "saved": saved }
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+/// The subject's CO-PO articulation matrix with attainment. `format=csv` or `format=pdf`
+/// downloads it for the accreditation file; JSON otherwise.
+pub async fn subject_attainment_handler(
+    State(state): State<AppState>,
+    Query(params): Query<AttainmentQuery>,
+) -> Response {
+    let report = match outcome_service::get_subject_attainment(&state.pool, &params).await {
+        Ok(r) => r,
+        Err((c, msg)) => return (c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        }))).into_response(),
+    };
+
+    match params.format.as_deref().map(|f| f.to_lowercase()).as_deref() {
+        Some("csv") => {
+            let disposition = format!("attachment; filename=\"{}\"", outcome_service::subject_filename(&report, "csv"));
+            (StatusCode::OK, [(header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()), (header::CONTENT_DISPOSITION, disposition)], outcome_service::subject_csv(&report)).into_response()
+        }
+        Some("pdf") => {
+            let disposition = format!("attachment; filename=\"{}\"", outcome_service::subject_filename(&report, "pdf"));
+            (StatusCode::OK, [(header::CONTENT_TYPE, "application/pdf".to_string()), (header::CONTENT_DISPOSITION, disposition)], outcome_service::subject_pdf(&report)).into_response()
+        }
+        _ => Json(json!({
+            "success": true,
+            "message": "Attainment computed successfully",
+            "data": report
+        })).into_response(),
+    }
+}
+
+/// PO attainment per subject across the branch's programme; exports like the subject matrix.
+pub async fn program_attainment_handler(
+    State(state): State<AppState>,
+    Query(params): Query<ProgramAttainmentQuery>,
+) -> Response {
+    let report = match outcome_service::get_program_attainment(&state.pool, &params).await {
+        Ok(r) => r,
+        Err((c, msg)) => return (c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        }))).into_response(),
+    };
+
+    match params.format.as_deref().map(|f| f.to_lowercase()).as_deref() {
+        Some("csv") => {
+            let disposition = format!("attachment; filename=\"{}\"", outcome_service::program_filename(&report, "csv"));
+            (StatusCode::OK, [(header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()), (header::CONTENT_DISPOSITION, disposition)], outcome_service::program_csv(&report)).into_response()
+        }
+        Some("pdf") => {
+            let disposition = format!("attachment; filename=\"{}\"", outcome_service::program_filename(&report, "pdf"));
+            (StatusCode::OK, [(header::CONTENT_TYPE, "application/pdf".to_string()), (header::CONTENT_DISPOSITION, disposition)], outcome_service::program_pdf(&report)).into_response()
+        }
+        _ => Json(json!({
+            "success": true,
+            "message": "Program attainment computed successfully",
+            "data": report
+        })).into_response(),
+    }
+}
@@ -0,0 +1,101 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::regulation::{
+    AssignRegulationRequest, DeleteEquivalenceRequest, EquivalenceQuery, SaveEquivalenceRequest, StudentRegulationQuery,
+};
+use crate::services::regulation_service;
+
+pub async fn get_equivalences_handler(
+    State(state): State<AppState>,
+    Query(params): Query<EquivalenceQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match regulation_service::get_equivalences(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Subject equivalences fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn save_equivalence_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SaveEquivalenceRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match regulation_service::save_equivalence(&state.pool, payload).await {
+        Ok(id) => Ok(Json(json!({
+            "success": true,
+            "message": "Subject equivalence saved",
+            "data": { "id": id }
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn delete_equivalence_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<DeleteEquivalenceRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match regulation_service::delete_equivalence(&state.pool, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Subject equivalence deleted",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_student_regulation_handler(
+    State(state): State<AppState>,
+    Query(params): Query<StudentRegulationQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match regulation_service::get_student_regulation(&state.pool, &params.student_id).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Student regulation fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn assign_regulation_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<AssignRegulationRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match regulation_service::assign_regulation(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Student regulation assigned",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
@@ -0,0 +1,117 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::room::{FreeRoomQuery, RelocateClassRequest, RelocationQuery, RoomListQuery, RoomUtilizationQuery, SaveRoomRequest};
+use crate::services::room_service;
+
+pub async fn save_room_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SaveRoomRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match room_service::save_room(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Room saved",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_rooms_handler(
+    State(state): State<AppState>,
+    Query(params): Query<RoomListQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match room_service::get_rooms(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Rooms fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch rooms",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_free_rooms_handler(
+    State(state): State<AppState>,
+    Query(params): Query<FreeRoomQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match room_service::get_free_rooms(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Free rooms fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to search free rooms",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn relocate_class_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<RelocateClassRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match room_service::relocate_class(&state.pool, payload).await {
+        Ok(id) => Ok(Json(json!({
+            "success": true,
+            "message": "Class relocated",
+            "data": { "relocationId": id }
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_relocations_handler(
+    State(state): State<AppState>,
+    Query(params): Query<RelocationQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match room_service::get_relocations(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Relocations fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch relocations",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_room_utilization_handler(
+    State(state): State<AppState>,
+    Query(params): Query<RoomUtilizationQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match room_service::get_utilization(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Room utilization fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch room utilization",
+            "data": null
+        })))),
+    }
+}
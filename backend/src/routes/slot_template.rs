@@ -0,0 +1,82 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::slot_template::{PreviewSlotTemplateRequest, SaveSlotTemplateRequest, SlotTemplateHistoryQuery, SlotTemplateQuery};
+use crate::services::slot_template_service;
+
+pub async fn get_slot_templates_handler(
+    State(state): State<AppState>,
+    Query(params): Query<SlotTemplateHistoryQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match slot_template_service::get_templates(&state.pool, &params.branch).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Slot templates fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch slot templates",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn save_slot_template_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SaveSlotTemplateRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match slot_template_service::save_template(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Slot template saved successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+/// Clock times per period_index of the template in effect on `date` (default today).
+pub async fn get_slot_template_preview_handler(
+    State(state): State<AppState>,
+    Query(params): Query<SlotTemplateQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match slot_template_service::preview_saved(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Slot template preview generated successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+/// Validates an unsaved template and returns its clock times.
+pub async fn preview_slot_template_handler(
+    Json(payload): Json<PreviewSlotTemplateRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match slot_template_service::preview_draft(payload) {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Slot template preview generated successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
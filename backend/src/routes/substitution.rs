@@ -0,0 +1,46 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::substitution::{PlanSubstitutesRequest, SubstituteQuery};
+use crate::services::substitution_service;
+
+pub async fn recommend_substitutes_handler(
+    State(state): State<AppState>,
+    Query(params): Query<SubstituteQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match substitution_service::recommend_substitutes(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Substitute recommendations fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn plan_substitutes_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<PlanSubstitutesRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let applied = payload.apply;
+    match substitution_service::plan_substitutes(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": if applied { "Substitutes assigned and notified" } else { "Substitute plan prepared" },
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
@@ -0,0 +1,47 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::syllabus_forecast::{RefreshForecastsRequest, SyllabusForecastQuery};
+use crate::services::syllabus_forecast_service;
+
+/// Returns the stored forecasts of a branch's lesson plans, worst first.
+pub async fn get_syllabus_forecasts_handler(
+    State(state): State<AppState>,
+    Query(params): Query<SyllabusForecastQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match syllabus_forecast_service::get_forecasts(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Syllabus forecasts fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+/// Recomputes the forecasts of a branch's lesson plans and returns them.
+pub async fn refresh_syllabus_forecasts_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<RefreshForecastsRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match syllabus_forecast_service::refresh_branch_forecasts(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Syllabus forecasts refreshed successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
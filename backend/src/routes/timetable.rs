@@ -0,0 +1,235 @@
+use axum::{
+    extract::{State, Query, Path},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::models::AppState;
+use crate::models::timetable::{
+    CloneVersionRequest, CreateVersionRequest, DraftListQuery, GenerateTimetableRequest, PublishDraftRequest,
+    VersionDiffQuery, VersionEntryRequest
+};
+use crate::services::timetable_service;
+
+pub async fn generate_timetable_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<GenerateTimetableRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::generate_draft(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable draft generated",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_timetable_drafts_handler(
+    State(state): State<AppState>,
+    Query(params): Query<DraftListQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::get_drafts(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable drafts fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch timetable drafts",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_timetable_draft_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::get_draft(&state.pool, id).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable draft fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch timetable draft",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn publish_timetable_draft_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<PublishDraftRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::publish_draft(&state.pool, id, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable published",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn discard_timetable_draft_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::discard_draft(&state.pool, id).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable draft discarded",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+// --- Versions ---
+
+pub async fn create_timetable_version_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateVersionRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::create_version(&state.pool, payload).await {
+        Ok(id) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable version created",
+            "data": { "id": id }
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_timetable_versions_handler(
+    State(state): State<AppState>,
+    Query(params): Query<DraftListQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::get_versions(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable versions fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch timetable versions",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_timetable_version_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::get_version(&state.pool, id).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable version fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch timetable version",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn set_timetable_version_entry_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<VersionEntryRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::set_version_entry(&state.pool, id, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable version updated",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn clone_timetable_version_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CloneVersionRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::clone_version(&state.pool, id, payload).await {
+        Ok(new_id) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable version cloned",
+            "data": { "id": new_id }
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn publish_timetable_version_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<PublishDraftRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::publish_version(&state.pool, id, payload).await {
+        Ok(_) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable version published",
+            "data": null
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn diff_timetable_versions_handler(
+    State(state): State<AppState>,
+    Query(params): Query<VersionDiffQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match timetable_service::diff_versions(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Timetable versions compared",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
@@ -0,0 +1,159 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::StatusCode,
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::topic_feedback::{
+    FacultyFeedbackQuery, FeedbackInboxQuery, ResolveFeedbackRequest, RevisionSessionQuery, ScheduleRevisionRequest,
+    StudentFeedbackQuery, TopicFeedbackQuery, UpdateRevisionRequest,
+};
+use crate::services::topic_feedback_service;
+
+/// Per-topic rating distributions, issue breakdowns and weekly issue trend of a subject.
+pub async fn get_feedback_analytics_handler(
+    State(state): State<AppState>,
+    Query(params): Query<TopicFeedbackQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match topic_feedback_service::get_subject_analytics(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Feedback analytics fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_faculty_feedback_analytics_handler(
+    State(state): State<AppState>,
+    Query(params): Query<FacultyFeedbackQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match topic_feedback_service::get_faculty_analytics(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Faculty feedback analytics fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+/// The faculty member's feedback clustered by topic, section and issue type.
+pub async fn get_feedback_inbox_handler(
+    State(state): State<AppState>,
+    Query(params): Query<FeedbackInboxQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match topic_feedback_service::get_inbox(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Feedback inbox fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn get_revisions_handler(
+    State(state): State<AppState>,
+    Query(params): Query<RevisionSessionQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match topic_feedback_service::get_revisions(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Revision classes fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn schedule_revision_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<ScheduleRevisionRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match topic_feedback_service::schedule_revision(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Revision class scheduled",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn update_revision_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<UpdateRevisionRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match topic_feedback_service::update_revision(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Revision class updated",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+pub async fn resolve_feedback_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<ResolveFeedbackRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match topic_feedback_service::resolve_feedback(&state.pool, payload).await {
+        Ok(closed) => Ok(Json(json!({
+            "success": true,
+            "message": "Feedback closed",
+            "data": { "closed": closed }
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
+
+/// A student's topic feedback with whether and how it was acted on.
+pub async fn get_my_feedback_handler(
+    State(state): State<AppState>,
+    Query(params): Query<StudentFeedbackQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match topic_feedback_service::get_student_feedback(&state.pool, &params.student_id).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Feedback fetched successfully",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
@@ -547,8 +547,17 @@ pub async fn assign_class_handler(
     Json(payload): Json<AssignClassRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
     match crate::services::user::faculty_service::assign_class(&state.pool, payload).await {
-        Ok(_) => Ok(Json(json!({"success": true, "message": "Class assigned successfully"}))),
-        Err(e) => Err((e, Json(json!({"error": "Failed to assign class"})))),
+        Ok(overridden) => Ok(Json(json!({
+            "success": true,
+            "message": "Class assigned successfully",
+            "data": { "overriddenConflicts": overridden }
+        }))),
+        Err((c, msg, conflicts)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "error": "Failed to assign class",
+            "data": { "conflicts": conflicts }
+        })))),
     }
 }
 
@@ -628,7 +637,7 @@ pub async fn get_department_timings(
 
 pub async fn update_department_timings(
     State(state): State<AppState>,
-    Json(payload): Json<serde_json::Value>,
+    Json(payload): Json<crate::models::slot_template::UpdateDepartmentTimingRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
     match crate::services::user::faculty_service::update_department_timings(&state.pool, payload).await {
         Ok(res) => {
@@ -639,11 +648,11 @@ pub async fn update_department_timings(
                 "data": res
             })))
         },
-        Err(e) => {
-            println!("UPDATE Timings Error: {:?}", e);
+        Err((e, msg)) => {
+            eprintln!("ERROR: Failed to update department timings: {:?} {}", e, msg);
             Err((e, Json(json!({
                 "success": false,
-                "message": "Failed to update timings",
+                "message": msg,
                 "data": null
             }))))
         },
@@ -736,11 +745,11 @@ pub async fn assign_lesson_schedule_handler(
                 "data": res
             })))
         },
-        Err(e) => {
-            println!("ASSIGN Schedule Error: {:?}", e);
-            Err((e, Json(json!({
+        Err((code, msg)) => {
+            eprintln!("ERROR: Failed to assign lesson schedule: {:?} {}", code, msg);
+            Err((code, Json(json!({
                 "success": false,
-                "message": "Failed to assign schedule",
+                "message": msg,
                 "data": null
             }))))
         },
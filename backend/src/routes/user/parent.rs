@@ -96,7 +96,7 @@ pub async fn update_parent_request_status_handler(
             println!("UPDATE Parent Request Error: {:?}", e);
             Err((e, Json(serde_json::json!({
                 "success": false,
-                "message": "Failed to update status",
+                "message": if e == StatusCode::CONFLICT { "This request is a leave application; it is reviewed from leave applications" } else { "Failed to update status" },
                 "data": null
             }))))
         },
@@ -120,7 +120,7 @@ pub async fn delete_parent_request_handler(
             println!("DELETE Parent Request Error: {:?}", e);
             Err((e, Json(serde_json::json!({
                 "success": false,
-                "message": "Failed to delete request",
+                "message": if e == StatusCode::CONFLICT { "This request is a leave application; it is cancelled from leave applications" } else { "Failed to delete request" },
                 "data": null
             }))))
         },
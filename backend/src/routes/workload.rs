@@ -0,0 +1,77 @@
+use axum::{
+    extract::{State, Query},
+    Json, http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+use crate::models::AppState;
+use crate::models::workload::{SaveWorkloadNormsRequest, WorkloadNormsQuery, WorkloadQuery};
+use crate::services::workload_service;
+
+/// `format=csv` or `format=pdf` downloads the report for the appraisal committee; JSON otherwise.
+pub async fn workload_report_handler(
+    State(state): State<AppState>,
+    Query(params): Query<WorkloadQuery>,
+) -> Response {
+    let report = match workload_service::get_workload_report(&state.pool, &params).await {
+        Ok(r) => r,
+        Err((c, msg)) => return (c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        }))).into_response(),
+    };
+
+    match params.format.as_deref().map(|f| f.to_lowercase()).as_deref() {
+        Some("csv") => {
+            let disposition = format!("attachment; filename=\"{}\"", workload_service::export_filename(&report, "csv"));
+            (StatusCode::OK, [(header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()), (header::CONTENT_DISPOSITION, disposition)], workload_service::workload_csv(&report)).into_response()
+        }
+        Some("pdf") => {
+            let disposition = format!("attachment; filename=\"{}\"", workload_service::export_filename(&report, "pdf"));
+            (StatusCode::OK, [(header::CONTENT_TYPE, "application/pdf".to_string()), (header::CONTENT_DISPOSITION, disposition)], workload_service::workload_pdf(&report)).into_response()
+        }
+        _ => Json(json!({
+            "success": true,
+            "message": "Workload report fetched successfully",
+            "data": report
+        })).into_response(),
+    }
+}
+
+pub async fn get_workload_norms_handler(
+    State(state): State<AppState>,
+    Query(params): Query<WorkloadNormsQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match workload_service::get_norms(&state.pool, params).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Workload norms fetched successfully",
+            "data": res
+        }))),
+        Err(code) => Err((code, Json(json!({
+            "success": false,
+            "message": "Failed to fetch workload norms",
+            "data": null
+        })))),
+    }
+}
+
+pub async fn save_workload_norms_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SaveWorkloadNormsRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    match workload_service::save_norms(&state.pool, payload).await {
+        Ok(res) => Ok(Json(json!({
+            "success": true,
+            "message": "Workload norms saved",
+            "data": res
+        }))),
+        Err((c, msg)) => Err((c, Json(json!({
+            "success": false,
+            "message": msg,
+            "data": null
+        })))),
+    }
+}
@@ -0,0 +1,144 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::normalize_branch;
+use crate::models::academic_calendar::{AcademicSemester, AcademicYear, CalendarDay, CalendarEvent, CalendarRangeQuery, CreateAcademicYearRequest, CreateCalendarEventRequest, CreateSemesterRequest, DeleteCalendarEventRequest, SemesterQuery};
+use crate::repositories::{academic_calendar_repository, leave_repository};
+use crate::utils::user_utils::resolve_user_id;
+
+const EVENT_TYPES: [&str; 3] = ["HOLIDAY", "WORKING_SATURDAY", "EXAM"];
+const MAX_RANGE_DAYS: i64 = 366;
+
+async fn require_calendar_editor(pool: &PgPool, login: &str) -> Result<Uuid, (StatusCode, String)> {
+    let user_id = resolve_user_id(login, "Admin", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", login)))?;
+    let (_, role, _, _, _, _) = leave_repository::find_user_basics(pool, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    if !matches!(role.as_str(), "Admin" | "Principal" | "HOD" | "Coordinator") {
+        return Err((StatusCode::FORBIDDEN, "Only Admin, Principal, HOD or Coordinator can edit the academic calendar".to_string()));
+    }
+    Ok(user_id)
+}
+
+fn check_range(start: NaiveDate, end: NaiveDate) -> Result<(), (StatusCode, String)> {
+    if end < start {
+        return Err((StatusCode::BAD_REQUEST, "endDate must not be before startDate".to_string()));
+    }
+    Ok(())
+}
+
+pub async fn create_academic_year(pool: &PgPool, payload: CreateAcademicYearRequest) -> Result<AcademicYear, (StatusCode, String)> {
+    check_range(payload.start_date, payload.end_date)?;
+    let name = payload.year_name.trim();
+    if name.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "yearName is required".to_string()));
+    }
+    academic_calendar_repository::upsert_academic_year(pool, name, payload.start_date, payload.end_date)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub async fn get_academic_years(pool: &PgPool) -> Result<Vec<AcademicYear>, StatusCode> {
+    academic_calendar_repository::find_academic_years(pool)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch academic years: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+pub async fn create_semester(pool: &PgPool, payload: CreateSemesterRequest) -> Result<Uuid, (StatusCode, String)> {
+    check_range(payload.start_date, payload.end_date)?;
+    let term = payload.term.trim().to_uppercase();
+    if term != "ODD" && term != "EVEN" {
+        return Err((StatusCode::BAD_REQUEST, "term must be ODD or EVEN".to_string()));
+    }
+    let year = academic_calendar_repository::find_academic_year(pool, payload.academic_year_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Academic year not found".to_string()))?;
+    if let (Some(ys), Some(ye)) = (year.start_date, year.end_date) {
+        if payload.start_date < ys || payload.end_date > ye {
+            return Err((StatusCode::BAD_REQUEST, format!("Semester must fall within {} ({} to {})", year.year_name, ys, ye)));
+        }
+    }
+    let overlaps = academic_calendar_repository::count_overlapping_semesters(pool, payload.start_date, payload.end_date, (payload.academic_year_id, &term))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if overlaps > 0 {
+        return Err((StatusCode::CONFLICT, "Semester dates overlap another semester".to_string()));
+    }
+    academic_calendar_repository::upsert_semester(pool, &payload, &term)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub async fn get_semesters(pool: &PgPool, params: SemesterQuery) -> Result<Vec<AcademicSemester>, StatusCode> {
+    academic_calendar_repository::find_semesters(pool, params.academic_year_id)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch semesters: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+pub async fn create_event(pool: &PgPool, mut payload: CreateCalendarEventRequest) -> Result<Uuid, (StatusCode, String)> {
+    check_range(payload.start_date, payload.end_date)?;
+    let event_type = payload.event_type.trim().to_uppercase();
+    if !EVENT_TYPES.contains(&event_type.as_str()) {
+        return Err((StatusCode::BAD_REQUEST, "eventType must be HOLIDAY, WORKING_SATURDAY or EXAM".to_string()));
+    }
+    if payload.title.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "title is required".to_string()));
+    }
+    let created_by = require_calendar_editor(pool, &payload.created_by).await?;
+    payload.branch = payload.branch.as_deref().filter(|b| !b.trim().is_empty()).map(normalize_branch);
+    payload.year = payload.year.filter(|y| !y.trim().is_empty());
+    academic_calendar_repository::insert_event(pool, &payload, &event_type, created_by)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub async fn delete_event(pool: &PgPool, payload: DeleteCalendarEventRequest) -> Result<(), (StatusCode, String)> {
+    require_calendar_editor(pool, &payload.deleted_by).await?;
+    let deleted = academic_calendar_repository::delete_event(pool, payload.id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if deleted == 0 {
+        return Err((StatusCode::NOT_FOUND, "Calendar event not found".to_string()));
+    }
+    Ok(())
+}
+
+fn check_query(params: &CalendarRangeQuery) -> Result<(Option<String>, Option<String>), (StatusCode, String)> {
+    check_range(params.from, params.to)?;
+    if (params.to - params.from).num_days() >= MAX_RANGE_DAYS {
+        return Err((StatusCode::BAD_REQUEST, format!("Range may span at most {} days", MAX_RANGE_DAYS)));
+    }
+    Ok((params.branch.as_deref().map(normalize_branch), params.year.clone()))
+}
+
+pub async fn get_events(pool: &PgPool, params: CalendarRangeQuery) -> Result<Vec<CalendarEvent>, (StatusCode, String)> {
+    let (branch, year) = check_query(&params)?;
+    academic_calendar_repository::find_events(pool, params.from, params.to, branch.as_deref(), year.as_deref())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub async fn get_calendar_days(pool: &PgPool, params: CalendarRangeQuery) -> Result<Vec<CalendarDay>, (StatusCode, String)> {
+    let (branch, year) = check_query(&params)?;
+    academic_calendar_repository::find_calendar_days(pool, params.from, params.to, branch.as_deref(), year.as_deref())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Whether classes run on the date for the branch and year; unset branch or year only
+/// considers events that apply to everyone.
+pub async fn is_instructional_day(pool: &PgPool, date: NaiveDate, branch: Option<&str>, year: Option<&str>) -> Result<bool, sqlx::Error> {
+    let branch = branch.map(normalize_branch);
+    academic_calendar_repository::is_instructional_day(pool, date, branch.as_deref(), year).await
+}
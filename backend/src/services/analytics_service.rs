@@ -0,0 +1,105 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use chrono::{Duration, NaiveDate, Utc};
+use crate::models::analytics::{
+    AbsenceStreak, AbsenceStreakQuery, AttendanceAnalyticsQuery, AttendanceHeatmap, AttendanceTrendPoint,
+    FacultyPeriodComparison
+};
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::repositories::analytics_repository;
+
+/// How often the background task refreshes `attendance_daily_summary`.
+pub const SUMMARY_REFRESH_MINUTES: u64 = 15;
+const DEFAULT_RANGE_DAYS: i64 = 30;
+
+fn parse_range(from: Option<&str>, to: Option<&str>) -> Result<(NaiveDate, NaiveDate), StatusCode> {
+    let parse = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| StatusCode::BAD_REQUEST);
+    let to = match to {
+        Some(t) if !t.is_empty() => parse(t)?,
+        _ => Utc::now().date_naive(),
+    };
+    let from = match from {
+        Some(f) if !f.is_empty() => parse(f)?,
+        _ => to - Duration::days(DEFAULT_RANGE_DAYS),
+    };
+    if from > to {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok((from, to))
+}
+
+fn log_err(what: &str) -> impl Fn(sqlx::Error) -> StatusCode + '_ {
+    move |e| {
+        eprintln!("ERROR: Failed to fetch {}: {:?}", what, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+pub async fn refresh_summary(pool: &PgPool) -> Result<(), StatusCode> {
+    analytics_repository::refresh_daily_summary(pool).await.map(|_| ()).map_err(log_err("attendance summary refresh"))
+}
+
+/// Keeps the daily summary reasonably fresh without refreshing it on every request.
+pub async fn run_summary_refresh(pool: PgPool) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(SUMMARY_REFRESH_MINUTES * 60));
+    loop {
+        interval.tick().await;
+        let _ = refresh_summary(&pool).await;
+    }
+}
+
+pub async fn get_trends(pool: &PgPool, params: AttendanceAnalyticsQuery) -> Result<Vec<AttendanceTrendPoint>, StatusCode> {
+    let (from, to) = parse_range(params.from.as_deref(), params.to.as_deref())?;
+    let bucket = match params.granularity.as_deref().unwrap_or("daily") {
+        "daily" => "day",
+        "weekly" => "week",
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+    let variations = get_branch_variations(&params.branch);
+
+    analytics_repository::find_trends(pool, &variations, params.year.as_deref(), params.section.as_deref(), from, to, bucket)
+        .await
+        .map_err(log_err("attendance trends"))
+}
+
+pub async fn get_heatmap(pool: &PgPool, params: AttendanceAnalyticsQuery) -> Result<AttendanceHeatmap, StatusCode> {
+    let (from, to) = parse_range(params.from.as_deref(), params.to.as_deref())?;
+    let variations = get_branch_variations(&params.branch);
+    let branch_norm = normalize_branch(&params.branch);
+
+    let by_weekday = analytics_repository::find_weekday_heatmap(pool, &variations, params.year.as_deref(), params.section.as_deref(), from, to)
+        .await
+        .map_err(log_err("weekday heatmap"))?;
+    let by_period = analytics_repository::find_period_heatmap(pool, &branch_norm, params.year.as_deref(), params.section.as_deref(), from, to)
+        .await
+        .map_err(log_err("period heatmap"))?;
+
+    Ok(AttendanceHeatmap { by_weekday, by_period })
+}
+
+pub async fn get_absence_streaks(pool: &PgPool, params: AbsenceStreakQuery) -> Result<Vec<AbsenceStreak>, StatusCode> {
+    let (from, _) = parse_range(params.from.as_deref(), None)?;
+    let variations = get_branch_variations(&params.branch);
+
+    analytics_repository::find_absence_streaks(
+        pool,
+        &variations,
+        params.year.as_deref(),
+        params.section.as_deref(),
+        from,
+        params.min_days.unwrap_or(3).max(1),
+        params.ongoing_only.unwrap_or(false),
+    )
+    .await
+    .map_err(log_err("absence streaks"))
+}
+
+pub async fn get_faculty_comparison(pool: &PgPool, params: AttendanceAnalyticsQuery) -> Result<Vec<FacultyPeriodComparison>, StatusCode> {
+    let (from, to) = parse_range(params.from.as_deref(), params.to.as_deref())?;
+    let variations = get_branch_variations(&params.branch);
+    let branch_norm = normalize_branch(&params.branch);
+
+    analytics_repository::find_faculty_comparison(pool, &branch_norm, &variations, from, to)
+        .await
+        .map_err(log_err("faculty period comparison"))
+}
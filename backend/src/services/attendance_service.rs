@@ -0,0 +1,306 @@
+use sqlx::{PgPool, Postgres, Row};
+use std::collections::{HashMap, HashSet};
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use uuid::Uuid;
+use crate::models::attendance::{
+    AttendanceHistoryEntry, AttendanceHistoryQuery, AttendanceLockQuery, AttendanceLockStatus,
+    AttendanceSyncRequest, AttendanceSyncResponse, AttendanceWrite, SyncRecordResult, UpdateEditWindowRequest
+};
+use crate::models::{get_branch_variations, normalize_branch, BatchRecord};
+use crate::repositories::attendance_repository;
+use crate::repositories::user::faculty_repository;
+use crate::utils::timing_utils;
+use crate::utils::user_utils::resolve_user_id;
+
+/// Used when a department has not configured its own edit window.
+pub const DEFAULT_EDIT_WINDOW_HOURS: i32 = 48;
+
+/// Status written for sessions covered by an approved leave.
+pub const LEAVE_STATUS: &str = "L";
+
+/// Attendance for `date` stays editable until `window_hours` after the end of that day on campus.
+pub fn lock_deadline(date: NaiveDate, window_hours: i32) -> DateTime<Utc> {
+    let day_end = (date + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap_or_default() - timing_utils::campus_utc_offset();
+    day_end.and_utc() + Duration::hours(window_hours as i64)
+}
+
+pub async fn get_edit_window_hours(pool: &PgPool, branch: &str) -> Result<i32, StatusCode> {
+    let variations = get_branch_variations(branch);
+    attendance_repository::find_edit_window_hours(pool, &variations)
+        .await
+        .map(|h| h.unwrap_or(DEFAULT_EDIT_WINDOW_HOURS))
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch attendance edit window for {}: {:?}", branch, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+pub async fn update_edit_window(pool: &PgPool, payload: UpdateEditWindowRequest) -> Result<(), (StatusCode, String)> {
+    if payload.window_hours < 0 {
+        return Err((StatusCode::BAD_REQUEST, "Edit window cannot be negative".to_string()));
+    }
+    let branch_norm = normalize_branch(&payload.branch);
+    attendance_repository::upsert_edit_window_hours(pool, &branch_norm, payload.window_hours)
+        .await
+        .map(|_| ())
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub async fn get_lock_status(pool: &PgPool, params: AttendanceLockQuery) -> Result<AttendanceLockStatus, StatusCode> {
+    let date = NaiveDate::parse_from_str(&params.date, "%Y-%m-%d").map_err(|_| StatusCode::BAD_REQUEST)?;
+    let branch_norm = normalize_branch(&params.branch);
+    let window_hours = get_edit_window_hours(pool, &branch_norm).await?;
+    let locks_at = lock_deadline(date, window_hours);
+
+    Ok(AttendanceLockStatus { branch: branch_norm, date, window_hours, locks_at, locked: Utc::now() >= locks_at })
+}
+
+/// Rejects direct edits once the department's edit window for `date` has closed.
+/// Locked dates can only be changed through an approved attendance correction request.
+pub async fn ensure_editable(pool: &PgPool, branch: &str, date: &str) -> Result<(), (StatusCode, String)> {
+    let parsed = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid date: {}", date)))?;
+    let window_hours = get_edit_window_hours(pool, branch).await.map_err(|c| (c, "Failed to check attendance lock".to_string()))?;
+
+    if Utc::now() >= lock_deadline(parsed, window_hours) {
+        return Err((
+            StatusCode::LOCKED,
+            format!("Attendance for {} is locked. Submit an attendance correction request to change it.", date),
+        ));
+    }
+    Ok(())
+}
+
+/// Upserts the attendance row and records a new history version when the status changes.
+pub async fn record_attendance(executor: &mut sqlx::Transaction<'_, Postgres>, write: AttendanceWrite<'_>) -> Result<(), sqlx::Error> {
+    attendance_repository::lock_session_mark(executor, write.student_login_id, write.date, write.session).await?;
+    let previous_status = attendance_repository::find_current_status(executor, write.student_login_id, write.date, write.session).await?;
+
+    // Routine marking does not turn an approved leave into an absence; that needs a correction.
+    let is_absent = matches!(write.status.to_uppercase().as_str(), "A" | "ABSENT");
+    if previous_status.as_deref() == Some(LEAVE_STATUS) && is_absent && write.change_source != "CORRECTION" {
+        return Ok(());
+    }
+
+    let attendance_id = faculty_repository::insert_attendance(
+        executor,
+        write.student_uuid,
+        write.student_login_id,
+        write.faculty_uuid,
+        write.date,
+        write.status,
+        write.session,
+        write.section,
+    ).await?;
+
+    if previous_status.as_deref() != Some(write.status) {
+        attendance_repository::insert_history(executor, attendance_id, previous_status.as_deref(), &write).await?;
+    }
+    Ok(())
+}
+
+pub async fn get_attendance_history(pool: &PgPool, params: AttendanceHistoryQuery) -> Result<Vec<AttendanceHistoryEntry>, StatusCode> {
+    let student_uuid = resolve_user_id(&params.student_id, "Student", pool).await.map_err(|_| StatusCode::NOT_FOUND)?;
+    let date = match params.date.as_deref() {
+        Some(d) if !d.is_empty() => Some(NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| StatusCode::BAD_REQUEST)?),
+        _ => None,
+    };
+
+    attendance_repository::find_history(pool, student_uuid, date)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch attendance history: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+// --- Offline Sync ---
+
+/// Statuses a faculty device may mark; leave ("L") is only written by the leave flow.
+const SYNC_STATUSES: [&str; 4] = ["P", "A", "PRESENT", "ABSENT"];
+
+fn sync_result(batch_id: Uuid, record: &BatchRecord, result: &str, message: Option<String>) -> SyncRecordResult {
+    SyncRecordResult {
+        client_record_id: record.client_record_id.unwrap_or_default(),
+        client_batch_id: batch_id,
+        student_id: record.student_id.clone(),
+        result: result.to_string(),
+        message,
+        existing_status: None,
+        existing_marked_by: None,
+        existing_marked_at: None,
+        duplicate: false,
+    }
+}
+
+/// Applies queued attendance from an offline device.
+///
+/// Records are keyed by their client-generated id, so a replayed upload returns the
+/// stored outcome instead of writing again; a record id repeated within one upload gets
+/// the first occurrence's result. Conflicts follow the order the server receives marks:
+/// when another faculty member already marked a different status for the same student,
+/// date and session, the record is reported as a CONFLICT and the existing mark is kept.
+/// The device timestamp is only stored for auditing. A batch that fails to save is rolled
+/// back and its records are rejected without being stored, so the device can retry them.
+pub async fn sync_attendance(pool: &PgPool, payload: AttendanceSyncRequest) -> Result<AttendanceSyncResponse, (StatusCode, String)> {
+    let mut faculties: HashMap<String, Uuid> = HashMap::new();
+    for batch in &payload.batches {
+        let batch_id = batch.client_batch_id
+            .ok_or((StatusCode::BAD_REQUEST, "Every batch needs a clientBatchId".to_string()))?;
+        if batch.device_timestamp.is_none() {
+            return Err((StatusCode::BAD_REQUEST, format!("Batch {} has no deviceTimestamp", batch_id)));
+        }
+        if batch.records.iter().any(|r| r.client_record_id.is_none()) {
+            return Err((StatusCode::BAD_REQUEST, format!("Batch {} has records without a clientRecordId", batch_id)));
+        }
+        NaiveDate::parse_from_str(&batch.date, "%Y-%m-%d")
+            .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid date in batch {}: {}", batch_id, batch.date)))?;
+
+        if !faculties.contains_key(&batch.marked_by) {
+            let faculty_uuid = resolve_user_id(&batch.marked_by, "Faculty", pool).await.map_err(|e| {
+                eprintln!("ERROR: Failed to resolve faculty ID {}: {:?}", batch.marked_by, e);
+                (StatusCode::BAD_REQUEST, format!("Invalid Faculty ID: {}", batch.marked_by))
+            })?;
+            faculties.insert(batch.marked_by.clone(), faculty_uuid);
+        }
+    }
+
+    let record_ids: Vec<Uuid> = payload.batches.iter().flat_map(|b| b.records.iter().filter_map(|r| r.client_record_id)).collect();
+    let mut previous: HashMap<Uuid, SyncRecordResult> = attendance_repository::find_sync_results(pool, &record_ids)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch previous sync results: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error during sync".to_string())
+        })?
+        .into_iter()
+        .map(|r| (r.client_record_id, r))
+        .collect();
+
+    let student_ids: Vec<String> = payload.batches.iter().flat_map(|b| b.records.iter().map(|r| r.student_id.clone())).collect();
+    let student_rows = sqlx::query("SELECT id, login_id, branch FROM users WHERE login_id = ANY($1) AND role = 'Student'")
+        .bind(&student_ids)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch student map: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error during student resolution".to_string())
+        })?;
+
+    let mut students: HashMap<String, (Uuid, String)> = HashMap::new();
+    for row in student_rows {
+        let login_id: String = row.get("login_id");
+        let branch = row.get::<Option<String>, _>("branch").unwrap_or_default();
+        students.insert(login_id, (row.get("id"), branch));
+    }
+
+    let mut lock_checks: HashMap<(String, String), Option<String>> = HashMap::new();
+    let mut seen: HashSet<Uuid> = HashSet::new();
+    let mut results: Vec<SyncRecordResult> = Vec::with_capacity(record_ids.len());
+
+    for batch in &payload.batches {
+        let batch_id = batch.client_batch_id.unwrap_or_default();
+        let faculty_uuid = faculties[&batch.marked_by];
+        let session = batch.session.clone().unwrap_or_else(|| "MORNING".to_string()).to_uppercase();
+
+        let mut pending: Vec<&BatchRecord> = Vec::new();
+        let mut repeated: Vec<Uuid> = Vec::new();
+        for record in &batch.records {
+            let record_id = record.client_record_id.unwrap_or_default();
+            if !seen.insert(record_id) {
+                repeated.push(record_id);
+            } else if let Some(stored) = previous.remove(&record_id) {
+                results.push(stored);
+            } else if !SYNC_STATUSES.contains(&record.status.as_str()) {
+                let message = format!("Invalid status '{}'; expected one of {}", record.status, SYNC_STATUSES.join(", "));
+                results.push(sync_result(batch_id, record, "REJECTED", Some(message)));
+            } else {
+                pending.push(record);
+            }
+        }
+
+        let saved: Result<Vec<SyncRecordResult>, sqlx::Error> = async {
+            let mut tx = pool.begin().await?;
+            let mut saved = Vec::with_capacity(pending.len());
+
+            for record in &pending {
+                let mut result = sync_result(batch_id, record, "ACCEPTED", None);
+
+                match students.get(&record.student_id) {
+                    None => {
+                        result.result = "REJECTED".to_string();
+                        result.message = Some(format!("Student not found: {}", record.student_id));
+                    }
+                    Some((student_uuid, branch)) => {
+                        let key = (branch.clone(), batch.date.clone());
+                        if !lock_checks.contains_key(&key) {
+                            let lock = ensure_editable(pool, branch, &batch.date).await.err().map(|(_, msg)| msg);
+                            lock_checks.insert(key.clone(), lock);
+                        }
+
+                        if let Some(Some(msg)) = lock_checks.get(&key) {
+                            result.result = "REJECTED".to_string();
+                            result.message = Some(msg.clone());
+                        } else {
+                            match attendance_repository::find_existing_mark(&mut tx, &record.student_id, &batch.date, &session).await? {
+                                Some((status, marked_by, marked_by_name)) if marked_by != faculty_uuid && status != record.status => {
+                                    result.result = "CONFLICT".to_string();
+                                    result.message = Some("Attendance was already marked by another faculty member".to_string());
+                                    result.existing_status = Some(status);
+                                    result.existing_marked_by = marked_by_name;
+                                    result.existing_marked_at = attendance_repository::find_last_marked_at(&mut tx, &record.student_id, &batch.date, &session).await?;
+                                }
+                                _ => {
+                                    record_attendance(&mut tx, AttendanceWrite {
+                                        student_uuid: *student_uuid,
+                                        student_login_id: &record.student_id,
+                                        faculty_uuid,
+                                        date: &batch.date,
+                                        status: &record.status,
+                                        session: &session,
+                                        section: &batch.section,
+                                        change_source: "SYNC",
+                                        reason: None,
+                                        correction_request_id: None,
+                                    })
+                                    .await?;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                attendance_repository::insert_sync_record(&mut tx, &payload.device_id, faculty_uuid, batch, &session, &record.status, &result).await?;
+                saved.push(result);
+            }
+
+            tx.commit().await?;
+            Ok(saved)
+        }
+        .await;
+
+        match saved {
+            Ok(saved) => results.extend(saved),
+            Err(e) => {
+                eprintln!("ERROR: Failed to sync batch {}: {:?}", batch_id, e);
+                let message = || Some("Could not be saved; retry the upload".to_string());
+                results.extend(pending.iter().map(|record| sync_result(batch_id, record, "REJECTED", message())));
+            }
+        }
+
+        for record_id in repeated {
+            if let Some(first) = results.iter().find(|r| r.client_record_id == record_id) {
+                let echoed = SyncRecordResult { duplicate: true, ..first.clone() };
+                results.push(echoed);
+            }
+        }
+    }
+
+    let count = |kind: &str| results.iter().filter(|r| !r.duplicate && r.result == kind).count();
+    Ok(AttendanceSyncResponse {
+        accepted: count("ACCEPTED"),
+        rejected: count("REJECTED"),
+        conflicts: count("CONFLICT"),
+        duplicates: results.iter().filter(|r| r.duplicate).count(),
+        results,
+    })
+}
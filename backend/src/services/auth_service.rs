@@ -4,6 +4,7 @@ use crate::models::{LoginRequest, AuthResponse, SignupRequest, ProfileQuery, Che
 use uuid::Uuid;
 use chrono::{Utc, Datelike};
 use crate::repositories::auth;
+use crate::repositories::academic_calendar_repository;
 
 pub async fn login_user(
     pool: &PgPool,
@@ -64,6 +65,9 @@ pub async fn signup_user(
     
     let section = payload.section.clone().unwrap_or_else(|| "Section A".to_string());
 
+    // Prefer the academic calendar's semester for today; fall back to a June rollover when none is defined.
+    let term_today = academic_calendar_repository::find_term_on_date(pool, Utc::now().date_naive()).await.ok().flatten();
+
     let (final_branch, final_year, final_semester, final_batch) = if payload.role == "Student" {
         let parts: Vec<&str> = payload.login_id.split('-').collect();
         if parts.len() >= 2 {
@@ -76,7 +80,10 @@ pub async fn signup_user(
                 let current_year = now.year();
                 let current_month = now.month(); 
                 
-                let academic_year_start = if current_month < 6 { current_year - 1 } else { current_year };
+                let academic_year_start = match &term_today {
+                    Some(t) => t.start_year,
+                    None => if current_month < 6 { current_year - 1 } else { current_year },
+                };
                 let diff = academic_year_start - joining_year;
 
                 match diff {
@@ -90,8 +97,10 @@ pub async fn signup_user(
             };
 
             let derived_semester = if let Some(y) = &derived_year {
-                 let now = Utc::now();
-                 let is_even_sem = now.month() < 6; 
+                 let is_even_sem = match &term_today {
+                     Some(t) => t.term == "EVEN",
+                     None => Utc::now().month() < 6,
+                 };
                  match y.as_str() {
                      "1st Year" => Some("1st Year".to_string()),
                      "2nd Year" => Some(if is_even_sem { "4th Semester".to_string() } else { "3rd Semester".to_string() }),
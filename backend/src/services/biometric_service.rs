@@ -0,0 +1,276 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::models::attendance::{
+    AttendanceWrite, BiometricConflict, BiometricConflictQuery, BiometricIngestRequest, BiometricIngestSummary,
+    BiometricMappingRequest, BiometricPunch, ResolveBiometricConflictRequest
+};
+use crate::models::{get_branch_variations, normalize_branch, DepartmentTiming};
+use crate::repositories::{attendance_repository, biometric_repository, timetable_repository};
+use crate::services::attendance_service;
+use crate::utils::timing_utils;
+use crate::utils::user_utils::resolve_user_id;
+
+const PUNCH_TIME_FORMATS: [&str; 4] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%d/%m/%Y %H:%M:%S"];
+
+fn is_present(status: &str) -> bool {
+    matches!(status.to_uppercase().as_str(), "P" | "PRESENT")
+}
+
+pub async fn save_mappings(pool: &PgPool, payload: BiometricMappingRequest) -> Result<usize, (StatusCode, String)> {
+    for m in &payload.mappings {
+        biometric_repository::upsert_user_mapping(pool, m.device_user_code.trim(), m.login_id.trim())
+            .await
+            .map_err(|e| {
+                eprintln!("ERROR: Failed to map device code {}: {:?}", m.device_user_code, e);
+                (StatusCode::BAD_REQUEST, format!("Could not map {} to {}", m.device_user_code, m.login_id))
+            })?;
+    }
+    Ok(payload.mappings.len())
+}
+
+/// Parses a terminal export with `device_user_code,punched_at` columns. A header row is allowed,
+/// and extra columns after the timestamp are ignored.
+pub fn parse_punch_csv(body: &str) -> (Vec<BiometricPunch>, Vec<String>) {
+    let mut punches = Vec::new();
+    let mut invalid = Vec::new();
+
+    for (line_no, line) in body.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut cols = line.split(',').map(|c| c.trim().trim_matches('"'));
+        let code = cols.next().unwrap_or_default();
+        let raw_time = cols.next().unwrap_or_default();
+        let parsed = PUNCH_TIME_FORMATS.iter().find_map(|f| NaiveDateTime::parse_from_str(raw_time, f).ok());
+
+        match parsed {
+            Some(punched_at) if !code.is_empty() => punches.push(BiometricPunch { device_user_code: code.to_string(), punched_at }),
+            _ if line_no == 0 => {} // header
+            _ => invalid.push(format!("Line {}: {}", line_no + 1, line)),
+        }
+    }
+    (punches, invalid)
+}
+
+/// Stores raw punches and turns each student's first punch in a session into a PRESENT mark.
+///
+/// Re-sending the same punches is a no-op. Sessions that were already marked manually are
+/// never overwritten: a matching PRESENT is counted as matched, anything else is filed as a
+/// conflict for faculty to resolve. Absences are not inferred from missing punches.
+///
+/// Attendance is applied from the stored punches that have not been applied yet, each session
+/// in its own transaction together with marking its punches applied, so a retry after a failure
+/// picks up whatever was left over.
+///
+/// Period-level marks are out of scope: attendance is keyed per session
+/// (`UNIQUE(student_login_id, date, session)`), so a punch yields at most one mark for its
+/// session. The period it falls in is kept on the punch for reference and never written to
+/// attendance.
+pub async fn ingest_punches(pool: &PgPool, payload: BiometricIngestRequest) -> Result<BiometricIngestSummary, (StatusCode, String)> {
+    let ingested_by = resolve_user_id(&payload.ingested_by, "Faculty", pool).await.map_err(|e| {
+        eprintln!("ERROR: Failed to resolve ingesting user {}: {:?}", payload.ingested_by, e);
+        (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.ingested_by))
+    })?;
+
+    let mut summary = BiometricIngestSummary { received: payload.punches.len(), ..Default::default() };
+
+    let mut codes: Vec<String> = payload.punches.iter().map(|p| p.device_user_code.clone()).collect();
+    codes.sort();
+    codes.dedup();
+    let students: HashMap<String, (Uuid, String, String, String)> = biometric_repository::find_students_by_codes(pool, &codes)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to resolve device user codes: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error during student resolution".to_string())
+        })?
+        .into_iter()
+        .map(|(code, id, login_id, branch, section)| (code, (id, login_id, normalize_branch(&branch.unwrap_or_default()), section.unwrap_or_default())))
+        .collect();
+
+    // Keyed by (branch, date) since the slot template in effect can change between punch dates.
+    let mut timings: HashMap<(String, NaiveDate), Option<DepartmentTiming>> = HashMap::new();
+
+    for punch in &payload.punches {
+        let student = students.get(&punch.device_user_code);
+        if student.is_none() && !summary.unmapped_codes.contains(&punch.device_user_code) {
+            summary.unmapped_codes.push(punch.device_user_code.clone());
+        }
+
+        let mut session = None;
+        let mut period = None;
+        if let Some((_, _, branch, _)) = student {
+            let key = (branch.clone(), punch.punched_at.date());
+            if !timings.contains_key(&key) {
+                let timing = timetable_repository::find_department_timing(pool, &get_branch_variations(branch), key.1)
+                    .await
+                    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+                timings.insert(key.clone(), timing);
+            }
+            let slots = timing_utils::day_slots_on(timings[&key].as_ref(), punch.punched_at.weekday());
+            session = timing_utils::session_at(&slots, punch.punched_at.time());
+            period = timing_utils::period_at(&slots, punch.punched_at.time());
+        }
+
+        let stored = biometric_repository::insert_punch(
+            pool,
+            &payload.device_id,
+            punch,
+            student.map(|s| s.1.as_str()),
+            session,
+            period,
+            ingested_by,
+        )
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to store punch for {}: {:?}", punch.device_user_code, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        })?;
+
+        if stored.is_none() {
+            summary.duplicates += 1;
+            continue;
+        }
+        summary.stored += 1;
+        if student.is_some() && session.is_none() {
+            summary.outside_sessions += 1;
+        }
+    }
+
+    // login_id -> (id, branch, section)
+    let by_login: HashMap<&str, (Uuid, &str, &str)> = students
+        .values()
+        .map(|(id, login_id, branch, section)| (login_id.as_str(), (*id, branch.as_str(), section.as_str())))
+        .collect();
+    let login_ids: Vec<String> = by_login.keys().map(|l| l.to_string()).collect();
+    let pending = biometric_repository::find_pending_sessions(pool, &login_ids)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut lock_checks: HashMap<(String, NaiveDate), bool> = HashMap::new();
+    for (login_id, date, session, first_punch) in pending {
+        let (student_uuid, branch, section) = by_login[login_id.as_str()];
+        let date_str = date.format("%Y-%m-%d").to_string();
+
+        let key = (branch.to_string(), date);
+        if !lock_checks.contains_key(&key) {
+            let editable = attendance_service::ensure_editable(pool, branch, &date_str).await.is_ok();
+            lock_checks.insert(key.clone(), editable);
+        }
+
+        let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        if lock_checks[&key] {
+            let existing = attendance_repository::find_existing_mark(&mut tx, &login_id, &date_str, &session)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+            match existing {
+                Some((status, _, _)) if is_present(&status) => summary.matched += 1,
+                Some(_) => {
+                    biometric_repository::insert_conflict(&mut tx, student_uuid, date, &session, first_punch)
+                        .await
+                        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+                    summary.conflicts += 1;
+                }
+                None => {
+                    attendance_service::record_attendance(&mut tx, AttendanceWrite {
+                        student_uuid,
+                        student_login_id: &login_id,
+                        faculty_uuid: ingested_by,
+                        date: &date_str,
+                        status: "PRESENT",
+                        session: &session,
+                        section,
+                        change_source: "BIOMETRIC",
+                        reason: None,
+                        correction_request_id: None,
+                    })
+                    .await
+                    .map_err(|e| {
+                        eprintln!("ERROR: Failed to apply biometric attendance for {}: {:?}", login_id, e);
+                        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+                    })?;
+                    summary.applied += 1;
+                }
+            }
+        } else {
+            summary.locked += 1;
+        }
+        biometric_repository::mark_punches_applied(&mut tx, &login_id, date, &session)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    Ok(summary)
+}
+
+pub async fn get_conflicts(pool: &PgPool, params: BiometricConflictQuery) -> Result<Vec<BiometricConflict>, StatusCode> {
+    let date = match params.date.as_deref() {
+        Some(d) if !d.is_empty() => Some(NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| StatusCode::BAD_REQUEST)?),
+        _ => None,
+    };
+    let variations = get_branch_variations(&params.branch);
+    let status = params.status.as_deref().unwrap_or("OPEN").to_uppercase();
+
+    biometric_repository::find_conflicts(pool, &variations, date, params.section.as_deref(), &status)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch biometric conflicts: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// ACCEPT_PUNCH overwrites the manual mark with PRESENT; KEEP_MANUAL just closes the conflict.
+pub async fn resolve_conflict(pool: &PgPool, payload: ResolveBiometricConflictRequest) -> Result<(), (StatusCode, String)> {
+    let resolver = resolve_user_id(&payload.resolved_by, "Faculty", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.resolved_by)))?;
+
+    let conflict = biometric_repository::find_conflict_by_id(pool, payload.conflict_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Conflict not found".to_string()))?;
+    if conflict.status != "OPEN" {
+        return Err((StatusCode::CONFLICT, "Conflict is already resolved".to_string()));
+    }
+
+    let action = payload.action.to_uppercase();
+    let new_status = match action.as_str() {
+        "ACCEPT_PUNCH" => "ACCEPTED_PUNCH",
+        "KEEP_MANUAL" => "KEPT_MANUAL",
+        _ => return Err((StatusCode::BAD_REQUEST, "Action must be ACCEPT_PUNCH or KEEP_MANUAL".to_string())),
+    };
+
+    let date_str = conflict.date.format("%Y-%m-%d").to_string();
+    if action == "ACCEPT_PUNCH" {
+        attendance_service::ensure_editable(pool, conflict.branch.as_deref().unwrap_or_default(), &date_str).await?;
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if action == "ACCEPT_PUNCH" {
+        attendance_service::record_attendance(&mut tx, AttendanceWrite {
+            student_uuid: conflict.student_uuid,
+            student_login_id: &conflict.student_login_id,
+            faculty_uuid: resolver,
+            date: &date_str,
+            status: "PRESENT",
+            session: &conflict.session,
+            section: conflict.section.as_deref().unwrap_or_default(),
+            change_source: "BIOMETRIC",
+            reason: Some("Biometric punch accepted over manual mark"),
+            correction_request_id: None,
+        })
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+    biometric_repository::update_conflict_status(&mut tx, conflict.id, new_status, resolver)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(())
+}
@@ -0,0 +1,293 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::models::calendar_feed::{CalendarFeed, CreateFeedRequest, FeedAnnouncement, FeedListQuery, FeedOwner, FeedTarget, RevokeFeedRequest};
+use crate::repositories::{academic_calendar_repository, calendar_feed_repository, leave_repository, slot_template_repository, timetable_repository};
+use crate::utils::timing_utils;
+use crate::utils::user_utils::resolve_user_id;
+
+// Window used when the academic calendar has no semester covering today.
+const FEED_WEEKS_BACK: i64 = 2;
+const FEED_WEEKS_AHEAD: i64 = 18;
+const FEED_PATH: &str = "/api/calendar/ics";
+
+// Start and end time of each period of one day.
+type PeriodTimes = HashMap<i32, (NaiveTime, NaiveTime)>;
+
+fn feed_url(token: &str) -> String {
+    format!("{}/{}.ics", FEED_PATH, token)
+}
+
+pub async fn create_feed(pool: &PgPool, payload: CreateFeedRequest) -> Result<CalendarFeed, (StatusCode, String)> {
+    let user_id = resolve_user_id(&payload.user_id, "Student", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.user_id)))?;
+    let (_, role, _, _, _, _) = leave_repository::find_user_basics(pool, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+
+    let scope = payload.scope.as_deref().unwrap_or("PERSONAL").to_uppercase();
+    let target = match scope.as_str() {
+        "PERSONAL" => FeedTarget::default(),
+        "SECTION" => {
+            if role == "Student" || role == "Parent" {
+                return Err((StatusCode::FORBIDDEN, "Section feeds are for staff; use a personal feed".to_string()));
+            }
+            match (payload.branch, payload.year, payload.section) {
+                (Some(b), Some(y), Some(s)) => FeedTarget { faculty_id: None, branch: Some(normalize_branch(&b)), year: Some(y), section: Some(s) },
+                _ => return Err((StatusCode::BAD_REQUEST, "branch, year and section are required for a section feed".to_string())),
+            }
+        }
+        _ => return Err((StatusCode::BAD_REQUEST, "scope must be PERSONAL or SECTION".to_string())),
+    };
+
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let id = calendar_feed_repository::insert_feed(pool, user_id, &token, &scope, &target)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(CalendarFeed {
+        id,
+        url: feed_url(&token),
+        token,
+        scope,
+        branch: target.branch,
+        year: target.year,
+        section: target.section,
+        created_at: Utc::now(),
+        last_accessed_at: None,
+    })
+}
+
+pub async fn get_feeds(pool: &PgPool, params: FeedListQuery) -> Result<Vec<CalendarFeed>, StatusCode> {
+    let user_id = resolve_user_id(&params.user_id, "Student", pool).await.map_err(|_| StatusCode::BAD_REQUEST)?;
+    let mut feeds = calendar_feed_repository::find_feeds(pool, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    for f in feeds.iter_mut() {
+        f.url = feed_url(&f.token);
+    }
+    Ok(feeds)
+}
+
+pub async fn revoke_feed(pool: &PgPool, payload: RevokeFeedRequest) -> Result<(), (StatusCode, String)> {
+    let user_id = resolve_user_id(&payload.user_id, "Student", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.user_id)))?;
+    let updated = calendar_feed_repository::revoke_feed(pool, payload.feed_id, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if updated == 0 {
+        return Err((StatusCode::NOT_FOUND, "Feed not found".to_string()));
+    }
+    Ok(())
+}
+
+fn feed_target(owner: &FeedOwner) -> FeedTarget {
+    if owner.scope == "SECTION" {
+        return FeedTarget { faculty_id: None, branch: owner.branch.clone(), year: owner.year.clone(), section: owner.section.clone() };
+    }
+    if owner.role == "Student" {
+        return FeedTarget {
+            faculty_id: None,
+            branch: owner.user_branch.as_deref().map(normalize_branch),
+            year: owner.user_year.clone(),
+            section: owner.user_section.clone(),
+        };
+    }
+    FeedTarget { faculty_id: Some(owner.login_id.clone()), ..FeedTarget::default() }
+}
+
+/// Audience entries are role labels ("Students", "Faculty", "HODs", "All", ...) plus optional
+/// "Branches: a, b" / "Years: ..." / "Sections: ..." filters added for student announcements.
+fn is_for(announcement: &FeedAnnouncement, owner: &FeedOwner) -> bool {
+    let role_label = match owner.role.as_str() {
+        "Student" => "Students",
+        "HOD" => "HODs",
+        "Incharge" => "Incharges",
+        "Parent" => "Parents",
+        other => other,
+    };
+    let audience = &announcement.audience;
+    let role_ok = audience.is_empty() || audience.iter().any(|a| a.eq_ignore_ascii_case("All") || a.eq_ignore_ascii_case(role_label));
+    let filter_ok = |prefix: &str, value: Option<String>| {
+        match (audience.iter().find_map(|a| a.strip_prefix(prefix)), value) {
+            (Some(list), Some(v)) => list.split(',').any(|item| item.trim().eq_ignore_ascii_case(v.trim())),
+            _ => true,
+        }
+    };
+    role_ok
+        && filter_ok("Branches: ", owner.user_branch.as_deref().map(normalize_branch))
+        && filter_ok("Years: ", owner.user_year.clone())
+        && filter_ok("Sections: ", owner.user_section.clone())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n").replace('\r', "")
+}
+
+/// Folds content lines at 75 octets as RFC 5545 requires, without splitting a character.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        let len = ch.len_utf8();
+        if width + len > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += len;
+    }
+    out.push_str("\r\n");
+}
+
+fn uid_part(text: &str) -> String {
+    text.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect()
+}
+
+/// Renders the feed behind a token. Classes are expanded date by date rather than as RRULEs so that
+/// holidays, timetable version changes and recorded timings all show up as they apply on each day.
+/// The feed spans the current semester of the academic calendar.
+pub async fn render_feed(pool: &PgPool, token: &str) -> Result<String, StatusCode> {
+    let token = token.trim_end_matches(".ics");
+    let owner = calendar_feed_repository::find_feed_owner(pool, token)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    calendar_feed_repository::touch_feed(pool, owner.feed_id).await.ok();
+
+    let today = Utc::now().date_naive();
+    let semester = academic_calendar_repository::find_semester(pool, None, today)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let (from, to) = match semester {
+        Some(s) => (s.start_date, s.end_date),
+        None => {
+            let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+            (week_start - Duration::weeks(FEED_WEEKS_BACK), week_start + Duration::weeks(FEED_WEEKS_AHEAD) - Duration::days(1))
+        }
+    };
+
+    let target = feed_target(&owner);
+    let classes = calendar_feed_repository::find_feed_classes(pool, from, to, &target)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to load calendar feed classes: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let announcements: Vec<FeedAnnouncement> = calendar_feed_repository::find_calendar_announcements(pool, from, to)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .filter(|a| is_for(a, &owner))
+        .collect();
+
+    // Academic calendar holidays and exam blocks for the feed's branch and year (faculty see their department's).
+    let calendar_branch = target.branch.clone().or_else(|| owner.user_branch.as_deref().map(normalize_branch));
+    let calendar_events = academic_calendar_repository::find_events(pool, from, to, calendar_branch.as_deref(), target.year.as_deref())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut holidays: HashSet<NaiveDate> = HashSet::new();
+    for a in announcements.iter().filter(|a| a.announcement_type.eq_ignore_ascii_case("holiday")) {
+        let mut d = a.start_date.date_naive();
+        while d <= a.end_date.date_naive() {
+            holidays.insert(d);
+            d += Duration::days(1);
+        }
+    }
+
+    // Timings are resolved on the feed's first day and again on each date a new slot template takes
+    // effect, keyed by (branch, that date, is Saturday) since a template may give Saturdays their own times.
+    let mut timing_starts: HashMap<String, Vec<NaiveDate>> = HashMap::new();
+    let mut period_times: HashMap<(String, NaiveDate, bool), PeriodTimes> = HashMap::new();
+    for branch in classes.iter().map(|c| c.branch.clone()).collect::<HashSet<_>>() {
+        let variations = get_branch_variations(&branch);
+        let mut starts: Vec<NaiveDate> = slot_template_repository::find_templates(pool, &variations)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .into_iter()
+            .map(|t| t.effective_from)
+            .filter(|d| *d > from && *d <= to)
+            .collect();
+        starts.push(from);
+        starts.sort();
+        starts.dedup();
+        for &start in &starts {
+            let timing = timetable_repository::find_department_timing(pool, &variations, start)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            for (weekday, saturday) in [(Weekday::Mon, false), (Weekday::Sat, true)] {
+                let times = timing_utils::day_slots_on(timing.as_ref(), weekday)
+                    .into_iter()
+                    .filter_map(|s| s.period_index.map(|p| (p, (s.start, s.end))))
+                    .collect();
+                period_times.insert((branch.clone(), start, saturday), times);
+            }
+        }
+        timing_starts.insert(branch, starts);
+    }
+
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//Campus//Timetable//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, "METHOD:PUBLISH");
+    push_line(&mut out, "X-WR-CALNAME:Timetable");
+
+    // Times are floating (no TZID) so calendars show them in the campus's local time.
+    for c in classes.iter().filter(|c| !holidays.contains(&c.date)) {
+        let Some(timing_start) = timing_starts.get(&c.branch).and_then(|s| s.iter().rev().find(|d| **d <= c.date)) else { continue };
+        let key = (c.branch.clone(), *timing_start, c.date.weekday() == Weekday::Sat);
+        let Some((start, end)) = period_times.get(&key).and_then(|t| t.get(&c.period_index)) else { continue };
+        let teacher = c.faculty_name.clone().unwrap_or_else(|| c.faculty_id.clone());
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!(
+            "UID:{}-{}-{}-{}-P{}@timetable",
+            c.date.format("%Y%m%d"), uid_part(&c.branch), uid_part(&c.year), uid_part(&c.section), c.period_index
+        ));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(&mut out, &format!("DTSTART:{}T{}", c.date.format("%Y%m%d"), start.format("%H%M%S")));
+        push_line(&mut out, &format!("DTEND:{}T{}", c.date.format("%Y%m%d"), end.format("%H%M%S")));
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&c.subject)));
+        push_line(&mut out, &format!("LOCATION:{}", escape(&format!("{} {} ({})", c.year, c.section, c.branch))));
+        push_line(&mut out, &format!("DESCRIPTION:{}", escape(&format!("Period {} with {}", c.period_index, teacher))));
+        push_line(&mut out, "CATEGORIES:CLASS");
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    for a in &announcements {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}@announcements", a.id));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(&mut out, &format!("DTSTART;VALUE=DATE:{}", a.start_date.date_naive().format("%Y%m%d")));
+        // DTEND is exclusive for all-day events.
+        push_line(&mut out, &format!("DTEND;VALUE=DATE:{}", (a.end_date.date_naive() + Duration::days(1)).format("%Y%m%d")));
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&a.title)));
+        push_line(&mut out, &format!("DESCRIPTION:{}", escape(&a.description)));
+        push_line(&mut out, &format!("CATEGORIES:{}", a.announcement_type.to_uppercase()));
+        push_line(&mut out, "TRANSP:TRANSPARENT");
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    for e in calendar_events.iter().filter(|e| e.event_type != "WORKING_SATURDAY") {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}@academic-calendar", e.id));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(&mut out, &format!("DTSTART;VALUE=DATE:{}", e.start_date.format("%Y%m%d")));
+        push_line(&mut out, &format!("DTEND;VALUE=DATE:{}", (e.end_date + Duration::days(1)).format("%Y%m%d")));
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&e.title)));
+        push_line(&mut out, &format!("CATEGORIES:{}", e.event_type));
+        push_line(&mut out, "TRANSP:TRANSPARENT");
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    Ok(out)
+}
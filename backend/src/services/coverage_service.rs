@@ -0,0 +1,320 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::models::coverage::{
+    CoverageReport, CoverageReportDetail, CoverageReportQuery, CoverageSnapshot, CoverageSummary, CoverageTopic,
+    GenerateCoverageRequest, ReviewCoverageRequest, SignCoverageRequest,
+};
+use crate::repositories::{academic_calendar_repository, coverage_repository, leave_repository, topic_feedback_repository};
+use crate::services::curriculum_service::{self, DEFAULT_REGULATION};
+use crate::utils::export_utils;
+use crate::utils::user_utils::resolve_user_id;
+
+fn internal(e: sqlx::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// (user id, login id, role, full name, branch) of the acting user.
+async fn actor(pool: &PgPool, login: &str, role_hint: &str) -> Result<(Uuid, String, String, String, Option<String>), (StatusCode, String)> {
+    let id = resolve_user_id(login, role_hint, pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", login)))?;
+    let (login_id, role, full_name, branch, _, _) = leave_repository::find_user_basics(pool, id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    Ok((id, login_id, role, full_name, branch))
+}
+
+async fn load(pool: &PgPool, id: Uuid) -> Result<CoverageReport, (StatusCode, String)> {
+    coverage_repository::find_report(pool, id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Coverage report not found".to_string()))
+}
+
+async fn load_snapshot(pool: &PgPool, id: Uuid) -> Result<CoverageSnapshot, (StatusCode, String)> {
+    let value = coverage_repository::find_snapshot(pool, id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Coverage report not found".to_string()))?;
+    serde_json::from_value(value).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Unreadable coverage report: {}", e)))
+}
+
+fn summarize(topics: &[CoverageTopic]) -> CoverageSummary {
+    let today = Utc::now().date_naive();
+    let mut s = CoverageSummary { total_topics: topics.len() as i64, ..Default::default() };
+    let deviations: Vec<i64> = topics.iter().filter_map(|t| t.deviation_days).collect();
+    for t in topics {
+        if t.status == "completed" {
+            s.completed += 1;
+            match t.deviation_days {
+                Some(d) if d > 0 => s.completed_late += 1,
+                Some(_) => s.completed_on_time += 1,
+                None => s.completed_unplanned += 1,
+            }
+        } else {
+            s.pending += 1;
+            if t.assigned_date.is_some_and(|d| d < today) {
+                s.overdue += 1;
+            }
+        }
+    }
+    s.coverage_percent = if s.total_topics > 0 { (s.completed as f64 * 1000.0 / s.total_topics as f64).round() / 10.0 } else { 0.0 };
+    s.average_deviation_days = (!deviations.is_empty()).then(|| deviations.iter().sum::<i64>() as f64 / deviations.len() as f64);
+    s
+}
+
+/// Assembles the section's coverage of the subject from curriculum_progress and its completion
+/// log and saves it as the faculty member's draft for the semester. A report already signed has
+/// to be returned by the HOD before it can be regenerated.
+pub async fn generate_report(pool: &PgPool, payload: GenerateCoverageRequest) -> Result<CoverageReportDetail, (StatusCode, String)> {
+    let (faculty_id, faculty_login, _, faculty_name, _) = actor(pool, &payload.faculty_id, "Faculty").await?;
+    let branch = normalize_branch(&payload.branch);
+    let variations = get_branch_variations(&branch);
+    if !topic_feedback_repository::teaches_section(pool, &faculty_login, &payload.subject_code, &variations, &payload.year, &payload.section)
+        .await
+        .map_err(internal)?
+    {
+        return Err((StatusCode::FORBIDDEN, "You do not teach this subject in this section".to_string()));
+    }
+    let term = academic_calendar_repository::find_semester(pool, payload.academic_semester_id, Utc::now().date_naive())
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::BAD_REQUEST, "No semester found in the academic calendar; pass academicSemesterId".to_string()))?;
+
+    let regulation = payload.regulation.as_deref().unwrap_or(DEFAULT_REGULATION);
+    let curriculum = curriculum_service::get_merged_curriculum(pool, &branch, payload.semester, regulation, &payload.subject_code, &payload.section, &payload.year)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+
+    let topics: Vec<CoverageTopic> = curriculum
+        .units
+        .iter()
+        .flat_map(|u| u.topics.iter().map(move |t| (u.unit_no, t)))
+        .filter(|(_, t)| t.topic_type.to_lowercase() != "unit")
+        .map(|(unit_no, t)| {
+            let status = t.status.clone().unwrap_or_else(|| "pending".to_string());
+            let completed_date = t.completed_date.filter(|_| status == "completed");
+            CoverageTopic {
+                unit_no,
+                sno: t.sno.clone(),
+                topic_id: t.id.clone(),
+                topic: t.topic.clone(),
+                deviation_days: completed_date.zip(t.assigned_date).map(|(c, a)| (c - a).num_days()),
+                status,
+                assigned_date: t.assigned_date,
+                completed_date,
+                remarks: t.remarks.clone(),
+            }
+        })
+        .collect();
+    let audit = coverage_repository::find_audit(pool, &payload.subject_code, &branch, &payload.year, &payload.section, payload.semester)
+        .await
+        .map_err(internal)?;
+
+    let snapshot = CoverageSnapshot {
+        subject_code: payload.subject_code,
+        subject_name: curriculum.subject_name.clone(),
+        regulation: curriculum.regulation.clone(),
+        branch,
+        year: payload.year,
+        section: payload.section,
+        semester: payload.semester,
+        term: format!("{} {}", term.year_name, term.term),
+        faculty_login,
+        faculty_name,
+        generated_at: Utc::now(),
+        summary: summarize(&topics),
+        topics,
+        audit,
+    };
+    let id = coverage_repository::upsert_report(pool, &snapshot, term.id, faculty_id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::CONFLICT, "This report has been signed; the HOD must return it before it can be regenerated".to_string()))?;
+    Ok(CoverageReportDetail { report: load(pool, id).await?, snapshot })
+}
+
+pub async fn get_reports(pool: &PgPool, params: CoverageReportQuery) -> Result<Vec<CoverageReport>, (StatusCode, String)> {
+    let variations = get_branch_variations(&normalize_branch(&params.branch));
+    let non_empty = |v: &Option<String>| v.clone().filter(|s| !s.trim().is_empty());
+    let (year, section, status, faculty) = (non_empty(&params.year), non_empty(&params.section), non_empty(&params.status), non_empty(&params.faculty_id));
+    coverage_repository::find_reports(pool, &variations, year.as_deref(), section.as_deref(), status.as_deref(), faculty.as_deref())
+        .await
+        .map_err(internal)
+}
+
+pub async fn get_report(pool: &PgPool, id: Uuid) -> Result<CoverageReportDetail, (StatusCode, String)> {
+    Ok(CoverageReportDetail { report: load(pool, id).await?, snapshot: load_snapshot(pool, id).await? })
+}
+
+/// The faculty member signs their draft and it goes to the branch's HODs for countersigning.
+pub async fn sign_report(pool: &PgPool, payload: SignCoverageRequest) -> Result<CoverageReport, (StatusCode, String)> {
+    let (faculty_id, _, _, faculty_name, _) = actor(pool, &payload.faculty_id, "Faculty").await?;
+    let statement = payload.statement.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    if coverage_repository::sign_report(pool, payload.report_id, faculty_id, statement).await.map_err(internal)? == 0 {
+        let report = load(pool, payload.report_id).await?;
+        return Err((StatusCode::CONFLICT, format!("Only the faculty member's draft or returned report can be signed; this one is {}", report.status)));
+    }
+    let report = load(pool, payload.report_id).await?;
+
+    let message = format!(
+        "{} signed the syllabus coverage report for {} ({} {}), {}% covered. Please review it.",
+        faculty_name, report.subject_code, report.year, report.section, report.coverage_percent.unwrap_or(0.0)
+    );
+    for hod in coverage_repository::find_hods(pool, &get_branch_variations(&report.branch)).await.map_err(internal)? {
+        let _ = coverage_repository::insert_notification(pool, &message, &hod, &report.branch).await;
+    }
+    Ok(report)
+}
+
+/// The HOD of the report's branch countersigns a signed report, which renders and archives its
+/// PDF for good, or returns it to the faculty member with a note.
+pub async fn review_report(pool: &PgPool, payload: ReviewCoverageRequest) -> Result<CoverageReport, (StatusCode, String)> {
+    let (hod_id, _, role, hod_name, hod_branch) = actor(pool, &payload.hod_id, "HOD").await?;
+    let report = load(pool, payload.report_id).await?;
+    let allowed = match role.as_str() {
+        "Admin" | "Principal" => true,
+        "HOD" => hod_branch.as_deref().map(normalize_branch) == Some(normalize_branch(&report.branch)),
+        _ => false,
+    };
+    if !allowed {
+        return Err((StatusCode::FORBIDDEN, "Only the HOD of the branch can countersign this report".to_string()));
+    }
+    if report.status != "FACULTY_SIGNED" {
+        return Err((StatusCode::CONFLICT, format!("Only a report signed by the faculty member can be reviewed; this one is {}", report.status)));
+    }
+    let note = payload.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+
+    if !payload.approve {
+        let note = note.ok_or((StatusCode::BAD_REQUEST, "Say why the report is returned in note".to_string()))?;
+        coverage_repository::return_report(pool, report.id, hod_id, note).await.map_err(internal)?;
+        let message = format!("{} returned your syllabus coverage report for {} ({} {}): {}", hod_name, report.subject_code, report.year, report.section, note);
+        let _ = coverage_repository::insert_notification(pool, &message, &report.faculty_login, &report.branch).await;
+        return load(pool, report.id).await;
+    }
+
+    let snapshot = load_snapshot(pool, report.id).await?;
+    let signed_at = Utc::now();
+    let pdf = render_pdf(&snapshot, &report, Some((&hod_name, signed_at, note)));
+    let mut tx = pool.begin().await.map_err(internal)?;
+    if coverage_repository::finalize_report(&mut tx, report.id, hod_id, note, signed_at).await.map_err(internal)? == 0 {
+        return Err((StatusCode::CONFLICT, "The report changed while it was being reviewed".to_string()));
+    }
+    coverage_repository::insert_archive(&mut tx, report.id, &filename(&snapshot), &pdf, hod_id).await.map_err(internal)?;
+    tx.commit().await.map_err(internal)?;
+
+    let message = format!("{} countersigned your syllabus coverage report for {} ({} {}).", hod_name, report.subject_code, report.year, report.section);
+    let _ = coverage_repository::insert_notification(pool, &message, &report.faculty_login, &report.branch).await;
+    load(pool, report.id).await
+}
+
+/// The archived PDF of a finalized report; a preview marked as unsigned otherwise.
+pub async fn get_pdf(pool: &PgPool, id: Uuid) -> Result<(String, Vec<u8>), (StatusCode, String)> {
+    if let Some(archived) = coverage_repository::find_archive(pool, id).await.map_err(internal)? {
+        return Ok(archived);
+    }
+    let report = load(pool, id).await?;
+    let snapshot = load_snapshot(pool, id).await?;
+    Ok((filename(&snapshot), render_pdf(&snapshot, &report, None)))
+}
+
+fn filename(snapshot: &CoverageSnapshot) -> String {
+    format!(
+        "syllabus-coverage-{}-{}-{}-{}.pdf",
+        snapshot.subject_code,
+        snapshot.year.replace(' ', ""),
+        snapshot.section.replace(' ', ""),
+        snapshot.term.replace(' ', "-")
+    )
+}
+
+fn date_or_dash(d: Option<chrono::NaiveDate>) -> String {
+    d.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn stamp(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+/// The statement as a text PDF: summary, topic-by-topic coverage, completion log and the
+/// signatures. `hod` is (name, signed at, note) once countersigned.
+fn render_pdf(snapshot: &CoverageSnapshot, report: &CoverageReport, hod: Option<(&str, DateTime<Utc>, Option<&str>)>) -> Vec<u8> {
+    let title = format!("Syllabus Coverage - {} {} - {} {}", snapshot.subject_code, snapshot.subject_name, snapshot.year, snapshot.section);
+    let s = &snapshot.summary;
+    let mut lines = vec![
+        format!("{} | Regulation {} | Semester {} | {}", snapshot.branch, snapshot.regulation, snapshot.semester, snapshot.term),
+        format!("Faculty: {} ({})    Generated: {}", snapshot.faculty_name, snapshot.faculty_login, stamp(snapshot.generated_at)),
+        format!("Report: {}", report.id),
+        String::new(),
+        format!(
+            "Topics {} | Completed {} ({:.1}%) | Pending {} (overdue {})",
+            s.total_topics, s.completed, s.coverage_percent, s.pending, s.overdue
+        ),
+        format!(
+            "On time {} | Late {} | Unplanned {} | Average deviation {}",
+            s.completed_on_time,
+            s.completed_late,
+            s.completed_unplanned,
+            s.average_deviation_days.map(|d| format!("{:+.1} days", d)).unwrap_or_else(|| "-".to_string())
+        ),
+        String::new(),
+    ];
+
+    let headers = ["Unit", "S.No", "Topic", "Status", "Assigned", "Completed", "Dev", "Remarks"];
+    let rows: Vec<Vec<String>> = snapshot
+        .topics
+        .iter()
+        .map(|t| {
+            vec![
+                t.unit_no.to_string(),
+                t.sno.clone(),
+                t.topic.clone(),
+                t.status.clone(),
+                date_or_dash(t.assigned_date),
+                date_or_dash(t.completed_date),
+                t.deviation_days.map(|d| format!("{:+}", d)).unwrap_or_else(|| "-".to_string()),
+                t.remarks.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    lines.extend(export_utils::text_table(&headers, &rows, 24));
+
+    lines.push(String::new());
+    lines.push("Completion log".to_string());
+    let topic_name = |id: &str| snapshot.topics.iter().find(|t| t.topic_id == id).map(|t| t.topic.clone()).unwrap_or_else(|| id.to_string());
+    let log_rows: Vec<Vec<String>> = snapshot
+        .audit
+        .iter()
+        .map(|a| vec![stamp(a.timestamp), topic_name(&a.topic_id), a.action.clone(), a.changed_by.clone().unwrap_or_else(|| "-".to_string())])
+        .collect();
+    if log_rows.is_empty() {
+        lines.push("No completions were logged.".to_string());
+    } else {
+        lines.extend(export_utils::text_table(&["When", "Topic", "Action", "By"], &log_rows, 30));
+    }
+
+    lines.push(String::new());
+    match (report.faculty_signed_at, report.status.as_str()) {
+        (Some(at), "FACULTY_SIGNED" | "FINALIZED") => {
+            lines.push(format!("Signed by faculty: {} on {}", snapshot.faculty_name, stamp(at)));
+            if let Some(statement) = report.faculty_statement.as_deref() {
+                lines.push(format!("Statement: {}", statement));
+            }
+        }
+        _ => lines.push("Not signed by the faculty member.".to_string()),
+    }
+    match hod {
+        Some((name, at, note)) => {
+            lines.push(format!("Countersigned by HOD: {} on {}", name, stamp(at)));
+            if let Some(note) = note {
+                lines.push(format!("Note: {}", note));
+            }
+        }
+        None => lines.push("DRAFT - not countersigned by the HOD.".to_string()),
+    }
+    export_utils::text_pdf(&title, &lines)
+}
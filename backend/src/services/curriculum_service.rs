@@ -1,10 +1,20 @@
-use std::path::Path;
-use tokio::fs;
-use crate::models::curriculum::{CurriculumJson, CurriculumProgressRow};
-use crate::repositories::curriculum_repository;
+use axum::http::StatusCode;
+use crate::models::curriculum::{
+    CurriculumJson, CurriculumRegulation, CurriculumRevision, CurriculumRevisionsQuery, CurriculumSubjectsQuery,
+    CurriculumTopic, CurriculumUnit, SectionProgress, UpdateProgressRequest,
+};
+use uuid::Uuid;
+use crate::models::get_branch_variations;
+use crate::repositories::{curriculum_repository, material_repository};
 use sqlx::PgPool;
-use serde_json;
 
+pub use backend::curriculum_validator::normalize_regulation;
+
+/// Regulation assumed when a request does not name one.
+pub const DEFAULT_REGULATION: &str = "C23";
+
+/// Loads the subject's current curriculum revision and overlays the section's progress and the
+/// students' topic feedback. Progress follows a topic across revisions by its stable identity.
 pub async fn get_merged_curriculum(
     pool: &PgPool,
     branch: &str,
@@ -14,57 +24,24 @@ pub async fn get_merged_curriculum(
     section: &str,
     year: &str,
 ) -> Result<CurriculumJson, Box<dyn std::error::Error>> {
-    // 1. Resolve JSON Path
-    // Path structure: ../frontend/assets/curriculum/{regulation}/{branch}/{semester}/{type}/{subject}.json
-    // We need to find the file. We can search in theory and practical folders.
-    
-    let branch_short = _map_to_short_branch(branch);
-    let mut semester_dir = format!("semester {}", semester);
-    
-    let root_dir = if Path::new("./static/assets/assets/curriculum").exists() {
-        semester_dir = format!("semester%20{}", semester);
-        "./static/assets/assets"
-    } else if Path::new("./static/assets/curriculum").exists() {
-        "./static/assets"
-    } else {
-        "../frontend/assets"
-    };
-
-    let base_path = format!(
-        "{}/curriculum/{}/{}/{}",
-        root_dir,
-        regulation.to_lowercase(),
-        branch_short.to_lowercase(),
-        semester_dir
-    );
-
-    let mut json_content = None;
-    
-    // Check direct path first (for regulations without theory/practical subdirectories like C26)
-    let direct_path = format!("{}/{}.json", base_path, subject_code);
-    let theory_path = format!("{}/theory/{}.json", base_path, subject_code);
-    let practical_path = format!("{}/practical/{}.json", base_path, subject_code);
-    
-    if Path::new(&direct_path).exists() {
-        json_content = Some(fs::read_to_string(&direct_path).await?);
-    } else if Path::new(&theory_path).exists() {
-        json_content = Some(fs::read_to_string(&theory_path).await?);
-    } else if Path::new(&practical_path).exists() {
-        json_content = Some(fs::read_to_string(&practical_path).await?);
-    }
-
-    let json_str = json_content.ok_or_else(|| format!("Curriculum JSON not found for {}. Tried: {} and {}", subject_code, theory_path, practical_path))?;
-    let mut curriculum: CurriculumJson = serde_json::from_str(&json_str)?;
+    let regulation = normalize_regulation(regulation);
+    let branch_code = map_to_short_branch(branch);
+    let revision = curriculum_repository::find_current_revision(pool, &regulation, branch_code, subject_code)
+        .await?
+        .ok_or_else(|| format!("Curriculum not found for {} ({} {}, semester {})", subject_code, regulation, branch_code, semester))?;
+    let mut curriculum = load_revision(pool, &revision).await?;
 
-    // 2. Fetch Progress from DB
     let progress_rows = curriculum_repository::get_progress(pool, subject_code, branch, section, year).await?;
     let feedback_stats = curriculum_repository::get_topic_feedback_stats(pool, subject_code).await?;
+    let visible_to = Some(section.trim()).filter(|s| !s.is_empty());
+    let materials = material_repository::find_materials(pool, subject_code, &get_branch_variations(branch), visible_to, None, None, false).await?;
 
-    // 3. Merge
     for unit in &mut curriculum.units {
         for topic in &mut unit.topics {
-            // Find progress
-            if let Some(row) = progress_rows.iter().find(|r| r.topic_id == topic.id) {
+            let uid = topic.uid.map(|u| u.to_string());
+            if let Some(row) = progress_rows.iter().find(|r| r.topic_uid.is_some() && r.topic_uid == topic.uid)
+                .or_else(|| progress_rows.iter().find(|r| r.topic_id == topic.id))
+            {
                 topic.status = row.status.clone();
                 topic.assigned_date = row.assigned_date;
                 topic.completed_date = row.completed_date;
@@ -73,105 +50,204 @@ pub async fn get_merged_curriculum(
                 topic.status = Some("pending".to_string());
             }
 
-            // Find feedback
-            if let Some(feedback) = feedback_stats.iter().find(|(tid, _, _)| tid == &topic.id) {
-                topic.feedback_count = Some(feedback.1);
-                topic.understood_percentage = Some(feedback.2);
-            } else {
-                topic.feedback_count = Some(0);
-                topic.understood_percentage = Some(0.0);
-            }
+            let feedback = feedback_stats.iter().filter(|(key, _, _)| Some(key) == uid.as_ref() || key == &topic.id);
+            let (count, weighted) = feedback.fold((0, 0.0), |(c, w), (_, n, pct)| (c + n, w + *n as f64 * pct));
+            topic.feedback_count = Some(count);
+            topic.understood_percentage = Some(if count > 0 { weighted / count as f64 } else { 0.0 });
+        }
+    }
+
+    // Topic materials follow the topic by identity; the rest hang off their unit or the subject.
+    for material in materials {
+        let topic = material.topic_id.as_deref().and_then(|topic_id| {
+            curriculum.units.iter_mut().flat_map(|u| u.topics.iter_mut()).find(|t| {
+                (material.topic_uid.is_some() && t.uid == material.topic_uid) || t.id == topic_id
+            })
+        });
+        if let Some(topic) = topic {
+            topic.materials.push(material);
+        } else if let Some(unit) = curriculum.units.iter_mut().find(|u| Some(u.unit_no) == material.unit_no) {
+            unit.materials.push(material);
+        } else {
+            curriculum.materials.push(material);
         }
     }
 
     Ok(curriculum)
 }
 
-pub async fn get_subjects_from_assets(
+/// Records a topic's progress for a section. Marking a topic completed, or reverting it, is
+/// logged in curriculum_completion_logs, which the syllabus forecasts take their pace from.
+pub async fn update_progress(pool: &PgPool, req: UpdateProgressRequest) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let was_completed = curriculum_repository::find_progress_status(&mut tx, &req).await?.flatten().as_deref() == Some("completed");
+    let progress_id = curriculum_repository::upsert_progress(&mut tx, &req).await?;
+    let completed = req.status == "completed";
+    if completed != was_completed {
+        let action = if completed { "marked_completed" } else { "reverted_pending" };
+        curriculum_repository::insert_completion_log(&mut tx, progress_id, action, Some(req.faculty_id)).await?;
+    }
+    tx.commit().await
+}
+
+/// Records a lesson plan item's progress for a section in the same store, and log, as curriculum
+/// topics. False when the item does not exist.
+pub async fn update_lesson_item_progress(pool: &PgPool, item_id: &str, section: &str, completed: bool, faculty_id: Option<Uuid>) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let was_completed = curriculum_repository::find_lesson_item_status(&mut tx, item_id, section).await?.flatten().as_deref() == Some("completed");
+    let Some(progress_id) = curriculum_repository::upsert_lesson_item_progress(&mut tx, item_id, section, completed, faculty_id).await? else {
+        return Ok(false);
+    };
+    if completed != was_completed {
+        let action = if completed { "marked_completed" } else { "reverted_pending" };
+        curriculum_repository::insert_completion_log(&mut tx, progress_id, action, faculty_id).await?;
+    }
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// The section's progress through a subject. Subjects still taught from a lesson plan count its
+/// items; the rest count the topics of their current curriculum. Either way the completion comes
+/// from curriculum_progress.
+pub async fn section_progress(
+    pool: &PgPool,
+    subject_code: &str,
     branch: &str,
+    year: &str,
+    section: &str,
     semester: i32,
     regulation: &str,
-) -> Result<Vec<(String, String, String)>, Box<dyn std::error::Error>> {
-    let branch_short = _map_to_short_branch(branch);
-    let mut semester_dir = format!("semester {}", semester);
-    
-    let root_dir = if Path::new("./static/assets/assets/curriculum").exists() {
-        semester_dir = format!("semester%20{}", semester);
-        "./static/assets/assets"
-    } else if Path::new("./static/assets/curriculum").exists() {
-        "./static/assets"
-    } else {
-        "../frontend/assets"
-    };
-
-    let base_path = format!(
-        "{}/curriculum/{}/{}/{}",
-        root_dir,
-        regulation.to_lowercase(),
-        branch_short.to_lowercase(),
-        semester_dir
-    );
-
-    let mut subjects = Vec::new();
-
-    // Read directly from semester folder (for regulations without theory/practical subdirectories)
-    if let Ok(mut entries) = fs::read_dir(&base_path).await {
-        while let Ok(Some(entry)) = entries.next_entry().await {
-            if let Some(name) = entry.file_name().to_str() {
-                if name.ends_with(".json") {
-                    let code = name.replace(".json", "");
-                    let content = fs::read_to_string(entry.path()).await?;
-                    let data: serde_json::Value = serde_json::from_str(&content)?;
-                    let sub_name = data["subjectName"].as_str().unwrap_or(&code).to_string();
-                    let suffix = code.chars().last().unwrap_or('T').to_ascii_uppercase();
-                    let sub_type = if ['L', 'D', 'P', 'C'].contains(&suffix) {
-                        "Practical"
-                    } else {
-                        "Theory"
-                    };
-                    subjects.push((code, sub_name, sub_type.to_string()));
-                }
-            }
-        }
+) -> SectionProgress {
+    let (total, completed, scheduled, ever_scheduled) = curriculum_repository::get_lesson_plan_stats(pool, subject_code, section)
+        .await
+        .unwrap_or_default();
+    if total > 0 {
+        return SectionProgress { total, completed, scheduled, has_schedule: ever_scheduled > 0 };
     }
 
-    // Read theory
-    let theory_path = format!("{}/theory", base_path);
-    if let Ok(mut entries) = fs::read_dir(&theory_path).await {
-        while let Ok(Some(entry)) = entries.next_entry().await {
-            if let Some(name) = entry.file_name().to_str() {
-                if name.ends_with(".json") {
-                    let code = name.replace(".json", "");
-                    let content = fs::read_to_string(entry.path()).await?;
-                    let data: serde_json::Value = serde_json::from_str(&content)?;
-                    let sub_name = data["subjectName"].as_str().unwrap_or(&code).to_string();
-                    subjects.push((code, sub_name, "Theory".to_string()));
-                }
+    let mut progress = SectionProgress::default();
+    let Ok(curriculum) = get_merged_curriculum(pool, branch, semester, regulation, subject_code, section, year).await else {
+        return progress;
+    };
+    let today = chrono::Utc::now().date_naive();
+    for topic in curriculum.units.iter().flat_map(|u| &u.topics).filter(|t| t.topic_type.to_lowercase() != "unit") {
+        progress.total += 1;
+        if topic.status.as_deref() == Some("completed") {
+            progress.completed += 1;
+        }
+        if let Some(assigned) = topic.assigned_date {
+            progress.has_schedule = true;
+            if assigned <= today {
+                progress.scheduled += 1;
             }
         }
     }
+    progress
+}
 
-    // Read practical
-    let practical_path = format!("{}/practical", base_path);
-    if let Ok(mut entries) = fs::read_dir(&practical_path).await {
-        while let Ok(Some(entry)) = entries.next_entry().await {
-            if let Some(name) = entry.file_name().to_str() {
-                if name.ends_with(".json") {
-                    let code = name.replace(".json", "");
-                    let content = fs::read_to_string(entry.path()).await?;
-                    let data: serde_json::Value = serde_json::from_str(&content)?;
-                    let sub_name = data["subjectName"].as_str().unwrap_or(&code).to_string();
-                    subjects.push((code, sub_name, "Practical".to_string()));
-                }
-            }
-        }
+/// The subject's current curriculum without any section's progress, if it has been imported.
+pub async fn find_current_curriculum(pool: &PgPool, branch: &str, regulation: &str, subject_code: &str) -> Result<Option<CurriculumJson>, sqlx::Error> {
+    let revision = curriculum_repository::find_current_revision(pool, &normalize_regulation(regulation), map_to_short_branch(branch), subject_code).await?;
+    match revision {
+        Some(revision) => load_revision(pool, &revision).await.map(Some),
+        None => Ok(None),
     }
+}
+
+async fn load_revision(pool: &PgPool, revision: &CurriculumRevision) -> Result<CurriculumJson, sqlx::Error> {
+    let units = curriculum_repository::find_revision_units(pool, revision.id).await?;
+    let topics = curriculum_repository::find_revision_topics(pool, revision.id).await?;
+    Ok(CurriculumJson {
+        subject_code: revision.subject_code.clone(),
+        subject_name: revision.subject_name.clone(),
+        regulation: revision.regulation.clone(),
+        semester: revision.semester,
+        total_periods: revision.total_periods,
+        revision: Some(revision.revision),
+        units: units
+            .into_iter()
+            .map(|u| CurriculumUnit {
+                unit_no: u.unit_no,
+                title: u.title,
+                total_periods: u.total_periods,
+                topics: topics
+                    .iter()
+                    .filter(|t| t.unit_id == u.id)
+                    .map(|t| CurriculumTopic {
+                        id: t.topic_key.clone(),
+                        sno: t.sno.clone().unwrap_or_default(),
+                        topic: t.topic.clone(),
+                        period: t.periods,
+                        topic_type: t.topic_type.clone(),
+                        uid: Some(t.topic_uid),
+                        status: None,
+                        assigned_date: None,
+                        completed_date: None,
+                        remarks: None,
+                        feedback_count: None,
+                        understood_percentage: None,
+                        materials: Vec::new(),
+                    })
+                    .collect(),
+                materials: Vec::new(),
+            })
+            .collect(),
+        materials: Vec::new(),
+    })
+}
 
-    Ok(subjects)
+/// (code, name, Theory/Practical) of the semester's subjects in the current curriculum.
+pub async fn get_curriculum_subjects(
+    pool: &PgPool,
+    branch: &str,
+    semester: i32,
+    regulation: &str,
+) -> Result<Vec<(String, String, String)>, sqlx::Error> {
+    let subjects = curriculum_repository::find_current_subjects(pool, &normalize_regulation(regulation), map_to_short_branch(branch), Some(semester)).await?;
+    Ok(subjects
+        .into_iter()
+        .map(|s| {
+            let kind = if s.subject_type == "PRACTICAL" { "Practical" } else { "Theory" };
+            (s.subject_code, s.subject_name, kind.to_string())
+        })
+        .collect())
+}
+
+pub async fn get_regulations(pool: &PgPool) -> Result<Vec<CurriculumRegulation>, StatusCode> {
+    curriculum_repository::find_regulations(pool)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch curriculum regulations: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
 }
 
-fn _map_to_short_branch(branch: &str) -> &str {
+pub async fn get_subjects(pool: &PgPool, params: CurriculumSubjectsQuery) -> Result<Vec<CurriculumRevision>, StatusCode> {
+    let regulation = normalize_regulation(params.regulation.as_deref().unwrap_or(DEFAULT_REGULATION));
+    curriculum_repository::find_current_subjects(pool, &regulation, map_to_short_branch(&params.branch), params.semester)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch curriculum subjects: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+pub async fn get_revisions(pool: &PgPool, params: CurriculumRevisionsQuery) -> Result<Vec<CurriculumRevision>, StatusCode> {
+    let regulation = normalize_regulation(params.regulation.as_deref().unwrap_or(DEFAULT_REGULATION));
+    curriculum_repository::find_revisions(pool, &regulation, map_to_short_branch(&params.branch), &params.subject_code)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch curriculum revisions: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// The folder code the curriculum was imported under; accepts either a department name or its code.
+pub fn map_to_short_branch(branch: &str) -> &str {
     let b_lower = branch.to_lowercase();
+    if let Some(code) = ["cme", "eee", "ece", "mech", "civ", "aiml"].into_iter().find(|c| *c == b_lower.trim()) {
+        return code;
+    }
     if b_lower.contains("computer") { "cme" }
     else if b_lower.contains("electrical") { "eee" }
     else if b_lower.contains("electronics") { "ece" }
@@ -0,0 +1,298 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::collections::HashSet;
+use uuid::Uuid;
+use crate::models::attendance::AttendanceWrite;
+use crate::models::leave::{ApplyLeaveRequest, CancelLeaveRequest, LeaveApplication, LeaveApplicationQuery, ReviewLeaveRequest};
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::repositories::{academic_calendar_repository, attendance_repository, leave_repository};
+use crate::repositories::auth::insert_notification;
+use crate::services::attendance_service::{self, LEAVE_STATUS};
+use crate::utils::user_utils::resolve_user_id;
+
+const SESSIONS: [&str; 2] = ["MORNING", "AFTERNOON"];
+
+fn normalize_session(session: Option<&str>, default: &str) -> Result<String, (StatusCode, String)> {
+    let s = session.unwrap_or(default).to_uppercase();
+    if SESSIONS.contains(&s.as_str()) {
+        Ok(s)
+    } else {
+        Err((StatusCode::BAD_REQUEST, format!("Invalid session: {}", s)))
+    }
+}
+
+/// Every (date, session) covered by a leave. Sundays and the given non-instructional days are skipped.
+fn leave_sessions(leave: &LeaveApplication, non_instructional: &HashSet<NaiveDate>) -> Vec<(NaiveDate, &'static str)> {
+    let mut out = Vec::new();
+    let mut date = leave.from_date;
+    while date <= leave.to_date {
+        if date.weekday() != Weekday::Sun && !non_instructional.contains(&date) {
+            for session in SESSIONS {
+                let before_start = date == leave.from_date && leave.from_session == "AFTERNOON" && session == "MORNING";
+                let after_end = date == leave.to_date && leave.to_session == "MORNING" && session == "AFTERNOON";
+                if !before_start && !after_end {
+                    out.push((date, session));
+                }
+            }
+        }
+        date += Duration::days(1);
+    }
+    out
+}
+
+pub async fn apply_leave(pool: &PgPool, mut payload: ApplyLeaveRequest) -> Result<Uuid, (StatusCode, String)> {
+    let applicant_uuid = resolve_user_id(&payload.applicant_id, "Student", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid applicant".to_string()))?;
+    let (applicant_login, applicant_role, applicant_name, _, _, _) = leave_repository::find_user_basics(pool, applicant_uuid)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Applicant not found".to_string()))?;
+
+    let student_uuid = match applicant_role.as_str() {
+        "Student" => applicant_uuid,
+        "Parent" => {
+            let sid = payload.student_id.as_deref().ok_or((StatusCode::BAD_REQUEST, "studentId is required".to_string()))?;
+            resolve_user_id(sid, "Student", pool).await.map_err(|_| (StatusCode::BAD_REQUEST, "Invalid student".to_string()))?
+        }
+        _ => return Err((StatusCode::FORBIDDEN, "Only students and parents can apply for leave".to_string())),
+    };
+    let (student_login, _, student_name, branch, year, section) = leave_repository::find_user_basics(pool, student_uuid)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Student not found".to_string()))?;
+
+    if applicant_role == "Parent" {
+        let linked = leave_repository::is_parent_of(pool, &applicant_login, &student_login)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        if !linked {
+            return Err((StatusCode::FORBIDDEN, "Parent is not linked to this student".to_string()));
+        }
+    }
+
+    let from_session = normalize_session(payload.from_session.as_deref(), "MORNING")?;
+    let to_session = normalize_session(payload.to_session.as_deref(), "AFTERNOON")?;
+    if payload.to_date < payload.from_date
+        || (payload.to_date == payload.from_date && from_session == "AFTERNOON" && to_session == "MORNING")
+    {
+        return Err((StatusCode::BAD_REQUEST, "Leave must end after it starts".to_string()));
+    }
+    if payload.reason.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Reason is required".to_string()));
+    }
+    payload.from_session = Some(from_session.clone());
+    payload.to_session = Some(to_session.clone());
+
+    let overlapping = leave_repository::count_overlapping(pool, student_uuid, payload.from_date, payload.to_date)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if overlapping > 0 {
+        return Err((StatusCode::CONFLICT, "A pending or approved leave already covers these dates".to_string()));
+    }
+
+    // Route to the class incharge by default, or to the HOD when asked or when the section has none.
+    let variations = get_branch_variations(branch.as_deref().unwrap_or_default());
+    let to_hod = payload.route_to.as_deref().map(|r| r.eq_ignore_ascii_case("HOD")).unwrap_or(false);
+    let mut reviewer = None;
+    if !to_hod {
+        reviewer = leave_repository::find_section_incharge(pool, &variations, year.as_deref(), section.as_deref())
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+    if reviewer.is_none() {
+        reviewer = leave_repository::find_hod(pool, &variations)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let parent_request_id = if applicant_role == "Parent" {
+        let duration = format!("{} ({}) to {} ({})", payload.from_date, from_session, payload.to_date, to_session);
+        let subject = format!("Leave: {}", payload.leave_type.as_deref().unwrap_or("Personal"));
+        Some(
+            leave_repository::insert_parent_leave_request(&mut tx, applicant_uuid, student_uuid, &subject, &payload.reason, &duration, reviewer.as_ref().map(|r| r.0))
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+        )
+    } else {
+        None
+    };
+    let leave_id = leave_repository::insert_leave(&mut tx, student_uuid, applicant_uuid, &applicant_role, parent_request_id, reviewer.as_ref().map(|r| r.0), &payload)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if let Some((_, reviewer_login)) = reviewer {
+        let msg = format!("{} applied for leave for {} from {} to {}", applicant_name, student_name, payload.from_date, payload.to_date);
+        insert_notification(pool, "LEAVE_REQUEST", msg, &applicant_login, branch.as_deref(), Some(&reviewer_login)).await.ok();
+    }
+
+    Ok(leave_id)
+}
+
+pub async fn get_leave_applications(pool: &PgPool, params: LeaveApplicationQuery) -> Result<Vec<LeaveApplication>, StatusCode> {
+    let student_id = match params.student_id.as_deref() {
+        Some(s) => Some(resolve_user_id(s, "Student", pool).await.map_err(|_| StatusCode::NOT_FOUND)?),
+        None => None,
+    };
+    let reviewer_id = match params.reviewer_id.as_deref() {
+        Some(r) => Some(resolve_user_id(r, "Faculty", pool).await.map_err(|_| StatusCode::NOT_FOUND)?),
+        None => None,
+    };
+    let variations = params.branch.as_deref().map(get_branch_variations);
+    let status = params.status.as_deref().map(|s| s.to_uppercase());
+
+    leave_repository::find_leaves(pool, student_id, reviewer_id, variations.as_deref(), status.as_deref())
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch leave applications: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Approving a leave marks every covered instructional session as "L". Sessions already marked
+/// present are left alone, and so are dates whose edit window has closed: those are returned so
+/// the reviewer can raise a correction for them.
+pub async fn review_leave(pool: &PgPool, payload: ReviewLeaveRequest) -> Result<Vec<NaiveDate>, (StatusCode, String)> {
+    let reviewer_uuid = resolve_user_id(&payload.reviewer_id, "Faculty", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid reviewer".to_string()))?;
+    let (reviewer_login, reviewer_role, _, _, _, _) = leave_repository::find_user_basics(pool, reviewer_uuid)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Reviewer not found".to_string()))?;
+
+    let leave = leave_repository::find_leave_by_id(pool, payload.leave_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Leave application not found".to_string()))?;
+    if leave.status != "PENDING" {
+        return Err((StatusCode::CONFLICT, format!("Leave is already {}", leave.status.to_lowercase())));
+    }
+    if leave.assigned_to != Some(reviewer_uuid) && !matches!(reviewer_role.as_str(), "HOD" | "Principal") {
+        return Err((StatusCode::FORBIDDEN, "Leave is assigned to another reviewer".to_string()));
+    }
+
+    let (status, parent_status) = match payload.action.to_uppercase().as_str() {
+        "APPROVE" => ("APPROVED", "Approved"),
+        "REJECT" => ("REJECTED", "Rejected"),
+        _ => return Err((StatusCode::BAD_REQUEST, "Action must be APPROVE or REJECT".to_string())),
+    };
+
+    let mut sessions = Vec::new();
+    let mut locked_dates = Vec::new();
+    if status == "APPROVED" {
+        let branch = normalize_branch(leave.branch.as_deref().unwrap_or_default());
+        let year = leave_repository::find_user_basics(pool, leave.student_id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .and_then(|basics| basics.4)
+            .unwrap_or_default();
+        let non_instructional: HashSet<NaiveDate> = academic_calendar_repository::find_non_instructional_dates(pool, leave.from_date, leave.to_date, &branch, &year)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .into_iter()
+            .collect();
+
+        for (date, session) in leave_sessions(&leave, &non_instructional) {
+            if locked_dates.contains(&date) {
+                continue;
+            }
+            let date_str = date.format("%Y-%m-%d").to_string();
+            match attendance_service::ensure_editable(pool, &branch, &date_str).await {
+                Ok(()) => sessions.push((date_str, session)),
+                Err((StatusCode::LOCKED, _)) => locked_dates.push(date),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let updated = leave_repository::update_leave_status(&mut tx, leave.id, status, Some(reviewer_uuid), payload.note.as_deref())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if updated == 0 {
+        return Err((StatusCode::CONFLICT, "Leave was already reviewed".to_string()));
+    }
+
+    if status == "APPROVED" {
+        let student_login = leave.student_login_id.clone().unwrap_or_default();
+        let section = leave.section.clone().unwrap_or_default();
+        for (date_str, session) in &sessions {
+            let session = *session;
+            let existing = attendance_repository::find_existing_mark(&mut tx, &student_login, date_str, session)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            if matches!(existing.as_ref().map(|e| e.0.to_uppercase()).as_deref(), Some("P" | "PRESENT")) {
+                continue;
+            }
+
+            attendance_service::record_attendance(&mut tx, AttendanceWrite {
+                student_uuid: leave.student_id,
+                student_login_id: &student_login,
+                faculty_uuid: reviewer_uuid,
+                date: date_str,
+                status: LEAVE_STATUS,
+                session,
+                section: &section,
+                change_source: "LEAVE",
+                reason: Some(&leave.reason),
+                correction_request_id: None,
+            })
+            .await
+            .map_err(|e| {
+                eprintln!("ERROR: Failed to apply leave {} on {}: {:?}", leave.id, date_str, e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+            })?;
+        }
+    }
+
+    if let Some(pr_id) = leave.parent_request_id {
+        leave_repository::update_parent_request_status(&mut tx, pr_id, parent_status)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let msg = format!("Leave from {} to {} was {}", leave.from_date, leave.to_date, status.to_lowercase());
+    if let Some(student_login) = leave.student_login_id.as_deref() {
+        insert_notification(pool, "LEAVE_STATUS", msg.clone(), &reviewer_login, leave.branch.as_deref(), Some(student_login)).await.ok();
+    }
+    if leave.applied_by != leave.student_id {
+        if let Ok(Some((parent_login, _, _, _, _, _))) = leave_repository::find_user_basics(pool, leave.applied_by).await {
+            insert_notification(pool, "LEAVE_STATUS", msg, &reviewer_login, leave.branch.as_deref(), Some(&parent_login)).await.ok();
+        }
+    }
+
+    Ok(locked_dates)
+}
+
+pub async fn cancel_leave(pool: &PgPool, payload: CancelLeaveRequest) -> Result<(), (StatusCode, String)> {
+    let applicant_uuid = resolve_user_id(&payload.applicant_id, "Student", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid applicant".to_string()))?;
+    let leave = leave_repository::find_leave_by_id(pool, payload.leave_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Leave application not found".to_string()))?;
+    if leave.applied_by != applicant_uuid {
+        return Err((StatusCode::FORBIDDEN, "Only the applicant can cancel this leave".to_string()));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let updated = leave_repository::update_leave_status(&mut tx, leave.id, "CANCELLED", None, None)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if updated == 0 {
+        return Err((StatusCode::CONFLICT, "Only pending leaves can be cancelled".to_string()));
+    }
+    if let Some(pr_id) = leave.parent_request_id {
+        leave_repository::update_parent_request_status(&mut tx, pr_id, "Cancelled")
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,346 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use chrono::{Duration, NaiveDate, Utc};
+use std::collections::HashSet;
+use uuid::Uuid;
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::models::curriculum::CurriculumJson;
+use crate::models::lesson_plan::{
+    GenerateLessonPlanRequest, LessonPlan, LessonPlanDetail, LessonPlanQuery, PlanCalendar, PlanSummary, PlanTarget, PlannedTopic, SubjectSlot,
+};
+use crate::repositories::{academic_calendar_repository, curriculum_repository, leave_repository, lesson_plan_repository};
+use crate::services::curriculum_service::{self, DEFAULT_REGULATION};
+use crate::utils::timing_utils;
+use crate::utils::user_utils::resolve_user_id;
+
+/// Dates of the subject's periods from `from` to `to`, one entry per period, skipping days the
+/// calendar has no classes on and periods recorded as not conducted. Also returns how many
+/// periods were lost that way.
+fn period_dates(
+    from: NaiveDate,
+    to: NaiveDate,
+    slots: &[SubjectSlot],
+    closed: &HashSet<NaiveDate>,
+    lost: &HashSet<(NaiveDate, i32)>,
+) -> (Vec<NaiveDate>, i32) {
+    let mut dates = Vec::new();
+    let mut lost_count = 0;
+    let mut date = from;
+    while date <= to {
+        if !closed.contains(&date) {
+            let day = date.format("%A").to_string();
+            let mut periods: Vec<i32> = slots.iter().filter(|s| s.day == day).map(|s| s.period_index).collect();
+            periods.sort_unstable();
+            periods.dedup();
+            for period in periods {
+                if lost.contains(&(date, period)) {
+                    lost_count += 1;
+                } else {
+                    dates.push(date);
+                }
+            }
+        }
+        date += Duration::days(1);
+    }
+    (dates, lost_count)
+}
+
+/// The subject's periods over the plan's semester: its timetable slots on every day the calendar
+/// has classes, less the periods already lost, and the start of the exam block they lead up to.
+pub async fn plan_calendar(pool: &PgPool, target: &PlanTarget, slots: &[SubjectSlot]) -> Result<PlanCalendar, (StatusCode, String)> {
+    let internal = |e: sqlx::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    let semester = academic_calendar_repository::find_semester(pool, Some(target.academic_semester_id), Utc::now().date_naive())
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Semester not found in the academic calendar".to_string()))?;
+    let (from, to) = (semester.start_date, semester.end_date);
+
+    let closed: HashSet<NaiveDate> = academic_calendar_repository::find_non_instructional_dates(pool, from, to, &target.branch, &target.year)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .collect();
+    // The syllabus has to be covered before the semester's last exam block starts; earlier
+    // blocks are mid-term exams.
+    let exam_start = academic_calendar_repository::find_events(pool, from, to, Some(&target.branch), Some(&target.year))
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .filter(|e| e.event_type == "EXAM" && e.start_date >= from)
+        .map(|e| e.start_date)
+        .max();
+    let variations = get_branch_variations(&target.branch);
+    let lost: HashSet<(NaiveDate, i32)> = lesson_plan_repository::find_lost_periods(pool, &variations, &target.year, &target.section, from, to)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .collect();
+
+    let (dates, lost_periods) = period_dates(from, to, slots, &closed, &lost);
+    Ok(PlanCalendar { from, to, exam_start, dates, lost_periods })
+}
+
+/// Lays the subject's pending topics over its periods from today on and stores the dates in
+/// curriculum_progress. Topics are taken in syllabus order, each starting on the date of its first
+/// period. Completed topics keep the date they already have, and so do pending topics dated by hand
+/// unless `overwrite` is set; the periods of those hand-set dates are not given to other topics.
+async fn build_plan(
+    pool: &PgPool,
+    target: &PlanTarget,
+    curriculum: &CurriculumJson,
+    slots: &[SubjectSlot],
+    generated_by: Option<Uuid>,
+    overwrite: bool,
+) -> Result<LessonPlanDetail, (StatusCode, String)> {
+    let internal = |e: sqlx::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    let PlanCalendar { from, exam_start, dates, lost_periods, .. } = plan_calendar(pool, target, slots).await?;
+    let progress = curriculum_repository::get_progress(pool, &target.subject_code, &target.branch, &target.section, &target.year)
+        .await
+        .map_err(internal)?;
+    let plan_start = timing_utils::campus_now().date().max(from);
+
+    // Each topic with its status and, for completed and hand-dated ones, the date it keeps.
+    let mut kept_topics = Vec::new();
+    for unit in &curriculum.units {
+        for topic in &unit.topics {
+            let row = progress.iter().find(|r| r.topic_uid.is_some() && r.topic_uid == topic.uid)
+                .or_else(|| progress.iter().find(|r| r.topic_id == topic.id));
+            let status = row.and_then(|r| r.status.clone()).unwrap_or_else(|| "pending".to_string());
+            let kept = match row {
+                Some(r) if status == "completed" => Some(r.assigned_date.or(r.completed_date)),
+                Some(r) if !overwrite && !r.date_from_plan && r.assigned_date.is_some() => Some(r.assigned_date),
+                _ => None,
+            };
+            kept_topics.push((unit, topic, status, kept));
+        }
+    }
+
+    let mut open: Vec<NaiveDate> = dates.iter().copied().filter(|d| *d >= plan_start).collect();
+    for (_, topic, status, kept) in &kept_topics {
+        let Some(Some(date)) = kept else { continue };
+        if status == "completed" {
+            continue;
+        }
+        for _ in 0..topic.period.max(1) {
+            match open.iter().position(|d| d == date) {
+                Some(i) => { open.remove(i); }
+                None => break,
+            }
+        }
+    }
+
+    let mut cursor = 0usize;
+    let mut planned_periods = 0;
+    let mut topics = Vec::new();
+    let mut scheduled = Vec::new();
+    for (unit, topic, status, kept) in kept_topics {
+        let periods = topic.period.max(1);
+        let assigned_date = match kept {
+            Some(date) => date,
+            None => {
+                let date = open.get(cursor).copied();
+                cursor += periods as usize;
+                planned_periods += periods;
+                date
+            }
+        };
+        scheduled.push(kept.is_none());
+        topics.push(PlannedTopic {
+            topic_id: topic.id.clone(),
+            topic_uid: topic.uid,
+            unit_no: unit.unit_no,
+            topic: topic.topic.clone(),
+            periods,
+            status,
+            assigned_date,
+            after_exam_start: match (assigned_date, exam_start) {
+                (Some(d), Some(exam)) => d >= exam,
+                (None, _) => true,
+                _ => false,
+            },
+        });
+    }
+
+    let hand_dated_finish = topics.iter().zip(&scheduled)
+        .filter(|(t, planned)| !**planned && t.status != "completed")
+        .filter_map(|(t, _)| t.assigned_date)
+        .max();
+    let available_periods = open.iter().filter(|d| exam_start.is_none_or(|exam| **d < exam)).count() as i32;
+    let mut week: Vec<(&str, i32)> = slots.iter().map(|s| (s.day.as_str(), s.period_index)).collect();
+    week.sort_unstable();
+    week.dedup();
+    let summary = PlanSummary {
+        plan_start,
+        exam_start,
+        weekly_periods: week.len() as i32,
+        required_periods: planned_periods,
+        available_periods,
+        lost_periods,
+        projected_finish: if cursor > open.len() { None } else { cursor.checked_sub(1).and_then(|i| open.get(i).copied()).max(hand_dated_finish) },
+        at_risk: planned_periods > available_periods,
+    };
+
+    let mut tx = pool.begin().await.map_err(internal)?;
+    let plan = lesson_plan_repository::upsert_plan(&mut tx, target, &summary, generated_by).await.map_err(internal)?;
+    for (topic, _) in topics.iter().zip(&scheduled).filter(|(_, planned)| **planned) {
+        lesson_plan_repository::set_assigned_date(&mut tx, target, &topic.topic_id, topic.topic_uid, topic.assigned_date)
+            .await
+            .map_err(internal)?;
+    }
+    tx.commit().await.map_err(internal)?;
+
+    Ok(LessonPlanDetail { plan, subject_name: curriculum.subject_name.clone(), topics })
+}
+
+pub async fn subject_slots(pool: &PgPool, target: &PlanTarget, subject_name: &str) -> Result<Vec<SubjectSlot>, (StatusCode, String)> {
+    let slots = lesson_plan_repository::find_subject_slots(pool, &get_branch_variations(&target.branch), &target.year, &target.section, &target.subject_code, subject_name)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if slots.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, format!("{} has no periods in the {} {} timetable", target.subject_code, target.year, target.section)));
+    }
+    Ok(slots)
+}
+
+/// Generates (or regenerates) the dated plan for a subject in a section. Faculty may plan the
+/// subjects they teach; HODs their department's; Admin, Principal and Coordinators any.
+pub async fn generate_plan(pool: &PgPool, payload: GenerateLessonPlanRequest) -> Result<LessonPlanDetail, (StatusCode, String)> {
+    let user_id = resolve_user_id(&payload.generated_by, "Faculty", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.generated_by)))?;
+    let (login_id, role, _, user_branch, _, _) = leave_repository::find_user_basics(pool, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    let branch = normalize_branch(&payload.branch);
+    let allowed = match role.as_str() {
+        "Admin" | "Principal" | "Coordinator" => true,
+        "HOD" => user_branch.as_deref().map(normalize_branch).as_deref() == Some(branch.as_str()),
+        "Faculty" => true, // checked against the timetable below
+        _ => false,
+    };
+    if !allowed {
+        return Err((StatusCode::FORBIDDEN, "Not allowed to generate lesson plans for this branch".to_string()));
+    }
+
+    let regulation = payload.regulation.clone().unwrap_or_else(|| DEFAULT_REGULATION.to_string());
+    let curriculum = curriculum_service::find_current_curriculum(pool, &branch, &regulation, &payload.subject_code)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("No curriculum imported for {}", payload.subject_code)))?;
+    let semester = academic_calendar_repository::find_semester(pool, payload.academic_semester_id, Utc::now().date_naive())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::BAD_REQUEST, "No semester found in the academic calendar; pass academicSemesterId".to_string()))?;
+
+    let mut target = PlanTarget {
+        subject_code: payload.subject_code,
+        regulation: curriculum.regulation.clone(),
+        branch,
+        year: payload.year,
+        section: payload.section,
+        semester: payload.semester,
+        academic_semester_id: semester.id,
+        faculty_id: user_id,
+    };
+    let slots = subject_slots(pool, &target, &curriculum.subject_name).await?;
+    if role == "Faculty" && !slots.iter().any(|s| s.faculty_id == login_id) {
+        return Err((StatusCode::FORBIDDEN, "You do not teach this subject in this section".to_string()));
+    }
+    // Progress rows are recorded against the faculty who teaches the subject.
+    if role != "Faculty" {
+        if let Some(teacher) = slots.first().map(|s| s.faculty_id.clone()) {
+            target.faculty_id = resolve_user_id(&teacher, "Faculty", pool).await.unwrap_or(user_id);
+        }
+    }
+
+    build_plan(pool, &target, &curriculum, &slots, Some(user_id), payload.overwrite_dates).await
+}
+
+pub async fn get_plans(pool: &PgPool, params: LessonPlanQuery) -> Result<Vec<LessonPlan>, StatusCode> {
+    let variations = get_branch_variations(&normalize_branch(&params.branch));
+    lesson_plan_repository::find_plans(pool, &variations, params.year.as_deref(), params.section.as_deref(), params.at_risk.unwrap_or(false))
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch lesson plans: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+pub fn plan_target(plan: &LessonPlan) -> PlanTarget {
+    PlanTarget {
+        subject_code: plan.subject_code.clone(),
+        regulation: plan.regulation.clone(),
+        branch: plan.branch.clone(),
+        year: plan.year.clone(),
+        section: plan.section.clone(),
+        semester: plan.semester,
+        academic_semester_id: plan.academic_semester_id,
+        faculty_id: plan.faculty_id,
+    }
+}
+
+/// Recomputes every plan of the section, e.g. after a period was marked not conducted. A plan
+/// that can no longer be built (its curriculum or timetable slots are gone) is logged and left
+/// as it was. Returns how many plans were recomputed.
+pub async fn replan_section(pool: &PgPool, branch: &str, year: &str, section: &str) -> Result<usize, (StatusCode, String)> {
+    let plans = lesson_plan_repository::find_plans(pool, &get_branch_variations(branch), Some(year), Some(section), false)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let mut recomputed = 0;
+    for plan in &plans {
+        match replan(pool, &plan_target(plan)).await {
+            Ok(()) => recomputed += 1,
+            Err((_, e)) => eprintln!("ERROR: Failed to recompute lesson plan {}: {}", plan.id, e),
+        }
+    }
+    Ok(recomputed)
+}
+
+async fn replan(pool: &PgPool, target: &PlanTarget) -> Result<(), (StatusCode, String)> {
+    let curriculum = curriculum_service::find_current_curriculum(pool, &target.branch, &target.regulation, &target.subject_code)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("No curriculum imported for {}", target.subject_code)))?;
+    let slots = subject_slots(pool, target, &curriculum.subject_name).await?;
+    build_plan(pool, target, &curriculum, &slots, None, false).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, d).unwrap()
+    }
+
+    fn slot(day: &str, period_index: i32) -> SubjectSlot {
+        SubjectSlot { day: day.to_string(), period_index, faculty_id: "F1".to_string() }
+    }
+
+    #[test]
+    fn lists_one_date_per_period_in_order() {
+        let slots = [slot("Wednesday", 5), slot("Monday", 2), slot("Monday", 1)];
+        let (dates, lost) = period_dates(date(19), date(28), &slots, &HashSet::new(), &HashSet::new());
+        assert_eq!(dates, [date(19), date(19), date(21), date(26), date(26), date(28)]);
+        assert_eq!(lost, 0);
+    }
+
+    #[test]
+    fn counts_a_period_once_when_two_faculty_share_it() {
+        let slots = [slot("Monday", 3), SubjectSlot { day: "Monday".to_string(), period_index: 3, faculty_id: "F2".to_string() }];
+        let (dates, _) = period_dates(date(19), date(19), &slots, &HashSet::new(), &HashSet::new());
+        assert_eq!(dates, [date(19)]);
+    }
+
+    #[test]
+    fn skips_closed_days_and_counts_lost_periods() {
+        let slots = [slot("Monday", 1), slot("Monday", 2), slot("Tuesday", 4)];
+        let closed = HashSet::from([date(20)]);
+        let lost = HashSet::from([(date(19), 2), (date(20), 4)]);
+        let (dates, lost_count) = period_dates(date(19), date(27), &slots, &closed, &lost);
+        assert_eq!(dates, [date(19), date(26), date(26), date(27)]);
+        // A period on a closed day is not taught anyway, so it is not counted as lost.
+        assert_eq!(lost_count, 1);
+    }
+}
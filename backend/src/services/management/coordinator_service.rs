@@ -85,6 +85,7 @@ pub async fn pin_announcement(pool: &PgPool, announcement_id: Uuid, is_pinned: b
 
 pub async fn get_all_branches_syllabus_progress(pool: &PgPool, _course_id: &str) -> Result<Vec<serde_json::Value>, StatusCode> {
     let branches = coordinator_repository::find_all_branches(pool).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let risk = crate::services::syllabus_forecast_service::get_risk_breakdown(pool).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     let mut results = Vec::new();
     for branch_name in branches {
@@ -97,7 +98,8 @@ pub async fn get_all_branches_syllabus_progress(pool: &PgPool, _course_id: &str)
             total_avg += progress as f64;
             year_data.push(serde_json::json!({ "year": year.to_string(), "percentage": progress }));
         }
-        results.push(serde_json::json!({ "branch": branch_name, "overallPercentage": ((total_avg / 3.0) as f64).round() as i32, "years": year_data }));
+        let branch_risk = risk.get(&branch_name).cloned().unwrap_or_default();
+        results.push(serde_json::json!({ "branch": branch_name, "overallPercentage": (total_avg / 3.0).round() as i32, "years": year_data, "risk": branch_risk }));
     }
     Ok(results)
 }
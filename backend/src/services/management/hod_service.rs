@@ -3,12 +3,13 @@ use axum::http::StatusCode;
 use std::collections::HashMap;
 use crate::models::{
     MasterTimetableQuery, MasterTimetableResponse, MasterTimetableRow, FacultyClash, 
-    TimetableEntry, normalize_branch, BranchProgressResponse, YearProgressResponse,
+    TimetableEntry, normalize_branch, get_branch_variations, BranchProgressResponse, YearProgressResponse,
     SectionProgressResponse, SubjectProgressResponse, AddCourseSubjectRequest, 
     SectionQuery, SubjectQuery, BranchProgressQuery, YearSectionsProgressQuery, 
     SectionSubjectsProgressQuery, FacultyAssignmentQuery
 };
 use crate::repositories::management::hod_repository;
+use crate::repositories::room_repository;
 
 pub async fn get_hod_departments(pool: &PgPool) -> Result<Vec<String>, StatusCode> {
     hod_repository::find_hod_departments(pool)
@@ -116,7 +117,11 @@ pub async fn get_master_timetable(pool: &PgPool, params: MasterTimetableQuery) -
         });
     }
 
-    let lab_names = hod_repository::find_lab_names_by_branch(pool, &branch_norm).await.unwrap_or_default();
+    // Registered labs show up even when nothing is booked in them yet.
+    let mut lab_names = hod_repository::find_lab_names_by_branch(pool, &branch_norm).await.unwrap_or_default();
+    lab_names.extend(room_repository::find_lab_codes(pool, &get_branch_variations(&branch_norm)).await.unwrap_or_default());
+    lab_names.sort();
+    lab_names.dedup();
 
     let mut lab_rows = Vec::new();
     for lab_name in lab_names {
@@ -254,7 +259,7 @@ pub async fn get_section_subjects_progress(pool: &PgPool, params: SectionSubject
 
     let mut responses = Vec::new();
     for (sid, sname) in subjects {
-        let (progress, status) = calculate_subject_progress(pool, &sid, &params.section).await;
+        let (progress, status) = calculate_subject_progress(pool, &sid, &branch_norm, course_id, &params.year, &params.section).await;
         responses.push(SubjectProgressResponse {
             subject_id: sid,
             subject_name: sname,
@@ -296,7 +301,7 @@ pub async fn calculate_section_progress(pool: &PgPool, branch: &str, course_id:
     use futures::future::join_all;
     let mut futures = Vec::new();
     for (sid, _) in &subjects {
-        futures.push(calculate_subject_progress(pool, sid, section));
+        futures.push(calculate_subject_progress(pool, sid, branch, course_id, year, section));
     }
 
     let results = join_all(futures).await;
@@ -308,24 +313,12 @@ pub async fn calculate_section_progress(pool: &PgPool, branch: &str, course_id:
     Some((total / subjects.len() as f64).round() as i32)
 }
 
-pub async fn calculate_subject_progress(pool: &PgPool, subject_id: &str, section: &str) -> (i32, String) {
-    let stats = hod_repository::get_subject_progress_stats(pool, subject_id, section).await.ok();
-
-    if let Some((total, completed, scheduled)) = stats {
-        let percentage = if total > 0 { (completed as f64 * 100.0 / total as f64).round() as i32 } else { 0 };
-        
-        let status = if completed < scheduled {
-            "Lagging".to_string()
-        } else if completed > scheduled {
-            "Overfast".to_string()
-        } else {
-            "On Track".to_string()
-        };
-
-        (percentage, status)
-    } else {
-        (0, "On Track".to_string())
-    }
+/// Reads the same progress the students see, from curriculum_progress.
+pub async fn calculate_subject_progress(pool: &PgPool, subject_id: &str, branch: &str, course_id: &str, year: &str, section: &str) -> (i32, String) {
+    // Only names the semester if the curriculum is missing.
+    let semester = match year { "2nd Year" => 3, "3rd Year" => 5, _ => 1 };
+    let progress = crate::services::curriculum_service::section_progress(pool, subject_id, branch, year, section, semester, course_id).await;
+    (progress.percentage(), progress.status().to_string())
 }
 
 pub async fn delete_course_subject(pool: &PgPool, id: uuid::Uuid) -> Result<(), StatusCode> {
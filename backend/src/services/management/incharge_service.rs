@@ -1,29 +1,56 @@
 use sqlx::{PgPool};
 use axum::http::StatusCode;
-use chrono::NaiveDate;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+use std::collections::HashSet;
+use tokio::sync::broadcast;
 use crate::models::{
     InchargeTimetableLookupQuery, UpdateClassStatusRequest,
-    normalize_branch, ClassPeriodStatus, DailyReportQuery, DailyClassActivityReport
+    normalize_branch, get_branch_variations, ClassPeriodStatus, DailyReportQuery, DailyClassActivityReport
 };
 use crate::repositories::management::incharge_repository;
+use crate::repositories::room_repository;
+use crate::models::live_status::{ClassStatusChange, LiveClassEvent, UnattendedPeriod};
+use crate::repositories::timetable_repository;
+use crate::utils::timing_utils;
+use crate::services::{academic_calendar_service, lesson_plan_service, timetable_service};
 
 pub async fn incharge_timetable_lookup(pool: &PgPool, params: InchargeTimetableLookupQuery) -> Result<serde_json::Value, StatusCode> {
     let branch_norm = normalize_branch(&params.branch);
+    let date = params.date.as_deref()
+        .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| StatusCode::BAD_REQUEST))
+        .transpose()?;
+    let version_id = match date {
+        Some(date) => timetable_service::resolve_snapshot_version(pool, &branch_norm, date)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+        None => None,
+    };
     
-    let entry = incharge_repository::find_timetable_entry(pool, &branch_norm, &params.year, &params.section, &params.day, params.period_index)
+    let entry = incharge_repository::find_timetable_entry(pool, &branch_norm, &params.year, &params.section, &params.day, params.period_index, version_id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    // A one-day relocation on the date overrides the timetable's room.
+    let relocated = match date {
+        Some(date) => room_repository::find_relocated_room(pool, date, &get_branch_variations(&branch_norm), &params.year, &params.section, params.period_index)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+        None => None,
+    };
+
     match entry {
-        Some(e) => Ok(serde_json::json!({ "subject": e.subject, "faculty": e.faculty_name.unwrap_or_else(|| e.faculty_id) })),
+        Some(e) => Ok(serde_json::json!({ "subject": e.subject, "faculty": e.faculty_name.unwrap_or(e.faculty_id), "room": relocated.or(e.room) })),
         None => Ok(serde_json::json!({ "subject": "No Class", "faculty": "---" }))
     }
 }
 
-pub async fn update_class_status(pool: &PgPool, payload: UpdateClassStatusRequest) -> Result<(), (StatusCode, String)> {
+pub async fn update_class_status(pool: &PgPool, live: &broadcast::Sender<LiveClassEvent>, payload: UpdateClassStatusRequest) -> Result<(), (StatusCode, String)> {
     let branch_norm = normalize_branch(&payload.branch);
     let status_date = NaiveDate::parse_from_str(&payload.status_date, "%Y-%m-%d").map_err(|_| (StatusCode::BAD_REQUEST, "Invalid date format".to_string()))?;
 
+    let previous = incharge_repository::find_period_status(pool, &branch_norm, &payload.year, &payload.section, &payload.day, payload.period_index, status_date)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
     incharge_repository::upsert_class_status(
         pool, 
         &branch_norm, 
@@ -40,6 +67,39 @@ pub async fn update_class_status(pool: &PgPool, payload: UpdateClassStatusReques
         &payload.updated_by.to_string()
     ).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    let period_lost = |status: &str| status == "not_conducted";
+    let replan = previous.as_deref().is_some_and(period_lost) != period_lost(&payload.status);
+
+    // Nobody may be watching the live board; a send without receivers is fine to drop.
+    let _ = live.send(LiveClassEvent::StatusChanged(ClassStatusChange {
+        branch: branch_norm.clone(),
+        year: payload.year.clone(),
+        section: payload.section.clone(),
+        day: payload.day,
+        period_index: payload.period_index,
+        status_date,
+        status: payload.status,
+        original_subject: payload.original_subject,
+        original_faculty: payload.original_faculty,
+        actual_subject: payload.actual_subject,
+        actual_faculty: payload.actual_faculty,
+        updated_at: Utc::now(),
+    }));
+
+    // A period lost (or restored) moves the section's planned topic dates. Recomputing every plan
+    // of the section is slow, so it runs after the response.
+    if replan {
+        tokio::spawn({
+            let pool = pool.clone();
+            let (year, section) = (payload.year, payload.section);
+            async move {
+                if let Err((_, e)) = lesson_plan_service::replan_section(&pool, &branch_norm, &year, &section).await {
+                    eprintln!("ERROR: Failed to recompute lesson plans for {} {} {}: {}", branch_norm, year, section, e);
+                }
+            }
+        });
+    }
+
     Ok(())
 }
 
@@ -61,7 +121,11 @@ pub async fn get_daily_activity_report(pool: &PgPool, params: DailyReportQuery)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    Ok(DailyClassActivityReport { day, date: params.date, total_classes: total, conducted, substitute, not_conducted })
+    let working_day = academic_calendar_service::is_instructional_day(pool, date, Some(&branch_norm), None)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(DailyClassActivityReport { day, date: params.date, total_classes: total, conducted, substitute, not_conducted, working_day })
 }
 
 pub async fn get_branch_daily_detail_report(pool: &PgPool, params: DailyReportQuery) -> Result<Vec<serde_json::Value>, StatusCode> {
@@ -69,7 +133,90 @@ pub async fn get_branch_daily_detail_report(pool: &PgPool, params: DailyReportQu
     let date = NaiveDate::parse_from_str(&params.date, "%Y-%m-%d").map_err(|_| StatusCode::BAD_REQUEST)?;
     let day = date.format("%A").to_string();
 
-    incharge_repository::find_daily_detail_report(pool, &branch_norm, date, &day)
+    let version_id = timetable_service::resolve_snapshot_version(pool, &branch_norm, date)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    incharge_repository::find_daily_detail_report(pool, &branch_norm, date, &day, version_id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
 }
+
+/// How long a period may run without a status before the live board flags it.
+const UNATTENDED_GRACE_MINUTES: i64 = 10;
+const UNATTENDED_CHECK_SECONDS: u64 = 60;
+
+/// Periods of the branch that started at least the grace period before `at` on its date and have
+/// no status yet. With `running_only`, just the period in progress at `at`.
+async fn find_unattended(pool: &PgPool, branch: &str, at: NaiveDateTime, running_only: bool) -> Result<Vec<UnattendedPeriod>, sqlx::Error> {
+    let timing = timetable_repository::find_department_timing(pool, &get_branch_variations(branch), at.date()).await?;
+    let slots = timing_utils::day_slots_on(timing.as_ref(), at.date().weekday());
+    let due: Vec<_> = slots
+        .iter()
+        .filter(|s| s.period_index.is_some() && s.start + Duration::minutes(UNATTENDED_GRACE_MINUTES) <= at.time())
+        .filter(|s| !running_only || at.time() < s.end)
+        .collect();
+    if due.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let periods: Vec<i32> = due.iter().filter_map(|s| s.period_index).collect();
+    let date = at.date();
+    let day = date.format("%A").to_string();
+    let mut rows = incharge_repository::find_unattended_periods(pool, branch, date, &day, &periods).await?;
+    for r in rows.iter_mut() {
+        if let Some(slot) = due.iter().find(|s| s.period_index == Some(r.period_index)) {
+            r.status_date = date;
+            r.starts_at = slot.start.format("%H:%M").to_string();
+            r.minutes_elapsed = (at.time() - slot.start).num_minutes();
+        }
+    }
+    Ok(rows)
+}
+
+/// Everything unattended so far today, for the live board to start from.
+pub async fn get_unattended_periods(pool: &PgPool, branch: &str) -> Result<Vec<UnattendedPeriod>, StatusCode> {
+    find_unattended(pool, &normalize_branch(branch), timing_utils::campus_now(), false)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to find unattended periods: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Checks every minute for running periods with no status and pushes one alert per class period
+/// to the live board. Skips the work while nobody is subscribed.
+pub async fn run_unattended_alerts(pool: PgPool, live: broadcast::Sender<LiveClassEvent>) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(UNATTENDED_CHECK_SECONDS));
+    let mut alerted: HashSet<(NaiveDate, String, String, String, i32)> = HashSet::new();
+    loop {
+        interval.tick().await;
+        if live.receiver_count() == 0 {
+            continue;
+        }
+        let now = timing_utils::campus_now();
+        alerted.retain(|k| k.0 == now.date());
+
+        let branches = match incharge_repository::find_timetable_branches(&pool).await {
+            Ok(b) => b,
+            Err(e) => {
+                eprintln!("ERROR: Unattended period check failed: {:?}", e);
+                continue;
+            }
+        };
+        for branch in branches {
+            let found = match find_unattended(&pool, &branch, now, true).await {
+                Ok(f) => f,
+                Err(e) => {
+                    eprintln!("ERROR: Unattended period check failed for {}: {:?}", branch, e);
+                    continue;
+                }
+            };
+            for u in found {
+                if alerted.insert((u.status_date, u.branch.clone(), u.year.clone(), u.section.clone(), u.period_index)) {
+                    let _ = live.send(LiveClassEvent::Unattended(u));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use uuid::Uuid;
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::models::material::{
+    ArchiveMaterialRequest, CourseMaterial, CourseMaterialVersion, DownloadMaterialQuery, MaterialAnalyticsQuery, MaterialBody,
+    MaterialContent, MaterialDownloadStats, MaterialPlacement, MaterialQuery, SaveLinkRequest, UpdateVisibilityRequest,
+    UploadMaterialQuery,
+};
+use crate::repositories::{leave_repository, material_repository};
+use crate::services::curriculum_service::{self, DEFAULT_REGULATION};
+use crate::utils::storage_utils::{FileStorage, StorageBackend};
+use crate::utils::user_utils::resolve_user_id;
+
+/// Largest file a material upload accepts.
+pub const MAX_UPLOAD_BYTES: usize = 25 * 1024 * 1024;
+
+const UPLOADER_ROLES: [&str; 5] = ["Faculty", "HOD", "Coordinator", "Principal", "Admin"];
+
+fn internal(e: sqlx::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+fn storage_error(e: std::io::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("Material storage failed: {}", e))
+}
+
+struct Actor {
+    id: Uuid,
+    login: String,
+    role: String,
+    branch: Option<String>,
+    section: Option<String>,
+}
+
+async fn actor(pool: &PgPool, login: &str, role_hint: &str) -> Result<Actor, (StatusCode, String)> {
+    let id = resolve_user_id(login, role_hint, pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", login)))?;
+    let (login, role, _, branch, _, section) = leave_repository::find_user_basics(pool, id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    Ok(Actor { id, login, role, branch, section })
+}
+
+async fn uploader(pool: &PgPool, login: &str) -> Result<Actor, (StatusCode, String)> {
+    let actor = actor(pool, login, "Faculty").await?;
+    if !UPLOADER_ROLES.contains(&actor.role.as_str()) {
+        return Err((StatusCode::FORBIDDEN, "Only staff can share course materials".to_string()));
+    }
+    Ok(actor)
+}
+
+/// The material's uploader may change it, as may the HOD of its branch and Admin or Principal.
+async fn owner(pool: &PgPool, login: &str, material_id: Uuid) -> Result<(Actor, CourseMaterial), (StatusCode, String)> {
+    let actor = uploader(pool, login).await?;
+    let material = material_repository::find_material(pool, material_id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Material not found".to_string()))?;
+    let allowed = actor.login == material.uploaded_by_login
+        || matches!(actor.role.as_str(), "Admin" | "Principal")
+        || (actor.role == "HOD" && actor.branch.as_deref().map(normalize_branch) == Some(normalize_branch(&material.branch)));
+    if !allowed {
+        return Err((StatusCode::FORBIDDEN, "Only the uploader or the HOD can change this material".to_string()));
+    }
+    Ok((actor, material))
+}
+
+fn clean_sections<'a>(sections: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for s in sections.map(str::trim).filter(|s| !s.is_empty()) {
+        if !out.iter().any(|o| o == s) {
+            out.push(s.to_string());
+        }
+    }
+    out
+}
+
+/// Keeps letters, digits, dots, dashes and underscores so the name is safe as a storage key.
+fn clean_file_name(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.').to_string();
+    if cleaned.is_empty() { "file".to_string() } else { cleaned }
+}
+
+/// Checks the unit and topic against the subject's current curriculum and fills in the topic's
+/// unit and stable identity.
+async fn place(pool: &PgPool, mut placement: MaterialPlacement) -> Result<MaterialPlacement, (StatusCode, String)> {
+    if placement.subject_code.trim().is_empty() || placement.title.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "subjectCode and title are required".to_string()));
+    }
+    let curriculum = curriculum_service::find_current_curriculum(pool, &placement.branch, &placement.regulation, &placement.subject_code)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::BAD_REQUEST, format!("No curriculum for {} under {}", placement.subject_code, placement.regulation)))?;
+
+    if let Some(topic_id) = placement.topic_id.as_deref() {
+        let (unit_no, topic) = curriculum
+            .units
+            .iter()
+            .flat_map(|u| u.topics.iter().map(move |t| (u.unit_no, t)))
+            .find(|(_, t)| t.id == topic_id)
+            .ok_or((StatusCode::BAD_REQUEST, format!("{} has no topic {}", placement.subject_code, topic_id)))?;
+        if placement.unit_no.is_some_and(|u| u != unit_no) {
+            return Err((StatusCode::BAD_REQUEST, format!("Topic {} is not in unit {}", topic_id, placement.unit_no.unwrap_or_default())));
+        }
+        placement.unit_no = Some(unit_no);
+        placement.topic_uid = topic.uid;
+    } else if let Some(unit_no) = placement.unit_no {
+        if !curriculum.units.iter().any(|u| u.unit_no == unit_no) {
+            return Err((StatusCode::BAD_REQUEST, format!("{} has no unit {}", placement.subject_code, unit_no)));
+        }
+    }
+    Ok(placement)
+}
+
+fn placement(
+    subject_code: Option<String>,
+    branch: Option<String>,
+    regulation: Option<String>,
+    unit_no: Option<i32>,
+    topic_id: Option<String>,
+    title: Option<String>,
+) -> MaterialPlacement {
+    MaterialPlacement {
+        subject_code: subject_code.unwrap_or_default().trim().to_string(),
+        branch: normalize_branch(branch.as_deref().unwrap_or_default()),
+        regulation: curriculum_service::normalize_regulation(regulation.as_deref().unwrap_or(DEFAULT_REGULATION)),
+        unit_no,
+        topic_id: topic_id.filter(|t| !t.trim().is_empty()),
+        topic_uid: None,
+        title: title.unwrap_or_default().trim().to_string(),
+        description: None,
+        sections: Vec::new(),
+    }
+}
+
+/// Creates the material from `new` or adds a version to `existing`. `content` describes the
+/// version once its number is known; `file` is put in the storage it names before the version is
+/// recorded, and removed again if recording fails.
+async fn save_version(
+    pool: &PgPool,
+    existing: Option<Uuid>,
+    new: Option<(&MaterialPlacement, &str)>,
+    content: impl FnOnce(Uuid, i32) -> MaterialContent,
+    uploaded_by: Uuid,
+    file: Option<&[u8]>,
+) -> Result<Uuid, (StatusCode, String)> {
+    let mut tx = pool.begin().await.map_err(internal)?;
+    let (material_id, version) = match (existing, new) {
+        (Some(id), _) => (id, material_repository::next_version(&mut tx, id).await.map_err(internal)?),
+        (None, Some((placement, kind))) => (material_repository::insert_material(&mut tx, placement, kind, uploaded_by).await.map_err(internal)?, 1),
+        (None, None) => return Err((StatusCode::BAD_REQUEST, "Nothing to save".to_string())),
+    };
+    let content = content(material_id, version);
+
+    let storage = content.storage_backend.as_deref().and_then(StorageBackend::named);
+    if let (Some(storage), Some(key), Some(bytes)) = (&storage, content.storage_key.as_deref(), file) {
+        storage.put(key, bytes).await.map_err(storage_error)?;
+    }
+    let saved = async {
+        material_repository::insert_version(&mut tx, material_id, version, &content, uploaded_by).await?;
+        tx.commit().await
+    }
+    .await;
+    if let Err(e) = saved {
+        if let (Some(storage), Some(key)) = (&storage, content.storage_key.as_deref()) {
+            let _ = storage.delete(key).await;
+        }
+        return Err(internal(e));
+    }
+    Ok(material_id)
+}
+
+async fn load(pool: &PgPool, id: Uuid) -> Result<CourseMaterial, (StatusCode, String)> {
+    material_repository::find_material(pool, id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Material not found".to_string()))
+}
+
+pub async fn get_materials(pool: &PgPool, params: MaterialQuery) -> Result<Vec<CourseMaterial>, (StatusCode, String)> {
+    let variations = get_branch_variations(&normalize_branch(&params.branch));
+    let section = params.section.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let topic_id = params.topic_id.as_deref().filter(|t| !t.trim().is_empty());
+    material_repository::find_materials(pool, &params.subject_code, &variations, section, params.unit_no, topic_id, params.include_archived.unwrap_or(false))
+        .await
+        .map_err(internal)
+}
+
+/// Stores an uploaded file as a new material, or as the next version of `material_id`.
+pub async fn upload_file(pool: &PgPool, params: UploadMaterialQuery, bytes: &[u8]) -> Result<CourseMaterial, (StatusCode, String)> {
+    if bytes.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "The file is empty".to_string()));
+    }
+    let file_name = clean_file_name(&params.file_name);
+    let content_type = mime_guess::from_path(&file_name).first_or_octet_stream().to_string();
+    let storage = StorageBackend::configured();
+    let backend = storage.name().to_string();
+    let size = bytes.len() as i64;
+    let change_note = params.change_note.clone().filter(|n| !n.trim().is_empty());
+
+    let (uploaded_by, new) = match params.material_id {
+        Some(id) => {
+            let (actor, material) = owner(pool, &params.uploaded_by, id).await?;
+            if material.kind != "FILE" || material.archived {
+                return Err((StatusCode::BAD_REQUEST, "Only active file materials take a new file version".to_string()));
+            }
+            (actor.id, None)
+        }
+        None => {
+            let actor = uploader(pool, &params.uploaded_by).await?;
+            let mut p = placement(params.subject_code, params.branch, params.regulation, params.unit_no, params.topic_id, params.title);
+            p.description = params.description.filter(|d| !d.trim().is_empty());
+            p.sections = clean_sections(params.sections.as_deref().unwrap_or_default().split(','));
+            (actor.id, Some(place(pool, p).await?))
+        }
+    };
+
+    let id = save_version(
+        pool,
+        params.material_id,
+        new.as_ref().map(|p| (p, "FILE")),
+        |material_id, version| MaterialContent {
+            storage_backend: Some(backend),
+            storage_key: Some(format!("materials/{}/v{}/{}", material_id, version, file_name)),
+            url: None,
+            file_name: Some(file_name.clone()),
+            content_type: Some(content_type),
+            size_bytes: Some(size),
+            change_note,
+        },
+        uploaded_by,
+        Some(bytes),
+    )
+    .await?;
+    load(pool, id).await
+}
+
+/// Shares a link as a new material, or points `material_id` at a new URL as its next version.
+pub async fn save_link(pool: &PgPool, payload: SaveLinkRequest) -> Result<CourseMaterial, (StatusCode, String)> {
+    let url = payload.url.trim().to_string();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err((StatusCode::BAD_REQUEST, "url must start with http:// or https://".to_string()));
+    }
+    let change_note = payload.change_note.clone().filter(|n| !n.trim().is_empty());
+
+    let (uploaded_by, new) = match payload.material_id {
+        Some(id) => {
+            let (actor, material) = owner(pool, &payload.uploaded_by, id).await?;
+            if material.kind != "LINK" || material.archived {
+                return Err((StatusCode::BAD_REQUEST, "Only active link materials take a new link version".to_string()));
+            }
+            (actor.id, None)
+        }
+        None => {
+            let actor = uploader(pool, &payload.uploaded_by).await?;
+            let mut p = placement(payload.subject_code, payload.branch, payload.regulation, payload.unit_no, payload.topic_id, payload.title);
+            p.description = payload.description.filter(|d| !d.trim().is_empty());
+            p.sections = clean_sections(payload.sections.iter().flatten().map(String::as_str));
+            (actor.id, Some(place(pool, p).await?))
+        }
+    };
+
+    let id = save_version(
+        pool,
+        payload.material_id,
+        new.as_ref().map(|p| (p, "LINK")),
+        |_, _| MaterialContent {
+            storage_backend: None,
+            storage_key: None,
+            url: Some(url),
+            file_name: None,
+            content_type: None,
+            size_bytes: None,
+            change_note,
+        },
+        uploaded_by,
+        None,
+    )
+    .await?;
+    load(pool, id).await
+}
+
+pub async fn update_visibility(pool: &PgPool, payload: UpdateVisibilityRequest) -> Result<CourseMaterial, (StatusCode, String)> {
+    owner(pool, &payload.updated_by, payload.material_id).await?;
+    let sections = clean_sections(payload.sections.iter().map(String::as_str));
+    material_repository::update_sections(pool, payload.material_id, &sections).await.map_err(internal)?;
+    load(pool, payload.material_id).await
+}
+
+pub async fn archive_material(pool: &PgPool, payload: ArchiveMaterialRequest) -> Result<(), (StatusCode, String)> {
+    owner(pool, &payload.archived_by, payload.id).await?;
+    match material_repository::archive_material(pool, payload.id).await.map_err(internal)? {
+        0 => Err((StatusCode::CONFLICT, "Material is already archived".to_string())),
+        _ => Ok(()),
+    }
+}
+
+pub async fn get_versions(pool: &PgPool, material_id: Uuid) -> Result<Vec<CourseMaterialVersion>, (StatusCode, String)> {
+    material_repository::find_versions(pool, material_id).await.map_err(internal)
+}
+
+/// The file or link of a material version. Students only reach active materials shared with their
+/// section, and only their downloads are counted.
+pub async fn download(pool: &PgPool, params: DownloadMaterialQuery) -> Result<MaterialBody, (StatusCode, String)> {
+    let actor = actor(pool, &params.user_id, "Student").await?;
+    let found = material_repository::find_download(pool, params.material_id, params.version)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Material version not found".to_string()))?;
+
+    if actor.role == "Student" {
+        let same_branch = actor.branch.as_deref().map(normalize_branch) == Some(normalize_branch(&found.branch));
+        let visible = found.sections.is_empty() || actor.section.as_deref().is_some_and(|s| found.sections.iter().any(|f| f == s.trim()));
+        if found.archived || !same_branch || !visible {
+            return Err((StatusCode::FORBIDDEN, "This material is not shared with your section".to_string()));
+        }
+    }
+
+    let body = match (found.kind.as_str(), found.url) {
+        ("LINK", Some(url)) => MaterialBody::Link(url),
+        _ => {
+            let storage = found
+                .storage_backend
+                .as_deref()
+                .and_then(StorageBackend::named)
+                .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Material storage backend is not available".to_string()))?;
+            let key = found.storage_key.unwrap_or_default();
+            let bytes = storage.get(&key).await.map_err(storage_error)?;
+            MaterialBody::File {
+                file_name: found.file_name.unwrap_or_else(|| "material".to_string()),
+                content_type: found.content_type.unwrap_or_else(|| "application/octet-stream".to_string()),
+                bytes,
+            }
+        }
+    };
+
+    if actor.role == "Student" {
+        material_repository::insert_download(pool, params.material_id, found.version, actor.id).await.map_err(internal)?;
+    }
+    Ok(body)
+}
+
+pub async fn get_download_analytics(pool: &PgPool, params: MaterialAnalyticsQuery) -> Result<Vec<MaterialDownloadStats>, (StatusCode, String)> {
+    let variations = get_branch_variations(&normalize_branch(&params.branch));
+    let subject_code = params.subject_code.as_deref().filter(|s| !s.trim().is_empty());
+    material_repository::find_download_stats(pool, &variations, subject_code, params.from, params.to)
+        .await
+        .map_err(internal)
+}
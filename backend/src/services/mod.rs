@@ -6,4 +6,22 @@ pub mod issue_service;
 pub mod management;
 pub mod user;
 pub mod finance_service;
+pub mod attendance_service;
 
+pub mod biometric_service;
+pub mod leave_service;
+pub mod analytics_service;
+pub mod timetable_service;
+pub mod substitution_service;
+pub mod calendar_feed_service;
+pub mod academic_calendar_service;
+pub mod room_service;
+pub mod workload_service;
+pub mod slot_template_service;
+pub mod lesson_plan_service;
+pub mod syllabus_forecast_service;
+pub mod outcome_service;
+pub mod regulation_service;
+pub mod topic_feedback_service;
+pub mod material_service;
+pub mod coverage_service;
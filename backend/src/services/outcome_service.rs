@@ -0,0 +1,532 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::models::outcome::{
+    AssessmentComponent, AssessmentMark, AttainmentQuery, CoAttainment, CourseOutcome, CourseOutcomeQuery,
+    DeleteOutcomeRequest, ProgramAttainment, ProgramAttainmentQuery, ProgramOutcome, SaveAssessmentComponentRequest,
+    SaveAssessmentMarksRequest, SaveCourseOutcomeRequest, SaveProgramOutcomeRequest, SubjectAttainment, SubjectPoAttainment,
+};
+use crate::repositories::{leave_repository, outcome_repository, topic_feedback_repository};
+use crate::services::curriculum_service::{self, DEFAULT_REGULATION};
+use crate::utils::export_utils;
+use crate::utils::user_utils::resolve_user_id;
+
+const DEFAULT_THRESHOLD_PERCENT: f64 = 60.0;
+/// Share of a CO's attainment taken from marks; the rest comes from student feedback.
+const DIRECT_WEIGHT: f64 = 0.8;
+/// Percentage of students attaining, from which each attainment level (3, 2, 1) starts.
+const LEVEL_BANDS: [(f64, i32); 3] = [(70.0, 3), (60.0, 2), (50.0, 1)];
+
+fn internal(e: sqlx::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+fn regulation_of(raw: Option<&str>) -> String {
+    curriculum_service::normalize_regulation(raw.filter(|r| !r.trim().is_empty()).unwrap_or(DEFAULT_REGULATION))
+}
+
+/// Checks the user may maintain outcomes: Admin, Principal and Coordinators anywhere, HODs for
+/// their department and, with `allow_faculty`, Faculty. Returns the user's id, login and role.
+async fn authorize(pool: &PgPool, login: &str, branch: &str, allow_faculty: bool) -> Result<(Uuid, String, String), (StatusCode, String)> {
+    let user_id = resolve_user_id(login, "Faculty", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", login)))?;
+    let (login_id, role, _, user_branch, _, _) = leave_repository::find_user_basics(pool, user_id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    let allowed = match role.as_str() {
+        "Admin" | "Principal" | "Coordinator" => true,
+        "HOD" => user_branch.as_deref().map(normalize_branch).as_deref() == Some(branch),
+        "Faculty" => allow_faculty,
+        _ => false,
+    };
+    if !allowed {
+        return Err((StatusCode::FORBIDDEN, "Not allowed to maintain course outcomes".to_string()));
+    }
+    Ok((user_id, login_id, role))
+}
+
+/// The normalized branch of the subject under the regulation.
+async fn subject_branch(pool: &PgPool, subject_code: &str, regulation: &str) -> Result<String, (StatusCode, String)> {
+    outcome_repository::find_subject_branch(pool, subject_code, regulation)
+        .await
+        .map_err(internal)?
+        .map(|b| normalize_branch(&b))
+        .ok_or((StatusCode::NOT_FOUND, format!("Unknown subject {}", subject_code)))
+}
+
+/// "PO" or "PSO" for a well formed outcome code such as PO3 or PSO2.
+fn outcome_kind(code: &str) -> Option<&'static str> {
+    let numbered = |prefix: &str| code.strip_prefix(prefix).is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    if numbered("PSO") {
+        Some("PSO")
+    } else if numbered("PO") {
+        Some("PO")
+    } else {
+        None
+    }
+}
+
+fn attainment_level(percent: f64) -> i32 {
+    LEVEL_BANDS.iter().find(|(from, _)| percent >= *from).map(|(_, level)| *level).unwrap_or(0)
+}
+
+/// A CO's attainment from its direct and indirect levels, either alone when the other has no data.
+fn weighted_attainment(direct_level: Option<i32>, indirect_level: Option<i32>) -> Option<f64> {
+    match (direct_level, indirect_level) {
+        (Some(d), Some(i)) => Some(DIRECT_WEIGHT * d as f64 + (1.0 - DIRECT_WEIGHT) * i as f64),
+        (Some(d), None) => Some(d as f64),
+        (None, Some(i)) => Some(i as f64),
+        (None, None) => None,
+    }
+}
+
+pub async fn get_program_outcomes(pool: &PgPool, branch: &str) -> Result<Vec<ProgramOutcome>, (StatusCode, String)> {
+    outcome_repository::find_program_outcomes(pool, &normalize_branch(branch)).await.map_err(internal)
+}
+
+pub async fn save_program_outcome(pool: &PgPool, payload: SaveProgramOutcomeRequest) -> Result<ProgramOutcome, (StatusCode, String)> {
+    let branch = normalize_branch(&payload.branch);
+    authorize(pool, &payload.updated_by, &branch, false).await?;
+    let code = payload.code.trim().to_uppercase();
+    let kind = outcome_kind(&code).ok_or((StatusCode::BAD_REQUEST, format!("Invalid outcome code {}: use PO1.. or PSO1..", payload.code)))?;
+    if payload.description.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Description is required".to_string()));
+    }
+    outcome_repository::upsert_program_outcome(pool, &branch, &code, kind, payload.description.trim()).await.map_err(internal)
+}
+
+pub async fn delete_program_outcome(pool: &PgPool, payload: DeleteOutcomeRequest) -> Result<(), (StatusCode, String)> {
+    let branch = outcome_repository::find_program_outcome_branch(pool, payload.id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Program outcome not found".to_string()))?;
+    authorize(pool, &payload.deleted_by, &normalize_branch(&branch), false).await?;
+    match outcome_repository::delete_program_outcome(pool, payload.id).await.map_err(internal)? {
+        0 => Err((StatusCode::NOT_FOUND, "Program outcome not found".to_string())),
+        _ => Ok(()),
+    }
+}
+
+pub async fn get_course_outcomes(pool: &PgPool, params: CourseOutcomeQuery) -> Result<Vec<CourseOutcome>, (StatusCode, String)> {
+    let regulation = regulation_of(params.regulation.as_deref());
+    outcome_repository::find_course_outcomes(pool, &params.subject_code, &regulation).await.map_err(internal)
+}
+
+/// Saves a CO together with its row of the articulation matrix and the topics it is taught
+/// through; the mappings given replace the previous ones.
+pub async fn save_course_outcome(pool: &PgPool, payload: SaveCourseOutcomeRequest) -> Result<CourseOutcome, (StatusCode, String)> {
+    let regulation = regulation_of(payload.regulation.as_deref());
+    authorize(pool, &payload.updated_by, &subject_branch(pool, &payload.subject_code, &regulation).await?, false).await?;
+    let code = payload.code.trim().to_uppercase();
+    if code.strip_prefix("CO").is_none_or(|n| n.is_empty() || !n.chars().all(|c| c.is_ascii_digit())) {
+        return Err((StatusCode::BAD_REQUEST, format!("Invalid course outcome code {}: use CO1..", payload.code)));
+    }
+    if payload.description.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Description is required".to_string()));
+    }
+    if payload.po_levels.iter().any(|p| !(1..=3).contains(&p.level)) {
+        return Err((StatusCode::BAD_REQUEST, "Mapping levels must be 1 (low), 2 (medium) or 3 (high)".to_string()));
+    }
+    let mut po_ids: Vec<Uuid> = payload.po_levels.iter().map(|p| p.program_outcome_id).collect();
+    po_ids.sort_unstable();
+    po_ids.dedup();
+    if po_ids.len() != payload.po_levels.len() {
+        return Err((StatusCode::BAD_REQUEST, "Each program outcome may be mapped only once".to_string()));
+    }
+    if outcome_repository::count_program_outcomes(pool, &po_ids).await.map_err(internal)? != po_ids.len() as i64 {
+        return Err((StatusCode::BAD_REQUEST, "Unknown program outcome in mapping".to_string()));
+    }
+    let mut topic_uids = payload.topic_uids.clone();
+    topic_uids.sort_unstable();
+    topic_uids.dedup();
+    if outcome_repository::count_subject_topics(pool, &payload.subject_code, &topic_uids).await.map_err(internal)? != topic_uids.len() as i64 {
+        return Err((StatusCode::BAD_REQUEST, format!("Some topics are not topics of {}", payload.subject_code)));
+    }
+
+    let mut tx = pool.begin().await.map_err(internal)?;
+    let id = outcome_repository::upsert_course_outcome(&mut tx, &payload.subject_code, &regulation, &code, payload.description.trim())
+        .await
+        .map_err(internal)?;
+    outcome_repository::replace_course_outcome_mappings(&mut tx, id, &payload.po_levels, &topic_uids).await.map_err(internal)?;
+    tx.commit().await.map_err(internal)?;
+
+    outcome_repository::find_course_outcomes(pool, &payload.subject_code, &regulation)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .find(|co| co.id == id)
+        .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Saved course outcome not found".to_string()))
+}
+
+pub async fn delete_course_outcome(pool: &PgPool, payload: DeleteOutcomeRequest) -> Result<(), (StatusCode, String)> {
+    let (subject_code, regulation) = outcome_repository::find_course_outcome_subject(pool, payload.id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Course outcome not found".to_string()))?;
+    authorize(pool, &payload.deleted_by, &subject_branch(pool, &subject_code, &regulation).await?, false).await?;
+    match outcome_repository::delete_course_outcome(pool, payload.id).await.map_err(internal)? {
+        0 => Err((StatusCode::NOT_FOUND, "Course outcome not found".to_string())),
+        _ => Ok(()),
+    }
+}
+
+pub async fn get_components(pool: &PgPool, params: CourseOutcomeQuery) -> Result<Vec<AssessmentComponent>, (StatusCode, String)> {
+    let regulation = regulation_of(params.regulation.as_deref());
+    outcome_repository::find_components(pool, &params.subject_code, &regulation).await.map_err(internal)
+}
+
+pub async fn save_component(pool: &PgPool, payload: SaveAssessmentComponentRequest) -> Result<AssessmentComponent, (StatusCode, String)> {
+    let regulation = regulation_of(payload.regulation.as_deref());
+    authorize(pool, &payload.updated_by, &subject_branch(pool, &payload.subject_code, &regulation).await?, false).await?;
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Component name is required".to_string()));
+    }
+    if payload.max_marks <= 0.0 {
+        return Err((StatusCode::BAD_REQUEST, "Maximum marks must be positive".to_string()));
+    }
+    let threshold = payload.threshold_percent.unwrap_or(DEFAULT_THRESHOLD_PERCENT);
+    if threshold <= 0.0 || threshold > 100.0 {
+        return Err((StatusCode::BAD_REQUEST, "Threshold must be a percentage above 0 and up to 100".to_string()));
+    }
+    let mut codes: Vec<String> = payload.course_outcomes.iter().map(|c| c.trim().to_uppercase()).collect();
+    codes.sort_unstable();
+    codes.dedup();
+    if codes.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Map the component to at least one course outcome".to_string()));
+    }
+
+    let mut tx = pool.begin().await.map_err(internal)?;
+    let (id, linked) = outcome_repository::upsert_component(&mut tx, &payload.subject_code, &regulation, name, payload.max_marks, threshold, &codes)
+        .await
+        .map_err(internal)?;
+    if linked != codes.len() as u64 {
+        return Err((StatusCode::BAD_REQUEST, format!("Not all of {} are course outcomes of {}", codes.join(", "), payload.subject_code)));
+    }
+    tx.commit().await.map_err(internal)?;
+
+    outcome_repository::find_component(pool, id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Saved component not found".to_string()))
+}
+
+pub async fn delete_component(pool: &PgPool, payload: DeleteOutcomeRequest) -> Result<(), (StatusCode, String)> {
+    let component = outcome_repository::find_component(pool, payload.id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Assessment component not found".to_string()))?;
+    authorize(pool, &payload.deleted_by, &subject_branch(pool, &component.subject_code, &component.regulation).await?, false).await?;
+    match outcome_repository::delete_component(pool, payload.id).await.map_err(internal)? {
+        0 => Err((StatusCode::NOT_FOUND, "Assessment component not found".to_string())),
+        _ => Ok(()),
+    }
+}
+
+pub async fn get_marks(pool: &PgPool, component_id: Uuid) -> Result<Vec<AssessmentMark>, (StatusCode, String)> {
+    outcome_repository::find_marks(pool, component_id).await.map_err(internal)
+}
+
+/// Records the marks of a component; all entries are saved or none. Returns the number saved.
+/// Faculty may enter marks only for students of sections they teach the subject to.
+pub async fn save_marks(pool: &PgPool, payload: SaveAssessmentMarksRequest) -> Result<usize, (StatusCode, String)> {
+    let component = outcome_repository::find_component(pool, payload.component_id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Assessment component not found".to_string()))?;
+    let branch = subject_branch(pool, &component.subject_code, &component.regulation).await?;
+    let (entered_by, login_id, role) = authorize(pool, &payload.entered_by, &branch, true).await?;
+
+    let mut entries = Vec::with_capacity(payload.marks.len());
+    let mut sections: HashSet<(String, String, String)> = HashSet::new();
+    for entry in &payload.marks {
+        if entry.marks < 0.0 || entry.marks > component.max_marks {
+            return Err((StatusCode::BAD_REQUEST, format!("Marks for {} must be between 0 and {}", entry.student_id, component.max_marks)));
+        }
+        let student_id = resolve_user_id(&entry.student_id, "Student", pool)
+            .await
+            .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid student: {}", entry.student_id)))?;
+        if role == "Faculty" {
+            let (_, _, _, student_branch, year, section) = leave_repository::find_user_basics(pool, student_id)
+                .await
+                .map_err(internal)?
+                .ok_or((StatusCode::BAD_REQUEST, format!("Invalid student: {}", entry.student_id)))?;
+            match (student_branch, year, section) {
+                (Some(b), Some(y), Some(s)) => { sections.insert((normalize_branch(&b), y, s)); }
+                _ => return Err((StatusCode::BAD_REQUEST, format!("{} is not assigned to a section", entry.student_id))),
+            }
+        }
+        entries.push((student_id, entry.marks));
+    }
+    for (student_branch, year, section) in &sections {
+        let teaches = topic_feedback_repository::teaches_section(pool, &login_id, &component.subject_code, &get_branch_variations(student_branch), year, section)
+            .await
+            .map_err(internal)?;
+        if !teaches {
+            return Err((StatusCode::FORBIDDEN, format!("You do not teach {} to {} {}", component.subject_code, year, section)));
+        }
+    }
+
+    let mut tx = pool.begin().await.map_err(internal)?;
+    for (student_id, marks) in &entries {
+        outcome_repository::upsert_mark(&mut tx, component.id, *student_id, *marks, entered_by).await.map_err(internal)?;
+    }
+    tx.commit().await.map_err(internal)?;
+    Ok(entries.len())
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// (program outcome id, level) pairs of a CO's `po_levels`.
+fn mapped_levels(co: &CourseOutcome) -> Vec<(Uuid, i16)> {
+    co.po_levels
+        .as_array()
+        .map(|levels| levels.iter().filter_map(|l| {
+            let id = l.get("programOutcomeId")?.as_str()?.parse().ok()?;
+            let level = l.get("level")?.as_i64()?;
+            Some((id, level as i16))
+        }).collect())
+        .unwrap_or_default()
+}
+
+/// The articulation matrix of a subject against the branch's POs and PSOs, with each CO's
+/// attainment. Direct attainment is the average, over the CO's assessment components, of the
+/// share of students reaching the component threshold; indirect attainment is the share of
+/// topic feedback on the CO's topics reporting the topic understood. Both are banded into levels
+/// 0-3 and weighted 80/20 (either alone when the other has no data). A PO's attainment is the
+/// mapping-level weighted average of the attainment of the COs mapped to it.
+async fn compute_subject_attainment(
+    pool: &PgPool,
+    subject_code: &str,
+    regulation: &str,
+    branch: &str,
+    section: Option<&str>,
+) -> Result<SubjectAttainment, (StatusCode, String)> {
+    let outcomes = outcome_repository::find_program_outcomes(pool, branch).await.map_err(internal)?;
+    let course_outcomes = outcome_repository::find_course_outcomes(pool, subject_code, regulation).await.map_err(internal)?;
+    let components = outcome_repository::find_components(pool, subject_code, regulation).await.map_err(internal)?;
+    let variations = get_branch_variations(branch);
+    let results = outcome_repository::find_component_results(pool, subject_code, regulation, &variations, section).await.map_err(internal)?;
+    let feedback = outcome_repository::find_outcome_feedback(pool, subject_code, regulation, &variations, section).await.map_err(internal)?;
+
+    let component_percent: HashMap<Uuid, f64> = results
+        .iter()
+        .filter(|r| r.students > 0)
+        .map(|r| (r.component_id, r.attained as f64 * 100.0 / r.students as f64))
+        .collect();
+    let columns: HashMap<Uuid, usize> = outcomes.iter().enumerate().map(|(i, po)| (po.id, i)).collect();
+
+    let mut rows = Vec::with_capacity(course_outcomes.len());
+    for co in &course_outcomes {
+        let direct: Vec<f64> = components
+            .iter()
+            .filter(|c| c.course_outcomes.contains(&co.code))
+            .filter_map(|c| component_percent.get(&c.id).copied())
+            .collect();
+        let direct_percent = mean(&direct);
+        let indirect_percent = feedback
+            .iter()
+            .find(|f| f.course_outcome_id == co.id && f.responses > 0)
+            .map(|f| f.understood as f64 * 100.0 / f.responses as f64);
+        let direct_level = direct_percent.map(attainment_level);
+        let indirect_level = indirect_percent.map(attainment_level);
+        let attainment = weighted_attainment(direct_level, indirect_level);
+        let mut po_levels = vec![None; outcomes.len()];
+        for (po_id, level) in mapped_levels(co) {
+            if let Some(i) = columns.get(&po_id) {
+                po_levels[*i] = Some(level);
+            }
+        }
+        rows.push(CoAttainment {
+            code: co.code.clone(),
+            description: co.description.clone(),
+            direct_percent,
+            direct_level,
+            indirect_percent,
+            indirect_level,
+            attainment,
+            po_levels,
+        });
+    }
+
+    let mapping_average = (0..outcomes.len())
+        .map(|i| mean(&rows.iter().filter_map(|r| r.po_levels[i].map(f64::from)).collect::<Vec<_>>()))
+        .collect();
+    let po_attainment = (0..outcomes.len())
+        .map(|i| {
+            let (weighted, levels) = rows.iter().fold((0.0, 0.0), |(w, l), r| match (r.po_levels[i], r.attainment) {
+                (Some(level), Some(a)) => (w + level as f64 * a, l + level as f64),
+                _ => (w, l),
+            });
+            if levels > 0.0 { Some(weighted / levels) } else { None }
+        })
+        .collect();
+
+    Ok(SubjectAttainment {
+        subject_code: subject_code.to_string(),
+        regulation: regulation.to_string(),
+        branch: branch.to_string(),
+        section: section.map(str::to_string),
+        outcomes: outcomes.into_iter().map(|po| po.code).collect(),
+        course_outcomes: rows,
+        mapping_average,
+        po_attainment,
+    })
+}
+
+pub async fn get_subject_attainment(pool: &PgPool, params: &AttainmentQuery) -> Result<SubjectAttainment, (StatusCode, String)> {
+    let regulation = regulation_of(params.regulation.as_deref());
+    let section = params.section.as_deref().filter(|s| !s.trim().is_empty());
+    compute_subject_attainment(pool, &params.subject_code, &regulation, &normalize_branch(&params.branch), section).await
+}
+
+/// PO attainment of every subject of the branch's programme that declares course outcomes, and
+/// the programme's average per PO.
+pub async fn get_program_attainment(pool: &PgPool, params: &ProgramAttainmentQuery) -> Result<ProgramAttainment, (StatusCode, String)> {
+    let regulation = regulation_of(params.regulation.as_deref());
+    let branch = normalize_branch(&params.branch);
+    let outcomes: Vec<String> = outcome_repository::find_program_outcomes(pool, &branch)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(|po| po.code)
+        .collect();
+    let subject_codes = outcome_repository::find_program_subjects(pool, &regulation, curriculum_service::map_to_short_branch(&branch))
+        .await
+        .map_err(internal)?;
+
+    let mut subjects = Vec::with_capacity(subject_codes.len());
+    for subject_code in subject_codes {
+        let attainment = compute_subject_attainment(pool, &subject_code, &regulation, &branch, None).await?;
+        subjects.push(SubjectPoAttainment { subject_code, po_attainment: attainment.po_attainment });
+    }
+    let average = (0..outcomes.len())
+        .map(|i| mean(&subjects.iter().filter_map(|s| s.po_attainment[i]).collect::<Vec<_>>()))
+        .collect();
+
+    Ok(ProgramAttainment { branch, regulation, outcomes, subjects, average })
+}
+
+fn fmt_value(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.2}", v)).unwrap_or_else(|| "-".to_string())
+}
+
+fn subject_export(report: &SubjectAttainment) -> (Vec<&str>, Vec<Vec<String>>) {
+    let mut headers = vec!["CO", "Direct %", "Direct level", "Indirect %", "Indirect level", "Attainment"];
+    headers.extend(report.outcomes.iter().map(String::as_str));
+    let mut rows: Vec<Vec<String>> = report.course_outcomes.iter().map(|co| {
+        let mut row = vec![
+            co.code.clone(),
+            co.direct_percent.map(|p| format!("{:.1}", p)).unwrap_or_else(|| "-".to_string()),
+            co.direct_level.map(|l| l.to_string()).unwrap_or_else(|| "-".to_string()),
+            co.indirect_percent.map(|p| format!("{:.1}", p)).unwrap_or_else(|| "-".to_string()),
+            co.indirect_level.map(|l| l.to_string()).unwrap_or_else(|| "-".to_string()),
+            fmt_value(co.attainment),
+        ];
+        row.extend(co.po_levels.iter().map(|l| l.map(|l| l.to_string()).unwrap_or_else(|| "-".to_string())));
+        row
+    }).collect();
+    let mut average = vec!["Average".to_string(), String::new(), String::new(), String::new(), String::new(), String::new()];
+    average.extend(report.mapping_average.iter().map(|v| fmt_value(*v)));
+    rows.push(average);
+    let mut attained = vec!["Attainment".to_string(), String::new(), String::new(), String::new(), String::new(), String::new()];
+    attained.extend(report.po_attainment.iter().map(|v| fmt_value(*v)));
+    rows.push(attained);
+    (headers, rows)
+}
+
+fn program_export(report: &ProgramAttainment) -> (Vec<&str>, Vec<Vec<String>>) {
+    let mut headers = vec!["Subject"];
+    headers.extend(report.outcomes.iter().map(String::as_str));
+    let mut rows: Vec<Vec<String>> = report.subjects.iter().map(|s| {
+        let mut row = vec![s.subject_code.clone()];
+        row.extend(s.po_attainment.iter().map(|v| fmt_value(*v)));
+        row
+    }).collect();
+    let mut average = vec!["Programme".to_string()];
+    average.extend(report.average.iter().map(|v| fmt_value(*v)));
+    rows.push(average);
+    (headers, rows)
+}
+
+fn file_part(value: &str) -> String {
+    value.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
+}
+
+pub fn subject_filename(report: &SubjectAttainment, extension: &str) -> String {
+    format!("co_po_{}_{}_{}.{}", file_part(&report.subject_code), report.regulation, file_part(&report.branch), extension)
+}
+
+pub fn program_filename(report: &ProgramAttainment, extension: &str) -> String {
+    format!("po_attainment_{}_{}.{}", file_part(&report.branch), report.regulation, extension)
+}
+
+pub fn subject_csv(report: &SubjectAttainment) -> String {
+    let (headers, rows) = subject_export(report);
+    export_utils::to_csv(&headers, &rows)
+}
+
+pub fn subject_pdf(report: &SubjectAttainment) -> Vec<u8> {
+    let title = format!(
+        "CO-PO Articulation Matrix - {} ({}) - {}{}",
+        report.subject_code, report.regulation, report.branch,
+        report.section.as_deref().map(|s| format!(" Section {}", s)).unwrap_or_default()
+    );
+    let (headers, rows) = subject_export(report);
+    let mut lines = vec![
+        format!("Levels: 3 from {}%, 2 from {}%, 1 from {}% of students attaining", LEVEL_BANDS[0].0, LEVEL_BANDS[1].0, LEVEL_BANDS[2].0),
+        format!("Attainment = {:.0}% direct (marks) + {:.0}% indirect (topic feedback)", DIRECT_WEIGHT * 100.0, (1.0 - DIRECT_WEIGHT) * 100.0),
+        String::new(),
+    ];
+    lines.extend(export_utils::text_table(&headers, &rows, 12));
+    lines.push(String::new());
+    lines.extend(report.course_outcomes.iter().map(|co| format!("{}: {}", co.code, co.description)));
+    export_utils::text_pdf(&title, &lines)
+}
+
+pub fn program_csv(report: &ProgramAttainment) -> String {
+    let (headers, rows) = program_export(report);
+    export_utils::to_csv(&headers, &rows)
+}
+
+pub fn program_pdf(report: &ProgramAttainment) -> Vec<u8> {
+    let title = format!("PO Attainment - {} ({})", report.branch, report.regulation);
+    let (headers, rows) = program_export(report);
+    export_utils::text_pdf(&title, &export_utils::text_table(&headers, &rows, 12))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bands_percentages_into_levels() {
+        assert_eq!(attainment_level(100.0), 3);
+        assert_eq!(attainment_level(70.0), 3);
+        assert_eq!(attainment_level(69.9), 2);
+        assert_eq!(attainment_level(60.0), 2);
+        assert_eq!(attainment_level(59.9), 1);
+        assert_eq!(attainment_level(50.0), 1);
+        assert_eq!(attainment_level(49.9), 0);
+        assert_eq!(attainment_level(0.0), 0);
+    }
+
+    #[test]
+    fn weights_direct_and_indirect_attainment() {
+        assert!(weighted_attainment(Some(3), Some(1)).is_some_and(|a| (a - 2.6).abs() < 1e-9));
+        assert_eq!(weighted_attainment(Some(2), Some(2)), Some(2.0));
+        assert_eq!(weighted_attainment(Some(1), None), Some(1.0));
+        assert_eq!(weighted_attainment(None, Some(3)), Some(3.0));
+        assert_eq!(weighted_attainment(None, None), None);
+    }
+}
@@ -0,0 +1,146 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use uuid::Uuid;
+use crate::models::normalize_branch;
+use crate::models::regulation::{
+    AssignRegulationRequest, DeleteEquivalenceRequest, EquivalenceQuery, SaveEquivalenceRequest, StudentRegulation,
+    SubjectEquivalence,
+};
+use crate::repositories::{leave_repository, regulation_repository};
+use crate::repositories::user::student_repository;
+use crate::utils::user_utils::resolve_user_id;
+
+const REASONS: [&str; 3] = ["DETAINED", "READMITTED", "OTHER"];
+
+fn internal(e: sqlx::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// The regulation a batch studies under: first years the new one, seniors the old.
+pub fn batch_regulation(year: &str) -> &'static str {
+    if year == "1st Year" { "C-26" } else { "C-23" }
+}
+
+/// The student's regulation: their assigned one if they switched, else their batch's.
+pub async fn resolve_regulation(pool: &PgPool, student_id: Uuid, year: &str) -> Result<String, sqlx::Error> {
+    Ok(regulation_repository::find_student_regulation(pool, student_id)
+        .await?
+        .map(|(course_id, _, _)| course_id)
+        .unwrap_or_else(|| batch_regulation(year).to_string()))
+}
+
+/// Admin, Principal and Coordinators may maintain regulations; HODs too, for their department's
+/// students when `branch` is given.
+async fn authorize(pool: &PgPool, login: &str, branch: Option<&str>) -> Result<Uuid, (StatusCode, String)> {
+    let user_id = resolve_user_id(login, "HOD", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", login)))?;
+    let (_, role, _, user_branch, _, _) = leave_repository::find_user_basics(pool, user_id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    let allowed = match role.as_str() {
+        "Admin" | "Principal" | "Coordinator" => true,
+        "HOD" => branch.is_none_or(|b| user_branch.as_deref().map(normalize_branch) == Some(normalize_branch(b))),
+        _ => false,
+    };
+    if !allowed {
+        return Err((StatusCode::FORBIDDEN, "Not allowed to manage regulations".to_string()));
+    }
+    Ok(user_id)
+}
+
+async fn require_course(pool: &PgPool, course_id: &str) -> Result<(), (StatusCode, String)> {
+    if regulation_repository::course_exists(pool, course_id).await.map_err(internal)? {
+        Ok(())
+    } else {
+        Err((StatusCode::BAD_REQUEST, format!("Unknown regulation {}", course_id)))
+    }
+}
+
+pub async fn get_equivalences(pool: &PgPool, params: EquivalenceQuery) -> Result<Vec<SubjectEquivalence>, (StatusCode, String)> {
+    regulation_repository::find_equivalences(pool, params.course_id.as_deref(), params.subject_code.as_deref())
+        .await
+        .map_err(internal)
+}
+
+pub async fn save_equivalence(pool: &PgPool, payload: SaveEquivalenceRequest) -> Result<Uuid, (StatusCode, String)> {
+    let created_by = authorize(pool, &payload.created_by, None).await?;
+    if payload.from_course_id == payload.to_course_id {
+        return Err((StatusCode::BAD_REQUEST, "Equivalent subjects must belong to different regulations".to_string()));
+    }
+    require_course(pool, &payload.from_course_id).await?;
+    require_course(pool, &payload.to_course_id).await?;
+    for (code, course_id) in [(&payload.from_subject_code, &payload.from_course_id), (&payload.to_subject_code, &payload.to_course_id)] {
+        if !regulation_repository::subject_in_course(pool, code, course_id).await.map_err(internal)? {
+            return Err((StatusCode::BAD_REQUEST, format!("{} is not a subject of {}", code, course_id)));
+        }
+    }
+    regulation_repository::insert_equivalence(pool, &payload, created_by).await.map_err(internal)
+}
+
+pub async fn delete_equivalence(pool: &PgPool, payload: DeleteEquivalenceRequest) -> Result<(), (StatusCode, String)> {
+    authorize(pool, &payload.deleted_by, None).await?;
+    match regulation_repository::delete_equivalence(pool, payload.id).await.map_err(internal)? {
+        0 => Err((StatusCode::NOT_FOUND, "Equivalence not found".to_string())),
+        _ => Ok(()),
+    }
+}
+
+pub async fn get_student_regulation(pool: &PgPool, student: &str) -> Result<StudentRegulation, (StatusCode, String)> {
+    let student_id = resolve_user_id(student, "Student", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid student: {}", student)))?;
+    let (_, year, _, _, login_id) = student_repository::get_student_basics(pool, student_id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Student not found".to_string()))?;
+    let assigned = regulation_repository::find_student_regulation(pool, student_id).await.map_err(internal)?;
+    Ok(match assigned {
+        Some((course_id, previous_course_id, reason)) => StudentRegulation {
+            student_id, login_id, course_id, previous_course_id, reason: Some(reason), assigned: true,
+        },
+        None => StudentRegulation {
+            student_id,
+            login_id,
+            course_id: batch_regulation(&year.unwrap_or_default()).to_string(),
+            previous_course_id: None,
+            reason: None,
+            assigned: false,
+        },
+    })
+}
+
+/// Moves a detained or readmitted student to another regulation. Their marks stay under the
+/// previous regulation and count on the transcript through subject equivalences.
+pub async fn assign_regulation(pool: &PgPool, payload: AssignRegulationRequest) -> Result<StudentRegulation, (StatusCode, String)> {
+    let student_id = resolve_user_id(&payload.student_id, "Student", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid student: {}", payload.student_id)))?;
+    let (branch, year, _, _, _) = student_repository::get_student_basics(pool, student_id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Student not found".to_string()))?;
+    let assigned_by = authorize(pool, &payload.assigned_by, Some(branch.as_deref().unwrap_or_default())).await?;
+
+    let reason = payload.reason.trim().to_uppercase();
+    if !REASONS.contains(&reason.as_str()) {
+        return Err((StatusCode::BAD_REQUEST, "reason must be DETAINED, READMITTED or OTHER".to_string()));
+    }
+    require_course(pool, &payload.course_id).await?;
+    let previous = match payload.previous_course_id.as_deref().filter(|c| !c.trim().is_empty()) {
+        Some(c) => c.to_string(),
+        None => resolve_regulation(pool, student_id, &year.unwrap_or_default()).await.map_err(internal)?,
+    };
+    let previous = if previous == payload.course_id {
+        None
+    } else {
+        require_course(pool, &previous).await?;
+        Some(previous)
+    };
+
+    regulation_repository::upsert_student_regulation(pool, student_id, &payload.course_id, previous.as_deref(), &reason, assigned_by)
+        .await
+        .map_err(internal)?;
+    get_student_regulation(pool, &student_id.to_string()).await
+}
@@ -0,0 +1,207 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::models::room::{FreeRoomQuery, RelocateClassRequest, RelocationQuery, Room, RoomListQuery, RoomRelocation, RoomUtilization, RoomUtilizationQuery, SaveRoomRequest};
+use crate::repositories::{leave_repository, room_repository, timetable_repository};
+use crate::repositories::auth::insert_notification;
+use crate::services::timetable_service::DEFAULT_WORKING_DAYS;
+use crate::utils::timing_utils;
+use crate::utils::user_utils::resolve_user_id;
+
+const ROOM_TYPES: [&str; 3] = ["CLASSROOM", "LAB", "SEMINAR_HALL"];
+const ROOM_STATUSES: [&str; 3] = ["ACTIVE", "MAINTENANCE", "RETIRED"];
+
+async fn require_role(pool: &PgPool, login: &str, roles: &[&str]) -> Result<(Uuid, String), (StatusCode, String)> {
+    let user_id = resolve_user_id(login, "Admin", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", login)))?;
+    let (login_id, role, _, _, _, _) = leave_repository::find_user_basics(pool, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    if !roles.contains(&role.as_str()) {
+        return Err((StatusCode::FORBIDDEN, format!("Only {} can do this", roles.join(", "))));
+    }
+    Ok((user_id, login_id))
+}
+
+fn upper_in(value: Option<String>, allowed: &[&str], field: &str) -> Result<Option<String>, (StatusCode, String)> {
+    match value.map(|v| v.trim().to_uppercase()) {
+        Some(v) if !allowed.contains(&v.as_str()) => Err((StatusCode::BAD_REQUEST, format!("{} must be one of {}", field, allowed.join(", ")))),
+        other => Ok(other),
+    }
+}
+
+pub async fn save_room(pool: &PgPool, mut payload: SaveRoomRequest) -> Result<Room, (StatusCode, String)> {
+    require_role(pool, &payload.updated_by, &["Admin", "Principal", "HOD"]).await?;
+    payload.code = payload.code.trim().to_string();
+    if payload.code.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "code is required".to_string()));
+    }
+    if payload.capacity.map(|c| c <= 0).unwrap_or(false) {
+        return Err((StatusCode::BAD_REQUEST, "capacity must be positive".to_string()));
+    }
+    payload.room_type = upper_in(payload.room_type, &ROOM_TYPES, "roomType")?;
+    payload.status = upper_in(payload.status, &ROOM_STATUSES, "status")?;
+    payload.department = payload.department.as_deref().filter(|d| !d.trim().is_empty()).map(normalize_branch);
+    room_repository::save_room(pool, &payload)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+pub async fn get_rooms(pool: &PgPool, mut params: RoomListQuery) -> Result<Vec<Room>, StatusCode> {
+    params.department = params.department.as_deref().map(normalize_branch);
+    params.room_type = params.room_type.map(|t| t.to_uppercase());
+    params.status = params.status.map(|s| s.to_uppercase());
+    room_repository::find_rooms(pool, &params)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch rooms: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Active rooms nobody holds at the slot, the department's own rooms first.
+pub async fn get_free_rooms(pool: &PgPool, mut params: FreeRoomQuery) -> Result<Vec<Room>, StatusCode> {
+    params.room_type = params.room_type.map(|t| t.to_uppercase());
+    let department = params.department.as_deref().map(normalize_branch);
+    let equipment: Vec<String> = params.equipment.as_deref()
+        .map(|e| e.split(',').map(|i| i.trim().to_string()).filter(|i| !i.is_empty()).collect())
+        .unwrap_or_default();
+    room_repository::find_free_rooms(pool, &params, department.as_deref(), &equipment)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to search free rooms: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Moves a class period into another room, for one date or for good. The target must be active,
+/// free at that slot and large enough for the section.
+pub async fn relocate_class(pool: &PgPool, payload: RelocateClassRequest) -> Result<Option<Uuid>, (StatusCode, String)> {
+    let (relocated_by, relocator_login) = require_role(pool, &payload.relocated_by, &["Incharge", "HOD", "Admin", "Principal"]).await?;
+    let branch = normalize_branch(&payload.branch);
+    if let Some(date) = payload.date {
+        if date.format("%A").to_string() != payload.day {
+            return Err((StatusCode::BAD_REQUEST, format!("{} is not a {}", date, payload.day)));
+        }
+    }
+
+    let target = room_repository::find_room(pool, &payload.to_room)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Room {} is not registered", payload.to_room)))?;
+    if target.status != "ACTIVE" {
+        return Err((StatusCode::CONFLICT, format!("Room {} is {}", target.code, target.status.to_lowercase())));
+    }
+
+    // The timetable may store the branch in any of its spellings; relocations use the stored one.
+    let (entry_branch, from_room, faculty_id) = room_repository::find_entry_room(pool, &get_branch_variations(&branch), &payload.year, &payload.section, (&payload.day, payload.period_index), payload.date)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "No class is scheduled in that slot".to_string()))?;
+    if from_room.as_deref() == Some(target.code.as_str()) && payload.date.is_none() {
+        return Err((StatusCode::BAD_REQUEST, format!("Class is already in {}", target.code)));
+    }
+
+    if let Some(holder) = room_repository::find_slot_holder(pool, &payload, &entry_branch)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        return Err((StatusCode::CONFLICT, format!(
+            "{} is occupied by {} for {} {} ({})",
+            target.code, holder.subject, holder.year, holder.section, holder.branch
+        )));
+    }
+
+    let strength = room_repository::count_section_students(pool, &get_branch_variations(&branch), &payload.year, &payload.section)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if strength > target.capacity as i64 {
+        return Err((StatusCode::CONFLICT, format!("{} seats {} but {} {} has {} students", target.code, target.capacity, payload.year, payload.section, strength)));
+    }
+
+    let relocation_id = match payload.date {
+        Some(_) => Some(
+            room_repository::upsert_relocation(pool, &payload, &entry_branch, from_room.as_deref(), relocated_by)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+        ),
+        None => {
+            let lab_row = from_room.as_deref().map(|r| (r, faculty_id.as_str()));
+            room_repository::update_entry_room(pool, &payload, &entry_branch, lab_row, target.room_type == "LAB")
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            None
+        }
+    };
+
+    let when = payload.date.map(|d| format!("on {}", d)).unwrap_or_else(|| "from now on".to_string());
+    let msg = format!(
+        "{} {} period {} on {} moves to {} {}{}",
+        payload.year, payload.section, payload.period_index, payload.day, target.code, when,
+        payload.reason.as_deref().map(|r| format!(" ({})", r)).unwrap_or_default()
+    );
+    insert_notification(pool, "ROOM_CHANGE", msg.clone(), &relocator_login, Some(&branch), None).await.ok();
+    insert_notification(pool, "ROOM_CHANGE", msg, &relocator_login, None, Some(&faculty_id)).await.ok();
+
+    Ok(relocation_id)
+}
+
+pub async fn get_relocations(pool: &PgPool, params: RelocationQuery) -> Result<Vec<RoomRelocation>, StatusCode> {
+    let variations = params.branch.as_deref().map(get_branch_variations);
+    room_repository::find_relocations(pool, params.date, variations.as_deref())
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch relocations: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Weekly booked periods against the periods the owning department's timings offer across a
+/// Monday to Saturday week. Shared rooms use the default timings.
+pub async fn get_utilization(pool: &PgPool, params: RoomUtilizationQuery) -> Result<Vec<RoomUtilization>, StatusCode> {
+    let rooms = get_rooms(pool, RoomListQuery { department: params.department, room_type: params.room_type, status: None }).await?;
+    let loads = room_repository::find_room_day_loads(pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let mut by_room: HashMap<String, Vec<(String, i64)>> = HashMap::new();
+    for l in loads {
+        by_room.entry(l.code).or_default().push((l.day, l.periods));
+    }
+
+    let today = timing_utils::campus_now().date();
+    let mut periods_per_day: HashMap<Option<String>, i64> = HashMap::new();
+    let mut report = Vec::new();
+    for room in rooms {
+        if !periods_per_day.contains_key(&room.department) {
+            let timing = match room.department.as_deref() {
+                Some(d) => timetable_repository::find_department_timing(pool, &get_branch_variations(d), today)
+                    .await
+                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+                None => None,
+            };
+            let count = timing_utils::day_slots(timing.as_ref()).iter().filter(|s| s.period_index.is_some()).count() as i64;
+            periods_per_day.insert(room.department.clone(), count);
+        }
+        let available = periods_per_day[&room.department] * DEFAULT_WORKING_DAYS.len() as i64;
+        let days = by_room.remove(&room.code).unwrap_or_default();
+        let booked: i64 = days.iter().map(|(_, p)| p).sum();
+        let busiest_day = days.iter().max_by_key(|(_, p)| *p).map(|(d, _)| d.clone());
+        report.push(RoomUtilization {
+            utilization_percent: if available > 0 { (booked as f64 / available as f64 * 1000.0).round() / 10.0 } else { 0.0 },
+            code: room.code,
+            name: room.name,
+            room_type: room.room_type,
+            department: room.department,
+            status: room.status,
+            booked_periods: booked,
+            available_periods: available,
+            busiest_day,
+        });
+    }
+    report.sort_by(|a, b| b.utilization_percent.total_cmp(&a.utilization_percent));
+    Ok(report)
+}
@@ -0,0 +1,354 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use chrono::{NaiveDate, NaiveTime, Timelike};
+use uuid::Uuid;
+use crate::models::{get_branch_variations, normalize_branch, DepartmentTiming};
+use crate::models::slot_template::{
+    DayPreview, DaySchedule, PreviewSlot, PreviewSlotTemplateRequest, SaveSlotTemplateRequest, SlotTemplate,
+    SlotTemplatePreview, SlotTemplateQuery, TemplateSlot, UpdateDepartmentTimingRequest, WeekSchedule,
+};
+use crate::repositories::{leave_repository, slot_template_repository, timetable_repository};
+use crate::utils::timing_utils::{self, DaySlot};
+use crate::utils::user_utils::resolve_user_id;
+
+const PERIOD_MINUTES: (i32, i32) = (20, 120);
+const SHORT_BREAK_MINUTES: (i32, i32) = (5, 30);
+const LUNCH_MINUTES: (i32, i32) = (20, 90);
+const LAB_PERIODS: (i32, i32) = (2, 4);
+const MAX_PERIODS_PER_DAY: i32 = 12;
+/// Nothing may be scheduled past this time of day.
+const LATEST_END: (u32, u32) = (20, 0);
+
+/// Checks one day of a template: a valid start, known kinds with sensible lengths, periods at both
+/// ends, no back-to-back breaks, at most one lunch, and a day that ends by the evening cut-off.
+fn validate_schedule(schedule: &DaySchedule, day: &str) -> Result<(), String> {
+    let start = NaiveTime::parse_from_str(&schedule.start_time, "%H:%M")
+        .map_err(|_| format!("{}: startTime must be HH:MM", day))?;
+    if schedule.slots.is_empty() {
+        return Err(format!("{}: at least one slot is required", day));
+    }
+
+    let mut periods = 0;
+    let mut lunches = 0;
+    let mut minutes: i64 = 0;
+    let mut previous_break = false;
+    for (i, slot) in schedule.slots.iter().enumerate() {
+        let position = i + 1;
+        let (range, is_break) = match slot.kind.as_str() {
+            "PERIOD" | "LAB" => (PERIOD_MINUTES, false),
+            "SHORT_BREAK" => (SHORT_BREAK_MINUTES, true),
+            "LUNCH" => (LUNCH_MINUTES, true),
+            other => return Err(format!("{}: slot {} has unknown kind '{}'; use PERIOD, LAB, SHORT_BREAK or LUNCH", day, position, other)),
+        };
+        if slot.minutes < range.0 || slot.minutes > range.1 {
+            return Err(format!("{}: {} at slot {} must be {}-{} minutes", day, slot.kind, position, range.0, range.1));
+        }
+
+        let count = match (slot.kind.as_str(), slot.periods) {
+            ("LAB", p) => {
+                let p = p.unwrap_or(LAB_PERIODS.0);
+                if p < LAB_PERIODS.0 || p > LAB_PERIODS.1 {
+                    return Err(format!("{}: LAB at slot {} must span {}-{} periods", day, position, LAB_PERIODS.0, LAB_PERIODS.1));
+                }
+                p
+            }
+            (_, Some(_)) => return Err(format!("{}: only LAB slots take a periods count (slot {})", day, position)),
+            _ => 1,
+        };
+
+        if is_break {
+            if i == 0 || i == schedule.slots.len() - 1 {
+                return Err(format!("{}: the day must start and end with a period", day));
+            }
+            if previous_break {
+                return Err(format!("{}: slot {} is a break directly after another break", day, position));
+            }
+            if slot.kind == "LUNCH" {
+                lunches += 1;
+            }
+        } else {
+            periods += count;
+        }
+        previous_break = is_break;
+        minutes += slot.minutes as i64 * count as i64;
+    }
+
+    if lunches > 1 {
+        return Err(format!("{}: only one LUNCH is allowed", day));
+    }
+    if periods > MAX_PERIODS_PER_DAY {
+        return Err(format!("{}: {} periods exceed the limit of {}", day, periods, MAX_PERIODS_PER_DAY));
+    }
+    let latest = NaiveTime::from_hms_opt(LATEST_END.0, LATEST_END.1, 0).unwrap_or_default();
+    let end_minutes = (start - NaiveTime::MIN).num_minutes() + minutes;
+    if end_minutes > (latest - NaiveTime::MIN).num_minutes() {
+        return Err(format!("{}: the day would end after {}", day, latest.format("%H:%M")));
+    }
+    Ok(())
+}
+
+fn validate_week(weekday: &DaySchedule, saturday: Option<&DaySchedule>) -> Result<(), (StatusCode, String)> {
+    validate_schedule(weekday, "weekday").map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    if let Some(s) = saturday {
+        validate_schedule(s, "saturday").map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+    Ok(())
+}
+
+fn day_preview(slots: &[DaySlot]) -> DayPreview {
+    let fmt = |t: NaiveTime| t.format("%H:%M").to_string();
+    DayPreview {
+        starts_at: slots.first().map(|s| fmt(s.start)).unwrap_or_default(),
+        ends_at: slots.last().map(|s| fmt(s.end)).unwrap_or_default(),
+        periods: slots.iter().filter(|s| s.period_index.is_some()).count() as i32,
+        teaching_minutes: slots.iter().filter(|s| s.period_index.is_some()).map(|s| (s.end - s.start).num_minutes()).sum(),
+        slots: slots
+            .iter()
+            .map(|s| PreviewSlot {
+                kind: s.kind.clone(),
+                period_index: s.period_index,
+                lab_block: s.lab_block,
+                label: s.label.clone(),
+                start: fmt(s.start),
+                end: fmt(s.end),
+                minutes: (s.end - s.start).num_minutes(),
+            })
+            .collect(),
+    }
+}
+
+fn week_preview(branch: Option<String>, effective_from: Option<NaiveDate>, week: &WeekSchedule) -> SlotTemplatePreview {
+    SlotTemplatePreview {
+        branch,
+        effective_from,
+        weekday: day_preview(&timing_utils::schedule_slots(&week.weekday)),
+        saturday: week.saturday.as_ref().map(|s| day_preview(&timing_utils::schedule_slots(s))),
+    }
+}
+
+/// The fixed columns the timing screen shows, derived from a template's weekday schedule. Lab
+/// blocks are written as plain periods since the screen has no notion of them.
+fn legacy_columns(branch: &str, weekday: &DaySchedule, short_code: Option<String>) -> DepartmentTiming {
+    let start = NaiveTime::parse_from_str(&weekday.start_time, "%H:%M").unwrap_or_default();
+    let minutes_of = |kinds: &[&str], fallback: i32| {
+        weekday.slots.iter().find(|s| kinds.contains(&s.kind.as_str())).map(|s| s.minutes).unwrap_or(fallback)
+    };
+    let config: Vec<String> = timing_utils::schedule_slots(weekday).into_iter().map(|s| s.kind).collect();
+    DepartmentTiming {
+        branch: branch.to_string(),
+        start_hour: start.hour() as i32,
+        start_minute: start.minute() as i32,
+        class_duration: minutes_of(&["PERIOD", "LAB"], 50),
+        short_break_duration: minutes_of(&["SHORT_BREAK"], 10),
+        lunch_duration: minutes_of(&["LUNCH"], 50),
+        short_code,
+        slot_config: Some(serde_json::json!(config)),
+        slot_template: None,
+    }
+}
+
+/// Records the template and, when it is already in effect, refreshes the fixed timing columns.
+async fn store_template(
+    pool: &PgPool,
+    branch: &str,
+    effective_from: NaiveDate,
+    week: &WeekSchedule,
+    created_by: Option<Uuid>,
+    short_code: Option<String>,
+) -> Result<Uuid, (StatusCode, String)> {
+    let to_json = |s: &DaySchedule| serde_json::to_value(s).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()));
+    let weekday = to_json(&week.weekday)?;
+    let saturday = week.saturday.as_ref().map(to_json).transpose()?;
+
+    let id = slot_template_repository::upsert_template(pool, branch, effective_from, &weekday, saturday.as_ref(), created_by)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let result = if effective_from <= timing_utils::campus_now().date() {
+        slot_template_repository::upsert_department_timing(pool, &legacy_columns(branch, &week.weekday, short_code)).await
+    } else {
+        slot_template_repository::ensure_department_timing(pool, branch).await
+    };
+    result.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(id)
+}
+
+/// HODs set their own department's timings; admins, the principal and coordinators may set any department's.
+async fn authorize(pool: &PgPool, login: &str, branch: &str) -> Result<Uuid, (StatusCode, String)> {
+    let user_id = resolve_user_id(login, "Admin", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", login)))?;
+    let (_, role, _, user_branch, _, _) = leave_repository::find_user_basics(pool, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    let allowed = match role.as_str() {
+        "Admin" | "Principal" | "Coordinator" => true,
+        "HOD" => user_branch.as_deref().map(normalize_branch).as_deref() == Some(branch),
+        _ => false,
+    };
+    if !allowed {
+        return Err((StatusCode::FORBIDDEN, "Not allowed to change this department's timings".to_string()));
+    }
+    Ok(user_id)
+}
+
+pub async fn save_template(pool: &PgPool, payload: SaveSlotTemplateRequest) -> Result<SlotTemplate, (StatusCode, String)> {
+    let branch = normalize_branch(&payload.branch);
+    let user_id = authorize(pool, &payload.created_by, &branch).await?;
+
+    // History is kept as it was lived; only today or later may be (re)defined.
+    if payload.effective_from < timing_utils::campus_now().date() {
+        return Err((StatusCode::BAD_REQUEST, "effectiveFrom cannot be in the past".to_string()));
+    }
+    validate_week(&payload.weekday, payload.saturday.as_ref())?;
+
+    let week = WeekSchedule { weekday: payload.weekday, saturday: payload.saturday };
+    let id = store_template(pool, &branch, payload.effective_from, &week, Some(user_id), None).await?;
+    slot_template_repository::find_template(pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Saved template not found".to_string()))
+}
+
+pub async fn get_templates(pool: &PgPool, branch: &str) -> Result<Vec<SlotTemplate>, StatusCode> {
+    slot_template_repository::find_templates(pool, &get_branch_variations(&normalize_branch(branch)))
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch slot templates: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Clock times of an unsaved template, so it can be checked before saving.
+pub fn preview_draft(payload: PreviewSlotTemplateRequest) -> Result<SlotTemplatePreview, (StatusCode, String)> {
+    validate_week(&payload.weekday, payload.saturday.as_ref())?;
+    let week = WeekSchedule { weekday: payload.weekday, saturday: payload.saturday };
+    Ok(week_preview(None, None, &week))
+}
+
+/// Clock times of the template in effect on the date, or of the fixed-column timing (or the
+/// default day) for departments that have no template yet.
+pub async fn preview_saved(pool: &PgPool, params: SlotTemplateQuery) -> Result<SlotTemplatePreview, (StatusCode, String)> {
+    let branch = normalize_branch(&params.branch);
+    let variations = get_branch_variations(&branch);
+    let date = params.date.unwrap_or_else(|| timing_utils::campus_now().date());
+
+    let template = slot_template_repository::find_template_on(pool, &variations, date)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if let Some(t) = template {
+        let week = serde_json::from_value::<WeekSchedule>(serde_json::json!({ "weekday": t.weekday_schedule, "saturday": t.saturday_schedule }))
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        return Ok(week_preview(Some(branch), Some(t.effective_from), &week));
+    }
+
+    let timing = timetable_repository::find_department_timing(pool, &variations, date)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(|t| DepartmentTiming { slot_template: None, ..t });
+    Ok(SlotTemplatePreview {
+        branch: Some(branch),
+        effective_from: None,
+        weekday: day_preview(&timing_utils::day_slots(timing.as_ref())),
+        saturday: None,
+    })
+}
+
+/// Saves the timing screen's fixed-column form. It is checked as a template and recorded in the
+/// history as starting today, keeping any Saturday schedule already in effect.
+pub async fn save_department_timing(pool: &PgPool, payload: UpdateDepartmentTimingRequest) -> Result<(), (StatusCode, String)> {
+    let branch = normalize_branch(&payload.branch);
+    let user_id = authorize(pool, &payload.updated_by, &branch).await?;
+    if !(0..24).contains(&payload.start_hour) || !(0..60).contains(&payload.start_minute) {
+        return Err((StatusCode::BAD_REQUEST, "start_hour must be 0-23 and start_minute 0-59".to_string()));
+    }
+    let start = NaiveTime::from_hms_opt(payload.start_hour as u32, payload.start_minute as u32, 0).unwrap_or_default();
+
+    let config = payload.slot_config.clone()
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| timing_utils::DEFAULT_SLOT_CONFIG.iter().map(|s| s.to_string()).collect());
+    let slots = config
+        .iter()
+        .map(|code| {
+            let (kind, minutes) = match code.as_str() {
+                "P" => ("PERIOD", payload.class_duration),
+                "SB" => ("SHORT_BREAK", payload.short_break_duration),
+                "LB" => ("LUNCH", payload.lunch_duration),
+                other => return Err((StatusCode::BAD_REQUEST, format!("Unknown slot '{}' in slot_config; use P, SB or LB", other))),
+            };
+            Ok(TemplateSlot { kind: kind.to_string(), minutes, periods: None, label: None })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let weekday = DaySchedule { start_time: start.format("%H:%M").to_string(), slots };
+    validate_week(&weekday, None)?;
+
+    let variations = get_branch_variations(&branch);
+    let today = timing_utils::campus_now().date();
+    let saturday = slot_template_repository::find_template_on(pool, &variations, today)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .and_then(|t| t.saturday_schedule)
+        .and_then(|s| serde_json::from_value::<DaySchedule>(s).ok());
+
+    let week = WeekSchedule { weekday, saturday };
+    store_template(pool, &branch, today, &week, Some(user_id), payload.short_code).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(kind: &str, minutes: i32, periods: Option<i32>) -> TemplateSlot {
+        TemplateSlot { kind: kind.to_string(), minutes, periods, label: None }
+    }
+
+    fn day(start_time: &str, slots: Vec<TemplateSlot>) -> DaySchedule {
+        DaySchedule { start_time: start_time.to_string(), slots }
+    }
+
+    #[test]
+    fn accepts_a_regular_day_with_a_lab_block() {
+        let schedule = day("09:00", vec![
+            slot("PERIOD", 50, None),
+            slot("SHORT_BREAK", 10, None),
+            slot("LAB", 50, Some(3)),
+            slot("LUNCH", 45, None),
+            slot("PERIOD", 50, None),
+        ]);
+        assert_eq!(validate_schedule(&schedule, "weekday"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_malformed_days() {
+        let cases = vec![
+            day("9am", vec![slot("PERIOD", 50, None)]),
+            day("09:00", Vec::new()),
+            day("09:00", vec![slot("SEMINAR", 50, None)]),
+            day("09:00", vec![slot("PERIOD", 10, None)]),
+            day("09:00", vec![slot("LAB", 50, Some(5))]),
+            day("09:00", vec![slot("PERIOD", 50, Some(2))]),
+            day("09:00", vec![slot("SHORT_BREAK", 10, None), slot("PERIOD", 50, None)]),
+            day("09:00", vec![slot("PERIOD", 50, None), slot("LUNCH", 45, None)]),
+            day("09:00", vec![slot("PERIOD", 50, None), slot("SHORT_BREAK", 10, None), slot("LUNCH", 45, None), slot("PERIOD", 50, None)]),
+            day("09:00", vec![
+                slot("PERIOD", 50, None), slot("LUNCH", 45, None), slot("PERIOD", 50, None), slot("LUNCH", 45, None), slot("PERIOD", 50, None),
+            ]),
+            day("09:00", (0..13).map(|_| slot("PERIOD", 20, None)).collect()),
+            day("17:00", vec![slot("PERIOD", 120, None), slot("PERIOD", 120, None)]),
+        ];
+        for schedule in cases {
+            assert!(validate_schedule(&schedule, "weekday").is_err(), "accepted {:?}", schedule);
+        }
+    }
+
+    #[test]
+    fn checks_the_saturday_schedule_too() {
+        let weekday = day("09:00", vec![slot("PERIOD", 50, None)]);
+        let saturday = day("09:00", vec![slot("LUNCH", 45, None)]);
+        let err = validate_week(&weekday, Some(&saturday)).unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        assert!(err.1.starts_with("saturday:"));
+    }
+}
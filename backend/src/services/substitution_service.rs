@@ -0,0 +1,188 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::collections::{HashMap, HashSet};
+use crate::models::normalize_branch;
+use crate::models::substitution::{
+    CandidateRow, PlanSubstitutesRequest, SubstituteCandidate, SubstitutePlanItem, SubstituteQuery,
+    SubstituteRecommendation, SubstituteSlot
+};
+use crate::repositories::auth::insert_notification;
+use crate::repositories::leave_repository;
+use crate::repositories::management::incharge_repository;
+use crate::repositories::substitution_repository;
+use crate::utils::user_utils::resolve_user_id;
+
+const DEFAULT_CANDIDATE_LIMIT: i64 = 10;
+const MAX_PLAN_DAYS: i64 = 31;
+
+// Ranking weights. Competence in the subject matters most, then knowing the class,
+// then staying within the department; load spreads cover across staff.
+const SUBJECT_WEIGHT: i64 = 40;
+const CLASS_WEIGHT: i64 = 20;
+const DEPARTMENT_WEIGHT: i64 = 15;
+const WEEK_SUBSTITUTION_PENALTY: i64 = 10;
+const DAY_LOAD_PENALTY: i64 = 3;
+
+fn week_of(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+    (start, start + Duration::days(6))
+}
+
+/// `extra_load` counts substitutions already handed out in the current plan but not yet recorded.
+fn rank(slot: &SubstituteSlot, rows: Vec<CandidateRow>, extra_load: &HashMap<String, i64>) -> Vec<SubstituteCandidate> {
+    let branch = normalize_branch(&slot.branch);
+    let mut candidates: Vec<SubstituteCandidate> = rows
+        .into_iter()
+        .map(|r| {
+            let same_department = r.branch.as_deref().map(|b| normalize_branch(b) == branch).unwrap_or(false);
+            let week_substitutions = r.week_substitutions + extra_load.get(&r.login_id).copied().unwrap_or(0);
+            let mut reasons = Vec::new();
+            let mut score = 0;
+            if r.teaches_subject {
+                score += SUBJECT_WEIGHT;
+                reasons.push(format!("Teaches {}", slot.subject));
+            }
+            if r.teaches_class {
+                score += CLASS_WEIGHT;
+                reasons.push(format!("Already teaches {} {}", slot.year, slot.section));
+            }
+            if same_department {
+                score += DEPARTMENT_WEIGHT;
+                reasons.push("Same department".to_string());
+            }
+            score -= week_substitutions * WEEK_SUBSTITUTION_PENALTY + r.periods_that_day * DAY_LOAD_PENALTY;
+            reasons.push(format!("{} substitutions this week, {} periods that day", week_substitutions, r.periods_that_day));
+
+            SubstituteCandidate {
+                faculty_id: r.login_id,
+                name: r.full_name,
+                department: r.branch,
+                score,
+                teaches_subject: r.teaches_subject,
+                teaches_class: r.teaches_class,
+                same_department,
+                week_substitutions,
+                periods_that_day: r.periods_that_day,
+                reasons,
+            }
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+    candidates
+}
+
+pub async fn recommend_substitutes(pool: &PgPool, params: SubstituteQuery) -> Result<SubstituteRecommendation, (StatusCode, String)> {
+    let branch = normalize_branch(&params.branch);
+    let day = params.date.format("%A").to_string();
+
+    let slot = substitution_repository::find_scheduled_slot(pool, params.date, (&branch, &params.year, &params.section), &day, params.period_index)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "No class is scheduled for this period".to_string()))?;
+
+    let rows = substitution_repository::find_candidates(pool, &slot, week_of(params.date))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let mut candidates = rank(&slot, rows, &HashMap::new());
+    candidates.truncate(params.limit.unwrap_or(DEFAULT_CANDIDATE_LIMIT).max(1) as usize);
+
+    Ok(SubstituteRecommendation { slot, candidates })
+}
+
+/// Picks the best free substitute for every uncovered period of an absent faculty member.
+/// A substitute is never given two periods at the same time within the plan.
+pub async fn plan_substitutes(pool: &PgPool, payload: PlanSubstitutesRequest) -> Result<Vec<SubstitutePlanItem>, (StatusCode, String)> {
+    if payload.to_date < payload.from_date {
+        return Err((StatusCode::BAD_REQUEST, "toDate is before fromDate".to_string()));
+    }
+    if (payload.to_date - payload.from_date).num_days() >= MAX_PLAN_DAYS {
+        return Err((StatusCode::BAD_REQUEST, format!("Plans can cover at most {} days", MAX_PLAN_DAYS)));
+    }
+    let planner_id = resolve_user_id(&payload.planned_by, "Incharge", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.planned_by)))?;
+    let (planner_login, _, _, _, _, _) = leave_repository::find_user_basics(pool, planner_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::BAD_REQUEST, "Planner not found".to_string()))?;
+    let absent_id = resolve_user_id(&payload.faculty_id, "Faculty", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid faculty: {}", payload.faculty_id)))?;
+    let (absent_login, _, _, _, _, _) = leave_repository::find_user_basics(pool, absent_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::BAD_REQUEST, "Faculty not found".to_string()))?;
+
+    let mut plan = Vec::new();
+    let mut taken: HashSet<(NaiveDate, i32, String)> = HashSet::new();
+    let mut extra_load: HashMap<String, i64> = HashMap::new();
+    let mut date = payload.from_date;
+    while date <= payload.to_date {
+        if date.weekday() != Weekday::Sun {
+            let day = date.format("%A").to_string();
+            let slots = substitution_repository::find_uncovered_slots(pool, &absent_login, date, &day)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            for slot in slots {
+                let rows = substitution_repository::find_candidates(pool, &slot, week_of(date))
+                    .await
+                    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+                let substitute = rank(&slot, rows, &extra_load)
+                    .into_iter()
+                    .find(|c| !taken.contains(&(date, slot.period_index, c.faculty_id.clone())));
+                if let Some(sub) = &substitute {
+                    taken.insert((date, slot.period_index, sub.faculty_id.clone()));
+                    *extra_load.entry(sub.faculty_id.clone()).or_default() += 1;
+                }
+                plan.push(SubstitutePlanItem { slot, substitute });
+            }
+        }
+        date += Duration::days(1);
+    }
+
+    if payload.apply {
+        apply_plan(pool, &plan, planner_id, &planner_login).await?;
+    }
+    Ok(plan)
+}
+
+async fn apply_plan(pool: &PgPool, plan: &[SubstitutePlanItem], planner_id: uuid::Uuid, planner_login: &str) -> Result<(), (StatusCode, String)> {
+    let mut per_substitute: HashMap<&str, Vec<String>> = HashMap::new();
+    for item in plan {
+        let Some(sub) = &item.substitute else { continue };
+        let slot = &item.slot;
+        let original = slot.faculty_name.clone().unwrap_or_else(|| slot.faculty_id.clone());
+        incharge_repository::upsert_class_status(
+            pool,
+            &slot.branch,
+            &slot.year,
+            &slot.section,
+            &slot.day,
+            slot.period_index,
+            slot.date,
+            &slot.subject,
+            &original,
+            Some(slot.subject.as_str()),
+            Some(sub.name.as_str()),
+            "substitute",
+            &planner_id.to_string(),
+        )
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        per_substitute.entry(sub.faculty_id.as_str()).or_default().push(format!(
+            "{} P{}: {} ({} {})",
+            slot.date.format("%d %b"),
+            slot.period_index,
+            slot.subject,
+            slot.year,
+            slot.section
+        ));
+    }
+
+    for (login, periods) in per_substitute {
+        let msg = format!("You have been assigned as substitute for: {}", periods.join(", "));
+        insert_notification(pool, "SUBSTITUTION", msg, planner_login, None, Some(login)).await.ok();
+    }
+    Ok(())
+}
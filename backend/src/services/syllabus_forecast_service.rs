@@ -0,0 +1,238 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use chrono::{Duration, NaiveDate, Utc};
+use std::collections::HashMap;
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::models::lesson_plan::LessonPlan;
+use crate::models::syllabus_forecast::{ForecastValues, RefreshForecastsRequest, RiskBreakdown, SyllabusForecast, SyllabusForecastQuery};
+use crate::repositories::{leave_repository, lesson_plan_repository, syllabus_forecast_repository};
+use crate::services::{curriculum_service, lesson_plan_service};
+use crate::utils::user_utils::resolve_user_id;
+
+const FORECAST_REFRESH_MINUTES: u64 = 60;
+/// How far back the completion pace is measured.
+const PACE_WINDOW_DAYS: i64 = 28;
+const NOTICE_INTERVAL_DAYS: i64 = 7;
+const STATUSES: [&str; 3] = ["ON_TRACK", "AT_RISK", "BEHIND"];
+
+/// Forecast for one plan as of `today`. The pace is the syllabus periods completed per week over
+/// the last four weeks; in a plan's first week, before there is any history, the timetable's
+/// weekly periods stand in for it. A subject is BEHIND when fewer periods are left before the
+/// deadline than syllabus remains, AT_RISK when the periods suffice but the pace does not, and
+/// ON_TRACK otherwise.
+async fn forecast_plan(pool: &PgPool, plan: &LessonPlan, today: NaiveDate) -> Result<ForecastValues, (StatusCode, String)> {
+    let target = lesson_plan_service::plan_target(plan);
+    let curriculum = curriculum_service::find_current_curriculum(pool, &target.branch, &target.regulation, &target.subject_code)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("No curriculum imported for {}", target.subject_code)))?;
+    let slots = lesson_plan_service::subject_slots(pool, &target, &curriculum.subject_name).await?;
+    let calendar = lesson_plan_service::plan_calendar(pool, &target, &slots).await?;
+    let completions = syllabus_forecast_repository::find_completion_dates(pool, &target)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let start = today.max(calendar.from);
+    let window_start = (start - Duration::days(PACE_WINDOW_DAYS)).max(calendar.from);
+    let (mut completed_periods, mut remaining_periods, mut recent_periods) = (0, 0, 0);
+    let mut last_completed = None;
+    for topic in curriculum.units.iter().flat_map(|u| u.topics.iter()) {
+        let periods = topic.period.max(1);
+        let done = completions.iter().find(|(_, uid, _)| uid.is_some() && *uid == topic.uid)
+            .or_else(|| completions.iter().find(|(id, _, _)| *id == topic.id));
+        match done {
+            Some((_, _, on)) => {
+                completed_periods += periods;
+                if *on >= window_start {
+                    recent_periods += periods;
+                }
+                last_completed = last_completed.max(Some(*on));
+            }
+            None => remaining_periods += periods,
+        }
+    }
+
+    let deadline = calendar.exam_start.map(|d| d - Duration::days(1)).unwrap_or(calendar.to);
+    let scheduled_periods = calendar.dates.iter().filter(|d| **d >= start && **d <= deadline).count() as i32;
+    let elapsed_days = (start - window_start).num_days();
+    let weekly_rate = if elapsed_days < 7 {
+        plan.weekly_periods as f64
+    } else {
+        recent_periods as f64 * 7.0 / elapsed_days as f64
+    };
+    let projected_completion = if remaining_periods == 0 {
+        last_completed.or(Some(start))
+    } else if weekly_rate > 0.0 {
+        Some(start + Duration::days((remaining_periods as f64 / weekly_rate * 7.0).ceil() as i64))
+    } else {
+        None
+    };
+    let status = if remaining_periods == 0 {
+        "ON_TRACK"
+    } else if scheduled_periods < remaining_periods {
+        "BEHIND"
+    } else if projected_completion.is_none_or(|d| d > deadline) {
+        "AT_RISK"
+    } else {
+        "ON_TRACK"
+    };
+
+    Ok(ForecastValues { completed_periods, remaining_periods, scheduled_periods, weekly_rate, deadline, projected_completion, status })
+}
+
+/// Recomputes and stores the forecasts of the given plans. A plan that can no longer be
+/// forecast (its curriculum or timetable slots are gone) keeps its previous forecast.
+async fn refresh_forecasts(pool: &PgPool, plans: &[LessonPlan]) -> Result<(), sqlx::Error> {
+    let today = Utc::now().date_naive();
+    for plan in plans {
+        match forecast_plan(pool, plan, today).await {
+            Ok(forecast) => {
+                syllabus_forecast_repository::upsert_forecast(pool, plan.id, &forecast).await?;
+            }
+            Err((_, e)) => eprintln!("ERROR: Failed to forecast lesson plan {}: {}", plan.id, e),
+        }
+    }
+    Ok(())
+}
+
+/// The forecasts as last computed by the hourly job or an explicit refresh, worst first.
+pub async fn get_forecasts(pool: &PgPool, params: SyllabusForecastQuery) -> Result<Vec<SyllabusForecast>, (StatusCode, String)> {
+    let status = params.status.as_deref().map(|s| s.trim().to_uppercase());
+    if let Some(s) = status.as_deref() {
+        if !STATUSES.contains(&s) {
+            return Err((StatusCode::BAD_REQUEST, "status must be ON_TRACK, AT_RISK or BEHIND".to_string()));
+        }
+    }
+    let variations = get_branch_variations(&normalize_branch(&params.branch));
+    syllabus_forecast_repository::find_forecasts(pool, &variations, params.year.as_deref(), params.section.as_deref(), status.as_deref())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Recomputes the forecasts of a branch's lesson plans now rather than at the next hourly run.
+/// HODs may refresh their own department; Admin, Principal and Coordinators any.
+pub async fn refresh_branch_forecasts(pool: &PgPool, payload: RefreshForecastsRequest) -> Result<Vec<SyllabusForecast>, (StatusCode, String)> {
+    let user_id = resolve_user_id(&payload.requested_by, "HOD", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.requested_by)))?;
+    let (_, role, _, user_branch, _, _) = leave_repository::find_user_basics(pool, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    let branch = normalize_branch(&payload.branch);
+    let allowed = match role.as_str() {
+        "Admin" | "Principal" | "Coordinator" => true,
+        "HOD" => user_branch.as_deref().map(normalize_branch).as_deref() == Some(branch.as_str()),
+        _ => false,
+    };
+    if !allowed {
+        return Err((StatusCode::FORBIDDEN, "Not allowed to refresh this department's forecasts".to_string()));
+    }
+
+    let variations = get_branch_variations(&branch);
+    let plans = lesson_plan_repository::find_plans(pool, &variations, payload.year.as_deref(), payload.section.as_deref(), false)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    refresh_forecasts(pool, &plans)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    syllabus_forecast_repository::find_forecasts(pool, &variations, payload.year.as_deref(), payload.section.as_deref(), None)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Forecast status counts per branch, as last computed.
+pub async fn get_risk_breakdown(pool: &PgPool) -> Result<HashMap<String, RiskBreakdown>, sqlx::Error> {
+    let mut breakdown: HashMap<String, RiskBreakdown> = HashMap::new();
+    for (branch, status, count) in syllabus_forecast_repository::count_statuses(pool).await? {
+        let entry = breakdown.entry(branch).or_default();
+        match status.as_str() {
+            "ON_TRACK" => entry.on_track += count,
+            "AT_RISK" => entry.at_risk += count,
+            "BEHIND" => entry.behind += count,
+            _ => {}
+        }
+    }
+    Ok(breakdown)
+}
+
+fn faculty_notice(f: &SyllabusForecast) -> String {
+    let outlook = match f.projected_completion {
+        Some(d) => format!("at the current pace it would finish on {}", d.format("%d %b %Y")),
+        None => "no topics were completed in the last four weeks".to_string(),
+    };
+    format!(
+        "{} ({} {}) is {}: {} periods of syllabus remain with {} periods scheduled before {}; {}.",
+        f.subject_code,
+        f.year,
+        f.section,
+        if f.status == "BEHIND" { "behind schedule" } else { "at risk of not finishing" },
+        f.remaining_periods,
+        f.scheduled_periods,
+        f.deadline.format("%d %b %Y"),
+        outlook
+    )
+}
+
+fn hod_digest(branch: &str, forecasts: &[SyllabusForecast]) -> String {
+    let count = |s: &str| forecasts.iter().filter(|f| f.status == s).count();
+    let mut message = format!(
+        "Weekly syllabus forecast for {}: {} on track, {} at risk, {} behind.",
+        branch,
+        count("ON_TRACK"),
+        count("AT_RISK"),
+        count("BEHIND")
+    );
+    let behind: Vec<String> = forecasts
+        .iter()
+        .filter(|f| f.status == "BEHIND")
+        .map(|f| format!("{} ({} {}, {})", f.subject_code, f.year, f.section, f.faculty_name))
+        .collect();
+    if !behind.is_empty() {
+        message.push_str(&format!(" Behind: {}.", behind.join(", ")));
+    }
+    message
+}
+
+/// Sends each branch due for it this week's notices: faculty hear about their subjects that are
+/// at risk or behind, the HOD gets one digest for the department.
+async fn send_weekly_notices(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let today = Utc::now().date_naive();
+    for branch in syllabus_forecast_repository::find_branches_due_notice(pool, today - Duration::days(NOTICE_INTERVAL_DAYS)).await? {
+        let forecasts = syllabus_forecast_repository::find_forecasts(pool, std::slice::from_ref(&branch), None, None, None).await?;
+        if forecasts.is_empty() {
+            continue;
+        }
+        let mut tx = pool.begin().await?;
+        for f in forecasts.iter().filter(|f| f.status != "ON_TRACK" && !f.faculty_login.is_empty()) {
+            syllabus_forecast_repository::insert_notification(&mut tx, &faculty_notice(f), &f.faculty_login, &branch).await?;
+        }
+        syllabus_forecast_repository::insert_notification(&mut tx, &hod_digest(&branch, &forecasts), "HOD_RECIPIENT", &branch).await?;
+        syllabus_forecast_repository::mark_notice_sent(&mut tx, &branch, today).await?;
+        tx.commit().await?;
+    }
+    Ok(())
+}
+
+/// Keeps every plan's forecast current and sends the weekly notices once per
+/// `NOTICE_INTERVAL_DAYS` per branch.
+pub async fn run_weekly_forecasts(pool: PgPool) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(FORECAST_REFRESH_MINUTES * 60));
+    loop {
+        interval.tick().await;
+        let plans = match lesson_plan_repository::find_all_plans(&pool).await {
+            Ok(plans) => plans,
+            Err(e) => {
+                eprintln!("ERROR: Failed to load lesson plans for forecasting: {:?}", e);
+                continue;
+            }
+        };
+        if let Err(e) = refresh_forecasts(&pool, &plans).await {
+            eprintln!("ERROR: Failed to refresh syllabus forecasts: {:?}", e);
+            continue;
+        }
+        if let Err(e) = send_weekly_notices(&pool).await {
+            eprintln!("ERROR: Failed to send syllabus forecast notices: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,1004 @@
+use sqlx::{PgPool, Postgres};
+use axum::http::StatusCode;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use chrono::{NaiveDate, Weekday};
+use uuid::Uuid;
+use crate::models::timetable::{
+    ClassKey, DraftEntry, DraftListQuery, FacultyUnavailability, GenerateTimetableRequest, OccupiedSlot, PublishDraftRequest,
+    ScheduleConflict, SubjectRequirement, TimetableDraft, TimetableDraftSummary, UnplacedRequirement,
+    CloneVersionRequest, CreateVersionRequest, TimetableVersion, TimetableVersionDetail, VersionDiffItem, VersionDiffQuery,
+    VersionEntryRequest
+};
+use crate::models::AssignClassRequest;
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::repositories::management::hod_repository;
+use crate::repositories::timetable_repository;
+use crate::repositories::room_repository;
+use crate::utils::timing_utils;
+use crate::utils::user_utils::resolve_user_id;
+
+pub const DEFAULT_WORKING_DAYS: [&str; 6] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+const DEFAULT_LAB_BLOCK: i32 = 3;
+const DEFAULT_MAX_CONSECUTIVE_SUBJECT: i32 = 2;
+const DEFAULT_MAX_CONSECUTIVE_FACULTY: i32 = 3;
+const VERSION_ACTIVATION_MINUTES: u64 = 60;
+
+/// `subjects.semester` values that belong to a year of study.
+fn semester_patterns(year: &str) -> Vec<String> {
+    let (a, b) = match year {
+        "1st Year" => (1, 2),
+        "2nd Year" => (3, 4),
+        _ => (5, 6),
+    };
+    let ordinal = |n: i32| match n {
+        1 => "1st".to_string(),
+        2 => "2nd".to_string(),
+        3 => "3rd".to_string(),
+        n => format!("{}th", n),
+    };
+    vec![
+        format!("{}%", year),
+        format!("{} Semester%", ordinal(a)),
+        format!("{} Semester%", ordinal(b)),
+        format!("Semester {}%", a),
+        format!("Semester {}%", b),
+    ]
+}
+
+fn is_lab_type(subject_type: &str) -> bool {
+    let t = subject_type.to_lowercase();
+    t.contains("lab") || t.contains("practical")
+}
+
+// --- Solver ---
+
+/// Everything the solver needs, already resolved from the database.
+pub struct GeneratorInput {
+    pub classes: Vec<ClassKey>,
+    pub requirements: Vec<SubjectRequirement>,
+    pub days: Vec<String>,
+    /// Runs of consecutive period indices that are not split by lunch. Lab blocks must fit inside one.
+    pub segments: Vec<Vec<i32>>,
+    /// Lab double-periods fixed by the department's slot template. When set, lab blocks are
+    /// placed only inside these instead of anywhere in a segment.
+    pub lab_windows: Vec<Vec<i32>>,
+    /// Periods held on Saturday when its schedule differs from the weekday one.
+    pub saturday_periods: Option<Vec<i32>>,
+    pub lab_rooms: Vec<String>,
+    pub faculty_busy: HashSet<(String, String, i32)>,
+    pub unavailable: Vec<FacultyUnavailability>,
+    pub max_consecutive_subject: i32,
+    pub max_consecutive_faculty: i32,
+}
+
+pub struct GeneratorOutput {
+    pub entries: Vec<DraftEntry>,
+    pub unplaced: Vec<UnplacedRequirement>,
+    pub warnings: Vec<String>,
+}
+
+struct Cell {
+    subject_code: String,
+    is_lab: bool,
+}
+
+struct Solver<'a> {
+    input: &'a GeneratorInput,
+    cells: HashMap<(usize, usize, i32), Cell>, // (class, day, period)
+    faculty_busy: HashSet<(String, usize, i32)>,
+    room_busy: HashSet<(String, usize, i32)>,
+    lab_load: Vec<i32>, // lab periods per day across the branch
+    entries: Vec<DraftEntry>,
+    unplaced: Vec<UnplacedRequirement>,
+}
+
+impl<'a> Solver<'a> {
+    fn new(input: &'a GeneratorInput) -> Self {
+        let all_periods: Vec<i32> = input.segments.iter().flatten().copied().collect();
+        let mut faculty_busy = HashSet::new();
+        for (fid, day, p) in &input.faculty_busy {
+            if let Some(d) = input.days.iter().position(|x| x.eq_ignore_ascii_case(day)) {
+                faculty_busy.insert((fid.clone(), d, *p));
+            }
+        }
+        for u in &input.unavailable {
+            if let Some(d) = input.days.iter().position(|x| x.eq_ignore_ascii_case(&u.day)) {
+                let periods = u.period_index.map(|p| vec![p]).unwrap_or_else(|| all_periods.clone());
+                for p in periods {
+                    faculty_busy.insert((u.faculty_id.clone(), d, p));
+                }
+            }
+        }
+        Solver {
+            input,
+            cells: HashMap::new(),
+            faculty_busy,
+            room_busy: HashSet::new(),
+            lab_load: vec![0; input.days.len()],
+            entries: Vec::new(),
+            unplaced: Vec::new(),
+        }
+    }
+
+    fn class_index(&self, req: &SubjectRequirement) -> Option<usize> {
+        self.input.classes.iter().position(|c| c.year == req.year && c.section == req.section)
+    }
+
+    fn holds_period(&self, day: usize, period: i32) -> bool {
+        match &self.input.saturday_periods {
+            Some(periods) if self.input.days[day].eq_ignore_ascii_case("Saturday") => periods.contains(&period),
+            _ => true,
+        }
+    }
+
+    fn is_free(&self, class: usize, faculty: &str, day: usize, period: i32) -> bool {
+        self.holds_period(day, period)
+            && !self.cells.contains_key(&(class, day, period)) && !self.faculty_busy.contains(&(faculty.to_string(), day, period))
+    }
+
+    fn place(&mut self, class: usize, req: &SubjectRequirement, day: usize, period: i32, lab_room: Option<&str>) {
+        let c = &self.input.classes[class];
+        self.cells.insert((class, day, period), Cell { subject_code: req.subject_code.clone(), is_lab: req.lab_block.is_some() });
+        self.faculty_busy.insert((req.faculty_id.clone(), day, period));
+        if let Some(room) = lab_room {
+            self.room_busy.insert((room.to_string(), day, period));
+        }
+        self.entries.push(DraftEntry {
+            year: c.year.clone(),
+            section: c.section.clone(),
+            day: self.input.days[day].clone(),
+            period_index: period,
+            subject: req.subject_name.clone(),
+            subject_code: Some(req.subject_code.clone()),
+            faculty_id: req.faculty_id.clone(),
+            lab_room: lab_room.map(|r| r.to_string()),
+        });
+    }
+
+    fn unplace(&mut self, req: &SubjectRequirement, missing: i32, reason: &str) {
+        self.unplaced.push(UnplacedRequirement {
+            year: req.year.clone(),
+            section: req.section.clone(),
+            subject_code: req.subject_code.clone(),
+            faculty_id: req.faculty_id.clone(),
+            missing_periods: missing,
+            reason: reason.to_string(),
+        });
+    }
+
+    /// Consecutive periods of `code` for a class through `period`, counting `period` itself.
+    fn subject_run(&self, class: usize, day: usize, period: i32, code: &str) -> i32 {
+        let same = |p: i32| self.cells.get(&(class, day, p)).map(|c| c.subject_code == code).unwrap_or(false);
+        let mut len = 1;
+        let mut p = period - 1;
+        while same(p) { len += 1; p -= 1; }
+        p = period + 1;
+        while same(p) { len += 1; p += 1; }
+        len
+    }
+
+    /// Consecutive teaching periods for a faculty member through `period`, counting `period` itself.
+    fn faculty_run(&self, faculty: &str, day: usize, period: i32) -> i32 {
+        let busy = |p: i32| self.faculty_busy.contains(&(faculty.to_string(), day, p));
+        let mut len = 1;
+        let mut p = period - 1;
+        while busy(p) { len += 1; p -= 1; }
+        p = period + 1;
+        while busy(p) { len += 1; p += 1; }
+        len
+    }
+
+    /// Hard constraints: class and faculty free for the whole block, which sits inside one segment,
+    /// and a lab room free for all of it when rooms are known. Soft: one lab per class per day,
+    /// spread lab load across the week.
+    fn place_lab(&mut self, req: &SubjectRequirement) {
+        let Some(class) = self.class_index(req) else { return };
+        let block = req.lab_block.unwrap_or(DEFAULT_LAB_BLOCK).max(1) as usize;
+        let sessions = (req.weekly_periods.max(1) as usize).div_ceil(block);
+
+        for _ in 0..sessions {
+            let mut best: Option<(i32, usize, Vec<i32>, Option<String>)> = None;
+            for day in 0..self.input.days.len() {
+                let class_has_lab = self.cells.iter().any(|((c, d, _), cell)| *c == class && *d == day && cell.is_lab);
+                let runs = if self.input.lab_windows.is_empty() { &self.input.segments } else { &self.input.lab_windows };
+                for segment in runs {
+                    for window in segment.windows(block) {
+                        if !window.iter().all(|p| self.is_free(class, &req.faculty_id, day, *p)) {
+                            continue;
+                        }
+                        let room = if self.input.lab_rooms.is_empty() {
+                            None
+                        } else {
+                            match self.input.lab_rooms.iter().find(|r| window.iter().all(|p| !self.room_busy.contains(&(r.to_string(), day, *p)))) {
+                                Some(r) => Some(r.clone()),
+                                None => continue,
+                            }
+                        };
+                        let score = if class_has_lab { 100 } else { 0 } + self.lab_load[day];
+                        if best.as_ref().map(|b| score < b.0).unwrap_or(true) {
+                            best = Some((score, day, window.to_vec(), room));
+                        }
+                    }
+                }
+            }
+
+            match best {
+                Some((_, day, periods, room)) => {
+                    for p in &periods {
+                        self.place(class, req, day, *p, room.as_deref());
+                    }
+                    self.lab_load[day] += periods.len() as i32;
+                }
+                None => self.unplace(req, block as i32, "No contiguous block with the class, faculty and a lab room free"),
+            }
+        }
+    }
+
+    fn best_theory_slot(&self, class: usize, req: &SubjectRequirement) -> Option<(usize, i32)> {
+        let mut best: Option<(i32, usize, i32)> = None;
+        for day in 0..self.input.days.len() {
+            let same_day = self.cells.iter().filter(|((c, d, _), cell)| *c == class && *d == day && cell.subject_code == req.subject_code).count() as i32;
+            let day_load = self.cells.keys().filter(|(c, d, _)| *c == class && *d == day).count() as i32;
+            for period in self.input.segments.iter().flatten().copied() {
+                if !self.is_free(class, &req.faculty_id, day, period) {
+                    continue;
+                }
+                let mut score = same_day * 10 + day_load;
+                if self.subject_run(class, day, period, &req.subject_code) > self.input.max_consecutive_subject {
+                    score += 100;
+                }
+                if self.faculty_run(&req.faculty_id, day, period) > self.input.max_consecutive_faculty {
+                    score += 30;
+                }
+                if best.map(|b| score < b.0).unwrap_or(true) {
+                    best = Some((score, day, period));
+                }
+            }
+        }
+        best.map(|(_, d, p)| (d, p))
+    }
+
+    /// Theory periods are placed one per requirement per round so that no subject
+    /// claims the best slots before the others have had a turn.
+    fn place_theory(&mut self, reqs: &[&SubjectRequirement]) {
+        let mut remaining: Vec<i32> = reqs.iter().map(|r| r.weekly_periods.max(0)).collect();
+        while remaining.iter().any(|r| *r > 0) {
+            for (i, req) in reqs.iter().enumerate() {
+                if remaining[i] == 0 {
+                    continue;
+                }
+                let Some(class) = self.class_index(req) else {
+                    remaining[i] = 0;
+                    continue;
+                };
+                match self.best_theory_slot(class, req) {
+                    Some((day, period)) => {
+                        self.place(class, req, day, period, None);
+                        remaining[i] -= 1;
+                    }
+                    None => {
+                        self.unplace(req, remaining[i], "No free period for both the class and the faculty");
+                        remaining[i] = 0;
+                    }
+                }
+            }
+        }
+    }
+
+    fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let mut per_day: HashMap<(usize, usize, &str), i32> = HashMap::new();
+        for ((c, d, _), cell) in &self.cells {
+            if !cell.is_lab {
+                *per_day.entry((*c, *d, cell.subject_code.as_str())).or_default() += 1;
+            }
+        }
+        let mut repeated: Vec<_> = per_day.into_iter().filter(|(_, n)| *n > 1).collect();
+        repeated.sort();
+        for ((c, d, code), n) in repeated {
+            let class = &self.input.classes[c];
+            warnings.push(format!("{} {}: {} has {} periods on {}", class.year, class.section, code, n, self.input.days[d]));
+        }
+        warnings
+    }
+}
+
+/// Builds a clash-free weekly timetable. Labs are placed first since they need contiguous blocks,
+/// then theory periods are spread across the week. Requirements that cannot be met are reported
+/// rather than forced in.
+pub fn generate(input: &GeneratorInput) -> GeneratorOutput {
+    let mut solver = Solver::new(input);
+
+    // Most heavily loaded faculty first, they have the fewest options.
+    let mut load: HashMap<&str, i32> = HashMap::new();
+    for r in &input.requirements {
+        *load.entry(r.faculty_id.as_str()).or_default() += r.weekly_periods;
+    }
+    let mut ordered: Vec<&SubjectRequirement> = input.requirements.iter().collect();
+    ordered.sort_by(|a, b| load[b.faculty_id.as_str()].cmp(&load[a.faculty_id.as_str()])
+        .then_with(|| (&a.year, &a.section, &a.subject_code).cmp(&(&b.year, &b.section, &b.subject_code))));
+
+    for req in ordered.iter().filter(|r| r.lab_block.is_some()) {
+        solver.place_lab(req);
+    }
+    let theory: Vec<&SubjectRequirement> = ordered.iter().filter(|r| r.lab_block.is_none()).copied().collect();
+    solver.place_theory(&theory);
+
+    let warnings = solver.warnings();
+    let mut entries = solver.entries;
+    entries.sort_by(|a, b| (&a.year, &a.section, &a.day, a.period_index).cmp(&(&b.year, &b.section, &b.day, b.period_index)));
+    GeneratorOutput { entries, unplaced: solver.unplaced, warnings }
+}
+
+// --- Drafts ---
+
+async fn derive_requirements(pool: &PgPool, variations: &[String], classes: &[ClassKey]) -> Result<Vec<SubjectRequirement>, (StatusCode, String)> {
+    let mut requirements = Vec::new();
+    for class in classes {
+        let rows = timetable_repository::find_section_subjects(pool, variations, &class.section, &semester_patterns(&class.year))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        let mut seen = HashSet::new();
+        for (subject_id, name, subject_type, credit, faculty_id) in rows {
+            if !seen.insert(subject_id.clone()) {
+                continue;
+            }
+            let lab = is_lab_type(&subject_type);
+            requirements.push(SubjectRequirement {
+                year: class.year.clone(),
+                section: class.section.clone(),
+                subject_code: subject_id,
+                subject_name: name,
+                faculty_id,
+                weekly_periods: if lab { DEFAULT_LAB_BLOCK } else { credit.unwrap_or(3).max(1) },
+                lab_block: if lab { Some(DEFAULT_LAB_BLOCK) } else { None },
+            });
+        }
+    }
+    Ok(requirements)
+}
+
+pub async fn generate_draft(pool: &PgPool, payload: GenerateTimetableRequest) -> Result<TimetableDraft, (StatusCode, String)> {
+    let created_by = resolve_user_id(&payload.created_by, "HOD", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.created_by)))?;
+    let branch = normalize_branch(&payload.branch);
+    let variations = get_branch_variations(&branch);
+
+    let classes = match &payload.classes {
+        Some(c) if !c.is_empty() => c.clone(),
+        _ => hod_repository::find_class_combos(pool, &branch)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .into_iter()
+            .map(|(year, section)| ClassKey { year, section })
+            .collect(),
+    };
+    if classes.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "No classes found for this branch".to_string()));
+    }
+
+    let requirements = match &payload.requirements {
+        Some(r) if !r.is_empty() => r.clone(),
+        _ => derive_requirements(pool, &variations, &classes).await?,
+    };
+    if requirements.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "No subject requirements found; assign faculty subjects or pass requirements".to_string()));
+    }
+
+    let timing = timetable_repository::find_department_timing(pool, &variations, timing_utils::campus_now().date())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let mut segments: Vec<Vec<i32>> = vec![Vec::new()];
+    for slot in timing_utils::day_slots(timing.as_ref()) {
+        match slot.period_index {
+            Some(p) => segments.last_mut().unwrap().push(p),
+            None if slot.kind == "LB" => segments.push(Vec::new()),
+            None => {}
+        }
+    }
+    segments.retain(|s| !s.is_empty());
+    let mut lab_windows: Vec<Vec<i32>> = Vec::new();
+    for slot in timing_utils::day_slots(timing.as_ref()) {
+        if let (Some(block), Some(p)) = (slot.lab_block, slot.period_index) {
+            if lab_windows.len() < block as usize {
+                lab_windows.push(Vec::new());
+            }
+            lab_windows[block as usize - 1].push(p);
+        }
+    }
+    let saturday_periods = timing.as_ref()
+        .and_then(|t| t.slot_template.as_ref())
+        .filter(|t| !t["saturday"].is_null())
+        .map(|_| {
+            timing_utils::day_slots_on(timing.as_ref(), Weekday::Sat)
+                .into_iter()
+                .filter_map(|s| s.period_index)
+                .collect::<Vec<i32>>()
+        });
+
+    let mut faculty_ids: Vec<String> = requirements.iter().map(|r| r.faculty_id.clone()).collect();
+    faculty_ids.sort();
+    faculty_ids.dedup();
+    let faculty_busy = timetable_repository::find_external_faculty_load(pool, &branch, &faculty_ids)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .into_iter()
+        .collect();
+    // Registered labs first; branches that haven't registered theirs keep the names from the lab grid.
+    let mut lab_rooms = room_repository::find_lab_codes(pool, &get_branch_variations(&branch)).await.unwrap_or_default();
+    if lab_rooms.is_empty() {
+        lab_rooms = hod_repository::find_lab_names_by_branch(pool, &branch).await.unwrap_or_default();
+    }
+
+    let input = GeneratorInput {
+        classes,
+        requirements,
+        days: payload.working_days.clone().unwrap_or_else(|| DEFAULT_WORKING_DAYS.iter().map(|d| d.to_string()).collect()),
+        segments,
+        lab_windows,
+        saturday_periods,
+        lab_rooms,
+        faculty_busy,
+        unavailable: payload.faculty_unavailable.clone(),
+        max_consecutive_subject: payload.max_consecutive_same_subject.unwrap_or(DEFAULT_MAX_CONSECUTIVE_SUBJECT),
+        max_consecutive_faculty: payload.max_consecutive_faculty.unwrap_or(DEFAULT_MAX_CONSECUTIVE_FACULTY),
+    };
+    let output = generate(&input);
+
+    let params = serde_json::json!({
+        "classes": input.classes,
+        "requirements": input.requirements,
+        "workingDays": input.days,
+        "facultyUnavailable": input.unavailable,
+        "maxConsecutiveSameSubject": input.max_consecutive_subject,
+        "maxConsecutiveFaculty": input.max_consecutive_faculty,
+    });
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let draft_id = timetable_repository::insert_draft(
+        &mut tx,
+        &branch,
+        created_by,
+        params,
+        serde_json::to_value(&output.unplaced).unwrap_or_default(),
+        serde_json::to_value(&output.warnings).unwrap_or_default(),
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    for entry in &output.entries {
+        timetable_repository::insert_draft_entry(&mut tx, draft_id, entry)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(TimetableDraft {
+        id: draft_id,
+        branch,
+        status: "DRAFT".to_string(),
+        entries: output.entries,
+        unplaced: output.unplaced,
+        warnings: output.warnings,
+    })
+}
+
+pub async fn get_drafts(pool: &PgPool, params: DraftListQuery) -> Result<Vec<TimetableDraftSummary>, StatusCode> {
+    timetable_repository::find_drafts(pool, &normalize_branch(&params.branch))
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch timetable drafts: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+pub async fn get_draft(pool: &PgPool, id: Uuid) -> Result<TimetableDraft, StatusCode> {
+    let (branch, status, unplaced, warnings) = timetable_repository::find_draft(pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let entries = timetable_repository::find_draft_entries(pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(TimetableDraft {
+        id,
+        branch,
+        status,
+        entries,
+        unplaced: serde_json::from_value(unplaced).unwrap_or_default(),
+        warnings: serde_json::from_value(warnings).unwrap_or_default(),
+    })
+}
+
+/// Replaces the timetable of every class in the draft, plus the rows of the lab rooms it uses.
+pub async fn publish_draft(pool: &PgPool, id: Uuid, payload: PublishDraftRequest) -> Result<(), (StatusCode, String)> {
+    let publisher = resolve_user_id(&payload.published_by, "HOD", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.published_by)))?;
+    let draft = get_draft(pool, id).await.map_err(|c| (c, "Draft not found".to_string()))?;
+    if draft.status != "DRAFT" {
+        return Err((StatusCode::CONFLICT, format!("Draft is already {}", draft.status.to_lowercase())));
+    }
+
+    let mut classes: Vec<(&str, &str)> = draft.entries.iter().map(|e| (e.year.as_str(), e.section.as_str())).collect();
+    classes.sort();
+    classes.dedup();
+    let mut rooms: Vec<&str> = draft.entries.iter().filter_map(|e| e.lab_room.as_deref()).collect();
+    rooms.sort();
+    rooms.dedup();
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    for (year, section) in &classes {
+        timetable_repository::delete_class_entries(&mut tx, &draft.branch, year, section)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+    for room in &rooms {
+        timetable_repository::delete_class_entries(&mut tx, &draft.branch, "Lab", room)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+    for entry in &draft.entries {
+        timetable_repository::insert_timetable_entry(&mut tx, &draft.branch, &entry.year, &entry.section, entry)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        if let Some(room) = entry.lab_room.as_deref() {
+            // Lab rows mirror the class slot so the lab grid in the master timetable shows who is using it.
+            let lab_entry = DraftEntry { subject: format!("{} ({} {})", entry.subject, entry.year, entry.section), ..entry.clone() };
+            timetable_repository::insert_timetable_entry(&mut tx, &draft.branch, "Lab", room, &lab_entry)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        }
+    }
+    let updated = timetable_repository::update_draft_status(&mut tx, id, "PUBLISHED", Some(publisher))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if updated == 0 {
+        return Err((StatusCode::CONFLICT, "Draft was already published or discarded".to_string()));
+    }
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(())
+}
+
+pub async fn discard_draft(pool: &PgPool, id: Uuid) -> Result<(), (StatusCode, String)> {
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let updated = timetable_repository::update_draft_status(&mut tx, id, "DISCARDED", None)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if updated == 0 {
+        return Err((StatusCode::CONFLICT, "Only drafts can be discarded".to_string()));
+    }
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(())
+}
+
+// --- Assignment checks ---
+
+fn slot_conflict(kind: &str, message: String, slot: OccupiedSlot) -> ScheduleConflict {
+    ScheduleConflict {
+        kind: kind.to_string(),
+        message,
+        branch: Some(slot.branch),
+        year: Some(slot.year),
+        section: Some(slot.section),
+        subject: Some(slot.subject),
+        faculty_id: Some(slot.faculty_id),
+    }
+}
+
+/// Everything that would make a manual assignment clash: the faculty member teaching elsewhere
+/// in any branch, the room or lab under maintenance or already held by someone else, or the
+/// subject missing from their approved `faculty_subjects`.
+///
+/// Runs inside the transaction that saves the slot: the faculty member's and the room's slot are
+/// locked first, so concurrent assignments cannot both pass the check.
+pub async fn check_assignment(pool: &PgPool, executor: &mut sqlx::Transaction<'_, Postgres>, payload: &AssignClassRequest) -> Result<Vec<ScheduleConflict>, sqlx::Error> {
+    let mut conflicts = Vec::new();
+    let room = if payload.year == "Lab" { Some(payload.section.as_str()) } else { payload.room.as_deref() };
+    let room = room.filter(|r| !r.trim().is_empty());
+
+    timetable_repository::lock_assignment_slot(executor, &format!("faculty:{}", payload.faculty_id), &payload.day, payload.period_index).await?;
+    if let Some(room) = room {
+        timetable_repository::lock_assignment_slot(executor, &format!("room:{}", room), &payload.day, payload.period_index).await?;
+    }
+
+    for slot in timetable_repository::find_faculty_clashes(executor, payload).await? {
+        let message = format!(
+            "{} already teaches {} to {} {} ({}) on {} period {}",
+            payload.faculty_id, slot.subject, slot.year, slot.section, slot.branch, payload.day, payload.period_index
+        );
+        conflicts.push(slot_conflict("FACULTY_BUSY", message, slot));
+    }
+
+    if let Some(room) = room {
+        if let Some(r) = room_repository::find_room(pool, room).await?.filter(|r| r.status != "ACTIVE") {
+            conflicts.push(ScheduleConflict {
+                kind: "ROOM_UNAVAILABLE".to_string(),
+                message: format!("{} is {}", r.code, r.status.to_lowercase()),
+                branch: None,
+                year: None,
+                section: None,
+                subject: None,
+                faculty_id: None,
+            });
+        }
+        if let Some(slot) = timetable_repository::find_room_occupant(executor, payload, room).await? {
+            let message = format!("{} is occupied by {} ({}) on {} period {}", room, slot.subject, slot.faculty_id, payload.day, payload.period_index);
+            conflicts.push(slot_conflict("ROOM_OCCUPIED", message, slot));
+        }
+    }
+
+    // Lab grid rows describe room usage rather than teaching, so they carry no subject assignment.
+    if payload.year != "Lab" {
+        let variations = get_branch_variations(&normalize_branch(&payload.branch));
+        if !timetable_repository::faculty_has_subject(pool, payload, &variations).await? {
+            conflicts.push(ScheduleConflict {
+                kind: "SUBJECT_NOT_ASSIGNED".to_string(),
+                message: format!("{} is not assigned {} in faculty subjects", payload.faculty_id, payload.subject),
+                branch: None,
+                year: None,
+                section: None,
+                subject: Some(payload.subject.clone()),
+                faculty_id: Some(payload.faculty_id.clone()),
+            });
+        }
+    }
+
+    Ok(conflicts)
+}
+
+// --- Versions ---
+
+type SlotKey = (String, String, String, i32); // (year, section, day, period_index)
+
+fn parse_effective(from: &str, to: Option<&str>) -> Result<(NaiveDate, Option<NaiveDate>), (StatusCode, String)> {
+    let parse = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid date: {}", s)));
+    let from = parse(from)?;
+    let to = to.map(parse).transpose()?;
+    if to.map(|t| t < from).unwrap_or(false) {
+        return Err((StatusCode::BAD_REQUEST, "effectiveTo is before effectiveFrom".to_string()));
+    }
+    Ok((from, to))
+}
+
+/// Entries of a version. The live version answers from the live grid so edits made since it went live are included.
+async fn version_entries(pool: &PgPool, version: &TimetableVersion) -> Result<Vec<DraftEntry>, sqlx::Error> {
+    if version.is_live {
+        timetable_repository::find_live_entries(pool, &version.branch).await
+    } else {
+        timetable_repository::find_version_entries(pool, version.id).await
+    }
+}
+
+async fn find_version_or_404(pool: &PgPool, id: Uuid) -> Result<TimetableVersion, (StatusCode, String)> {
+    timetable_repository::find_version(pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Timetable version not found".to_string()))
+}
+
+pub async fn create_version(pool: &PgPool, payload: CreateVersionRequest) -> Result<Uuid, (StatusCode, String)> {
+    let created_by = resolve_user_id(&payload.created_by, "HOD", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.created_by)))?;
+    let effective = parse_effective(&payload.effective_from, payload.effective_to.as_deref())?;
+    let branch = normalize_branch(&payload.branch);
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let id = timetable_repository::insert_version(&mut tx, &branch, &payload.name, effective, None, created_by)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    timetable_repository::snapshot_live_grid(&mut tx, id, &branch)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(id)
+}
+
+pub async fn clone_version(pool: &PgPool, source_id: Uuid, payload: CloneVersionRequest) -> Result<Uuid, (StatusCode, String)> {
+    let created_by = resolve_user_id(&payload.created_by, "HOD", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.created_by)))?;
+    let effective = parse_effective(&payload.effective_from, payload.effective_to.as_deref())?;
+    let source = find_version_or_404(pool, source_id).await?;
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let id = timetable_repository::insert_version(&mut tx, &source.branch, &payload.name, effective, Some(source_id), created_by)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let copied = if source.is_live {
+        timetable_repository::snapshot_live_grid(&mut tx, id, &source.branch).await
+    } else {
+        timetable_repository::copy_version_entries(&mut tx, source_id, id).await
+    };
+    copied.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(id)
+}
+
+pub async fn get_versions(pool: &PgPool, params: DraftListQuery) -> Result<Vec<TimetableVersion>, StatusCode> {
+    timetable_repository::find_versions(pool, &normalize_branch(&params.branch))
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch timetable versions: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+pub async fn get_version(pool: &PgPool, id: Uuid) -> Result<TimetableVersionDetail, StatusCode> {
+    let version = timetable_repository::find_version(pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let entries = version_entries(pool, &version).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(TimetableVersionDetail { version, entries })
+}
+
+pub async fn set_version_entry(pool: &PgPool, id: Uuid, payload: VersionEntryRequest) -> Result<(), (StatusCode, String)> {
+    let version = find_version_or_404(pool, id).await?;
+    if version.status != "DRAFT" {
+        return Err((StatusCode::CONFLICT, "Published versions cannot be edited; clone it instead".to_string()));
+    }
+
+    let entry = DraftEntry {
+        year: payload.year,
+        section: payload.section,
+        day: payload.day,
+        period_index: payload.period_index,
+        subject: payload.subject.unwrap_or_default(),
+        subject_code: payload.subject_code,
+        faculty_id: payload.faculty_id.unwrap_or_default(),
+        lab_room: payload.room.filter(|r| !r.trim().is_empty()),
+    };
+    let res = if payload.clear {
+        timetable_repository::delete_version_entry(pool, id, &entry).await
+    } else {
+        if entry.subject.trim().is_empty() || entry.faculty_id.trim().is_empty() {
+            return Err((StatusCode::BAD_REQUEST, "subject and facultyId are required".to_string()));
+        }
+        timetable_repository::upsert_version_entry(pool, id, &entry).await
+    };
+    res.map(|_| ()).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Publishing ends the previous version the day before this one starts. A version already starting
+/// today or earlier goes live straight away; later ones are picked up by `run_version_activation`.
+pub async fn publish_version(pool: &PgPool, id: Uuid, payload: PublishDraftRequest) -> Result<(), (StatusCode, String)> {
+    let publisher = resolve_user_id(&payload.published_by, "HOD", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.published_by)))?;
+    let version = find_version_or_404(pool, id).await?;
+    if version.status != "DRAFT" {
+        return Err((StatusCode::CONFLICT, "Version is already published".to_string()));
+    }
+
+    let later = timetable_repository::count_later_versions(pool, &version.branch, version.effective_from, version.effective_to)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if later > 0 {
+        return Err((StatusCode::CONFLICT, "Another published version starts within this version's dates".to_string()));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    timetable_repository::close_previous_versions(&mut tx, &version.branch, version.effective_from)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let updated = timetable_repository::mark_version_published(&mut tx, id, publisher)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if updated == 0 {
+        return Err((StatusCode::CONFLICT, "Version is already published".to_string()));
+    }
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    activate_due_versions(pool).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(())
+}
+
+pub async fn diff_versions(pool: &PgPool, params: VersionDiffQuery) -> Result<Vec<VersionDiffItem>, (StatusCode, String)> {
+    let from = find_version_or_404(pool, params.from).await?;
+    let to = find_version_or_404(pool, params.to).await?;
+    let key = |e: &DraftEntry| (e.year.clone(), e.section.clone(), e.day.clone(), e.period_index);
+
+    let mut slots: BTreeMap<SlotKey, (Option<DraftEntry>, Option<DraftEntry>)> = BTreeMap::new();
+    for e in version_entries(pool, &from).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))? {
+        let k = key(&e);
+        slots.entry(k).or_default().0 = Some(e);
+    }
+    for e in version_entries(pool, &to).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))? {
+        let k = key(&e);
+        slots.entry(k).or_default().1 = Some(e);
+    }
+
+    Ok(slots
+        .into_iter()
+        .filter_map(|((year, section, day, period_index), (before, after))| {
+            let change = match (&before, &after) {
+                (None, Some(_)) => "ADDED",
+                (Some(_), None) => "REMOVED",
+                (Some(b), Some(a)) if b.subject != a.subject || b.subject_code != a.subject_code || b.faculty_id != a.faculty_id || b.lab_room != a.lab_room => "CHANGED",
+                _ => return None,
+            };
+            Some(VersionDiffItem { year, section, day, period_index, change: change.to_string(), before, after })
+        })
+        .collect())
+}
+
+/// Makes the version in effect today live for each branch. The outgoing live version first takes a
+/// snapshot of the live grid, so edits made while it was live stay in its history.
+pub async fn activate_due_versions(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let mut activated = 0;
+    for (id, branch, is_live) in timetable_repository::find_current_versions(pool).await? {
+        if is_live {
+            continue;
+        }
+        let mut tx = pool.begin().await?;
+        if let Some(previous) = timetable_repository::find_live_version(&mut tx, &branch).await? {
+            timetable_repository::snapshot_live_grid(&mut tx, previous, &branch).await?;
+            timetable_repository::set_version_live(&mut tx, previous, false).await?;
+        }
+        timetable_repository::load_live_grid(&mut tx, id, &branch).await?;
+        timetable_repository::set_version_live(&mut tx, id, true).await?;
+        tx.commit().await?;
+        activated += 1;
+    }
+    Ok(activated)
+}
+
+pub async fn run_version_activation(pool: PgPool) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(VERSION_ACTIVATION_MINUTES * 60));
+    loop {
+        interval.tick().await;
+        if let Err(e) = activate_due_versions(&pool).await {
+            eprintln!("ERROR: Failed to activate timetable versions: {:?}", e);
+        }
+    }
+}
+
+/// Version whose snapshot answers lookups for `date`, or `None` when the live grid does.
+pub async fn resolve_snapshot_version(pool: &PgPool, branch: &str, date: NaiveDate) -> Result<Option<Uuid>, sqlx::Error> {
+    timetable_repository::find_snapshot_version(pool, branch, date).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requirement(class: &str, code: &str, faculty: &str, weekly_periods: i32, lab_block: Option<i32>) -> SubjectRequirement {
+        SubjectRequirement {
+            year: "2nd Year".to_string(),
+            section: class.to_string(),
+            subject_code: code.to_string(),
+            subject_name: code.to_string(),
+            faculty_id: faculty.to_string(),
+            weekly_periods,
+            lab_block,
+        }
+    }
+
+    fn input(classes: &[&str], days: &[&str], segments: Vec<Vec<i32>>, requirements: Vec<SubjectRequirement>) -> GeneratorInput {
+        GeneratorInput {
+            classes: classes.iter().map(|c| ClassKey { year: "2nd Year".to_string(), section: c.to_string() }).collect(),
+            requirements,
+            days: days.iter().map(|d| d.to_string()).collect(),
+            segments,
+            lab_windows: Vec::new(),
+            saturday_periods: None,
+            lab_rooms: Vec::new(),
+            faculty_busy: HashSet::new(),
+            unavailable: Vec::new(),
+            max_consecutive_subject: DEFAULT_MAX_CONSECUTIVE_SUBJECT,
+            max_consecutive_faculty: DEFAULT_MAX_CONSECUTIVE_FACULTY,
+        }
+    }
+
+    /// Periods of `day` held by entries matching `pred`, in order.
+    fn periods(out: &GeneratorOutput, day: &str, pred: impl Fn(&DraftEntry) -> bool) -> Vec<i32> {
+        let mut p: Vec<i32> = out.entries.iter().filter(|e| e.day == day && pred(e)).map(|e| e.period_index).collect();
+        p.sort();
+        p
+    }
+
+    fn longest_run(periods: &[i32]) -> usize {
+        let mut best = 0;
+        let mut run = 0;
+        for (i, p) in periods.iter().enumerate() {
+            run = if i > 0 && periods[i - 1] + 1 == *p { run + 1 } else { 1 };
+            best = best.max(run);
+        }
+        best
+    }
+
+    #[test]
+    fn never_double_books_a_class_or_faculty_member() {
+        let mut input = input(
+            &["A", "B"],
+            &["Monday", "Tuesday"],
+            vec![vec![1, 2, 3], vec![4, 5, 6]],
+            vec![
+                requirement("A", "CS301", "F1", 4, None),
+                requirement("B", "CS301", "F1", 4, None),
+                requirement("A", "CS302", "F2", 3, None),
+                requirement("B", "CS302", "F2", 3, None),
+            ],
+        );
+        // Busy in another branch's timetable, stored with different casing.
+        input.faculty_busy.insert(("F1".to_string(), "monday".to_string(), 1));
+        input.unavailable.push(FacultyUnavailability { faculty_id: "F2".to_string(), day: "TUESDAY".to_string(), period_index: None });
+
+        let out = generate(&input);
+        let mut faculty_slots = HashSet::new();
+        let mut class_slots = HashSet::new();
+        for e in &out.entries {
+            assert!(faculty_slots.insert((e.faculty_id.clone(), e.day.clone(), e.period_index)), "faculty double-booked: {:?}", e);
+            assert!(class_slots.insert((e.section.clone(), e.day.clone(), e.period_index)), "class double-booked: {:?}", e);
+        }
+        assert!(!faculty_slots.contains(&("F1".to_string(), "Monday".to_string(), 1)));
+        assert!(out.entries.iter().all(|e| !(e.faculty_id == "F2" && e.day == "Tuesday")));
+        assert!(out.unplaced.is_empty(), "{:?}", out.unplaced);
+    }
+
+    #[test]
+    fn places_labs_as_contiguous_blocks_inside_one_segment() {
+        let segments = vec![vec![1, 2, 3, 4], vec![5, 6, 7]];
+        let input = input(
+            &["A", "B"],
+            &["Monday", "Tuesday", "Wednesday"],
+            segments.clone(),
+            vec![
+                requirement("A", "CS351", "F1", 3, Some(3)),
+                requirement("B", "CS351", "F1", 3, Some(3)),
+                requirement("A", "CS352", "F2", 6, Some(3)),
+                requirement("A", "CS301", "F3", 4, None),
+            ],
+        );
+
+        let out = generate(&input);
+        assert!(out.unplaced.is_empty(), "{:?}", out.unplaced);
+        for class in ["A", "B"] {
+            for day in &input.days {
+                for code in ["CS351", "CS352"] {
+                    let block = periods(&out, day, |e| e.section == class && e.subject_code.as_deref() == Some(code));
+                    if block.is_empty() {
+                        continue;
+                    }
+                    assert_eq!(block.len(), 3, "{} {} on {}: {:?}", class, code, day, block);
+                    assert_eq!(longest_run(&block), 3, "{} {} on {} is split: {:?}", class, code, day, block);
+                    assert!(segments.iter().any(|s| block.iter().all(|p| s.contains(p))), "{} {} on {} crosses lunch: {:?}", class, code, day, block);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_consecutive_periods_within_the_limits_when_there_is_room() {
+        let mut input = input(
+            &["A", "B"],
+            &["Monday", "Tuesday", "Wednesday"],
+            vec![vec![1, 2, 3, 4, 5, 6]],
+            vec![
+                requirement("A", "CS301", "F1", 5, None),
+                requirement("B", "CS302", "F1", 5, None),
+                requirement("A", "CS303", "F2", 5, None),
+            ],
+        );
+        input.max_consecutive_subject = 2;
+        input.max_consecutive_faculty = 3;
+
+        let out = generate(&input);
+        assert!(out.unplaced.is_empty(), "{:?}", out.unplaced);
+        for day in &input.days {
+            for class in ["A", "B"] {
+                for code in ["CS301", "CS302", "CS303"] {
+                    let p = periods(&out, day, |e| e.section == class && e.subject_code.as_deref() == Some(code));
+                    assert!(longest_run(&p) <= 2, "{} {} on {}: {:?}", class, code, day, p);
+                }
+            }
+            for faculty in ["F1", "F2"] {
+                let p = periods(&out, day, |e| e.faculty_id == faculty);
+                assert!(longest_run(&p) <= 3, "{} on {}: {:?}", faculty, day, p);
+            }
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::models::curriculum::CurriculumJson;
+use crate::models::topic_feedback::{
+    FacultyFeedbackAnalytics, FacultyFeedbackQuery, FeedbackCluster, FeedbackInboxQuery, IssueBreakdown, IssueTrend,
+    ResolveFeedbackRequest, RevisionSession, RevisionSessionQuery, ScheduleRevisionRequest, StudentFeedbackStatus,
+    SubjectFeedbackAnalytics, TopicFeedbackAnalytics, TopicFeedbackQuery, UpdateRevisionRequest,
+};
+use crate::repositories::{leave_repository, topic_feedback_repository};
+use crate::services::curriculum_service::{self, DEFAULT_REGULATION};
+use crate::services::regulation_service;
+use crate::utils::timing_utils;
+use crate::utils::user_utils::resolve_user_id;
+
+/// A topic needs a revision class once this many students have responded and either fewer than
+/// `REVISION_UNDERSTOOD_BELOW` percent understood it or the average rating is below
+/// `REVISION_RATING_BELOW`.
+const REVISION_MIN_RESPONSES: i64 = 3;
+const REVISION_UNDERSTOOD_BELOW: f64 = 60.0;
+const REVISION_RATING_BELOW: f64 = 3.0;
+
+fn internal(e: sqlx::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// Topic names of the subject's current curriculum by topic identity and by topic id.
+async fn topic_names(pool: &PgPool, branch: &str, regulation: &str, subject_code: &str) -> Result<HashMap<String, String>, (StatusCode, String)> {
+    let curriculum: Option<CurriculumJson> = curriculum_service::find_current_curriculum(pool, branch, regulation, subject_code)
+        .await
+        .map_err(internal)?;
+    let mut names = HashMap::new();
+    for topic in curriculum.iter().flat_map(|c| c.units.iter()).flat_map(|u| u.topics.iter()) {
+        if let Some(uid) = topic.uid {
+            names.insert(uid.to_string(), topic.topic.clone());
+        }
+        names.insert(topic.id.clone(), topic.topic.clone());
+    }
+    Ok(names)
+}
+
+fn lookup_name(names: &HashMap<String, String>, topic_uid: Option<Uuid>, topic_id: &str) -> Option<String> {
+    topic_uid.and_then(|u| names.get(&u.to_string())).or_else(|| names.get(topic_id)).cloned()
+}
+
+pub async fn get_subject_analytics(pool: &PgPool, params: TopicFeedbackQuery) -> Result<SubjectFeedbackAnalytics, (StatusCode, String)> {
+    let branch = normalize_branch(&params.branch);
+    let variations = get_branch_variations(&branch);
+    let year = params.year.as_deref().filter(|y| !y.trim().is_empty());
+    let section = params.section.as_deref().filter(|s| !s.trim().is_empty());
+    let rows = topic_feedback_repository::find_topic_stats(pool, &params.subject_code, &variations, year, section, params.from, params.to)
+        .await
+        .map_err(internal)?;
+    let trend = topic_feedback_repository::find_issue_trend(pool, &params.subject_code, &variations, year, section, params.from, params.to)
+        .await
+        .map_err(internal)?;
+    let regulation = params.regulation.as_deref().unwrap_or(DEFAULT_REGULATION);
+    let names = topic_names(pool, &branch, regulation, &params.subject_code).await?;
+
+    let mut topics: Vec<TopicFeedbackAnalytics> = rows
+        .into_iter()
+        .map(|r| {
+            let understood_percent = if r.responses > 0 { r.understood as f64 * 100.0 / r.responses as f64 } else { 0.0 };
+            let needs_revision = r.responses >= REVISION_MIN_RESPONSES
+                && (understood_percent < REVISION_UNDERSTOOD_BELOW || r.average_rating.is_some_and(|a| a < REVISION_RATING_BELOW));
+            TopicFeedbackAnalytics {
+                topic_name: lookup_name(&names, r.topic_uid, &r.topic_id),
+                topic_id: r.topic_id,
+                topic_uid: r.topic_uid,
+                responses: r.responses,
+                understood_percent,
+                average_rating: r.average_rating,
+                rating_distribution: [r.rating_1, r.rating_2, r.rating_3, r.rating_4, r.rating_5],
+                issues: IssueBreakdown { done: r.done, not_understood: r.not_understood, not_done: r.not_done },
+                open_items: r.open_items,
+                needs_revision,
+            }
+        })
+        .collect();
+    // Topics needing revision first, then the least understood
+    topics.sort_by(|a, b| {
+        b.needs_revision
+            .cmp(&a.needs_revision)
+            .then(a.understood_percent.total_cmp(&b.understood_percent))
+            .then(a.topic_id.cmp(&b.topic_id))
+    });
+
+    Ok(SubjectFeedbackAnalytics {
+        subject_code: params.subject_code,
+        branch,
+        section: section.map(str::to_string),
+        topics,
+        trend: trend
+            .into_iter()
+            .map(|(week_start, done, not_understood, not_done)| IssueTrend { week_start, issues: IssueBreakdown { done, not_understood, not_done } })
+            .collect(),
+    })
+}
+
+pub async fn get_faculty_analytics(pool: &PgPool, params: FacultyFeedbackQuery) -> Result<Vec<FacultyFeedbackAnalytics>, (StatusCode, String)> {
+    let variations = get_branch_variations(&normalize_branch(&params.branch));
+    topic_feedback_repository::find_faculty_stats(
+        pool,
+        &variations,
+        params.from,
+        params.to,
+        REVISION_MIN_RESPONSES,
+        REVISION_UNDERSTOOD_BELOW,
+        REVISION_RATING_BELOW,
+    )
+    .await
+    .map_err(internal)
+}
+
+pub async fn get_inbox(pool: &PgPool, params: FeedbackInboxQuery) -> Result<Vec<FeedbackCluster>, (StatusCode, String)> {
+    let user_id = resolve_user_id(&params.faculty_id, "Faculty", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", params.faculty_id)))?;
+    let (login_id, _, _, _, _, _) = leave_repository::find_user_basics(pool, user_id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    let open_only = !params.status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("all"));
+    let mut clusters = topic_feedback_repository::find_inbox(pool, &login_id, open_only).await.map_err(internal)?;
+
+    // Topic names come from the curriculum of the regulation each section's batch studies under.
+    let mut subjects: Vec<(String, String, String)> = clusters.iter().map(|c| (c.branch.clone(), c.year.clone(), c.subject_code.clone())).collect();
+    subjects.sort();
+    subjects.dedup();
+    let mut names = HashMap::new();
+    for (branch, year, subject_code) in subjects {
+        let subject_names = topic_names(pool, &branch, regulation_service::batch_regulation(&year), &subject_code).await?;
+        names.insert((branch, year, subject_code), subject_names);
+    }
+    for cluster in &mut clusters {
+        if let Some(subject_names) = names.get(&(cluster.branch.clone(), cluster.year.clone(), cluster.subject_code.clone())) {
+            cluster.topic_name = lookup_name(subject_names, cluster.topic_uid, &cluster.topic_id);
+        }
+    }
+    Ok(clusters)
+}
+
+/// Checks the user may act on feedback about a section: the faculty member teaching it the
+/// subject (checked by the caller against the timetable), the branch's HOD, or Admin, Principal and
+/// Coordinators. Returns (user id, login id, whether the role alone allows it).
+async fn responder(pool: &PgPool, login: &str, branch: Option<&str>) -> Result<(Uuid, String, bool), (StatusCode, String)> {
+    let user_id = resolve_user_id(login, "Faculty", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", login)))?;
+    let (login_id, role, _, user_branch, _, _) = leave_repository::find_user_basics(pool, user_id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    let privileged = match role.as_str() {
+        "Admin" | "Principal" | "Coordinator" => true,
+        "HOD" => branch.is_some_and(|b| user_branch.as_deref().map(normalize_branch).as_deref() == Some(b)),
+        _ => false,
+    };
+    Ok((user_id, login_id, privileged))
+}
+
+async fn notify_students(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>, students: &[String], message: &str, branch: &str) -> Result<(), sqlx::Error> {
+    let mut notified: Vec<&String> = students.iter().collect();
+    notified.sort();
+    notified.dedup();
+    for student in notified {
+        topic_feedback_repository::insert_notification(tx, message, student, branch).await?;
+    }
+    Ok(())
+}
+
+/// Schedules a class re-teaching a topic to a section and marks the section's open feedback on
+/// it as acknowledged; the students who gave it are told when the class is.
+pub async fn schedule_revision(pool: &PgPool, payload: ScheduleRevisionRequest) -> Result<RevisionSession, (StatusCode, String)> {
+    let branch = normalize_branch(&payload.branch);
+    let variations = get_branch_variations(&branch);
+    let (user_id, login_id, privileged) = responder(pool, &payload.faculty_id, Some(&branch)).await?;
+    if !privileged
+        && !topic_feedback_repository::teaches_section(pool, &login_id, &payload.subject_code, &variations, &payload.year, &payload.section)
+            .await
+            .map_err(internal)?
+    {
+        return Err((StatusCode::FORBIDDEN, format!("You do not teach {} to {} {}", payload.subject_code, payload.year, payload.section)));
+    }
+    if payload.scheduled_date < timing_utils::campus_now().date() {
+        return Err((StatusCode::BAD_REQUEST, "A revision class cannot be scheduled in the past".to_string()));
+    }
+
+    let mut tx = pool.begin().await.map_err(internal)?;
+    let session_id = topic_feedback_repository::insert_session(&mut tx, &payload, &branch, user_id).await.map_err(internal)?;
+    let students = topic_feedback_repository::link_feedback(&mut tx, session_id, &variations, payload.feedback_ids.as_deref())
+        .await
+        .map_err(internal)?;
+    let message = format!(
+        "A revision class on topic {} of {} is scheduled for {} in response to your feedback.",
+        payload.topic_id,
+        payload.subject_code,
+        payload.scheduled_date.format("%d %b %Y")
+    );
+    notify_students(&mut tx, &students, &message, &branch).await.map_err(internal)?;
+    tx.commit().await.map_err(internal)?;
+
+    topic_feedback_repository::find_session(pool, session_id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Scheduled session not found".to_string()))
+}
+
+pub async fn get_revisions(pool: &PgPool, params: RevisionSessionQuery) -> Result<Vec<RevisionSession>, (StatusCode, String)> {
+    let faculty_login = match params.faculty_id.as_deref().filter(|f| !f.trim().is_empty()) {
+        Some(f) => {
+            let user_id = resolve_user_id(f, "Faculty", pool)
+                .await
+                .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", f)))?;
+            let (login_id, _, _, _, _, _) = leave_repository::find_user_basics(pool, user_id)
+                .await
+                .map_err(internal)?
+                .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+            Some(login_id)
+        }
+        None => None,
+    };
+    let variations = params.branch.as_deref().map(|b| get_branch_variations(&normalize_branch(b)));
+    topic_feedback_repository::find_sessions(pool, faculty_login.as_deref(), variations.as_deref(), params.section.as_deref())
+        .await
+        .map_err(internal)
+}
+
+/// Marks a scheduled revision class conducted, which resolves the feedback it answered, or
+/// cancelled, which returns that feedback to the inbox.
+pub async fn update_revision(pool: &PgPool, payload: UpdateRevisionRequest) -> Result<RevisionSession, (StatusCode, String)> {
+    let status = payload.status.trim().to_uppercase();
+    if status != "CONDUCTED" && status != "CANCELLED" {
+        return Err((StatusCode::BAD_REQUEST, "status must be CONDUCTED or CANCELLED".to_string()));
+    }
+    let session = topic_feedback_repository::find_session(pool, payload.session_id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, "Revision session not found".to_string()))?;
+    let (user_id, login_id, privileged) = responder(pool, &payload.updated_by, Some(&normalize_branch(&session.branch))).await?;
+    if !privileged && login_id != session.faculty_login {
+        return Err((StatusCode::FORBIDDEN, "Only the faculty member who scheduled the class can update it".to_string()));
+    }
+
+    let mut tx = pool.begin().await.map_err(internal)?;
+    if topic_feedback_repository::update_session_status(&mut tx, session.id, &status).await.map_err(internal)? == 0 {
+        return Err((StatusCode::CONFLICT, format!("The revision class is already {}", session.status.to_lowercase())));
+    }
+    let note = payload.note.clone().filter(|n| !n.trim().is_empty());
+    let date = session.scheduled_date.format("%d %b %Y");
+    let (students, message) = if status == "CONDUCTED" {
+        let note = note.unwrap_or_else(|| format!("Revision class conducted on {}", date));
+        let students = topic_feedback_repository::resolve_session_feedback(&mut tx, session.id, user_id, &note).await.map_err(internal)?;
+        (students, format!("Your feedback on topic {} of {} was addressed: {}.", session.topic_id, session.subject_code, note))
+    } else {
+        let students = topic_feedback_repository::release_session_feedback(&mut tx, session.id).await.map_err(internal)?;
+        (students, format!("The revision class on topic {} of {} planned for {} was cancelled.", session.topic_id, session.subject_code, date))
+    };
+    notify_students(&mut tx, &students, &message, &session.branch).await.map_err(internal)?;
+    tx.commit().await.map_err(internal)?;
+
+    topic_feedback_repository::find_session(pool, session.id)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Revision session not found".to_string()))
+}
+
+/// Closes feedback the faculty member dealt with another way (a clarification, a shared note).
+/// Returns how many items were closed.
+pub async fn resolve_feedback(pool: &PgPool, payload: ResolveFeedbackRequest) -> Result<usize, (StatusCode, String)> {
+    let note = payload.note.trim();
+    if note.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Say how the feedback was addressed".to_string()));
+    }
+    if payload.feedback_ids.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "No feedback selected".to_string()));
+    }
+    let (user_id, login_id, privileged) = responder(pool, &payload.resolved_by, None).await?;
+    let mut ids = payload.feedback_ids.clone();
+    ids.sort_unstable();
+    ids.dedup();
+    if !privileged
+        && topic_feedback_repository::count_feedback_taught_by(pool, &login_id, &ids).await.map_err(internal)? != ids.len() as i64
+    {
+        return Err((StatusCode::FORBIDDEN, "You can only close feedback from sections you teach".to_string()));
+    }
+
+    let mut tx = pool.begin().await.map_err(internal)?;
+    let closed = topic_feedback_repository::resolve_feedback(&mut tx, &ids, user_id, note).await.map_err(internal)?;
+    for (student, subject_code, branch) in &closed {
+        let message = format!("Your feedback on {} was addressed: {}.", subject_code, note);
+        topic_feedback_repository::insert_notification(&mut tx, &message, student, branch.as_deref().unwrap_or_default())
+            .await
+            .map_err(internal)?;
+    }
+    tx.commit().await.map_err(internal)?;
+    Ok(closed.len())
+}
+
+pub async fn get_student_feedback(pool: &PgPool, student: &str) -> Result<Vec<StudentFeedbackStatus>, (StatusCode, String)> {
+    let student_id = resolve_user_id(student, "Student", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid student: {}", student)))?;
+    topic_feedback_repository::find_student_feedback(pool, student_id).await.map_err(internal)
+}
@@ -14,7 +14,16 @@ use crate::models::{
     SemesterSubjectResponse, LessonTopicResponse, StudentAttendanceItem,
     AttendanceStatsResponse
 };
+use crate::models::attendance::AttendanceWrite;
+use crate::models::timetable::ScheduleConflict;
 use crate::repositories::user::faculty_repository;
+use crate::repositories::attendance_repository;
+use crate::repositories::timetable_repository;
+use crate::services::attendance_service;
+use crate::services::timetable_service;
+use crate::services::academic_calendar_service;
+use chrono::NaiveDate;
+use crate::utils::timing_utils;
 use crate::utils::user_utils::resolve_user_id;
 
 pub async fn get_faculty_profile(pool: &PgPool, user_id: &str) -> Result<FacultyProfileResponse, StatusCode> {
@@ -101,11 +110,18 @@ pub async fn remove_faculty_subject(pool: &PgPool, payload: RemoveFacultySubject
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
 }
 
+/// Kept for lesson plan clients; the progress is recorded in curriculum_progress with everything else.
 pub async fn mark_lesson_plan_complete(pool: &PgPool, payload: MarkCompleteRequest) -> Result<(), StatusCode> {
-    faculty_repository::update_lesson_plan_complete(pool, &payload.item_id, payload.section.as_deref().unwrap_or(""), payload.completed)
-        .await
-        .map(|_| ())
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    let faculty_id = match payload.faculty_id.as_deref() {
+        Some(login) => Some(resolve_user_id(login, "Faculty", pool).await.map_err(|_| StatusCode::BAD_REQUEST)?),
+        None => None,
+    };
+    let section = payload.section.as_deref().unwrap_or("").trim();
+    match crate::services::curriculum_service::update_lesson_item_progress(pool, &payload.item_id, section, payload.completed, faculty_id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
 }
 
 pub async fn reply_to_feedback(pool: &PgPool, payload: ReplyFeedbackRequest) -> Result<(), StatusCode> {
@@ -151,14 +167,31 @@ pub async fn move_students(pool: &PgPool, payload: MoveStudentsRequest) -> Resul
 }
 
 pub async fn submit_attendance(pool: &PgPool, payload: SubmitAttendanceRequest) -> Result<(), (StatusCode, String)> {
-    let mut tx = pool.begin().await.map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to start transaction".to_string()))?;
-    
     let student_uuid = resolve_user_id(&payload.student_id, "Student", pool).await.map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid Student ID: {}", payload.student_id)))?;
     let faculty_uuid = resolve_user_id(&payload.faculty_id, "Faculty", pool).await.map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid Faculty ID: {}", payload.faculty_id)))?;
+
+    let branch = attendance_repository::find_student_branch(pool, student_uuid)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .unwrap_or_default();
+    attendance_service::ensure_editable(pool, &branch, &payload.date).await?;
+
+    let mut tx = pool.begin().await.map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to start transaction".to_string()))?;
     
     let session = payload.session.as_deref().unwrap_or("MORNING").to_uppercase();
     
-    faculty_repository::insert_attendance(&mut tx, student_uuid, &payload.student_id, faculty_uuid, &payload.date, &payload.status, &session, "")
+    attendance_service::record_attendance(&mut tx, AttendanceWrite {
+        student_uuid,
+        student_login_id: &payload.student_id,
+        faculty_uuid,
+        date: &payload.date,
+        status: &payload.status,
+        session: &session,
+        section: "",
+        change_source: "MANUAL",
+        reason: payload.reason.as_deref(),
+        correction_request_id: None,
+    })
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
@@ -177,7 +210,7 @@ pub async fn submit_attendance_batch(pool: &PgPool, payload: BatchAttendanceRequ
 
     // Optimize: Fetch all student UUIDs in one query
     let student_ids: Vec<String> = payload.records.iter().map(|r| r.student_id.clone()).collect();
-    let student_map_rows = sqlx::query("SELECT id, login_id FROM users WHERE login_id = ANY($1) AND role = 'Student'")
+    let student_map_rows = sqlx::query("SELECT id, login_id, branch FROM users WHERE login_id = ANY($1) AND role = 'Student'")
         .bind(&student_ids)
         .fetch_all(pool)
         .await
@@ -186,13 +219,19 @@ pub async fn submit_attendance_batch(pool: &PgPool, payload: BatchAttendanceRequ
             (StatusCode::INTERNAL_SERVER_ERROR, "Database error during student resolution".to_string())
         })?;
 
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     let mut student_id_to_uuid = HashMap::new();
+    let mut branches = HashSet::new();
     for row in student_map_rows {
         use sqlx::Row;
         let id: Uuid = row.get("id");
         let login_id: String = row.get("login_id");
         student_id_to_uuid.insert(login_id, id);
+        branches.insert(row.get::<Option<String>, _>("branch").unwrap_or_default());
+    }
+
+    for branch in &branches {
+        attendance_service::ensure_editable(pool, branch, &payload.date).await?;
     }
 
     let mut tx = pool.begin().await.map_err(|e| {
@@ -211,7 +250,18 @@ pub async fn submit_attendance_batch(pool: &PgPool, payload: BatchAttendanceRequ
             }
         };
         
-        faculty_repository::insert_attendance(&mut tx, user_uuid, &record.student_id, faculty_uuid, &payload.date, &record.status, &session, &payload.section)
+        attendance_service::record_attendance(&mut tx, AttendanceWrite {
+            student_uuid: user_uuid,
+            student_login_id: &record.student_id,
+            faculty_uuid,
+            date: &payload.date,
+            status: &record.status,
+            session: &session,
+            section: &payload.section,
+            change_source: "BATCH",
+            reason: payload.reason.as_deref(),
+            correction_request_id: None,
+        })
             .await
             .map_err(|e| {
                 eprintln!("ERROR: Failed to insert attendance for {}: {:?}", record.student_id, e);
@@ -234,7 +284,7 @@ pub async fn check_attendance_status(pool: &PgPool, params: CheckAttendanceQuery
     
     let count: i64 = sqlx::query_scalar(
         "SELECT COUNT(*) FROM attendance 
-         WHERE date = $1::DATE AND session = $2 
+         WHERE date = $1::DATE AND session = $2 AND status <> 'L'
          AND student_uuid IN (
              SELECT id FROM users 
              WHERE role = 'Student' AND branch = ANY($3) AND year = $4 AND (section = $5 OR $5 IS NULL)
@@ -319,6 +369,15 @@ pub async fn bulk_create_students(pool: &PgPool, payloads: Vec<CreateStudentRequ
     Ok(())
 }
 
+/// Whether the academic calendar has classes for the query's branch and year on its date.
+async fn stats_day_instructional(pool: &PgPool, params: &AttendanceStatsQuery) -> Result<bool, StatusCode> {
+    let date = NaiveDate::parse_from_str(&params.date, "%Y-%m-%d").map_err(|_| StatusCode::BAD_REQUEST)?;
+    academic_calendar_service::is_instructional_day(pool, date, Some(&params.branch), params.year.as_deref())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// On days without classes in the academic calendar nothing counts as present or absent.
 pub async fn get_attendance_stats_v2(pool: &PgPool, params: AttendanceStatsQuery) -> Result<AttendanceStatsResponse, StatusCode> {
     let session = params.session.as_deref().map(|s| s.to_uppercase());
     let section = params.section.as_deref().and_then(|s| if s.to_uppercase() == "ALL" || s.is_empty() { None } else { Some(s) });
@@ -327,12 +386,21 @@ pub async fn get_attendance_stats_v2(pool: &PgPool, params: AttendanceStatsQuery
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     
-    serde_json::from_value(stats).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    let mut stats: AttendanceStatsResponse = serde_json::from_value(stats).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !stats_day_instructional(pool, &params).await? {
+        stats.total_present = 0;
+        stats.total_absent = 0;
+        stats.is_marked = false;
+    }
+    Ok(stats)
 }
 
 pub async fn get_absent_students(pool: &PgPool, params: AttendanceStatsQuery) -> Result<Vec<StudentAttendanceItem>, StatusCode> {
     let session = params.session.as_deref().map(|s| s.to_uppercase());
     let section = params.section.as_deref().and_then(|s| if s.to_uppercase() == "ALL" || s.is_empty() { None } else { Some(s) });
+    if !stats_day_instructional(pool, &params).await? {
+        return Ok(Vec::new());
+    }
     
     faculty_repository::find_absent_students(pool, &params.branch, params.year.as_deref(), section, &params.date, session.as_deref())
         .await
@@ -438,12 +506,19 @@ pub async fn approve_attendance_correction(pool: &PgPool, payload: ApproveAttend
     // Resolve student's user_uuid from payload.sender_id (which is their login_id)
     let user_uuid = resolve_user_id(&payload.sender_id, "Student", pool).await.map_err(|_| (StatusCode::BAD_REQUEST, "Invalid sender".to_string()))?;
 
-    let (request_id, dates) = if let Some(rid) = payload.request_id {
+    let (request_id, dates, reason) = if let Some(rid) = payload.request_id {
         let req = faculty_repository::find_correction_request(pool, rid).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?.ok_or((StatusCode::NOT_FOUND, "Request not found".to_string()))?;
-        (rid, req["dates"].as_array().cloned().unwrap_or_default())
+        (rid, req["dates"].as_array().cloned().unwrap_or_default(), req["reason"].as_str().map(|r| r.to_string()))
     } else {
         let req = faculty_repository::find_pending_correction_request_by_user(pool, user_uuid).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?.ok_or((StatusCode::NOT_FOUND, "Request not found".to_string()))?;
-        (req["id"].as_str().and_then(|s| Uuid::parse_str(s).ok()).unwrap_or_default(), req["dates"].as_array().cloned().unwrap_or_default())
+        (req["id"].as_str().and_then(|s| Uuid::parse_str(s).ok()).unwrap_or_default(), req["dates"].as_array().cloned().unwrap_or_default(), req["reason"].as_str().map(|r| r.to_string()))
+    };
+
+    // Record the approver when the client sends it; older clients fall back to the student's own id,
+    // since Uuid::nil() violates the foreign key constraint.
+    let approver_uuid = match payload.approved_by.as_deref() {
+        Some(a) if !a.is_empty() => resolve_user_id(a, "HOD", pool).await.map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid approver: {}", a)))?,
+        _ => user_uuid,
     };
 
     if payload.action == "APPROVE" {
@@ -451,8 +526,19 @@ pub async fn approve_attendance_correction(pool: &PgPool, payload: ApproveAttend
             let date_str = d["date"].as_str().unwrap_or_default();
             let session = d["session"].as_str().unwrap_or_default();
             let section = d["section"].as_str().unwrap_or_default();
-            // Note: passing user_uuid as the faculty who approved it, since it's the HOD and we don't have their UUID in the payload right now, and Uuid::nil() violates the foreign key constraint.
-            faculty_repository::insert_attendance(&mut tx, user_uuid, &payload.sender_id, user_uuid, date_str, "P", session, section).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Insert attendance failed: {}", e)))?;
+            // Corrections are the only path allowed to change attendance after the edit window has closed.
+            attendance_service::record_attendance(&mut tx, AttendanceWrite {
+                student_uuid: user_uuid,
+                student_login_id: &payload.sender_id,
+                faculty_uuid: approver_uuid,
+                date: date_str,
+                status: "P",
+                session,
+                section,
+                change_source: "CORRECTION",
+                reason: reason.as_deref(),
+                correction_request_id: Some(request_id),
+            }).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Insert attendance failed: {}", e)))?;
         }
     }
 
@@ -494,11 +580,42 @@ pub async fn rename_section(pool: &PgPool, payload: RenameSectionRequest) -> Res
     Ok(())
 }
 
-pub async fn assign_class(pool: &PgPool, payload: AssignClassRequest) -> Result<(), StatusCode> {
-    faculty_repository::insert_timetable_entry(pool, &payload)
-        .await
-        .map(|_| ())
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+/// Saves a timetable slot after clash checks. With `force` the slot is saved anyway and the
+/// overridden conflicts are recorded against `forcedBy`; they are returned either way.
+pub async fn assign_class(pool: &PgPool, payload: AssignClassRequest) -> Result<Vec<ScheduleConflict>, (StatusCode, String, Vec<ScheduleConflict>)> {
+    let failed = |e: sqlx::Error| {
+        eprintln!("ERROR: Failed to assign class: {:?}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to assign class".to_string(), Vec::new())
+    };
+    // Checked and saved in one transaction; the override is only recorded together with the entry it let through.
+    let mut tx = pool.begin().await.map_err(failed)?;
+    let conflicts = timetable_service::check_assignment(pool, &mut tx, &payload).await.map_err(|e| {
+        eprintln!("ERROR: Failed to check timetable assignment: {:?}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to assign class".to_string(), Vec::new())
+    })?;
+
+    let mut forced_by = None;
+    if !conflicts.is_empty() {
+        if !payload.force {
+            let message = conflicts.iter().map(|c| c.message.as_str()).collect::<Vec<_>>().join("; ");
+            return Err((StatusCode::CONFLICT, message, conflicts));
+        }
+        forced_by = match payload.forced_by.as_deref() {
+            Some(id) => Some(resolve_user_id(id, "HOD", pool)
+                .await
+                .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", id), conflicts.clone()))?),
+            None => return Err((StatusCode::BAD_REQUEST, "forcedBy is required to override conflicts".to_string(), conflicts)),
+        };
+    }
+
+    faculty_repository::insert_timetable_entry(&mut tx, &payload).await.map_err(failed)?;
+    if let Some(forced_by) = forced_by {
+        timetable_repository::insert_override(&mut tx, &payload, forced_by, serde_json::to_value(&conflicts).unwrap_or_default())
+            .await
+            .map_err(failed)?;
+    }
+    tx.commit().await.map_err(failed)?;
+    Ok(conflicts)
 }
 
 pub async fn get_timetable(pool: &PgPool, params: std::collections::HashMap<String, String>) -> Result<Vec<crate::models::TimetableEntry>, StatusCode> {
@@ -515,24 +632,13 @@ pub async fn clear_class(pool: &PgPool, payload: AssignClassRequest) -> Result<(
 }
 
 pub async fn get_department_timings(pool: &PgPool, branch: Option<&str>) -> Result<Vec<crate::models::DepartmentTiming>, StatusCode> {
-    faculty_repository::find_department_timings(pool, branch)
+    faculty_repository::find_department_timings(pool, branch, timing_utils::campus_now().date())
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
 }
 
-pub async fn update_department_timings(pool: &PgPool, payload: serde_json::Value) -> Result<(), StatusCode> {
-    let branch = payload["branch"].as_str().unwrap_or("General");
-    let start_time = payload["startTime"].as_str().unwrap_or("09:00");
-    let end_time = payload["endTime"].as_str().unwrap_or("16:00");
-    let total_periods = payload["totalPeriods"].as_i64().unwrap_or(7) as i32;
-    let period_duration = payload["periodDuration"].as_i64().unwrap_or(50) as i32;
-    let lunch_after = payload["lunchAfter"].as_i64().unwrap_or(4) as i32;
-    let lunch_duration = payload["lunchDuration"].as_i64().unwrap_or(50) as i32;
-
-    faculty_repository::insert_department_timing(pool, branch, start_time, end_time, total_periods, period_duration, lunch_after, lunch_duration)
-        .await
-        .map(|_| ())
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+pub async fn update_department_timings(pool: &PgPool, payload: crate::models::slot_template::UpdateDepartmentTimingRequest) -> Result<(), (StatusCode, String)> {
+    crate::services::slot_template_service::save_department_timing(pool, payload).await
 }
 
 pub async fn get_courses(pool: &PgPool) -> Result<Vec<CourseResponse>, StatusCode> {
@@ -556,11 +662,20 @@ pub async fn get_lesson_topics(pool: &PgPool, params: LessonTopicsQuery) -> Resu
         })
 }
 
-pub async fn assign_lesson_schedule(pool: &PgPool, payload: AssignLessonScheduleRequest) -> Result<(), StatusCode> {
-    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+pub async fn assign_lesson_schedule(pool: &PgPool, payload: AssignLessonScheduleRequest) -> Result<(), (StatusCode, String)> {
+    let date = NaiveDate::parse_from_str(&payload.schedule_date, "%Y-%m-%d")
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid date format".to_string()))?;
+    let instructional = academic_calendar_service::is_instructional_day(pool, date, Some(&payload.branch), Some(&payload.year))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !instructional {
+        return Err((StatusCode::BAD_REQUEST, format!("{} is not an instructional day in the academic calendar", payload.schedule_date)));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
     faculty_repository::insert_lesson_schedule(&mut tx, &payload.subject_id, &payload.topic_id, &payload.schedule_date, &payload.branch, &payload.year, &payload.semester, &payload.section)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    tx.commit().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
     Ok(())
 }
@@ -6,6 +6,7 @@ use crate::models::{
     ParentRequestQuery
 };
 use crate::utils::user_utils::resolve_user_id;
+use crate::repositories::leave_repository;
 use crate::repositories::user::parent_repository;
 
 pub async fn get_parent_profile(pool: &PgPool, user_id: &str) -> Result<ParentProfileResponse, StatusCode> {
@@ -74,7 +75,18 @@ pub async fn get_parent_requests(pool: &PgPool, params: ParentRequestQuery) -> R
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
 }
 
+/// Leave applications mirrored here are reviewed and cancelled through the leave service, which
+/// also fills their attendance; changing the mirror directly is refused with CONFLICT.
+async fn ensure_not_leave_mirror(pool: &PgPool, request_id: Uuid) -> Result<(), StatusCode> {
+    match leave_repository::is_leave_mirror(pool, request_id).await {
+        Ok(true) => Err(StatusCode::CONFLICT),
+        Ok(false) => Ok(()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
 pub async fn update_parent_request_status(pool: &PgPool, request_id: Uuid, status: String) -> Result<(), StatusCode> {
+    ensure_not_leave_mirror(pool, request_id).await?;
     parent_repository::update_parent_request_status(pool, request_id, &status)
         .await
         .map(|_| ())
@@ -82,6 +94,7 @@ pub async fn update_parent_request_status(pool: &PgPool, request_id: Uuid, statu
 }
 
 pub async fn delete_parent_request(pool: &PgPool, request_id: Uuid) -> Result<(), StatusCode> {
+    ensure_not_leave_mirror(pool, request_id).await?;
     parent_repository::delete_parent_request(pool, request_id)
         .await
         .map(|_| ())
@@ -11,7 +11,9 @@ use crate::models::{
 };
 use crate::utils::user_utils::resolve_user_id;
 use crate::repositories::user::student_repository;
-use crate::services::curriculum_service;
+use crate::repositories::{academic_calendar_repository, regulation_repository};
+use std::collections::HashSet;
+use crate::services::{curriculum_service, regulation_service};
 
 pub async fn get_student_profile(pool: &PgPool, user_id: &str) -> Result<StudentProfileResponse, StatusCode> {
     let user_uuid = resolve_user_id(user_id, "Student", pool).await.map_err(|_| StatusCode::BAD_REQUEST)?;
@@ -100,14 +102,18 @@ pub async fn get_student_courses(pool: &PgPool, user_id: &str) -> Result<Vec<Stu
         _ => 1
     };
 
-    let mut subjects = student_repository::find_subjects_by_branch_and_semester(pool, &branch_norm, &semester_key, &section_str)
+    let regulation = regulation_service::resolve_regulation(pool, user_uuid, &year_str)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    // Fallback: If DB returns empty, try to fetch from Assets
+    let mut subjects = student_repository::find_subjects_by_branch_and_semester(pool, &branch_norm, &semester_key, &section_str, &regulation)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Fallback: If no subjects are assigned yet, list the semester's subjects from the curriculum
     if subjects.is_empty() {
-        if let Ok(asset_subjects) = curriculum_service::get_subjects_from_assets(&branch_norm, sem_int, "C23").await {
-            for (code, name, stype) in asset_subjects {
+        if let Ok(curriculum_subjects) = curriculum_service::get_curriculum_subjects(pool, &branch_norm, sem_int, &regulation).await {
+            for (code, name, stype) in curriculum_subjects {
                 subjects.push((code, name, stype, None, None, None, None, None));
             }
         }
@@ -214,106 +220,28 @@ pub async fn get_student_courses(pool: &PgPool, user_id: &str) -> Result<Vec<Stu
             }
         }
 
-        let mut total_items = student_repository::count_lesson_plan_items(pool, &sid).await.unwrap_or(0);
-        let mut completed_items = student_repository::count_completed_lesson_plan_items(pool, &sid, &section_str).await.unwrap_or(0);
-        let mut scheduled_items = 0;
-        let mut has_schedule = false;
-
-        if total_items == 0 {
-            // Try new curriculum system
-            if let Ok(curriculum) = curriculum_service::get_merged_curriculum(
-                pool,
-                &branch_norm,
-                sem_int,
-                "C23",
-                &sid,
-                &section_str,
-                &year_str,
-            ).await {
-                for unit in &curriculum.units {
-                    for topic in &unit.topics {
-                        if topic.topic_type.to_lowercase() != "unit" {
-                            total_items += 1;
-                            if topic.status.as_deref() == Some("completed") {
-                                // Under the new system, check completed status
-                                completed_items += 1;
-                            }
-                            if let Some(assigned) = topic.assigned_date {
-                                has_schedule = true;
-                                if assigned <= chrono::Utc::now().date_naive() {
-                                    scheduled_items += 1;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        } else {
-            // Old lesson plan system has_schedule / scheduled_items count
-            let sched_count = sqlx::query_scalar::<_, i64>(
-                r#"
-                SELECT COUNT(*) 
-                FROM lesson_plan_items lpi 
-                JOIN lesson_schedule ls ON lpi.id = ls.topic_id 
-                WHERE lpi.subject_id = $1 AND ls.section = $2 
-                  AND ls.schedule_date <= NOW() 
-                  AND LOWER(lpi.type) != 'unit'
-                "#
-            )
-            .bind(&sid)
-            .bind(&section_str)
-            .fetch_one(pool)
-            .await
-            .unwrap_or(0);
-            
-            scheduled_items = sched_count;
-            
-            let total_sched_count = sqlx::query_scalar::<_, i64>(
-                r#"
-                SELECT COUNT(*) 
-                FROM lesson_plan_items lpi 
-                JOIN lesson_schedule ls ON lpi.id = ls.topic_id 
-                WHERE lpi.subject_id = $1 AND ls.section = $2 
-                  AND LOWER(lpi.type) != 'unit'
-                "#
-            )
-            .bind(&sid)
-            .bind(&section_str)
-            .fetch_one(pool)
-            .await
-            .unwrap_or(0);
-            
-            if total_sched_count > 0 {
-                has_schedule = true;
-            }
-        }
-
-        let progress = if total_items > 0 { (completed_items * 100) / total_items } else { 0 } as i32;
-        
-        let status = if has_schedule {
-            if completed_items < scheduled_items {
-                "Lagging".to_string()
-            } else if completed_items > scheduled_items {
-                "Overfast".to_string()
-            } else {
-                "On Track".to_string()
-            }
-        } else {
-            "On Track".to_string()
-        };
+        let section_progress = curriculum_service::section_progress(
+            pool,
+            &sid,
+            &branch_norm,
+            &year_str,
+            &section_str,
+            sem_int,
+            &regulation,
+        ).await;
 
         courses.push(StudentCourse {
             id: sid,
             name: sname,
             faculty_name: rfn_val,
             credits: 3,
-            progress,
+            progress: section_progress.percentage(),
             subject_type: stype,
             faculty_email: fe_val,
             faculty_phone: fp_val,
             faculty_department: fd_val,
             faculty_id: fid_val,
-            status: Some(status),
+            status: Some(section_progress.status().to_string()),
         });
     }
 
@@ -422,12 +350,24 @@ pub async fn get_student_attendance(pool: &PgPool, student_id: &str) -> Result<A
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let (mut present, mut absent) = (0, 0);
-    for r in &history { if r.status == "P" || r.status == "PRESENT" { present += 1; } else if r.status == "A" || r.status == "ABSENT" { absent += 1; } }
+    // Marks taken on holidays, exam blocks or other non-instructional days don't count towards the percentage.
+    let mut skipped = HashSet::new();
+    if let (Some(first), Some(last)) = (history.iter().map(|r| r.date).min(), history.iter().map(|r| r.date).max()) {
+        if let Ok(Some((Some(branch), Some(year), _, _, _))) = student_repository::get_student_basics(pool, student_uuid).await {
+            skipped = academic_calendar_repository::find_non_instructional_dates(pool, first, last, &normalize_branch(&branch), &year)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .into_iter()
+                .collect();
+        }
+    }
+
+    let (mut present, mut absent, mut leave) = (0, 0, 0);
+    for r in history.iter().filter(|r| !skipped.contains(&r.date)) { if r.status == "P" || r.status == "PRESENT" { present += 1; } else if r.status == "A" || r.status == "ABSENT" { absent += 1; } else if r.status == "L" { leave += 1; } }
     let total = present + absent;
     let percentage = if total > 0 { (present as f64 / total as f64) * 100.0 } else { 0.0 };
 
-    Ok(AttendanceSummary { total_classes: total, present_count: present, absent_count: absent, percentage, history })
+    Ok(AttendanceSummary { total_classes: total, present_count: present, absent_count: absent, leave_count: leave, percentage, history })
 }
 
 pub async fn request_attendance_correction(pool: &PgPool, payload: AttendanceCorrectionRequestData) -> Result<Uuid, (StatusCode, String)> {
@@ -477,6 +417,9 @@ pub async fn get_student_academics(pool: &PgPool, user_id: &str) -> Result<Vec<S
         .await
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "DB Error".to_string()))?
         .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    let regulation = regulation_service::resolve_regulation(pool, user_uuid, year.as_deref().unwrap_or_default())
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "DB Error".to_string()))?;
 
     let branch_norm = normalize_branch(&branch.unwrap_or_default());
     let year_str = year.unwrap_or_default();
@@ -488,21 +431,29 @@ pub async fn get_student_academics(pool: &PgPool, user_id: &str) -> Result<Vec<S
     let sems = vec![("1st Year", "1st Year", "Semester 1"), ("2nd Year", "3rd Semester", "Semester 3"), ("2nd Year", "4th Semester", "Semester 4"), ("3rd Year", "5th Semester", "Semester 5"), ("3rd Year", "6th Semester", "Semester 6")];
     let mut result = Vec::new();
     for (yl, db_sem, disp_sem) in sems {
-        let subjects = student_repository::get_subjects_by_semester(pool, &branch_norm, db_sem).await.unwrap_or_default();
+        let subjects = student_repository::get_subjects_by_semester(pool, &branch_norm, db_sem, &regulation).await.unwrap_or_default();
         let mut sub_res = Vec::new();
         let (mut pts, mut crds) = (0.0, 0.0);
         let mut all_present = !subjects.is_empty();
         for (sid, sname, scrd) in subjects {
             let cval = scrd.unwrap_or(3);
-            let mark = student_repository::get_student_mark(pool, &login_id, db_sem, &sname).await.unwrap_or(None).flatten();
+            let mut mark = student_repository::get_student_mark(pool, &login_id, db_sem, &sname).await.unwrap_or(None).flatten();
+            let mut transferred_from = None;
+            if mark.is_none() {
+                // Marks earned under the previous regulation count through subject equivalences
+                if let Ok(Some(t)) = regulation_repository::find_transferred_mark(pool, &login_id, &sid, &regulation).await {
+                    mark = t.marks;
+                    transferred_from = Some(format!("{} ({})", t.subject_code, t.course_id));
+                }
+            }
             let (grade, gp) = if let Some(m) = mark {
                 let g = if m >= 90 { ("O", 10) } else if m >= 80 { ("A+", 9) } else if m >= 70 { ("A", 8) } else if m >= 60 { ("B+", 7) } else if m >= 50 { ("B", 6) } else if m >= 40 { ("C", 5) } else { ("F", 0) };
                 (Some(g.0.to_string()), Some(g.1))
             } else { all_present = false; (None, None) };
             if let Some(p) = gp { pts += (p * cval) as f64; crds += cval as f64; }
-            sub_res.push(SubjectMarkResponse { subject_id: sid, subject_name: sname, marks: mark, credit: cval, grade, grade_points: gp });
+            sub_res.push(SubjectMarkResponse { subject_id: sid, subject_name: sname, marks: mark, credit: cval, grade, grade_points: gp, transferred_from });
         }
-        result.push(SemesterAcademicsResponse { year_label: yl.to_string(), semester_name: disp_sem.to_string(), is_ongoing: db_sem == semester_key, subjects: sub_res, sgpa: if all_present && crds > 0.0 { Some(pts / crds) } else { None } });
+        result.push(SemesterAcademicsResponse { year_label: yl.to_string(), semester_name: disp_sem.to_string(), is_ongoing: db_sem == semester_key, regulation: regulation.clone(), subjects: sub_res, sgpa: if all_present && crds > 0.0 { Some(pts / crds) } else { None } });
         if db_sem == semester_key { break; }
     }
     Ok(result)
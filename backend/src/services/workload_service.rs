@@ -0,0 +1,214 @@
+use sqlx::PgPool;
+use axum::http::StatusCode;
+use chrono::{NaiveDate, Utc};
+use std::collections::HashMap;
+use crate::models::{get_branch_variations, normalize_branch};
+use crate::models::workload::{FacultyWorkload, SaveWorkloadNormsRequest, WorkloadNorms, WorkloadNormsQuery, WorkloadQuery, WorkloadReport};
+use crate::repositories::{academic_calendar_repository, leave_repository, timetable_repository, workload_repository};
+use crate::utils::export_utils;
+use crate::utils::user_utils::resolve_user_id;
+
+const MAX_REPORT_DAYS: i64 = 366;
+const DEFAULT_PERIOD_MINUTES: i32 = 50;
+
+fn default_norms(branch: &str) -> WorkloadNorms {
+    WorkloadNorms {
+        branch: branch.to_string(),
+        max_weekly_periods: 24,
+        min_weekly_periods: 12,
+        max_subjects: 3,
+        max_sections: 4,
+        updated_at: Utc::now(),
+    }
+}
+
+pub async fn get_norms(pool: &PgPool, params: WorkloadNormsQuery) -> Result<WorkloadNorms, StatusCode> {
+    let branch = params.branch.as_deref().map(normalize_branch).unwrap_or_else(|| "ALL".to_string());
+    workload_repository::find_norms(pool, &branch)
+        .await
+        .map(|n| n.unwrap_or_else(|| default_norms(&branch)))
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to fetch workload norms: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+pub async fn save_norms(pool: &PgPool, payload: SaveWorkloadNormsRequest) -> Result<WorkloadNorms, (StatusCode, String)> {
+    let user_id = resolve_user_id(&payload.updated_by, "Principal", pool)
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid user: {}", payload.updated_by)))?;
+    let (_, role, _, _, _, _) = leave_repository::find_user_basics(pool, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+    // HODs may set norms for their department; only the Principal or Admin sets the college default.
+    let allowed = match payload.branch {
+        Some(_) => matches!(role.as_str(), "Principal" | "Admin" | "HOD"),
+        None => matches!(role.as_str(), "Principal" | "Admin"),
+    };
+    if !allowed {
+        return Err((StatusCode::FORBIDDEN, "Not allowed to change workload norms".to_string()));
+    }
+    if [payload.max_weekly_periods, payload.max_subjects, payload.max_sections].iter().any(|v| *v <= 0) || payload.min_weekly_periods < 0 {
+        return Err((StatusCode::BAD_REQUEST, "Norms must be positive".to_string()));
+    }
+    if payload.min_weekly_periods > payload.max_weekly_periods {
+        return Err((StatusCode::BAD_REQUEST, "minWeeklyPeriods cannot exceed maxWeeklyPeriods".to_string()));
+    }
+
+    let branch = payload.branch.as_deref().map(normalize_branch).unwrap_or_else(|| "ALL".to_string());
+    workload_repository::save_norms(pool, &branch, &payload, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Report range: explicit dates, else the requested semester, else the semester covering today.
+async fn report_range(pool: &PgPool, params: &WorkloadQuery) -> Result<(NaiveDate, NaiveDate, Option<String>), (StatusCode, String)> {
+    let (from, to, label) = match (params.from, params.to) {
+        (Some(from), Some(to)) => (from, to, None),
+        _ => {
+            let semester = academic_calendar_repository::find_semester(pool, params.semester_id, Utc::now().date_naive())
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+                .ok_or((StatusCode::BAD_REQUEST, "No semester found; pass from and to".to_string()))?;
+            (semester.start_date, semester.end_date, Some(format!("{} {}", semester.year_name, semester.term)))
+        }
+    };
+    if to < from {
+        return Err((StatusCode::BAD_REQUEST, "to must not be before from".to_string()));
+    }
+    if (to - from).num_days() >= MAX_REPORT_DAYS {
+        return Err((StatusCode::BAD_REQUEST, format!("Range may span at most {} days", MAX_REPORT_DAYS)));
+    }
+    Ok((from, to, label))
+}
+
+fn flags(w: &FacultyWorkload, norms: &WorkloadNorms) -> Vec<String> {
+    let mut flags = Vec::new();
+    if w.weekly_periods > norms.max_weekly_periods as i64 {
+        flags.push("OVER_WEEKLY_PERIODS".to_string());
+    }
+    if w.weekly_periods < norms.min_weekly_periods as i64 {
+        flags.push("UNDER_WEEKLY_PERIODS".to_string());
+    }
+    if w.subject_count > norms.max_subjects as i64 {
+        flags.push("OVER_SUBJECTS".to_string());
+    }
+    if w.section_count > norms.max_sections as i64 {
+        flags.push("OVER_SECTIONS".to_string());
+    }
+    flags
+}
+
+/// Per-faculty teaching load for a branch: weekly periods and hours from the live timetable,
+/// subjects and sections from approved `faculty_subjects`, and over the range the periods
+/// scheduled (up to today) against those recorded as conducted, missed or covered.
+pub async fn get_workload_report(pool: &PgPool, params: &WorkloadQuery) -> Result<WorkloadReport, (StatusCode, String)> {
+    let branch = normalize_branch(&params.branch);
+    let variations = get_branch_variations(&branch);
+    let (from, to, semester) = report_range(pool, params).await?;
+    let scheduled_to = to.min(Utc::now().date_naive());
+
+    let norms = workload_repository::find_norms(pool, &branch)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .unwrap_or_else(|| default_norms(&branch));
+    let period_minutes = timetable_repository::find_department_timing(pool, &variations, scheduled_to)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(|t| t.class_duration)
+        .unwrap_or(DEFAULT_PERIOD_MINUTES);
+
+    let loads = workload_repository::find_faculty_loads(pool, &variations)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let logins: Vec<String> = loads.iter().map(|l| l.login_id.clone()).collect();
+    let mut counts: HashMap<String, _> = workload_repository::find_period_counts(pool, &logins, from, to, scheduled_to)
+        .await
+        .map_err(|e| {
+            eprintln!("ERROR: Failed to count faculty periods: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        })?
+        .into_iter()
+        .map(|c| (c.login_id.clone(), c))
+        .collect();
+
+    let mut faculty: Vec<FacultyWorkload> = loads
+        .into_iter()
+        .map(|l| {
+            let c = counts.remove(&l.login_id).unwrap_or_default();
+            let recorded = c.conducted + c.not_conducted + c.substitutions_given;
+            let mut w = FacultyWorkload {
+                weekly_hours: (l.weekly_periods as f64 * period_minutes as f64 / 60.0 * 10.0).round() / 10.0,
+                login_id: l.login_id,
+                full_name: l.full_name,
+                weekly_periods: l.weekly_periods,
+                subject_count: l.subject_count,
+                section_count: l.section_count,
+                scheduled_periods: c.scheduled,
+                conducted_periods: c.conducted,
+                not_conducted_periods: c.not_conducted,
+                unrecorded_periods: (c.scheduled - recorded).max(0),
+                substitutions_taken: c.substitutions_taken,
+                substitutions_given: c.substitutions_given,
+                delivery_percent: if c.scheduled > 0 { (c.conducted as f64 / c.scheduled as f64 * 1000.0).round() / 10.0 } else { 0.0 },
+                flags: Vec::new(),
+            };
+            w.flags = flags(&w, &norms);
+            w
+        })
+        .collect();
+    faculty.sort_by(|a, b| b.weekly_periods.cmp(&a.weekly_periods).then_with(|| a.full_name.cmp(&b.full_name)));
+
+    Ok(WorkloadReport { branch, from, to, semester, norms, faculty })
+}
+
+const EXPORT_HEADERS: [&str; 14] = [
+    "Login ID", "Name", "Weekly Periods", "Weekly Hours", "Subjects", "Sections", "Scheduled", "Conducted",
+    "Not Conducted", "Unrecorded", "Subs Taken", "Subs Given", "Delivery %", "Flags",
+];
+
+fn export_rows(report: &WorkloadReport) -> Vec<Vec<String>> {
+    report.faculty.iter().map(|w| vec![
+        w.login_id.clone(),
+        w.full_name.clone(),
+        w.weekly_periods.to_string(),
+        format!("{:.1}", w.weekly_hours),
+        w.subject_count.to_string(),
+        w.section_count.to_string(),
+        w.scheduled_periods.to_string(),
+        w.conducted_periods.to_string(),
+        w.not_conducted_periods.to_string(),
+        w.unrecorded_periods.to_string(),
+        w.substitutions_taken.to_string(),
+        w.substitutions_given.to_string(),
+        format!("{:.1}", w.delivery_percent),
+        w.flags.join(" "),
+    ]).collect()
+}
+
+pub fn export_filename(report: &WorkloadReport, extension: &str) -> String {
+    let branch: String = report.branch.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    format!("workload_{}_{}_{}.{}", branch, report.from, report.to, extension)
+}
+
+pub fn workload_csv(report: &WorkloadReport) -> String {
+    export_utils::to_csv(&EXPORT_HEADERS, &export_rows(report))
+}
+
+pub fn workload_pdf(report: &WorkloadReport) -> Vec<u8> {
+    let title = format!(
+        "Faculty Workload - {} - {} to {}{}",
+        report.branch, report.from, report.to,
+        report.semester.as_deref().map(|s| format!(" ({})", s)).unwrap_or_default()
+    );
+    let mut lines = vec![
+        format!(
+            "Norms: {}-{} periods/week, up to {} subjects and {} sections",
+            report.norms.min_weekly_periods, report.norms.max_weekly_periods, report.norms.max_subjects, report.norms.max_sections
+        ),
+        String::new(),
+    ];
+    lines.extend(export_utils::text_table(&EXPORT_HEADERS, &export_rows(report), 24));
+    export_utils::text_pdf(&title, &lines)
+}
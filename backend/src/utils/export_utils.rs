@@ -0,0 +1,105 @@
+/// Quotes a CSV field when it holds a comma, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// RFC 4180 CSV with a header row and CRLF line endings.
+pub fn to_csv(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut out = String::new();
+    out.push_str(&headers.iter().map(|h| csv_field(h)).collect::<Vec<_>>().join(","));
+    out.push_str("\r\n");
+    for row in rows {
+        out.push_str(&row.iter().map(|v| csv_field(v)).collect::<Vec<_>>().join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Lays rows out as fixed-width text columns for a monospaced page. Cells longer than
+/// `max_width` are cut with an ellipsis.
+pub fn text_table(headers: &[&str], rows: &[Vec<String>], max_width: usize) -> Vec<String> {
+    let cut = |s: &str| -> String {
+        if s.chars().count() > max_width {
+            format!("{}~", s.chars().take(max_width - 1).collect::<String>())
+        } else {
+            s.to_string()
+        }
+    };
+    let mut widths: Vec<usize> = headers.iter().map(|h| cut(h).chars().count()).collect();
+    for row in rows {
+        for (i, v) in row.iter().enumerate().take(widths.len()) {
+            widths[i] = widths[i].max(cut(v).chars().count());
+        }
+    }
+    let line = |cells: Vec<String>| -> String {
+        cells.iter().zip(&widths).map(|(c, w)| format!("{:<w$}", c, w = *w)).collect::<Vec<_>>().join("  ").trim_end().to_string()
+    };
+    let mut lines = vec![line(headers.iter().map(|h| cut(h)).collect())];
+    lines.push(widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("  "));
+    for row in rows {
+        lines.push(line(row.iter().map(|v| cut(v)).collect()));
+    }
+    lines
+}
+
+const PAGE_WIDTH: i32 = 842; // A4 landscape, in points
+const PAGE_HEIGHT: i32 = 595;
+const MARGIN: i32 = 36;
+const FONT_SIZE: i32 = 8;
+const LINE_HEIGHT: i32 = 10;
+
+fn pdf_text(line: &str) -> String {
+    line.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            c if c.is_ascii() && !c.is_ascii_control() => c.to_string(),
+            _ => "?".to_string(), // the standard Courier font only covers Latin-1
+        })
+        .collect()
+}
+
+/// A plain PDF of monospaced text lines on A4 landscape pages, with the title and page number on
+/// every page. Enough for tabular reports without pulling in a PDF library.
+pub fn text_pdf(title: &str, lines: &[String]) -> Vec<u8> {
+    let per_page = ((PAGE_HEIGHT - 2 * MARGIN) / LINE_HEIGHT - 3) as usize;
+    let pages: Vec<&[String]> = if lines.is_empty() { vec![&[]] } else { lines.chunks(per_page).collect() };
+
+    let mut objects: Vec<String> = Vec::new();
+    objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_string());
+    let kids = (0..pages.len()).map(|i| format!("{} 0 R", 4 + i * 2)).collect::<Vec<_>>().join(" ");
+    objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids, pages.len()));
+    objects.push("<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_string());
+
+    for (i, page) in pages.iter().enumerate() {
+        let mut content = format!("BT /F1 {} Tf {} TL {} {} Td\n", FONT_SIZE + 2, LINE_HEIGHT, MARGIN, PAGE_HEIGHT - MARGIN);
+        content.push_str(&format!("({}) Tj\n", pdf_text(title)));
+        content.push_str(&format!("/F1 {} Tf T* ({}) Tj T* T*\n", FONT_SIZE, pdf_text(&format!("Page {} of {}", i + 1, pages.len()))));
+        for line in page.iter() {
+            content.push_str(&format!("({}) Tj T*\n", pdf_text(line)));
+        }
+        content.push_str("ET\n");
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH, PAGE_HEIGHT, 5 + i * 2
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+    }
+
+    let mut out = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, obj) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, obj));
+    }
+    let xref = out.len();
+    out.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1));
+    for off in offsets {
+        out.push_str(&format!("{:010} 00000 n \n", off));
+    }
+    out.push_str(&format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref));
+    out.into_bytes()
+}
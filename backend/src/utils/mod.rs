@@ -1 +1,4 @@
 pub mod user_utils;
+pub mod timing_utils;
+pub mod export_utils;
+pub mod storage_utils;
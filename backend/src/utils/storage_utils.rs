@@ -0,0 +1,118 @@
+use std::future::Future;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Directory the local backend keeps files under when `MATERIAL_STORAGE_DIR` is unset.
+pub const DEFAULT_LOCAL_STORAGE_DIR: &str = "uploads";
+
+/// Somewhere uploaded files can be kept and read back by key. Keys are relative, '/'-separated
+/// paths chosen by the caller; the backend's name is stored next to each key so files stay
+/// readable after the configured backend changes.
+pub trait FileStorage {
+    fn name(&self) -> &'static str;
+    fn put(&self, key: &str, bytes: &[u8]) -> impl Future<Output = io::Result<()>> + Send;
+    fn get(&self, key: &str) -> impl Future<Output = io::Result<Vec<u8>>> + Send;
+    fn delete(&self, key: &str) -> impl Future<Output = io::Result<()>> + Send;
+}
+
+/// Files on the server's own disk, under `root`.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Refuses keys that are absolute or climb out of `root`.
+    fn path(&self, key: &str) -> io::Result<PathBuf> {
+        let relative = Path::new(key);
+        if key.is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid storage key {}", key)));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+impl FileStorage for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn put(&self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        let path = self.path(key)?;
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(path, bytes).await
+    }
+
+    async fn get(&self, key: &str) -> io::Result<Vec<u8>> {
+        tokio::fs::read(self.path(key)?).await
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        }
+    }
+}
+
+/// The storage backends the server can be configured with. A new backend implements
+/// `FileStorage` and gets a variant here.
+pub enum StorageBackend {
+    Local(LocalStorage),
+}
+
+impl StorageBackend {
+    /// The backend new files go to: `MATERIAL_STORAGE` (only `local` for now), rooted at
+    /// `MATERIAL_STORAGE_DIR`.
+    pub fn configured() -> Self {
+        let name = std::env::var("MATERIAL_STORAGE").unwrap_or_else(|_| "local".to_string());
+        Self::named(&name).unwrap_or_else(|| {
+            eprintln!("Unknown MATERIAL_STORAGE {}, using local storage", name);
+            Self::local()
+        })
+    }
+
+    /// The backend a stored file was written with.
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "local" => Some(Self::local()),
+            _ => None,
+        }
+    }
+
+    fn local() -> Self {
+        let dir = std::env::var("MATERIAL_STORAGE_DIR").unwrap_or_else(|_| DEFAULT_LOCAL_STORAGE_DIR.to_string());
+        Self::Local(LocalStorage::new(dir))
+    }
+}
+
+impl FileStorage for StorageBackend {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Local(s) => s.name(),
+        }
+    }
+
+    async fn put(&self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Local(s) => s.put(key, bytes).await,
+        }
+    }
+
+    async fn get(&self, key: &str) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Local(s) => s.get(key).await,
+        }
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        match self {
+            Self::Local(s) => s.delete(key).await,
+        }
+    }
+}
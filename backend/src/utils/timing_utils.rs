@@ -0,0 +1,213 @@
+use chrono::{Duration, NaiveDateTime, NaiveTime, Utc, Weekday};
+use crate::models::DepartmentTiming;
+use crate::models::slot_template::{DaySchedule, WeekSchedule};
+
+/// Matches the layout the department timing screen starts from when `slot_config` is unset.
+pub const DEFAULT_SLOT_CONFIG: [&str; 11] = ["P", "P", "SB", "P", "P", "LB", "P", "P", "SB", "P", "P"];
+
+/// How early before the first period a punch still counts towards the morning session.
+pub const ARRIVAL_GRACE_MINUTES: i64 = 60;
+
+/// Offset of campus local time from UTC when `CAMPUS_UTC_OFFSET_MINUTES` is unset (IST).
+pub const DEFAULT_CAMPUS_UTC_OFFSET_MINUTES: i64 = 330;
+
+/// One entry of a department's day: a period (P), short break (SB) or lunch break (LB).
+#[derive(Debug, Clone)]
+pub struct DaySlot {
+    pub kind: String,
+    pub period_index: Option<i32>, // 1-based, periods only
+    pub lab_block: Option<i32>, // 1-based LAB entry of the slot template, lab periods only
+    pub label: Option<String>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// The slot code a template kind expands to; LAB periods are ordinary periods on the day.
+pub fn slot_code(kind: &str) -> Option<&'static str> {
+    match kind {
+        "PERIOD" | "LAB" => Some("P"),
+        "SHORT_BREAK" => Some("SB"),
+        "LUNCH" => Some("LB"),
+        _ => None,
+    }
+}
+
+/// Expands a template day into its slots, numbering periods (including each period of a LAB
+/// block) from 1. Unknown kinds are skipped; schedules are validated when saved.
+pub fn schedule_slots(schedule: &DaySchedule) -> Vec<DaySlot> {
+    let mut time = NaiveTime::parse_from_str(&schedule.start_time, "%H:%M").unwrap_or_else(|_| NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default());
+    let mut period = 0;
+    let mut lab = 0;
+    let mut slots = Vec::new();
+    for slot in &schedule.slots {
+        let Some(code) = slot_code(&slot.kind) else { continue };
+        let (count, lab_block) = match slot.kind.as_str() {
+            "LAB" => {
+                lab += 1;
+                (slot.periods.unwrap_or(2).max(1), Some(lab))
+            }
+            _ => (1, None),
+        };
+        for _ in 0..count {
+            let period_index = if code == "P" {
+                period += 1;
+                Some(period)
+            } else {
+                None
+            };
+            let end = time + Duration::minutes(slot.minutes as i64);
+            slots.push(DaySlot { kind: code.to_string(), period_index, lab_block, label: slot.label.clone(), start: time, end });
+            time = end;
+        }
+    }
+    slots
+}
+
+/// Expands a department's weekday timing into its slots, falling back to 09:00 with 50 minute periods.
+pub fn day_slots(timing: Option<&DepartmentTiming>) -> Vec<DaySlot> {
+    day_slots_on(timing, Weekday::Mon)
+}
+
+/// As `day_slots`, using the template's Saturday schedule on Saturdays when it has one.
+pub fn day_slots_on(timing: Option<&DepartmentTiming>, weekday: Weekday) -> Vec<DaySlot> {
+    let template = timing
+        .and_then(|t| t.slot_template.as_ref())
+        .and_then(|v| serde_json::from_value::<WeekSchedule>(v.clone()).ok());
+    if let Some(week) = template {
+        let schedule = match week.saturday {
+            Some(saturday) if weekday == Weekday::Sat => saturday,
+            _ => week.weekday,
+        };
+        return schedule_slots(&schedule);
+    }
+
+    let (start_hour, start_minute, class, short_break, lunch) = match timing {
+        Some(t) => (t.start_hour, t.start_minute, t.class_duration, t.short_break_duration, t.lunch_duration),
+        None => (9, 0, 50, 10, 50),
+    };
+
+    let config: Vec<String> = timing
+        .and_then(|t| t.slot_config.as_ref())
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v.clone()).ok())
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| DEFAULT_SLOT_CONFIG.iter().map(|s| s.to_string()).collect());
+
+    let mut time = NaiveTime::from_hms_opt(start_hour as u32, start_minute as u32, 0).unwrap_or_default();
+    let mut period = 0;
+    let mut slots = Vec::with_capacity(config.len());
+    for kind in config {
+        let minutes = match kind.as_str() {
+            "P" => class,
+            "SB" => short_break,
+            "LB" => lunch,
+            _ => continue,
+        };
+        let period_index = if kind == "P" {
+            period += 1;
+            Some(period)
+        } else {
+            None
+        };
+        let end = time + Duration::minutes(minutes as i64);
+        slots.push(DaySlot { kind, period_index, lab_block: None, label: None, start: time, end });
+        time = end;
+    }
+    slots
+}
+
+/// The time at which the afternoon session begins: the start of lunch, or the middle period
+/// when the department has no lunch break configured.
+fn afternoon_start(slots: &[DaySlot]) -> Option<NaiveTime> {
+    if let Some(lunch) = slots.iter().find(|s| s.kind == "LB") {
+        return Some(lunch.start);
+    }
+    let periods: Vec<&DaySlot> = slots.iter().filter(|s| s.kind == "P").collect();
+    periods.get(periods.len() / 2).map(|p| p.start)
+}
+
+/// Maps a time of day to the attendance session (MORNING / AFTERNOON) it belongs to.
+pub fn session_at(slots: &[DaySlot], time: NaiveTime) -> Option<&'static str> {
+    let day_start = slots.first()?.start - Duration::minutes(ARRIVAL_GRACE_MINUTES);
+    let day_end = slots.last()?.end;
+    let split = afternoon_start(slots)?;
+
+    if time < day_start || time >= day_end {
+        None
+    } else if time < split {
+        Some("MORNING")
+    } else {
+        Some("AFTERNOON")
+    }
+}
+
+/// The period running at `time`, if any.
+pub fn period_at(slots: &[DaySlot], time: NaiveTime) -> Option<i32> {
+    slots.iter().find(|s| s.start <= time && time < s.end).and_then(|s| s.period_index)
+}
+
+/// How far campus local time is ahead of UTC.
+pub fn campus_utc_offset() -> Duration {
+    let offset = std::env::var("CAMPUS_UTC_OFFSET_MINUTES")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_CAMPUS_UTC_OFFSET_MINUTES);
+    Duration::minutes(offset)
+}
+
+/// Wall-clock time on campus, which department timings are written in.
+pub fn campus_now() -> NaiveDateTime {
+    Utc::now().naive_utc() + campus_utc_offset()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::slot_template::TemplateSlot;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn slot(kind: &str, minutes: i32, periods: Option<i32>) -> TemplateSlot {
+        TemplateSlot { kind: kind.to_string(), minutes, periods, label: None }
+    }
+
+    #[test]
+    fn numbers_periods_through_lab_blocks_and_skips_breaks() {
+        let schedule = DaySchedule {
+            start_time: "09:00".to_string(),
+            slots: vec![
+                slot("PERIOD", 50, None),
+                slot("SHORT_BREAK", 10, None),
+                slot("LAB", 45, Some(2)),
+                slot("LUNCH", 40, None),
+                slot("LAB", 50, None),
+                slot("PERIOD", 50, None),
+            ],
+        };
+        let slots = schedule_slots(&schedule);
+
+        let kinds: Vec<&str> = slots.iter().map(|s| s.kind.as_str()).collect();
+        assert_eq!(kinds, ["P", "SB", "P", "P", "LB", "P", "P", "P"]);
+        let periods: Vec<Option<i32>> = slots.iter().map(|s| s.period_index).collect();
+        assert_eq!(periods, [Some(1), None, Some(2), Some(3), None, Some(4), Some(5), Some(6)]);
+        let labs: Vec<Option<i32>> = slots.iter().map(|s| s.lab_block).collect();
+        assert_eq!(labs, [None, None, Some(1), Some(1), None, Some(2), Some(2), None]);
+
+        assert_eq!((slots[0].start, slots[0].end), (time(9, 0), time(9, 50)));
+        assert_eq!((slots[2].start, slots[3].end), (time(10, 0), time(11, 30)));
+        assert_eq!((slots[4].start, slots[4].end), (time(11, 30), time(12, 10)));
+        assert_eq!(slots[7].end, time(14, 40));
+    }
+
+    #[test]
+    fn ignores_unknown_kinds_and_falls_back_to_nine_for_a_bad_start() {
+        let schedule = DaySchedule {
+            start_time: "late".to_string(),
+            slots: vec![slot("ASSEMBLY", 30, None), slot("PERIOD", 50, None)],
+        };
+        let slots = schedule_slots(&schedule);
+        assert_eq!(slots.len(), 1);
+        assert_eq!((slots[0].start, slots[0].period_index), (time(9, 0), Some(1)));
+    }
+}